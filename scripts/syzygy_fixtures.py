@@ -0,0 +1,490 @@
+#!/usr/bin/env python3
+"""Writes the three-man Syzygy fixtures in tests/fixtures/syzygy.
+
+The files are in the published .rtbw/.rtbz format, built without any of the engine's
+code: positions are solved here by retrograde analysis, indexed as the reference
+prober (Stockfish's tbprobe.cpp) indexes them, and compressed with recursive pairing
+and a canonical Huffman code. The tables deliberately use features the engine's own
+test writer does not: pair symbols, codes of several lengths, a black-to-move DTZ
+table, a mapped DTZ table and a padded block length list.
+
+The published KRvK and KQvK files should hold the same values for every position the
+tests probe, so they can be dropped in instead: the tests only check probe results.
+
+Usage: scripts/syzygy_fixtures.py [output-dir]
+"""
+
+import collections
+import heapq
+import os
+import struct
+import sys
+
+WDL_MAGIC = bytes([0x71, 0xE8, 0x23, 0x5D])
+DTZ_MAGIC = bytes([0xD7, 0x66, 0x0C, 0xA5])
+
+# Table flags.
+SPLIT = 1
+STM = 1
+MAPPED = 2
+WIN_PLIES = 4
+LOSS_PLIES = 8
+
+W_KING, B_KING = 6, 14
+PIECE_CODES = {"R": 4, "Q": 5}
+
+TB_SIZE = 31332  # Three unique pieces.
+
+KING_STEPS = [(dx, dy) for dx in (-1, 0, 1) for dy in (-1, 0, 1) if dx or dy]
+SLIDES = {
+    "R": [(1, 0), (-1, 0), (0, 1), (0, -1)],
+    "Q": [(dx, dy) for dx in (-1, 0, 1) for dy in (-1, 0, 1) if dx or dy],
+}
+
+
+def file_of(sq):
+    return sq & 7
+
+
+def rank_of(sq):
+    return sq >> 3
+
+
+def steps(sq, dirs):
+    x, y = file_of(sq), rank_of(sq)
+    for dx, dy in dirs:
+        if 0 <= x + dx < 8 and 0 <= y + dy < 8:
+            yield (y + dy) * 8 + x + dx
+
+
+def adjacent(a, b):
+    return max(abs(file_of(a) - file_of(b)), abs(rank_of(a) - rank_of(b))) <= 1
+
+
+def slide(sq, dirs, blockers):
+    """Squares a slider on `sq` reaches, stopping before any blocker."""
+    out = []
+    for dx, dy in dirs:
+        x, y = file_of(sq) + dx, rank_of(sq) + dy
+        while 0 <= x < 8 and 0 <= y < 8 and y * 8 + x not in blockers:
+            out.append(y * 8 + x)
+            x, y = x + dx, y + dy
+    return out
+
+
+class Solver:
+    """KXvK by retrograde analysis. Positions are (wk, x, bk); white holds X."""
+
+    def __init__(self, piece):
+        self.dirs = SLIDES[piece]
+        n = 64 * 64 * 64
+        self.white = [None] * n  # plies to mate for white wins
+        self.black = [None] * n  # plies to mate for black losses
+        self.solve()
+
+    @staticmethod
+    def pos(wk, x, bk):
+        return (wk * 64 + x) * 64 + bk
+
+    def attacked_by_x(self, x, target, wk):
+        return target in slide(x, self.dirs, {wk})
+
+    def legal(self, wk, x, bk, stm):
+        if len({wk, x, bk}) < 3 or adjacent(wk, bk):
+            return False
+        return stm == 1 or not self.attacked_by_x(x, bk, wk)
+
+    def black_moves(self, wk, x, bk):
+        """Quiet black king moves, and whether black can take X."""
+        quiet, escape = [], False
+        for t in steps(bk, KING_STEPS):
+            if adjacent(t, wk):
+                continue
+            if t == x:
+                escape = True
+            elif not self.attacked_by_x(x, t, wk):
+                quiet.append(t)
+        return quiet, escape
+
+    def white_moves(self, wk, x, bk):
+        """Positions after each legal white move, black to move."""
+        out = [(t, x, bk) for t in steps(wk, KING_STEPS) if t != x and not adjacent(t, bk)]
+        out += [(wk, t, bk) for t in slide(x, self.dirs, {wk, bk})]
+        return out
+
+    def solve(self):
+        remaining = {}
+        level = []
+        for wk in range(64):
+            for x in range(64):
+                for bk in range(64):
+                    if not self.legal(wk, x, bk, 1):
+                        continue
+                    quiet, escape = self.black_moves(wk, x, bk)
+                    p = self.pos(wk, x, bk)
+                    if escape:
+                        continue
+                    if quiet:
+                        remaining[p] = len(quiet)
+                    elif self.attacked_by_x(x, bk, wk):
+                        self.black[p] = 0
+                        level.append((wk, x, bk))
+
+        plies = 0
+        while level:
+            wins = []
+            for wk, x, bk in level:
+                # White moves are reversible, so the moves from here are the moves back.
+                for pwk, px, pbk in self.white_moves(wk, x, bk):
+                    p = self.pos(pwk, px, pbk)
+                    if self.white[p] is None and self.legal(pwk, px, pbk, 0):
+                        self.white[p] = plies + 1
+                        wins.append((pwk, px, pbk))
+            level = []
+            for wk, x, bk in wins:
+                for s in steps(bk, KING_STEPS):
+                    if s in (wk, x) or adjacent(s, wk):
+                        continue
+                    p = self.pos(wk, x, s)
+                    if p not in remaining:
+                        continue
+                    # The king must be able to step from s to bk.
+                    if self.attacked_by_x(x, bk, wk):
+                        continue
+                    remaining[p] -= 1
+                    if remaining[p] == 0:
+                        del remaining[p]
+                        self.black[p] = plies + 2
+                        level.append((wk, x, s))
+            plies += 2
+
+    def value(self, wk, x, bk, stm):
+        """(wdl, plies) for the side to move; wdl is -2, 0 or 2."""
+        p = self.pos(wk, x, bk)
+        if stm == 0:
+            return (2, self.white[p]) if self.white[p] is not None else (0, 0)
+        return (-2, self.black[p]) if self.black[p] is not None else (0, 0)
+
+
+def off_diagonal(sq):
+    return rank_of(sq) - file_of(sq)
+
+
+MAP_B1H1H7 = {}
+MAP_A1D1D4 = {}
+
+
+def init_maps():
+    code = 0
+    for sq in range(64):
+        if off_diagonal(sq) < 0:
+            MAP_B1H1H7[sq] = code
+            code += 1
+    code = 0
+    diagonal = []
+    for sq in range(28):  # a1 to d4
+        if off_diagonal(sq) < 0 and file_of(sq) <= 3:
+            MAP_A1D1D4[sq] = code
+            code += 1
+        elif off_diagonal(sq) == 0 and file_of(sq) <= 3:
+            diagonal.append(sq)
+    for sq in diagonal:
+        MAP_A1D1D4[sq] = code
+        code += 1
+
+
+def encode(order, placed):
+    """Index of a position with three unique pieces. `order` is the table's piece
+    sequence and `placed` maps each piece code to its square."""
+    sq = [placed[piece] for piece in order]
+    if file_of(sq[0]) > 3:
+        sq = [s ^ 7 for s in sq]
+    if rank_of(sq[0]) > 3:
+        sq = [s ^ 56 for s in sq]
+    for i in range(3):
+        off = off_diagonal(sq[i])
+        if off == 0:
+            continue
+        if off > 0:
+            sq = sq[:i] + [((s >> 3) | (s << 3)) & 63 for s in sq[i:]]
+        break
+
+    s0, s1, s2 = sq
+    adjust1 = int(s1 > s0)
+    adjust2 = int(s2 > s0) + int(s2 > s1)
+    if off_diagonal(s0):
+        return (MAP_A1D1D4[s0] * 63 + (s1 - adjust1)) * 62 + s2 - adjust2
+    if off_diagonal(s1):
+        return (6 * 63 + rank_of(s0) * 28 + MAP_B1H1H7[s1]) * 62 + s2 - adjust2
+    if off_diagonal(s2):
+        return (
+            6 * 63 * 62
+            + 4 * 28 * 62
+            + rank_of(s0) * 7 * 28
+            + (rank_of(s1) - adjust1) * 28
+            + MAP_B1H1H7[s2]
+        )
+    return (
+        6 * 63 * 62
+        + 4 * 28 * 62
+        + 4 * 7 * 28
+        + rank_of(s0) * 7 * 6
+        + (rank_of(s1) - adjust1) * 6
+        + (rank_of(s2) - adjust2)
+    )
+
+
+def fill(solver, piece, order, stm, stored):
+    """Values by index for one side to move. `stored` maps (wdl, plies) to the stored
+    value, or None for an index the prober never reads."""
+    code = PIECE_CODES[piece]
+    values = [None] * TB_SIZE
+    for wk in range(64):
+        for x in range(64):
+            for bk in range(64):
+                if not solver.legal(wk, x, bk, stm):
+                    continue
+                value = stored(solver.value(wk, x, bk, stm))
+                if value is None:
+                    continue
+                idx = encode(order, {W_KING: wk, code: x, B_KING: bk})
+                assert values[idx] in (None, value), (wk, x, bk, stm)
+                values[idx] = value
+    filler = collections.Counter(v for v in values if v is not None).most_common(1)[0][0]
+    return [filler if v is None else v for v in values]
+
+
+class Compressed:
+    """Values compressed by recursive pairing and a canonical Huffman code."""
+
+    def __init__(self, values, block_log2, span_log2, pair_rounds, padding=0):
+        self.block_size = 1 << block_log2
+        self.block_log2 = block_log2
+        self.span_log2 = span_log2
+        self.padding = padding
+
+        # Symbols are leaves (value,) or pairs (left, right); `length` counts values.
+        syms = []
+        leaf = {}
+        for v in sorted(set(values)):
+            leaf[v] = len(syms)
+            syms.append((v,))
+        length = [1] * len(syms)
+        seq = [leaf[v] for v in values]
+        for _ in range(pair_rounds):
+            pairs = collections.Counter(zip(seq, seq[1:]))
+            candidates = [
+                (count, pair)
+                for pair, count in pairs.items()
+                if length[pair[0]] + length[pair[1]] <= 256
+            ]
+            if not candidates:
+                break
+            count, pair = max(candidates)
+            if count < 8:
+                break
+            new = len(syms)
+            syms.append(pair)
+            length.append(length[pair[0]] + length[pair[1]])
+            out = []
+            i = 0
+            while i < len(seq):
+                if i + 1 < len(seq) and (seq[i], seq[i + 1]) == pair:
+                    out.append(new)
+                    i += 2
+                else:
+                    out.append(seq[i])
+                    i += 1
+            seq = out
+
+        # Huffman code lengths for the symbols left in the stream.
+        freq = collections.Counter(seq)
+        heap = [(f, i, [s]) for i, (s, f) in enumerate(sorted(freq.items()))]
+        heapq.heapify(heap)
+        code_len = {s: 0 for s in freq}
+        tie = len(heap)
+        while len(heap) > 1:
+            f1, _, a = heapq.heappop(heap)
+            f2, _, b = heapq.heappop(heap)
+            for s in a + b:
+                code_len[s] += 1
+            heapq.heappush(heap, (f1 + f2, tie, a + b))
+            tie += 1
+        assert len(code_len) > 1
+
+        # Canonical numbering: longest codes get the lowest ids, symbols used only
+        # inside pairs come after every coded one.
+        self.min_len = min(code_len.values())
+        self.max_len = max(code_len.values())
+        coded = sorted(code_len, key=lambda s: (-code_len[s], s))
+        uncoded = [s for s in range(len(syms)) if s not in code_len]
+        new_id = {s: i for i, s in enumerate(coded + uncoded)}
+        count = collections.Counter(code_len.values())
+        self.lowest = {self.max_len: 0}
+        base = {self.max_len: 0}
+        for n in range(self.max_len - 1, self.min_len - 1, -1):
+            self.lowest[n] = self.lowest[n + 1] + count[n + 1]
+            assert (base[n + 1] + count[n + 1]) % 2 == 0
+            base[n] = (base[n + 1] + count[n + 1]) // 2
+        assert base[self.min_len] + count[self.min_len] == 1 << self.min_len
+        self.codes = {}
+        for s in coded:
+            n = code_len[s]
+            self.codes[new_id[s]] = (base[n] + new_id[s] - self.lowest[n], n)
+
+        self.btree = [None] * len(syms)
+        for s, sym in enumerate(syms):
+            if len(sym) == 1:
+                self.btree[new_id[s]] = (sym[0], 0xFFF)
+            else:
+                self.btree[new_id[s]] = (new_id[sym[0]], new_id[sym[1]])
+        seq = [new_id[s] for s in seq]
+        lengths = [0] * len(syms)
+        for s, n in enumerate(length):
+            lengths[new_id[s]] = n
+
+        # Blocks of whole symbols.
+        self.blocks = []
+        self.block_values = []
+        bits, block, block_values = 0, [], 0
+        for s in seq:
+            code, n = self.codes[s]
+            if bits + n > 8 * self.block_size or block_values + lengths[s] > 65536:
+                self.close_block(block, block_values)
+                bits, block, block_values = 0, [], 0
+            block.append((code, n))
+            bits += n
+            block_values += lengths[s]
+        self.close_block(block, block_values)
+
+        # Sparse entry k locates the value k * span + span / 2.
+        span = 1 << span_log2
+        starts = [0]
+        for n in self.block_values:
+            starts.append(starts[-1] + n)
+        self.sparse = []
+        for k in range((len(values) + span - 1) // span):
+            centre = k * span + span // 2
+            b = 0
+            while b + 1 < len(self.block_values) and starts[b + 1] <= centre:
+                b += 1
+            self.sparse.append((b, centre - starts[b]))
+
+    def close_block(self, block, values):
+        word = 0
+        bits = 0
+        for code, n in block:
+            word = (word << n) | code
+            bits += n
+        word <<= 8 * self.block_size - bits
+        self.blocks.append(word.to_bytes(self.block_size, "big"))
+        self.block_values.append(values)
+
+    def header(self, flags):
+        out = bytes([flags, self.block_log2, self.span_log2, self.padding])
+        out += struct.pack("<I", len(self.blocks))
+        out += bytes([self.max_len, self.min_len])
+        for n in range(self.min_len, self.max_len + 1):
+            out += struct.pack("<H", self.lowest[n])
+        out += struct.pack("<H", len(self.btree))
+        for left, right in self.btree:
+            out += bytes([left & 0xFF, (left >> 8) | ((right & 0xF) << 4), right >> 4])
+        if len(self.btree) & 1:
+            out += b"\0"
+        return out
+
+    def sparse_bytes(self):
+        return b"".join(struct.pack("<IH", b, off) for b, off in self.sparse)
+
+    def block_length_bytes(self):
+        lengths = [n - 1 for n in self.block_values] + [0] * self.padding
+        return b"".join(struct.pack("<H", n) for n in lengths)
+
+
+def align(out, to):
+    out += b"\0" * (-len(out) % to)
+
+
+def write_table(magic, orders, items, flags, dtz_map=b""):
+    """A pawnless, non-symmetric table. `orders` holds each side's piece sequence
+    (one side for DTZ) and `items` the matching compressed values."""
+    out = bytearray(magic)
+    out.append(SPLIT)
+    out.append(0x00)  # Both sides encode the one group first.
+    for k in range(3):
+        low = orders[0][k]
+        high = orders[1][k] if len(orders) > 1 else low
+        out.append(low | (high << 4))
+    align(out, 2)
+    for item, f in zip(items, flags):
+        out += item.header(f)
+    if dtz_map:
+        out += dtz_map
+        align(out, 2)
+    for item in items:
+        out += item.sparse_bytes()
+    for item in items:
+        out += item.block_length_bytes()
+    for item in items:
+        align(out, 64)
+        out += b"".join(item.blocks)
+    return bytes(out)
+
+
+def main():
+    out_dir = sys.argv[1] if len(sys.argv) > 1 else os.path.join(
+        os.path.dirname(os.path.abspath(__file__)), "..", "tests", "fixtures", "syzygy"
+    )
+    os.makedirs(out_dir, exist_ok=True)
+    init_maps()
+
+    def wdl(value):
+        return value[0] + 2
+
+    # KRvK: white-to-move DTZ stored in plies, two piece orders per WDL side.
+    krk = Solver("R")
+    orders = [[4, 6, 14], [14, 4, 6]]
+    items = [
+        Compressed(fill(krk, "R", orders[stm], stm, wdl), 5, 10, 40, padding=stm)
+        for stm in (0, 1)
+    ]
+    write_file(out_dir, "KRvK.rtbw", write_table(WDL_MAGIC, orders, items, [0, 0]))
+
+    def krk_dtz(value):
+        return value[1] - 1 if value[0] == 2 else None
+
+    order = [14, 6, 4]
+    item = Compressed(fill(krk, "R", order, 0, krk_dtz), 6, 8, 40)
+    table = write_table(DTZ_MAGIC, [order], [item], [WIN_PLIES | LOSS_PLIES])
+    write_file(out_dir, "KRvK.rtbz", table)
+
+    # KQvK: black-to-move DTZ, stored through a value map.
+    kqk = Solver("Q")
+    orders = [[6, 14, 5], [5, 14, 6]]
+    items = [Compressed(fill(kqk, "Q", orders[stm], stm, wdl), 6, 9, 40) for stm in (0, 1)]
+    write_file(out_dir, "KQvK.rtbw", write_table(WDL_MAGIC, orders, items, [0, 0]))
+
+    # A mated side stores a DTZ of one ply: the prober's one-ply search relies on it
+    # to recognise mating moves.
+    losses = sorted({max(n, 1) - 1 for n in kqk.black if n is not None})
+    loss_index = {plies: i for i, plies in enumerate(losses)}
+
+    def kqk_dtz(value):
+        return loss_index[max(value[1], 1) - 1] if value[0] == -2 else None
+
+    dtz_map = bytes([0]) + bytes([len(losses)] + losses) + bytes([0, 0])
+    order = [5, 6, 14]
+    item = Compressed(fill(kqk, "Q", order, 1, kqk_dtz), 5, 8, 40)
+    flags = STM | MAPPED | WIN_PLIES | LOSS_PLIES
+    table = write_table(DTZ_MAGIC, [order], [item], [flags], dtz_map)
+    write_file(out_dir, "KQvK.rtbz", table)
+
+
+def write_file(out_dir, name, data):
+    with open(os.path.join(out_dir, name), "wb") as f:
+        f.write(data)
+    print(f"{name}: {len(data)} bytes")
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,71 @@
+//! Builds an opening book from finished games.
+//!
+//! Reads `sprt --games` JSON files and `game_gen` `generated_games.txt` files, replays the
+//! first `--max-ply` moves of every finished game and writes the weighted moves to a
+//! binary book that `Engine::load_book` and `Book::from_bytes` load.
+
+use apeiron::search::BookBuilder;
+use std::env;
+
+const DEFAULT_MAX_PLY: usize = 16;
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let mut output = None;
+    let mut inputs = Vec::new();
+    let mut max_ply = DEFAULT_MAX_PLY;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-o" | "--output" => output = args.next(),
+            "--max-ply" => {
+                max_ply = args
+                    .next()
+                    .and_then(|v| v.parse().ok())
+                    .expect("--max-ply takes a number");
+            }
+            _ => inputs.push(arg),
+        }
+    }
+
+    let Some(output) = output.filter(|_| !inputs.is_empty()) else {
+        println!(
+            "Usage: cargo run --release --bin book_gen --features book_gen -- -o <BOOK> [--max-ply N] <GAMES>..."
+        );
+        return;
+    };
+
+    let mut builder = BookBuilder::new(max_ply);
+    let mut skipped = 0usize;
+
+    for path in &inputs {
+        let content = std::fs::read_to_string(path)
+            .unwrap_or_else(|e| panic!("Failed to read '{}': {}", path, e));
+
+        // sprt saves a JSON array of ICN strings; game_gen writes one game per line.
+        let results: Vec<Result<(), String>> = if content.trim_start().starts_with('[') {
+            let games: Vec<String> = serde_json::from_str(&content)
+                .unwrap_or_else(|e| panic!("Failed to parse '{}': {}", path, e));
+            games.iter().map(|icn| builder.add_icn_game(icn)).collect()
+        } else {
+            content
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| builder.add_generated_game(line))
+                .collect()
+        };
+        let added = results.iter().filter(|r| r.is_ok()).count();
+        skipped += results.len() - added;
+        println!("{}: {} games", path, added);
+    }
+
+    let book = builder.build();
+    std::fs::write(&output, book.to_bytes())
+        .unwrap_or_else(|e| panic!("Failed to write '{}': {}", output, e));
+    println!(
+        "Wrote {} positions to {} ({} games skipped)",
+        book.len(),
+        output,
+        skipped
+    );
+}
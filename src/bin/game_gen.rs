@@ -15,24 +15,8 @@ const BATCH_SIZE: usize = 100;
 const MAX_MOVES: usize = 300;
 const ADJUDICATION_THRESHOLD: i32 = 2000; // cp
 
-const VARIANTS: [Variant; 16] = [
-    Variant::Classical,
-    Variant::ConfinedClassical,
-    Variant::ClassicalPlus,
-    Variant::CoaIP,
-    Variant::CoaIPHO,
-    Variant::CoaIPRO,
-    Variant::CoaIPNO,
-    Variant::Palace,
-    Variant::Pawndard,
-    Variant::Core,
-    Variant::Standarch,
-    Variant::SpaceClassic,
-    Variant::Space,
-    Variant::PawnHorde,
-    Variant::Knightline,
-    Variant::Obstocean,
-];
+/// Registry tag of the variants games are generated for.
+const VARIANT_TAG: &str = "selfplay";
 
 fn main() {
     // Set stack size for search recursion
@@ -52,6 +36,12 @@ fn main() {
         return;
     }
 
+    let variants = Variant::tagged(VARIANT_TAG);
+    if variants.is_empty() {
+        println!("No variants tagged {}. Exiting.", VARIANT_TAG);
+        return;
+    }
+
     let remaining_games = NUM_GAMES - current_count;
     println!(
         "Starting generation of {} games in batches of {}...",
@@ -79,9 +69,9 @@ fn main() {
 
     (0..remaining_games).into_par_iter().for_each(|_| {
         let mut rng = rand::rng();
-        let variant = VARIANTS[rng.random_range(0..VARIANTS.len())];
+        let variant = variants[rng.random_range(0..variants.len())];
         let mut state = GameState::new();
-        state.setup_position_from_icn(variant.starting_icn());
+        state.setup_position_from_icn(&variant.starting_icn());
 
         let mut game_moves = Vec::new();
         let mut ply = 0;
@@ -209,7 +199,12 @@ fn main() {
 
         if !game_moves.is_empty() {
             let mut buffer = games_buffer.lock().unwrap();
-            buffer.push(format!("{:?}|{}|{}", variant, result, game_moves.join("|")));
+            buffer.push(format!(
+                "{}|{}|{}",
+                variant.to_str(),
+                result,
+                game_moves.join("|")
+            ));
 
             if buffer.len() >= BATCH_SIZE {
                 let mut w = writer.lock().unwrap();
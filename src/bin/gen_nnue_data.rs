@@ -29,15 +29,8 @@ use std::time::Instant;
 // CONSTANTS
 // ============================================================================
 
-/// Variants that only use standard chess pieces (suitable for NNUE training)
-const NNUE_VARIANTS: &[Variant] = &[
-    Variant::Classical,
-    Variant::ClassicalPlus,
-    Variant::Core,
-    Variant::Pawndard,
-    Variant::SpaceClassic,
-    Variant::Knightline,
-];
+/// Registry tag of the variants that only use standard chess pieces (suitable for NNUE training)
+const NNUE_VARIANT_TAG: &str = "nnue-eligible";
 
 /// Target number of training samples
 const DEFAULT_TARGET_SAMPLES: u64 = 10_000_000;
@@ -699,6 +692,7 @@ struct PendingSample {
     threat_black: Vec<u32>,
     stm: PlayerColor,
     teacher_cp: i16,
+    icn: String,
 }
 
 /// Final sample record ready for output
@@ -710,6 +704,8 @@ struct SampleRecord {
     threat_black: Vec<u32>,
     teacher_cp: i16,
     result_wdl: i8,
+    /// The position, for the `.icn` file written next to the data (see `texel`).
+    icn: String,
 }
 
 impl SampleRecord {
@@ -745,54 +741,6 @@ impl SampleRecord {
 // GAME PLAYING
 // ============================================================================
 
-/// Determine game result: +1 = White wins, 0 = Draw, -1 = Black wins
-fn determine_game_result(gs: &GameState) -> i8 {
-    let moves = gs.get_legal_moves();
-
-    // Filter for strictly legal moves (must not leave king in check)
-    let mut has_moves = false;
-    for m in moves {
-        let mut test_gs = gs.clone();
-        test_gs.make_move(&m);
-        if !test_gs.is_move_illegal() {
-            has_moves = true;
-            break;
-        }
-    }
-
-    // Check for checkmate
-    if gs.is_in_check() && !has_moves {
-        // Side to move is checkmated
-        match gs.turn {
-            PlayerColor::White => -1, // Black wins
-            PlayerColor::Black => 1,  // White wins
-            _ => 0,
-        }
-    } else if !has_moves {
-        // Stalemate
-        0
-    } else if gs.is_repetition(0) || gs.is_fifty() {
-        // Draw by repetition or 50-move rule
-        0
-    } else {
-        // Game not over (shouldn't happen if called correctly)
-        0
-    }
-}
-
-/// Check if there is any strictly legal move
-fn has_any_legal_move(gs: &GameState) -> bool {
-    let moves = gs.get_legal_moves();
-    for m in moves {
-        let mut test_gs = gs.clone();
-        test_gs.make_move(&m);
-        if !test_gs.is_move_illegal() {
-            return true;
-        }
-    }
-    false
-}
-
 /// Get weighted random move (higher weight for longer distance moves)
 fn get_weighted_random_move(gs: &GameState, prng: &mut Prng) -> Option<Move> {
     let moves = gs.get_legal_moves();
@@ -864,7 +812,7 @@ fn play_game(
 
     for ply in 0..MAX_GAME_PLY {
         // Check for game end
-        if gs.is_repetition(0) || gs.is_fifty() || !has_any_legal_move(&gs) {
+        if gs.outcome().is_some() {
             break;
         }
 
@@ -876,7 +824,7 @@ fn play_game(
                 None => {
                     // Fallback to first legal move
                     let fallback = gs.get_legal_moves().into_iter().next();
-                    fallback.expect("has_any_legal_move was true but no legal move found")
+                    fallback.expect("game is not over but no legal move found")
                 }
             }
         } else {
@@ -888,14 +836,14 @@ fn play_game(
                         .get_legal_moves()
                         .into_iter()
                         .next()
-                        .expect("has_any_legal_move was true but no legal move found"),
+                        .expect("game is not over but no legal move found"),
                 }
             } else {
                 get_weighted_random_move(&gs, &mut prng).unwrap_or_else(|| {
                     gs.get_legal_moves()
                         .into_iter()
                         .next()
-                        .expect("has_any_legal_move was true but no legal move found")
+                        .expect("game is not over but no legal move found")
                 })
             }
         };
@@ -936,6 +884,7 @@ fn play_game(
                     threat_black: build_threat_list(&gs, PlayerColor::Black),
                     stm: gs.turn,
                     teacher_cp: clamped_cp as i16,
+                    icn: gs.to_icn(),
                 });
             }
         }
@@ -944,14 +893,16 @@ fn play_game(
         gs.make_move(&chosen_move);
     }
 
-    // Determine game result
+    // Determine game result: +1 = White wins, 0 = Draw, -1 = Black wins
     // If the game ended naturally (checkmate, stalemate, repetition, 50-move),
     // use the real result. If we hit MAX_GAME_PLY, adjudicate via teacher eval
     // to avoid injecting fake draws into the dataset.
-    let game_ended_naturally = gs.is_repetition(0) || gs.is_fifty() || !has_any_legal_move(&gs);
-
-    let result = if game_ended_naturally {
-        determine_game_result(&gs)
+    let result = if let Some(outcome) = gs.outcome() {
+        match outcome.winner {
+            Some(PlayerColor::White) => 1,
+            Some(PlayerColor::Black) => -1,
+            _ => 0,
+        }
     } else {
         // Ply-cap reached — adjudicate with a teacher search
         let (_, adj_cp, _) = get_best_move(&mut gs, teacher_depth, u128::MAX, true, false)
@@ -1017,6 +968,7 @@ fn play_game(
                 threat_black: s.threat_black,
                 teacher_cp: s.teacher_cp,
                 result_wdl,
+                icn: s.icn,
             })
         })
         .collect();
@@ -1128,7 +1080,12 @@ fn main() {
     println!("[gen_nnue_data] Teacher depth: {}", teacher_depth);
     println!("[gen_nnue_data] Sample rate: {}", sample_rate);
     println!("[gen_nnue_data] Output: {}", output_path);
-    println!("[gen_nnue_data] Variants: {}", NNUE_VARIANTS.len());
+    let nnue_variants = Variant::tagged(NNUE_VARIANT_TAG);
+    if nnue_variants.is_empty() {
+        println!("[gen_nnue_data] No variants tagged {}", NNUE_VARIANT_TAG);
+        return;
+    }
+    println!("[gen_nnue_data] Variants: {}", nnue_variants.len());
 
     // Open or create output file
     let mut file = std::fs::OpenOptions::new()
@@ -1175,7 +1132,30 @@ fn main() {
         );
     }
 
-    let writer = Mutex::new(BufWriter::new(file));
+    // The records only hold features; the positions go to a line-per-record file next
+    // to them so the eval tuner can use the same data.
+    let icn_path = std::path::Path::new(&output_path).with_extension("icn");
+    let icn_file = if file_len == 0 {
+        File::create(&icn_path)
+    } else {
+        // Appending to a position file that is out of step with the records would pair
+        // every later position with the wrong record.
+        let positions = std::fs::read_to_string(&icn_path)
+            .map(|icns| icns.lines().count() as u64)
+            .unwrap_or(0);
+        if positions != initial_samples {
+            panic!(
+                "{} has {} positions for {} samples; delete both files to start over",
+                icn_path.display(),
+                positions,
+                initial_samples
+            );
+        }
+        std::fs::OpenOptions::new().append(true).open(&icn_path)
+    }
+    .expect("Failed to open position file");
+
+    let writer = Mutex::new((BufWriter::new(file), BufWriter::new(icn_file)));
     let samples_written = AtomicU64::new(initial_samples);
     let games_done = AtomicU64::new(0);
     let start_time = Instant::now();
@@ -1199,7 +1179,7 @@ fn main() {
             }
 
             // Select variant round-robin
-            let variant = NNUE_VARIANTS[(game_id % NNUE_VARIANTS.len() as u64) as usize];
+            let variant = nnue_variants[(game_id % nnue_variants.len() as u64) as usize];
 
             // Play game
             let (_games_run, samples) = play_game(
@@ -1213,7 +1193,7 @@ fn main() {
 
             // Write samples
             if !samples.is_empty() {
-                let mut w = writer.lock().unwrap();
+                let (w, icns) = &mut *writer.lock().unwrap();
 
                 // Re-check target within lock to avoid overshooting too much
                 let current = samples_written.load(Ordering::Relaxed);
@@ -1223,13 +1203,17 @@ fn main() {
                 }
 
                 for sample in &samples {
-                    sample.write_to(&mut *w).expect("Failed to write sample");
+                    sample.write_to(w).expect("Failed to write sample");
+                    writeln!(icns, "{}", sample.icn).expect("Failed to write position");
                 }
                 let total = samples_written.fetch_add(samples.len() as u64, Ordering::SeqCst)
                     + samples.len() as u64;
 
-                // Crash-safe header update: patch the count every write
-                if let Ok(pos) = w.stream_position() {
+                // Crash-safe header update: patch the count every write, once the
+                // positions it counts are on disk
+                if icns.flush().is_ok()
+                    && let Ok(pos) = w.stream_position()
+                {
                     let _ = w.seek(SeekFrom::Start(12));
                     let _ = w.write_all(&total.to_le_bytes());
                     let _ = w.seek(SeekFrom::Start(pos));
@@ -1263,8 +1247,9 @@ fn main() {
 
     // Flush and close writer before patching header
     {
-        let mut w = writer.lock().unwrap();
+        let (w, icns) = &mut *writer.lock().unwrap();
         w.flush().unwrap();
+        icns.flush().unwrap();
     }
     drop(writer);
 
@@ -1285,6 +1270,7 @@ fn main() {
     println!("[gen_nnue_data] Samples written: {}", total_samples);
     println!("[gen_nnue_data] Time: {:.1}s", elapsed.as_secs_f64());
     println!("[gen_nnue_data] Output: {}", output_path);
+    println!("[gen_nnue_data] Positions: {}", icn_path.display());
 }
 
 fn verify_data_file(path: &str) {
@@ -1045,20 +1045,18 @@ fn main() {
         min_y = min_y.saturating_sub(buffer);
         max_y = max_y.saturating_add(buffer);
 
-        let (cur_min_x, cur_max_x, cur_min_y, cur_max_y) =
-            apeiron::moves::get_coord_bounds();
-
-        let final_min_x = min_x.max(cur_min_x);
-        let final_max_x = max_x.min(cur_max_x);
-        let final_min_y = min_y.max(cur_min_y);
-        let final_max_y = max_y.min(cur_max_y);
-
-        apeiron::moves::set_world_bounds(
-            final_min_x,
-            final_max_x,
-            final_min_y,
-            final_max_y,
-        );
+        let cur = &mut game.game_rules.world_bounds;
+        let region = cur.region.take();
+
+        *cur = apeiron::moves::WorldBounds {
+            region,
+            ..apeiron::moves::WorldBounds::new(
+                min_x.max(cur.min_x),
+                max_x.min(cur.max_x),
+                min_y.max(cur.min_y),
+                max_y.min(cur.max_y),
+            )
+        };
     }
 
     println!(
@@ -155,28 +155,19 @@ fn main() {
                     if let Some(variant) = actual_variant {
                         let move_strs = &parts[2..];
                         let mut state = GameState::new();
-                        state.setup_position_from_icn(variant.starting_icn());
+                        state.setup_position_from_icn(&variant.starting_icn());
 
-                        let starting_icn = variant.starting_icn();
-                        let mut current_icn = starting_icn.to_string();
-
-                        for (i, m_str) in move_strs.iter().enumerate() {
+                        for m_str in move_strs {
                             if !is_suitable_for_puzzle(&state) {
                                 if let Some(mv) = parse_compact_move_fast(m_str, &state) {
                                     state.make_move(&mv);
-                                    if i == 0 {
-                                        current_icn.push(' ');
-                                    } else {
-                                        current_icn.push('|');
-                                    }
-                                    current_icn.push_str(m_str);
                                 }
                                 continue;
                             }
 
+                            let current_icn = state.to_icn();
                             if seen_positions.lock().unwrap().insert(current_icn.clone())
-                                && let Some(puzzle) =
-                                    analyze_position(&mut state, current_icn.clone())
+                                && let Some(puzzle) = analyze_position(&mut state, current_icn)
                             {
                                 puzzles_generated.fetch_add(1, Ordering::SeqCst);
                                 let _ = tx.send(puzzle);
@@ -184,12 +175,6 @@ fn main() {
 
                             if let Some(mv) = parse_compact_move_fast(m_str, &state) {
                                 state.make_move(&mv);
-                                if i == 0 {
-                                    current_icn.push(' ');
-                                } else {
-                                    current_icn.push('|');
-                                }
-                                current_icn.push_str(m_str);
                             } else {
                                 break;
                             }
@@ -1,5 +1,5 @@
-use std::io::Write;
-use std::process::{Command, Stdio};
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
 use std::time::Instant;
 
 use clap::{Parser, Subcommand};
@@ -7,9 +7,9 @@ use apeiron::Engine;
 use apeiron::Variant;
 use apeiron::board::{Coordinate, PieceType, PlayerColor};
 use apeiron::game::GameState;
+use apeiron::search::SearchLimits;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
-use std::sync::{Mutex, OnceLock};
 use std::sync::atomic::{AtomicBool, Ordering};
 
 // Commit SHA and date baked in at compile time by build.rs.
@@ -68,7 +68,7 @@ enum Commands {
         #[arg(long, default_value_t = 250)]
         min_games: usize,
 
-        /// Variants to test (comma-separated list)
+        /// Variants to test: comma-separated names, a preset or a registry variant tag
         #[arg(
             long,
             default_value = "Classical,Confined_Classical,Classical_Plus,Core,CoaIP,CoaIP_HO,CoaIP_RO,CoaIP_NO,Palace,Pawndard,Standarch,Space_Classic,Space,Knightline,Scattered_Leapers"
@@ -124,6 +124,10 @@ enum Commands {
     /// Used internally by the run manager to identify which snapshot the old binary was built from.
     CommitInfo,
 
+    /// Long-lived engine process driven by a line protocol on stdin
+    /// (`newgame`, `position icn ...`, `go ...`, `stop`, `isready`, `quit`).
+    Engine,
+
     /// Internal interface for subprocess move generation
     Search {
         /// ICN string of the position
@@ -158,6 +162,26 @@ enum Commands {
         #[arg(long)]
         fixed_time: Option<u32>,
 
+        /// Moves until the next time control
+        #[arg(long)]
+        moves_to_go: Option<u32>,
+
+        /// Per-move allowance for communication latency in ms
+        #[arg(long)]
+        move_overhead: Option<u64>,
+
+        /// Hard node budget for the search
+        #[arg(long)]
+        nodes: Option<u64>,
+
+        /// Stop once a mate in at most this many moves is found
+        #[arg(long)]
+        mate: Option<u32>,
+
+        /// Only search this root move (site token); repeatable
+        #[arg(long = "search-move", allow_hyphen_values = true)]
+        search_moves: Vec<String>,
+
         /// Search noise amplitude
         #[arg(long)]
         noise_amp: Option<i32>,
@@ -298,6 +322,9 @@ struct Config {
     adjudication_threshold: i32,
     new_bin: String,
     old_bin: String,
+    /// Whether each binary speaks the `engine` protocol; older ones get a `search` process per move.
+    new_persistent: bool,
+    old_persistent: bool,
     max_moves: usize,
     search_noise: i32,
     old_strength: u32,
@@ -328,15 +355,6 @@ struct GameOutcome {
     new_engine_timed_out: bool,
 }
 
-#[derive(Clone, Copy)]
-enum TerminalState {
-    Checkmate { white_won: bool },
-    AllPiecesCaptured { white_won: bool },
-    AllRoyalsCaptured { white_won: bool },
-    RoyalCapture { white_won: bool },
-    Draw(&'static str),
-}
-
 fn elo_to_score(elo_diff: f64) -> f64 {
     1.0 / (1.0 + 10.0f64.powf(-elo_diff / 400.0))
 }
@@ -533,19 +551,6 @@ fn parse_bestmove_to_icn(bestmove_str: &str, turn: PlayerColor) -> Option<String
     Some(result)
 }
 
-fn has_any_fully_legal_move(game: &GameState) -> bool {
-    let moves = game.get_legal_moves();
-    for m in moves {
-        let mut game_copy = game.clone();
-        game_copy.make_move(&m);
-        let legal = !game_copy.is_move_illegal();
-        if legal {
-            return true;
-        }
-    }
-    false
-}
-
 fn make_position_key(game: &GameState) -> String {
     // Build piece list sorted by position
     let mut pieces: Vec<String> = game
@@ -665,95 +670,434 @@ fn make_position_key(game: &GameState) -> String {
     )
 }
 
-fn detect_terminal_state(game: &GameState) -> Option<TerminalState> {
-    use apeiron::game::WinCondition;
+/// Per-move search limits, shared by the one-shot `search` subcommand and the `engine`
+/// protocol's `go` command.
+#[derive(Clone, Debug, Default)]
+struct GoLimits {
+    wtime: u64,
+    btime: u64,
+    winc: u64,
+    binc: u64,
+    max_depth: Option<usize>,
+    fixed_time: Option<u32>,
+    moves_to_go: Option<u32>,
+    move_overhead: Option<u64>,
+    nodes: Option<u64>,
+    mate: Option<u32>,
+    search_moves: Vec<String>,
+    noise_amp: Option<i32>,
+    seed: Option<u64>,
+    strength_level: Option<u32>,
+}
 
-    // Determine what the opponent must do to beat the current player.
-    let opp_wc = match game.turn {
-        PlayerColor::White => game.game_rules.black_win_condition,
-        PlayerColor::Black => game.game_rules.white_win_condition,
-        PlayerColor::Neutral => return None,
-    };
+impl GoLimits {
+    /// Parses the arguments of a `go` line, e.g. `wtime 9000 btime 9000 winc 100 binc 100 seed 7`.
+    /// `searchmove <token>` may be repeated. Unknown keys and malformed values are ignored.
+    fn parse(args: &str) -> GoLimits {
+        let mut limits = GoLimits::default();
+        let tokens: Vec<&str> = args.split_whitespace().collect();
+        for pair in tokens.chunks(2) {
+            let [key, value] = pair else { break };
+            match *key {
+                "wtime" => limits.wtime = value.parse().unwrap_or(0),
+                "btime" => limits.btime = value.parse().unwrap_or(0),
+                "winc" => limits.winc = value.parse().unwrap_or(0),
+                "binc" => limits.binc = value.parse().unwrap_or(0),
+                "depth" => limits.max_depth = value.parse().ok(),
+                "movetime" => limits.fixed_time = value.parse().ok(),
+                "movestogo" => limits.moves_to_go = value.parse().ok(),
+                "overhead" => limits.move_overhead = value.parse().ok(),
+                "nodes" => limits.nodes = value.parse().ok(),
+                "mate" => limits.mate = value.parse().ok(),
+                "searchmove" => limits.search_moves.push(value.to_string()),
+                "noise" => limits.noise_amp = value.parse().ok(),
+                "seed" => limits.seed = value.parse().ok(),
+                "strength" => limits.strength_level = value.parse().ok(),
+                _ => {}
+            }
+        }
+        limits
+    }
 
-    // ── AllPiecesCaptured ──────────────────────────────────────────────────────
-    // Game over when the current player has zero pieces AND zero royals.
-    // This MUST be checked before has_lost_by_royal_capture() because capturing
-    // the king (the last piece) sets royals=0, which causes that function to return
-    // true and fall into the wrong `_ => None` branch, silently skipping termination
-    // and also bypassing the fifty-move rule check below.
-    if opp_wc == WinCondition::AllPiecesCaptured {
-        let has_royals = match game.turn {
-            PlayerColor::White => !game.white_royals.is_empty(),
-            PlayerColor::Black => !game.black_royals.is_empty(),
-            _ => false,
-        };
-        if !game.has_pieces(game.turn) && !has_royals {
-            return Some(TerminalState::AllPiecesCaptured {
-                white_won: game.turn == PlayerColor::Black,
-            });
+    /// Formats these limits as the arguments of a `go` line (inverse of [`GoLimits::parse`]).
+    fn to_go_args(&self) -> String {
+        let mut args = format!(
+            "wtime {} btime {} winc {} binc {}",
+            self.wtime, self.btime, self.winc, self.binc
+        );
+        if let Some(d) = self.max_depth {
+            args.push_str(&format!(" depth {}", d));
+        }
+        if let Some(ft) = self.fixed_time {
+            args.push_str(&format!(" movetime {}", ft));
+        }
+        if let Some(mtg) = self.moves_to_go {
+            args.push_str(&format!(" movestogo {}", mtg));
+        }
+        if let Some(ms) = self.move_overhead {
+            args.push_str(&format!(" overhead {}", ms));
+        }
+        if let Some(n) = self.nodes {
+            args.push_str(&format!(" nodes {}", n));
         }
-        // Not yet over (opponent still has pieces/royals left to capture).
-        // Fall through so the fifty-move rule and other draw checks still run.
-
-    // ── RoyalCapture / AllRoyalsCaptured ──────────────────────────────────────
-    // Only run for win conditions that actually target royals.
-    } else if game.has_lost_by_royal_capture() {
-        return match opp_wc {
-            WinCondition::RoyalCapture => Some(TerminalState::RoyalCapture {
-                white_won: game.turn == PlayerColor::Black,
-            }),
-            WinCondition::AllRoyalsCaptured => Some(TerminalState::AllRoyalsCaptured {
-                white_won: game.turn == PlayerColor::Black,
-            }),
-            // Checkmate: losing royals is not a direct win condition here;
-            // fall through to legal-move detection below.
-            _ => return None,
+        if let Some(m) = self.mate {
+            args.push_str(&format!(" mate {}", m));
+        }
+        for token in &self.search_moves {
+            args.push_str(&format!(" searchmove {}", token));
+        }
+        if let Some(n) = self.noise_amp {
+            args.push_str(&format!(" noise {}", n));
+        }
+        if let Some(s) = self.seed {
+            args.push_str(&format!(" seed {}", s));
+        }
+        if let Some(l) = self.strength_level {
+            args.push_str(&format!(" strength {}", l));
+        }
+        args
+    }
+
+    /// Appends these limits to a one-shot `search` subprocess command line.
+    fn apply_to_command(&self, cmd: &mut Command) {
+        cmd.arg("--wtime")
+            .arg(self.wtime.to_string())
+            .arg("--btime")
+            .arg(self.btime.to_string())
+            .arg("--winc")
+            .arg(self.winc.to_string())
+            .arg("--binc")
+            .arg(self.binc.to_string());
+        if let Some(d) = self.max_depth {
+            cmd.arg("--max-depth").arg(d.to_string());
+        }
+        if let Some(ft) = self.fixed_time {
+            cmd.arg("--fixed-time").arg(ft.to_string());
+        }
+        if let Some(mtg) = self.moves_to_go {
+            cmd.arg("--moves-to-go").arg(mtg.to_string());
+        }
+        if let Some(ms) = self.move_overhead {
+            cmd.arg("--move-overhead").arg(ms.to_string());
+        }
+        if let Some(n) = self.nodes {
+            cmd.arg("--nodes").arg(n.to_string());
+        }
+        if let Some(m) = self.mate {
+            cmd.arg("--mate").arg(m.to_string());
+        }
+        for token in &self.search_moves {
+            cmd.arg("--search-move").arg(token);
+        }
+        if let Some(n) = self.noise_amp {
+            cmd.arg("--noise-amp").arg(n.to_string());
+        }
+        if let Some(s) = self.seed {
+            cmd.arg("--seed").arg(s.to_string());
+        }
+        if let Some(l) = self.strength_level {
+            cmd.arg("--strength-level").arg(l.to_string());
+        }
+    }
+}
+
+/// Sets `icn` on `engine` and searches it on the engine's own search state (so the TT and
+/// histories carry over between calls on the same engine). Returns the `bestmove` payload
+/// plus an `info` line, if any.
+fn search_position(
+    engine: &mut Engine,
+    icn: &str,
+    variant: Variant,
+    limits: &GoLimits,
+) -> (String, Option<String>) {
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        if let Err(e) = engine.set_position_native(icn) {
+            eprintln!("invalid icn {}: {}", icn, e);
+            return ("none".to_string(), None);
+        }
+        engine.set_strength_level(limits.strength_level);
+        engine.set_clock(limits.wtime, limits.btime, limits.winc, limits.binc);
+        engine.set_moves_to_go(limits.moves_to_go);
+        if let Some(ms) = limits.move_overhead {
+            engine.set_move_overhead(ms);
+        }
+        engine.game_mut().variant = Some(variant);
+        let mut search_moves = Vec::new();
+        for token in &limits.search_moves {
+            match engine.game_mut().find_site_move(token) {
+                Some(m) => search_moves.push(m),
+                None => eprintln!("ignoring illegal search move: {}", token),
+            }
+        }
+        engine.set_search_limits_native(SearchLimits {
+            nodes: limits.nodes,
+            mate: limits.mate,
+            search_moves,
+        });
+        if let Some(outcome) = engine.game_mut().outcome() {
+            match outcome.winner {
+                Some(winner) => eprintln!(
+                    "terminal {} winner {}",
+                    outcome.reason.as_str(),
+                    if winner == PlayerColor::White {
+                        "white"
+                    } else {
+                        "black"
+                    }
+                ),
+                None => eprintln!("terminal {}", outcome.reason.as_str()),
+            }
+            return ("none".to_string(), None);
+        }
+        let search_res = engine.search_native(
+            limits.fixed_time.unwrap_or(0),
+            limits.max_depth,
+            true,
+            limits.noise_amp,
+            limits.seed,
+        );
+        if let Some((m, score, stats)) = search_res {
+            let pv = engine.current_pv_native(limits.max_depth.unwrap_or(50));
+            let info = if pv.is_empty() {
+                format!("info score {} nodes {}", score, stats.nodes)
+            } else {
+                format!("info score {} nodes {} pv {}", score, stats.nodes, pv)
+            };
+            (move_to_string(&m), Some(info))
+        } else {
+            eprintln!("search returned None for icn: {}", icn);
+            ("none".to_string(), None)
+        }
+    }));
+    result.unwrap_or_else(|e| {
+        let msg = if let Some(s) = e.downcast_ref::<String>() {
+            s.clone()
+        } else if let Some(s) = e.downcast_ref::<&str>() {
+            s.to_string()
+        } else {
+            "unknown panic".to_string()
         };
+        eprintln!("PANIC in search subprocess: {}", msg);
+        // The search may have unwound mid-update; don't carry its tables into the next move.
+        engine.reset_search();
+        ("none".to_string(), None)
+    })
+}
+
+/// Long-lived engine loop behind the `engine` subcommand.
+///
+/// Protocol (one command per stdin line, replies on stdout):
+/// - `isready` → `readyok`
+/// - `newgame [variant]` clears the TT and histories and sets the variant for later searches
+/// - `position icn <ICN>` sets the position (starting setup plus moves, as one ICN string)
+/// - `go <limits>` searches it and replies `info score S nodes N [pv ...]` then `bestmove ...`
+/// - `stop` ends the running search early
+/// - `quit` exits
+fn run_engine_loop() {
+    let (tx, rx) = std::sync::mpsc::channel::<String>();
+
+    // One engine for the whole process, so its tables stay warm from move to move.
+    let mut engine = Engine::new_native(&Variant::Classical.starting_icn());
+    let search_ctx = std::sync::Arc::clone(
+        engine
+            .search_instance()
+            .expect("native engines own their search")
+            .context(),
+    );
+
+    // `stop` has to get through while the main thread is searching, so a reader thread
+    // handles it directly and forwards everything else.
+    std::thread::spawn(move || {
+        for line in std::io::stdin().lock().lines() {
+            let Ok(line) = line else { break };
+            if line.trim() == "stop" {
+                search_ctx.request_stop();
+            } else if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut variant = Variant::Classical;
+    let mut icn: Option<String> = None;
+    let mut stdout = std::io::stdout();
+
+    for line in rx {
+        let line = line.trim();
+        let (command, args) = line.split_once(' ').unwrap_or((line, ""));
+        match command {
+            "" => continue,
+            "isready" => println!("readyok"),
+            "newgame" => {
+                engine.reset_search();
+                if !args.trim().is_empty() {
+                    variant = Variant::parse(args.trim());
+                }
+                icn = None;
+            }
+            "position" => match args.trim().strip_prefix("icn ") {
+                Some(rest) => icn = Some(rest.trim().to_string()),
+                None => eprintln!("unsupported position command: {}", line),
+            },
+            "go" => {
+                let (bestmove, info) = match icn.as_deref() {
+                    Some(icn) => {
+                        search_position(&mut engine, icn, variant, &GoLimits::parse(args))
+                    }
+                    None => {
+                        eprintln!("go without a position");
+                        ("none".to_string(), None)
+                    }
+                };
+                if let Some(info) = info {
+                    println!("{}", info);
+                }
+                println!("bestmove {}", bestmove);
+            }
+            "quit" => break,
+            _ => eprintln!("unknown command: {}", line),
+        }
+        let _ = stdout.flush();
     }
+}
 
-    // ── No legal moves → checkmate / stalemate / piece-loss ───────────────────
-    let in_check = game.is_in_check();
-    let has_legal_move = has_any_fully_legal_move(game);
-    if !has_legal_move {
-        let lost_by_mate = in_check && game.must_escape_check();
-        let lost_by_piece_capture = !game.has_pieces(game.turn);
+/// A running `engine` subprocess. Kept for a whole game pair so the engine's tables stay
+/// warm between moves and the clock only measures the search itself.
+struct EngineProcess {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
 
-        if lost_by_mate {
-            return Some(TerminalState::Checkmate {
-                white_won: game.turn == PlayerColor::Black,
-            });
+impl EngineProcess {
+    /// Starts `bin engine` and waits for `readyok`. Returns None if the binary can't be
+    /// started or predates the engine protocol.
+    fn spawn(bin: &str, verbose: bool) -> Option<EngineProcess> {
+        let mut child = Command::new(bin)
+            .env("RAYON_NUM_THREADS", "1")
+            .arg("engine")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(if verbose {
+                Stdio::inherit()
+            } else {
+                Stdio::null()
+            })
+            .spawn()
+            .ok()?;
+        let stdin = child.stdin.take()?;
+        let stdout = BufReader::new(child.stdout.take()?);
+        let mut engine = EngineProcess {
+            child,
+            stdin,
+            stdout,
+        };
+        if engine.send("isready") && engine.read_until("readyok", false).is_some() {
+            Some(engine)
+        } else {
+            None
         }
+    }
 
-        if lost_by_piece_capture {
-            return Some(TerminalState::AllPiecesCaptured {
-                white_won: game.turn == PlayerColor::Black,
-            });
+    fn send(&mut self, line: &str) -> bool {
+        writeln!(self.stdin, "{}", line)
+            .and_then(|_| self.stdin.flush())
+            .is_ok()
+    }
+
+    /// Reads stdout until a line starting with `prefix`. Returns that line and the score
+    /// from the last `info` line before it; None if the process exited first.
+    fn read_until(&mut self, prefix: &str, verbose: bool) -> Option<(String, Option<f64>)> {
+        let mut score = None;
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if self.stdout.read_line(&mut line).ok()? == 0 {
+                return None;
+            }
+            let trimmed = line.trim();
+            if trimmed.starts_with(prefix) {
+                return Some((trimmed.to_string(), score));
+            }
+            if trimmed.starts_with("info") {
+                if verbose {
+                    eprintln!("{}", trimmed);
+                }
+                score = parse_info_score(trimmed).or(score);
+            }
         }
+    }
 
-        return Some(TerminalState::Draw("stalemate"));
+    /// Tells the engine a new game of `variant` is starting.
+    fn new_game(&mut self, variant: Variant) -> bool {
+        self.send(&format!("newgame {}", variant.to_str()))
+            && self.send("isready")
+            && self.read_until("readyok", false).is_some()
     }
 
-    // ── Draw conditions ────────────────────────────────────────────────────────
-    if apeiron::evaluation::insufficient_material::evaluate_insufficient_material_game_handler(game) {
-        return Some(TerminalState::Draw("insufficient_material"));
+    /// Searches `icn` and returns the `bestmove` payload and the reported score.
+    fn go(&mut self, icn: &str, limits: &GoLimits, verbose: bool) -> Option<(String, Option<f64>)> {
+        if !self.send(&format!("position icn {}", icn))
+            || !self.send(&format!("go {}", limits.to_go_args()))
+        {
+            return None;
+        }
+        let (line, score) = self.read_until("bestmove", verbose)?;
+        Some((
+            line.trim_start_matches("bestmove").trim().to_string(),
+            score,
+        ))
     }
+}
 
-    if game.is_fifty() {
-        return Some(TerminalState::Draw("fifty-move rule"));
+impl Drop for EngineProcess {
+    fn drop(&mut self) {
+        let _ = self.send("stop");
+        let _ = self.send("quit");
+        let _ = self.child.wait();
     }
+}
 
-    None
+/// Makes `slot` hold a live engine process ready for a new game, respawning it if it
+/// exited. Leaves `slot` empty when `bin` doesn't speak the engine protocol.
+fn start_engine_game(
+    slot: &mut Option<EngineProcess>,
+    bin: &str,
+    persistent: bool,
+    verbose: bool,
+    variant: Variant,
+) {
+    if !persistent {
+        return;
+    }
+    if slot.as_mut().is_some_and(|engine| engine.new_game(variant)) {
+        return;
+    }
+    *slot = EngineProcess::spawn(bin, verbose);
+    if let Some(engine) = slot.as_mut()
+        && !engine.new_game(variant)
+    {
+        *slot = None;
+    }
 }
 
-fn with_variant_bounds<T>(variant: Variant, f: impl FnOnce() -> T) -> T {
-    static WORLD_BOUNDS_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
-    let _guard = WORLD_BOUNDS_LOCK
-        .get_or_init(|| Mutex::new(()))
-        .lock()
-        .expect("world bounds lock poisoned");
-    let bounds = variant.get_default_bounds();
-    apeiron::moves::set_world_bounds(bounds.0, bounds.1, bounds.2, bounds.3);
-    f()
+/// Score from an `info ... score S ...` line.
+fn parse_info_score(line: &str) -> Option<f64> {
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    parts
+        .windows(2)
+        .filter(|w| w[0] == "score")
+        .filter_map(|w| w[1].parse::<f64>().ok())
+        .next_back()
+}
+
+/// Maps a finished game onto the new engine's result.
+fn outcome_result(outcome: &apeiron::game::GameOutcome, new_plays_white: bool) -> GameResult {
+    match outcome.winner {
+        None => GameResult::Draw,
+        Some(winner) if (winner == PlayerColor::White) == new_plays_white => GameResult::Win,
+        Some(_) => GameResult::Loss,
+    }
 }
 
 fn play_game(
@@ -762,15 +1106,29 @@ fn play_game(
     new_plays_white: bool,
     game_idx: usize,
     seeds: Vec<u64>,
+    new_engine: &mut Option<EngineProcess>,
+    old_engine: &mut Option<EngineProcess>,
 ) -> GameOutcome {
-    let mut game = with_variant_bounds(variant, || {
-        let mut game = GameState::new();
-        game.setup_position_from_icn(variant.starting_icn());
-        game.variant = Some(variant);
-        game
-    });
+    start_engine_game(
+        new_engine,
+        &config.new_bin,
+        config.new_persistent,
+        config.verbose,
+        variant,
+    );
+    start_engine_game(
+        old_engine,
+        &config.old_bin,
+        config.old_persistent,
+        config.verbose,
+        variant,
+    );
+
+    let mut game = GameState::new();
+    game.setup_position_from_icn(&variant.starting_icn());
+    game.variant = Some(variant);
 
-    let starting_board_setup = get_board_setup_icn(&game);
+    let starting_board_setup = game.to_icn();
 
     let mut white_clock = config.tc_base_ms;
     let mut black_clock = config.tc_base_ms;
@@ -783,9 +1141,9 @@ fn play_game(
 
     let get_eval = |g: &GameState| {
         #[cfg(feature = "nnue")]
-        return with_variant_bounds(variant, || apeiron::evaluation::evaluate(g, None));
+        return apeiron::evaluation::evaluate(g, None);
         #[cfg(not(feature = "nnue"))]
-        return with_variant_bounds(variant, || apeiron::evaluation::evaluate(g));
+        return apeiron::evaluation::evaluate(g);
     };
 
     /// Helper to create an outcome return value
@@ -803,7 +1161,7 @@ fn play_game(
                     $result_str,
                     &starting_board_setup,
                 ),
-                variant_name: variant.to_str().to_string(),
+                variant_name: variant.to_str(),
                 game_idx,
                 termination_reason: $reason.to_string(),
                 new_engine_timed_out: false,
@@ -823,7 +1181,7 @@ fn play_game(
                 return GameOutcome {
                     result: GameResult::Draw, // Dummy result
                     icn: String::new(),
-                    variant_name: variant.to_str().to_string(),
+                    variant_name: variant.to_str(),
                     game_idx,
                     termination_reason: "interrupted".to_string(),
                     new_engine_timed_out: false,
@@ -840,60 +1198,9 @@ fn play_game(
         }
 
         // Terminal state checks always run before adjudication or engine search.
-        if let Some(terminal) = with_variant_bounds(variant, || detect_terminal_state(&game)) {
-            match terminal {
-                TerminalState::Checkmate { white_won } => {
-                    let result = if white_won == new_plays_white {
-                        GameResult::Win
-                    } else {
-                        GameResult::Loss
-                    };
-                    return game_outcome!(
-                        result,
-                        "checkmate",
-                        if white_won { "1-0" } else { "0-1" }
-                    );
-                }
-                TerminalState::AllPiecesCaptured { white_won } => {
-                    let result = if white_won == new_plays_white {
-                        GameResult::Win
-                    } else {
-                        GameResult::Loss
-                    };
-                    return game_outcome!(
-                        result,
-                        "allpiecescaptured",
-                        if white_won { "1-0" } else { "0-1" }
-                    );
-                }
-                TerminalState::AllRoyalsCaptured { white_won } => {
-                    let result = if white_won == new_plays_white {
-                        GameResult::Win
-                    } else {
-                        GameResult::Loss
-                    };
-                    return game_outcome!(
-                        result,
-                        "allroyalscaptured",
-                        if white_won { "1-0" } else { "0-1" }
-                    );
-                }
-                TerminalState::RoyalCapture { white_won } => {
-                    let result = if white_won == new_plays_white {
-                        GameResult::Win
-                    } else {
-                        GameResult::Loss
-                    };
-                    return game_outcome!(
-                        result,
-                        "royalcapture",
-                        if white_won { "1-0" } else { "0-1" }
-                    );
-                }
-                TerminalState::Draw(reason) => {
-                    return game_outcome!(GameResult::Draw, reason, "1/2-1/2");
-                }
-            }
+        if let Some(outcome) = game.outcome() {
+            let result = outcome_result(&outcome, new_plays_white);
+            return game_outcome!(result, outcome.reason.as_str(), outcome.result_str());
         }
 
         // Material adjudication (after terminal checks, only if both engines agree)
@@ -952,87 +1259,99 @@ fn play_game(
             format!("{} {}", starting_board_setup, move_history_clean.join("|"))
         };
 
-        let mut cmd = Command::new(bin);
-        cmd.env("RAYON_NUM_THREADS", "1")
-            .arg("search")
-            .arg("--icn")
-            .arg(&subprocess_icn)
-            .arg("--wtime")
-            .arg(white_clock.to_string())
-            .arg("--btime")
-            .arg(black_clock.to_string())
-            .arg("--winc")
-            .arg(config.tc_inc_ms.to_string())
-            .arg("--binc")
-            .arg(config.tc_inc_ms.to_string())
-            .arg("--variant")
-            .arg(variant.to_str());
+        let limits = GoLimits {
+            wtime: white_clock,
+            btime: black_clock,
+            winc: config.tc_inc_ms,
+            binc: config.tc_inc_ms,
+            max_depth: config.tc_max_depth,
+            fixed_time: config.tc_fixed_ms,
+            moves_to_go: None,
+            move_overhead: None,
+            nodes: None,
+            mate: None,
+            search_moves: Vec::new(),
+            noise_amp: (ply < 8).then_some(config.search_noise),
+            seed: Some(seeds[ply]),
+            strength_level: (!is_new_turn && config.old_strength < 3)
+                .then_some(config.old_strength),
+        };
 
-        if let Some(d) = config.tc_max_depth {
-            cmd.arg("--max-depth").arg(d.to_string());
-        }
-        if let Some(ft) = config.tc_fixed_ms {
-            cmd.arg("--fixed-time").arg(ft.to_string());
-        }
+        let engine = if is_new_turn {
+            &mut *new_engine
+        } else {
+            &mut *old_engine
+        };
 
-        if ply < 8 {
-            cmd.arg("--noise-amp").arg(config.search_noise.to_string());
-        }
+        let start_time = Instant::now();
+        let (bestmove_icn, score, elapsed) = if let Some(engine) = engine.as_mut() {
+            let reply = engine.go(&subprocess_icn, &limits, config.verbose);
+            let elapsed = start_time.elapsed().as_millis() as u64;
+            if reply.is_none() && !USER_STOP.load(Ordering::SeqCst) {
+                eprintln!("[Game {}] Engine process {} exited mid-game", game_idx, bin);
+            }
+            let (bestmove, score) = reply.unzip();
+            (
+                bestmove.and_then(|m| parse_bestmove_to_icn(&m, game.turn)),
+                score.flatten(),
+                elapsed,
+            )
+        } else {
+            let mut cmd = Command::new(bin);
+            cmd.env("RAYON_NUM_THREADS", "1")
+                .arg("search")
+                .arg("--icn")
+                .arg(&subprocess_icn)
+                .arg("--variant")
+                .arg(variant.to_str());
+            limits.apply_to_command(&mut cmd);
+
+            if config.verbose {
+                cmd.stderr(Stdio::inherit());
+            }
 
-        let seed_val = seeds[ply];
-        cmd.arg("--seed").arg(seed_val.to_string());
+            let output = cmd
+                .output()
+                .unwrap_or_else(|e| panic!("Failed to execute engine binary {}: {}", bin, e));
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let stderr = String::from_utf8_lossy(&output.stderr);
 
-        if !is_new_turn && config.old_strength < 3 {
-            cmd.arg("--strength-level")
-                .arg(config.old_strength.to_string());
-        }
+            // Check for subprocess crash
+            if !(output.status.success()
+                || USER_STOP.load(Ordering::SeqCst) && is_ctrl_c_exit_code(output.status.code()))
+            {
+                eprintln!(
+                    "[Game {}] Subprocess crashed! exit={:?}\n  stderr={}",
+                    game_idx,
+                    output.status.code(),
+                    stderr.trim()
+                );
+            }
 
-        if config.verbose {
-            cmd.stderr(Stdio::inherit());
-        }
+            // Parse bestmove into ICN move format
+            let bestmove_icn =
+                if let Some(line) = stdout.lines().find(|l| l.starts_with("bestmove")) {
+                    let move_str = line.trim_start_matches("bestmove").trim();
+                    parse_bestmove_to_icn(move_str, game.turn)
+                } else {
+                    None
+                };
 
-        let start_time = Instant::now();
-        let output = cmd
-            .output()
-            .unwrap_or_else(|e| panic!("Failed to execute engine binary {}: {}", bin, e));
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let stderr = String::from_utf8_lossy(&output.stderr);
-
-        // Check for subprocess crash
-        if !(output.status.success()
-            || USER_STOP.load(Ordering::SeqCst) && is_ctrl_c_exit_code(output.status.code()))
-        {
-            eprintln!(
-                "[Game {}] Subprocess crashed! exit={:?}\n  stderr={}",
-                game_idx,
-                output.status.code(),
-                stderr.trim()
-            );
-        }
+            // Parse score/depth from stderr
+            let score = if config.verbose {
+                None
+            } else {
+                stderr
+                    .lines()
+                    .find(|l| l.contains("score"))
+                    .and_then(parse_info_score)
+            };
 
-        // Parse bestmove into ICN move format
-        let bestmove_icn = if let Some(line) = stdout.lines().find(|l| l.starts_with("bestmove")) {
-            let move_str = line.trim_start_matches("bestmove").trim();
-            parse_bestmove_to_icn(move_str, game.turn)
-        } else {
-            None
+            // Process start-up is not charged to the engine's clock.
+            let elapsed = (start_time.elapsed().as_millis() as u64).saturating_sub(20);
+            (bestmove_icn, score, elapsed)
         };
 
-        // Parse score/depth from stderr
-        let mut score = None;
-        if !config.verbose
-            && let Some(line) = stderr.lines().find(|l| l.contains("score"))
-        {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            for i in 0..parts.len() {
-                if parts[i] == "score" && i + 1 < parts.len() {
-                    score = parts[i + 1].parse::<f64>().ok();
-                }
-            }
-        }
-
-        let elapsed = (start_time.elapsed().as_millis() as u64).saturating_sub(20);
-
         let current_clock = if game.turn == PlayerColor::White {
             white_clock
         } else {
@@ -1068,7 +1387,7 @@ fn play_game(
                     result_str,
                     &starting_board_setup,
                 ),
-                variant_name: variant.to_str().to_string(),
+                variant_name: variant.to_str(),
                 game_idx,
                 termination_reason: "timeout".to_string(),
                 new_engine_timed_out: is_new_turn,
@@ -1105,12 +1424,9 @@ fn play_game(
             // Reconstruct game state from the full ICN (starting position + all moves).
             let new_icn = format!("{} {}", starting_board_setup, move_history_clean.join("|"));
             let old_turn = game.turn;
-            game = with_variant_bounds(variant, || {
-                let mut game = GameState::new();
-                game.setup_position_from_icn(&new_icn);
-                game.variant = Some(variant);
-                game
-            });
+            game = GameState::new();
+            game.setup_position_from_icn(&new_icn);
+            game.variant = Some(variant);
 
             // If the turn didn't change, the move wasn't applied (illegal or unparseable)
             if game.turn == old_turn {
@@ -1149,67 +1465,16 @@ fn play_game(
                 black_clock = remaining_clock;
             }
         } else {
-            if let Some(terminal) = with_variant_bounds(variant, || detect_terminal_state(&game)) {
-                match terminal {
-                    TerminalState::Checkmate { white_won } => {
-                        let result = if white_won == new_plays_white {
-                            GameResult::Win
-                        } else {
-                            GameResult::Loss
-                        };
-                        return game_outcome!(
-                            result,
-                            "checkmate",
-                            if white_won { "1-0" } else { "0-1" }
-                        );
-                    }
-                    TerminalState::AllPiecesCaptured { white_won } => {
-                        let result = if white_won == new_plays_white {
-                            GameResult::Win
-                        } else {
-                            GameResult::Loss
-                        };
-                        return game_outcome!(
-                            result,
-                            "allpiecescaptured",
-                            if white_won { "1-0" } else { "0-1" }
-                        );
-                    }
-                    TerminalState::AllRoyalsCaptured { white_won } => {
-                        let result = if white_won == new_plays_white {
-                            GameResult::Win
-                        } else {
-                            GameResult::Loss
-                        };
-                        return game_outcome!(
-                            result,
-                            "allroyalscaptured",
-                            if white_won { "1-0" } else { "0-1" }
-                        );
-                    }
-                    TerminalState::RoyalCapture { white_won } => {
-                        let result = if white_won == new_plays_white {
-                            GameResult::Win
-                        } else {
-                            GameResult::Loss
-                        };
-                        return game_outcome!(
-                            result,
-                            "royalcapture",
-                            if white_won { "1-0" } else { "0-1" }
-                        );
-                    }
-                    TerminalState::Draw(reason) => {
-                        return game_outcome!(GameResult::Draw, reason, "1/2-1/2");
-                    }
-                }
+            if let Some(outcome) = game.outcome() {
+                let result = outcome_result(&outcome, new_plays_white);
+                return game_outcome!(result, outcome.reason.as_str(), outcome.result_str());
             }
 
             if USER_STOP.load(Ordering::SeqCst) {
                 return GameOutcome {
                     result: GameResult::Draw, // Dummy result
                     icn: String::new(),
-                    variant_name: variant.to_str().to_string(),
+                    variant_name: variant.to_str(),
                     game_idx,
                     termination_reason: "interrupted".to_string(),
                     new_engine_timed_out: false,
@@ -1235,7 +1500,7 @@ fn play_game(
                     if white_won { "1-0" } else { "0-1" },
                     &starting_board_setup,
                 ),
-                variant_name: variant.to_str().to_string(),
+                variant_name: variant.to_str(),
                 game_idx,
                 termination_reason: termination_reason.unwrap_or("engine failure").to_string(),
                 new_engine_timed_out: false,
@@ -1244,56 +1509,9 @@ fn play_game(
     }
 
     // Final check: all terminal conditions before declaring max_moves draw
-    if let Some(terminal) = with_variant_bounds(variant, || detect_terminal_state(&game)) {
-        match terminal {
-            TerminalState::Checkmate { white_won } => {
-                let result = if white_won == new_plays_white {
-                    GameResult::Win
-                } else {
-                    GameResult::Loss
-                };
-                return game_outcome!(result, "checkmate", if white_won { "1-0" } else { "0-1" });
-            }
-            TerminalState::AllPiecesCaptured { white_won } => {
-                let result = if white_won == new_plays_white {
-                    GameResult::Win
-                } else {
-                    GameResult::Loss
-                };
-                return game_outcome!(
-                    result,
-                    "allpiecescaptured",
-                    if white_won { "1-0" } else { "0-1" }
-                );
-            }
-            TerminalState::AllRoyalsCaptured { white_won } => {
-                let result = if white_won == new_plays_white {
-                    GameResult::Win
-                } else {
-                    GameResult::Loss
-                };
-                return game_outcome!(
-                    result,
-                    "allroyalscaptured",
-                    if white_won { "1-0" } else { "0-1" }
-                );
-            }
-            TerminalState::RoyalCapture { white_won } => {
-                let result = if white_won == new_plays_white {
-                    GameResult::Win
-                } else {
-                    GameResult::Loss
-                };
-                return game_outcome!(
-                    result,
-                    "royalcapture",
-                    if white_won { "1-0" } else { "0-1" }
-                );
-            }
-            TerminalState::Draw(reason) => {
-                return game_outcome!(GameResult::Draw, reason, "1/2-1/2");
-            }
-        }
+    if let Some(outcome) = game.outcome() {
+        let result = outcome_result(&outcome, new_plays_white);
+        return game_outcome!(result, outcome.reason.as_str(), outcome.result_str());
     }
 
     // Check for threefold repetition at end of loop
@@ -1306,89 +1524,6 @@ fn play_game(
     game_outcome!(GameResult::Draw, "max_moves", "1/2-1/2")
 }
 
-fn get_board_setup_icn(game: &GameState) -> String {
-    let turn_str = "w";
-    let move_limit = game.game_rules.move_rule_limit.unwrap_or(100);
-    let promo_token = {
-        let white_rank = game.white_promo_rank;
-        let black_rank = game.black_promo_rank;
-        let promos = if let Some(p_types) = &game.game_rules.promotion_types {
-            p_types
-                .iter()
-                .map(|pt| pt.to_site_code().to_lowercase())
-                .collect::<Vec<_>>()
-                .join(",")
-        } else {
-            "q,r,b,n".to_string()
-        };
-        format!("({};{}|{};{})", white_rank, promos, black_rank, promos)
-    };
-    let bounds_token = if let Some(v) = &game.variant {
-        let bounds = v.get_default_bounds();
-        format!("{},{},{},{}", bounds.0, bounds.1, bounds.2, bounds.3)
-    } else {
-        "-999999999999999,1000000000000008,-999999999999999,1000000000000008".to_string()
-    };
-
-    let mut pieces: Vec<_> = game.board.iter().collect();
-    // Sort by Y descending, then X ascending
-    pieces.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
-
-    let pieces_str = pieces
-        .iter()
-        .map(|(x, y, piece)| {
-            let mut s = piece.piece_type().to_site_code().to_string();
-            if piece.color() == PlayerColor::Black || piece.color() == PlayerColor::Neutral {
-                s = s.to_lowercase();
-            }
-            let mut y_str = y.to_string();
-            if game.has_special_right(&Coordinate::new(*x, *y)) {
-                y_str.push('+');
-            }
-            format!("{}{},{}", s, x, y_str)
-        })
-        .collect::<Vec<_>>()
-        .join("|");
-
-    let variant_tag = if let Some(v) = &game.variant {
-        format!("[Variant \"{}\"] ", v.to_str())
-    } else {
-        String::new()
-    };
-
-    // Include win conditions if they differ from standard checkmate
-    let win_cond_token = if game.game_rules.white_win_condition
-        != apeiron::game::WinCondition::Checkmate
-        || game.game_rules.black_win_condition != apeiron::game::WinCondition::Checkmate
-    {
-        format!(
-            "{:?},{:?}",
-            game.game_rules.white_win_condition, game.game_rules.black_win_condition
-        )
-        .to_lowercase()
-    } else {
-        String::new()
-    };
-
-    if win_cond_token.is_empty() {
-        format!(
-            "{}{} 0/{} 1 {} {} {}",
-            variant_tag, turn_str, move_limit, promo_token, bounds_token, pieces_str
-        )
-    } else {
-        format!(
-            "{}{} 0/{} 1 {} {} {} {}",
-            variant_tag,
-            turn_str,
-            move_limit,
-            promo_token,
-            bounds_token,
-            win_cond_token,
-            pieces_str
-        )
-    }
-}
-
 fn generate_icn(
     variant: &Variant,
     move_log: &[String],
@@ -1537,172 +1672,34 @@ fn main() {
             };
 
             // ── Variant presets ──────────────────────────────────────────────────────
-            // all      — every variant in the engine
+            // Presets select by the registry's variant tags (see `apeiron::variant`), so
+            // variants loaded from APEIRON_VARIANTS join them through their tags.
+            // all      — every registered variant
             // site     — variants live on the public site (image order), no Abundance/Showcase
             // base_only — base-eval standard variants; no multi-king, no AllPieces, no Abundance
             //             Default for testing base.rs changes against typical positions.
             // base_full — all base-eval variants including multi-king and AllPiecesClassical
             // multi_king — only the double/triple-king variants
             // coaip    — only the Chess-on-an-Infinite-Plane family
-
-            // Every variant in the engine
-            const ALL_VARIANTS: &[Variant] = &[
-                Variant::Classical,
-                Variant::ConfinedClassical,
-                Variant::ClassicalPlus,
-                Variant::CoaIP,
-                Variant::CoaIPHO,
-                Variant::CoaIPRO,
-                Variant::CoaIPNO,
-                Variant::Palace,
-                Variant::Pawndard,
-                Variant::Core,
-                Variant::Standarch,
-                Variant::SpaceClassic,
-                Variant::Space,
-                Variant::Abundance,
-                Variant::PawnHorde,
-                Variant::Knightline,
-                Variant::Obstocean,
-                Variant::Chess,
-                Variant::ScatteredLeapers,
-                Variant::DoubleKingClassical,
-                Variant::DoubleKingChess,
-                Variant::TripleKingMaze,
-                Variant::AllPiecesClassical,
-            ];
-
-            // Variants live on the public site (image order), minus Abundance and Showcase
-            const SITE_VARIANTS: &[Variant] = &[
-                Variant::Classical,
-                Variant::ConfinedClassical,
-                Variant::ClassicalPlus,
-                Variant::CoaIP,
-                Variant::CoaIPHO,
-                Variant::CoaIPRO,
-                Variant::CoaIPNO,
-                Variant::Palace,
-                Variant::Pawndard,
-                Variant::Core,
-                Variant::Standarch,
-                Variant::SpaceClassic,
-                Variant::Space,
-                Variant::PawnHorde,
-                Variant::Knightline,
-                Variant::Obstocean,
-                Variant::Chess,
-            ];
-
-            // Standard base-eval variants — no Chess/Obstocean/PawnHorde custom evals,
-            // no multi-king, no AllPiecesClassical, no Abundance.
-            // Best default for testing base.rs changes.
-            const BASE_ONLY_VARIANTS: &[Variant] = &[
-                Variant::Classical,
-                Variant::ConfinedClassical,
-                Variant::ClassicalPlus,
-                Variant::CoaIP,
-                Variant::CoaIPHO,
-                Variant::CoaIPRO,
-                Variant::CoaIPNO,
-                Variant::Palace,
-                Variant::Pawndard,
-                Variant::Core,
-                Variant::Standarch,
-                Variant::SpaceClassic,
-                Variant::Space,
-                Variant::Knightline,
-                Variant::ScatteredLeapers,
-            ];
-
-            // All base-eval variants including multi-king and AllPiecesClassical.
-            // Use when you specifically want coverage of the exotic base-eval positions.
-            const BASE_FULL_VARIANTS: &[Variant] = &[
-                Variant::Classical,
-                Variant::ConfinedClassical,
-                Variant::ClassicalPlus,
-                Variant::CoaIP,
-                Variant::CoaIPHO,
-                Variant::CoaIPRO,
-                Variant::CoaIPNO,
-                Variant::Palace,
-                Variant::Pawndard,
-                Variant::Core,
-                Variant::Standarch,
-                Variant::SpaceClassic,
-                Variant::Space,
-                Variant::Knightline,
-                Variant::ScatteredLeapers,
-                Variant::DoubleKingClassical,
-                Variant::DoubleKingChess,
-                Variant::TripleKingMaze,
-                Variant::AllPiecesClassical,
-            ];
-
-            // Only the double/triple-king variants
-            const MULTI_KING_VARIANTS: &[Variant] = &[
-                Variant::DoubleKingClassical,
-                Variant::DoubleKingChess,
-                Variant::TripleKingMaze,
-            ];
-
-            // Only the Chess-on-an-Infinite-Plane family
-            const COAIP_VARIANTS: &[Variant] = &[
-                Variant::CoaIP,
-                Variant::CoaIPHO,
-                Variant::CoaIPRO,
-                Variant::CoaIPNO,
-            ];
-
+            // Any other tag selects the variants carrying it.
             let parsed_variants = match variants.trim().to_lowercase().as_str() {
-                "all"        => ALL_VARIANTS.to_vec(),
-                "site"       => SITE_VARIANTS.to_vec(),
-                "base_only"  => BASE_ONLY_VARIANTS.to_vec(),
-                "base_full"  => BASE_FULL_VARIANTS.to_vec(),
-                "multi_king" => MULTI_KING_VARIANTS.to_vec(),
-                "coaip"      => COAIP_VARIANTS.to_vec(),
-                _ => {
-                    let mut parsed = Vec::new();
-                    for name in variants.split(',') {
-                        let name_trimmed = name.trim();
-                        let name_lower = name_trimmed.to_lowercase().replace(' ', "_");
-                        let known = matches!(
-                            name_lower.as_str(),
-                            "classical"
-                                | "confined_classical"
-                                | "classical_plus"
-                                | "coaip"
-                                | "coaip_ho"
-                                | "coaip_ro"
-                                | "coaip_no"
-                                | "palace"
-                                | "pawndard"
-                                | "core"
-                                | "standarch"
-                                | "space_classic"
-                                | "space"
-                                | "abundance"
-                                | "pawn_horde"
-                                | "knightline"
-                                | "obstocean"
-                                | "chess"
-                                | "scattered_leapers"
-                                | "double_king_classical"
-                                | "double_king_chess"
-                                | "triple_king_maze"
-                                | "all_pieces_classical"
+                "base_only" => Variant::tagged("base-eval")
+                    .into_iter()
+                    .filter(|v| !v.has_tag("exotic"))
+                    .collect(),
+                "base_full" => Variant::tagged("base-eval"),
+                "multi_king" => Variant::tagged("multi-king"),
+                _ => match apeiron::variant::select(&variants) {
+                    Ok(parsed) => parsed,
+                    Err(e) => {
+                        eprintln!(
+                            "Error: {}. Valid presets: all, site, base_only, base_full, \
+                             multi_king, coaip, or any variant tag",
+                            e
                         );
-                        if !known {
-                            eprintln!(
-                                "Error: Unknown variant or preset '{}'. \
-                                 Valid presets: all, site, base_only, base_full, multi_king, coaip",
-                                name_trimmed
-                            );
-                            std::process::exit(1);
-                        }
-                        parsed.push(Variant::parse(name_trimmed));
+                        std::process::exit(1);
                     }
-                    parsed
-                }
+                },
             };
 
             let mut config = Config {
@@ -1722,6 +1719,8 @@ fn main() {
                 adjudication_threshold: adjudication,
                 new_bin: actual_new_bin,
                 old_bin,
+                new_persistent: false,
+                old_persistent: false,
                 max_moves,
                 search_noise,
                 old_strength,
@@ -1755,6 +1754,21 @@ fn main() {
                 try_get_commit_info_from_git("HEAD")
             };
 
+            // Binaries built before the engine protocol existed fall back to one process per move.
+            config.new_persistent = EngineProcess::spawn(&config.new_bin, false).is_some();
+            config.old_persistent = EngineProcess::spawn(&config.old_bin, false).is_some();
+            for (label, bin, persistent) in [
+                ("NEW", &config.new_bin, config.new_persistent),
+                ("OLD", &config.old_bin, config.old_persistent),
+            ] {
+                if !persistent {
+                    println!(
+                        "{} binary {} has no engine mode; spawning a search process per move.",
+                        label, bin
+                    );
+                }
+            }
+
             let games_path = games.or_else(|| resume.clone());
             let results_path = results;
 
@@ -1858,6 +1872,9 @@ fn main() {
 
                                     let play_new_white_first = rand::random::<bool>();
                                     let mut pair_outcomes = Vec::with_capacity(2);
+                                    // One process per engine for the whole pair.
+                                    let mut new_engine = None;
+                                    let mut old_engine = None;
 
                                     if play_new_white_first {
                                         pair_outcomes.push(play_game(
@@ -1866,6 +1883,8 @@ fn main() {
                                             true,
                                             game_idx_even,
                                             seeds.clone(),
+                                            &mut new_engine,
+                                            &mut old_engine,
                                         ));
                                         if STOP.load(Ordering::SeqCst) {
                                             let _ = tx.send(pair_outcomes);
@@ -1878,6 +1897,8 @@ fn main() {
                                                 false,
                                                 game_idx_odd,
                                                 seeds,
+                                                &mut new_engine,
+                                                &mut old_engine,
                                             ));
                                         }
                                     } else {
@@ -1888,6 +1909,8 @@ fn main() {
                                                 false,
                                                 game_idx_odd,
                                                 seeds.clone(),
+                                                &mut new_engine,
+                                                &mut old_engine,
                                             ));
                                         }
                                         if STOP.load(Ordering::SeqCst) {
@@ -1900,6 +1923,8 @@ fn main() {
                                             true,
                                             game_idx_even,
                                             seeds,
+                                            &mut new_engine,
+                                            &mut old_engine,
                                         ));
                                     }
 
@@ -2114,78 +2139,40 @@ fn main() {
             variant,
             max_depth,
             fixed_time,
+            moves_to_go,
+            move_overhead,
+            nodes,
+            mate,
+            search_moves,
             noise_amp,
             seed,
             strength_level,
         }) => {
-            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                let v = Variant::parse(&variant);
-                let mut engine = Engine::from_icn_native(icn.as_str(), strength_level);
-                engine.set_clock(wtime, btime, winc, binc);
-                engine.game_mut().variant = Some(v);
-                if let Some(terminal) = detect_terminal_state(engine.game_mut()) {
-                    match terminal {
-                        TerminalState::Checkmate { white_won } => {
-                            eprintln!(
-                                "terminal checkmate winner {}",
-                                if white_won { "white" } else { "black" }
-                            );
-                        }
-                        TerminalState::AllPiecesCaptured { white_won } => {
-                            eprintln!(
-                                "terminal allpiecescaptured winner {}",
-                                if white_won { "white" } else { "black" }
-                            );
-                        }
-                        TerminalState::AllRoyalsCaptured { white_won } => {
-                            eprintln!(
-                                "terminal allroyalscaptured winner {}",
-                                if white_won { "white" } else { "black" }
-                            );
-                        }
-                        TerminalState::RoyalCapture { white_won } => {
-                            eprintln!(
-                                "terminal royalcapture winner {}",
-                                if white_won { "white" } else { "black" }
-                            );
-                        }
-                        TerminalState::Draw(reason) => {
-                            eprintln!("terminal {}", reason);
-                        }
-                    }
-                    println!("bestmove none");
-                    return;
-                }
-                let search_res = if let Some(ft) = fixed_time {
-                    engine.search_native(ft, max_depth, true, noise_amp, seed)
-                } else {
-                    engine.search_native(0, max_depth, true, noise_amp, seed)
-                };
-                if let Some((m, score, stats)) = search_res {
-                    println!("bestmove {}", move_to_string(&m));
-                    let pv = engine.current_pv_native(max_depth.unwrap_or(50));
-                    if pv.is_empty() {
-                        eprintln!("info score {} nodes {}", score, stats.nodes);
-                    } else {
-                        eprintln!("info score {} nodes {} pv {}", score, stats.nodes, pv);
-                    }
-                } else {
-                    eprintln!("search returned None for icn: {}", icn);
-                    println!("bestmove none");
-                }
-            }));
-            if let Err(e) = result {
-                let msg = if let Some(s) = e.downcast_ref::<String>() {
-                    s.clone()
-                } else if let Some(s) = e.downcast_ref::<&str>() {
-                    s.to_string()
-                } else {
-                    "unknown panic".to_string()
-                };
-                eprintln!("PANIC in search subprocess: {}", msg);
-                println!("bestmove none");
+            let limits = GoLimits {
+                wtime,
+                btime,
+                winc,
+                binc,
+                max_depth,
+                fixed_time,
+                moves_to_go,
+                move_overhead,
+                nodes,
+                mate,
+                search_moves,
+                noise_amp,
+                seed,
+                strength_level,
+            };
+            let mut engine = Engine::new_native(&icn);
+            let (bestmove, info) =
+                search_position(&mut engine, &icn, Variant::parse(&variant), &limits);
+            println!("bestmove {}", bestmove);
+            if let Some(info) = info {
+                eprintln!("{}", info);
             }
         }
+        Some(Commands::Engine) => run_engine_loop(),
         Some(Commands::CommitInfo) => {
             let is_dirty = BUILD_DIRTY.map(|d| d == "1").unwrap_or(false);
             let info = CommitInfo {
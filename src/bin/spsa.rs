@@ -1,14 +1,15 @@
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
+use std::io::{BufRead, BufReader, Write};
 use std::path::{Path, PathBuf};
-use std::process::{Command, Stdio};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Instant;
 
 use clap::{Parser, Subcommand};
 use apeiron::board::PlayerColor;
 use apeiron::evaluation::{self};
-use apeiron::game::{GameState, WinCondition};
+use apeiron::game::GameState;
 use apeiron::search::params::{
     self, EvalParams, SearchParams, TUNABLE_EVAL_PARAM_SPECS, TUNABLE_PARAM_SPECS,
 };
@@ -19,7 +20,7 @@ use serde_json::{Number, Value};
 static STOP: AtomicBool = AtomicBool::new(false);
 static USER_STOP: AtomicBool = AtomicBool::new(false);
 
-const DEFAULT_VARIANTS: &str = "Classical,Confined_Classical,Classical_Plus,Core,CoaIP,CoaIP_HO,CoaIP_RO,CoaIP_NO,Palace,Pawndard,Standarch,Space_Classic,Space,Scattered_Leapers";
+const DEFAULT_VARIANTS: &str = "tuning";
 const DEFAULT_CHECKPOINT_DIR: &str = "sprt/spsa_checkpoints";
 const DEFAULT_RESULTS_PATH: &str = "sprt/spsa_final.json";
 const SEARCH_PARAMS_RS_PATH: &str = "src/search/params.rs";
@@ -57,7 +58,7 @@ enum Commands {
         /// Number of concurrent game workers.
         #[arg(long, default_value_t = 16)]
         concurrency: usize,
-        /// Comma-separated variant list to test.
+        /// Comma-separated variant list to test, `all`, or a registry variant tag.
         #[arg(long, default_value = DEFAULT_VARIANTS)]
         variants: String,
         /// Material-eval threshold for adjudication.
@@ -97,6 +98,16 @@ enum Commands {
         #[arg(long)]
         config: Option<PathBuf>,
     },
+    /// Long-lived engine process used by the SPSA match runner, driven by a line protocol
+    /// on stdin (`newgame`, `position icn ...`, `go ...`, `stop`, `isready`, `quit`).
+    Engine {
+        /// JSON-encoded search params override for this process.
+        #[arg(long)]
+        search_params_json: Option<String>,
+        /// JSON-encoded eval params override for this process.
+        #[arg(long)]
+        eval_params_json: Option<String>,
+    },
     /// Internal search subprocess used by the SPSA match runner.
     Search {
         /// ICN string of the position to search.
@@ -412,51 +423,264 @@ fn parse_bestmove_to_icn(bestmove_str: &str, turn: PlayerColor) -> Option<String
     Some(result)
 }
 
-fn has_any_fully_legal_move(game: &mut GameState) -> bool {
-    for m in game.get_legal_moves() {
-        let undo = game.make_move(&m);
-        let legal = !game.is_move_illegal();
-        game.undo_move(&m, undo);
-        if legal {
-            return true;
+/// Starting position followed by the moves played so far, as one ICN string.
+fn game_record_icn(starting: &str, moves: &[String]) -> String {
+    if moves.is_empty() {
+        starting.to_string()
+    } else {
+        format!("{} {}", starting, moves.join("|"))
+    }
+}
+
+/// Per-move search limits, shared by the one-shot `search` subcommand and the `engine`
+/// protocol's `go` command.
+#[derive(Clone, Debug, Default)]
+struct GoLimits {
+    wtime: u64,
+    btime: u64,
+    winc: u64,
+    binc: u64,
+    max_depth: Option<usize>,
+    fixed_time: Option<u32>,
+    noise_amp: Option<i32>,
+    seed: Option<u64>,
+}
+
+impl GoLimits {
+    /// Parses the arguments of a `go` line, e.g. `wtime 9000 btime 9000 winc 100 binc 100 seed 7`.
+    /// Unknown keys and malformed values are ignored.
+    fn parse(args: &str) -> GoLimits {
+        let mut limits = GoLimits::default();
+        let tokens: Vec<&str> = args.split_whitespace().collect();
+        for pair in tokens.chunks(2) {
+            let [key, value] = pair else { break };
+            match *key {
+                "wtime" => limits.wtime = value.parse().unwrap_or(0),
+                "btime" => limits.btime = value.parse().unwrap_or(0),
+                "winc" => limits.winc = value.parse().unwrap_or(0),
+                "binc" => limits.binc = value.parse().unwrap_or(0),
+                "depth" => limits.max_depth = value.parse().ok(),
+                "movetime" => limits.fixed_time = value.parse().ok(),
+                "noise" => limits.noise_amp = value.parse().ok(),
+                "seed" => limits.seed = value.parse().ok(),
+                _ => {}
+            }
         }
+        limits
     }
-    false
 }
 
-fn terminal_reason(game: &mut GameState) -> Option<(&'static str, Option<bool>)> {
-    if game.has_lost_by_royal_capture() {
-        let opponent_win_condition = match game.turn {
-            PlayerColor::White => game.game_rules.black_win_condition,
-            PlayerColor::Black => game.game_rules.white_win_condition,
-            PlayerColor::Neutral => return None,
-        };
-        let reason = match opponent_win_condition {
-            WinCondition::RoyalCapture => "royalcapture",
-            WinCondition::AllRoyalsCaptured => "allroyalscaptured",
-            _ => return None,
-        };
-        return Some((reason, Some(game.turn == PlayerColor::Black)));
+/// Sets `icn` on `engine` and searches it on the engine's own search state, returning the
+/// `bestmove` payload plus an `info` line, if any. Shared by `search` and the `engine`
+/// protocol, which keeps one engine so the TT and histories carry over between moves.
+fn search_position(
+    engine: &mut Engine,
+    icn: &str,
+    variant: Variant,
+    limits: &GoLimits,
+) -> (String, Option<String>) {
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        if engine.set_position_native(icn).is_err() {
+            return ("none".to_string(), None);
+        }
+        engine.set_clock(limits.wtime, limits.btime, limits.winc, limits.binc);
+        engine.game_mut().variant = Some(variant);
+        if engine.game_mut().outcome().is_some() {
+            return ("none".to_string(), None);
+        }
+        let search_res = engine.search_native(
+            limits.fixed_time.unwrap_or(0),
+            limits.max_depth,
+            true,
+            limits.noise_amp,
+            limits.seed,
+        );
+        if let Some((m, score, stats)) = search_res {
+            let pv = engine.current_pv_native(limits.max_depth.unwrap_or(50));
+            let info = if pv.is_empty() {
+                format!("info score {} nodes {}", score, stats.nodes)
+            } else {
+                format!("info score {} nodes {} pv {}", score, stats.nodes, pv)
+            };
+            (move_to_string(&m), Some(info))
+        } else {
+            ("none".to_string(), None)
+        }
+    }));
+    result.unwrap_or_else(|_| {
+        // The search may have unwound mid-update; don't carry its tables into the next move.
+        engine.reset_search();
+        ("none".to_string(), None)
+    })
+}
+
+/// Long-lived engine loop behind the `engine` subcommand. Speaks the same line protocol
+/// as `sprt engine`: `isready`, `newgame [variant]`, `position icn <ICN>`,
+/// `go wtime N btime N winc N binc N [depth N] [movetime N] [noise N] [seed N]`, `stop`
+/// and `quit`.
+fn run_engine_loop() {
+    let (tx, rx) = std::sync::mpsc::channel::<String>();
+
+    let mut engine = Engine::new_native(&Variant::Classical.starting_icn());
+    let search_ctx = std::sync::Arc::clone(
+        engine
+            .search_instance()
+            .expect("native engines own their search")
+            .context(),
+    );
+    std::thread::spawn(move || {
+        for line in std::io::stdin().lock().lines() {
+            let Ok(line) = line else { break };
+            if line.trim() == "stop" {
+                search_ctx.request_stop();
+            } else if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut variant = Variant::Classical;
+    let mut icn: Option<String> = None;
+    for line in rx {
+        let line = line.trim();
+        let (command, args) = line.split_once(' ').unwrap_or((line, ""));
+        match command {
+            "isready" => println!("readyok"),
+            "newgame" => {
+                engine.reset_search();
+                if !args.trim().is_empty() {
+                    variant = Variant::parse(args.trim());
+                }
+                icn = None;
+            }
+            "position" => {
+                icn = args
+                    .trim()
+                    .strip_prefix("icn ")
+                    .map(|s| s.trim().to_string())
+            }
+            "go" => {
+                let (bestmove, info) = match icn.as_deref() {
+                    Some(icn) => search_position(&mut engine, icn, variant, &GoLimits::parse(args)),
+                    None => ("none".to_string(), None),
+                };
+                if let Some(info) = info {
+                    println!("{}", info);
+                }
+                println!("bestmove {}", bestmove);
+            }
+            "quit" => break,
+            _ => {}
+        }
+        let _ = std::io::stdout().flush();
     }
+}
 
-    let in_check = game.is_in_check();
-    if !has_any_fully_legal_move(game) {
-        let lost_by_mate = in_check && game.must_escape_check();
-        let lost_by_piece_capture = !game.has_pieces(game.turn);
-        if lost_by_mate || lost_by_piece_capture {
-            return Some(("checkmate", Some(game.turn == PlayerColor::Black)));
+/// A running `engine` subprocess with one side's params applied, kept for a game pair.
+struct EngineProcess {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl EngineProcess {
+    /// Starts `bin engine` with the given param overrides and waits for `readyok`.
+    fn spawn(
+        bin: &Path,
+        search_json: Option<&str>,
+        eval_json: Option<&str>,
+        verbose: bool,
+    ) -> Option<EngineProcess> {
+        let mut cmd = Command::new(bin);
+        cmd.env("RAYON_NUM_THREADS", "1")
+            .arg("engine")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(if verbose {
+                Stdio::inherit()
+            } else {
+                Stdio::null()
+            });
+        if let Some(json) = search_json {
+            cmd.arg("--search-params-json").arg(json);
         }
-        return Some(("stalemate", None));
+        if let Some(json) = eval_json {
+            cmd.arg("--eval-params-json").arg(json);
+        }
+        let mut child = cmd.spawn().ok()?;
+        let stdin = child.stdin.take()?;
+        let stdout = BufReader::new(child.stdout.take()?);
+        let mut engine = EngineProcess {
+            child,
+            stdin,
+            stdout,
+        };
+        engine.ready().then_some(engine)
     }
-    if game.is_draw(0, in_check) {
-        if game.is_fifty() {
-            return Some(("fifty-move rule", None));
+
+    fn send(&mut self, line: &str) -> bool {
+        writeln!(self.stdin, "{}", line)
+            .and_then(|_| self.stdin.flush())
+            .is_ok()
+    }
+
+    /// Reads stdout up to the first line starting with `prefix`; None if the process exited.
+    fn read_until(&mut self, prefix: &str) -> Option<String> {
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if self.stdout.read_line(&mut line).ok()? == 0 {
+                return None;
+            }
+            if line.trim().starts_with(prefix) {
+                return Some(line.trim().to_string());
+            }
         }
-        if game.is_repetition(0) {
-            return Some(("threefold repetition", None));
+    }
+
+    fn ready(&mut self) -> bool {
+        self.send("isready") && self.read_until("readyok").is_some()
+    }
+
+    fn new_game(&mut self, variant: Variant) -> bool {
+        self.send(&format!("newgame {}", variant.to_str())) && self.ready()
+    }
+
+    /// Searches `icn` and returns the `bestmove` payload.
+    fn go(&mut self, icn: &str, go_args: &str) -> Option<String> {
+        if !self.send(&format!("position icn {}", icn)) || !self.send(&format!("go {}", go_args)) {
+            return None;
         }
+        let line = self.read_until("bestmove")?;
+        Some(line.trim_start_matches("bestmove").trim().to_string())
+    }
+}
+
+impl Drop for EngineProcess {
+    fn drop(&mut self) {
+        let _ = self.send("stop");
+        let _ = self.send("quit");
+        let _ = self.child.wait();
+    }
+}
+
+/// Makes `slot` hold a live engine process with the given params, ready for a new game.
+fn start_engine_game(
+    slot: &mut Option<EngineProcess>,
+    config: &RunConfig,
+    search_json: Option<&str>,
+    eval_json: Option<&str>,
+    variant: Variant,
+) {
+    if slot.as_mut().is_some_and(|engine| engine.new_game(variant)) {
+        return;
+    }
+    *slot = EngineProcess::spawn(&config.engine_bin, search_json, eval_json, config.verbose);
+    if let Some(engine) = slot.as_mut()
+        && !engine.new_game(variant)
+    {
+        *slot = None;
     }
-    None
 }
 
 fn play_game(
@@ -468,11 +692,27 @@ fn play_game(
     minus_search_json: Option<&str>,
     plus_eval_json: Option<&str>,
     minus_eval_json: Option<&str>,
+    plus_engine: &mut Option<EngineProcess>,
+    minus_engine: &mut Option<EngineProcess>,
 ) -> GameOutcome {
+    start_engine_game(
+        plus_engine,
+        config,
+        plus_search_json,
+        plus_eval_json,
+        variant,
+    );
+    start_engine_game(
+        minus_engine,
+        config,
+        minus_search_json,
+        minus_eval_json,
+        variant,
+    );
     let mut game = GameState::new();
-    game.setup_position_from_icn(variant.starting_icn());
+    game.setup_position_from_icn(&variant.starting_icn());
     game.variant = Some(variant);
-    let starting = variant.starting_icn().to_string();
+    let starting = variant.starting_icn();
     let mut white_clock = config.tc_base_ms;
     let mut black_clock = config.tc_base_ms;
     let mut moves = Vec::new();
@@ -492,15 +732,15 @@ fn play_game(
         if STOP.load(Ordering::SeqCst) {
             return GameOutcome {
                 result: GameResult::Draw,
-                variant_name: variant.to_str().to_string(),
+                variant_name: variant.to_str(),
                 termination_reason: "interrupted".to_string(),
                 icn: String::new(),
             };
         }
-        if let Some((reason, white_won)) = terminal_reason(&mut game) {
-            let result = match white_won {
-                Some(white_won) => {
-                    if white_won == plus_white {
+        if let Some(outcome) = game.outcome() {
+            let result = match outcome.winner {
+                Some(winner) => {
+                    if (winner == PlayerColor::White) == plus_white {
                         GameResult::Win
                     } else {
                         GameResult::Loss
@@ -508,15 +748,11 @@ fn play_game(
                 }
                 None => GameResult::Draw,
             };
-            let icn = if moves.is_empty() {
-                starting.clone()
-            } else {
-                format!("{} {}", starting, moves.join("|"))
-            };
+            let icn = game_record_icn(&starting, &moves);
             return GameOutcome {
                 result,
-                variant_name: variant.to_str().to_string(),
-                termination_reason: reason.to_string(),
+                variant_name: variant.to_str(),
+                termination_reason: outcome.reason.as_str().to_string(),
                 icn,
             };
         }
@@ -528,102 +764,59 @@ fn play_game(
             } else {
                 GameResult::Loss
             };
-            let icn = if moves.is_empty() {
-                starting.clone()
-            } else {
-                format!("{} {}", starting, moves.join("|"))
-            };
+            let icn = game_record_icn(&starting, &moves);
             return GameOutcome {
                 result,
-                variant_name: variant.to_str().to_string(),
+                variant_name: variant.to_str(),
                 termination_reason: "material adjudication".to_string(),
                 icn,
             };
         }
 
         let is_plus_turn = (game.turn == PlayerColor::White) == plus_white;
-        let search_json = if is_plus_turn {
-            plus_search_json
-        } else {
-            minus_search_json
-        };
-        let eval_json = if is_plus_turn {
-            plus_eval_json
-        } else {
-            minus_eval_json
-        };
-        let subprocess_icn = if moves.is_empty() {
-            starting.clone()
+        let engine = if is_plus_turn {
+            &mut *plus_engine
         } else {
-            format!("{} {}", starting, moves.join("|"))
+            &mut *minus_engine
         };
+        let subprocess_icn = game_record_icn(&starting, &moves);
 
-        let mut cmd = Command::new(&config.engine_bin);
-        cmd.env("RAYON_NUM_THREADS", "1")
-            .arg("search")
-            .arg("--icn")
-            .arg(&subprocess_icn)
-            .arg("--wtime")
-            .arg(white_clock.to_string())
-            .arg("--btime")
-            .arg(black_clock.to_string())
-            .arg("--winc")
-            .arg(config.tc_inc_ms.to_string())
-            .arg("--binc")
-            .arg(config.tc_inc_ms.to_string())
-            .arg("--variant")
-            .arg(variant.to_str())
-            .arg("--seed")
-            .arg(seeds[ply].to_string());
-        if let Some(json) = search_json {
-            cmd.arg("--search-params-json").arg(json);
-        }
-        if let Some(json) = eval_json {
-            cmd.arg("--eval-params-json").arg(json);
-        }
+        let mut go_args = format!(
+            "wtime {} btime {} winc {} binc {} seed {}",
+            white_clock, black_clock, config.tc_inc_ms, config.tc_inc_ms, seeds[ply]
+        );
         if let Some(depth) = config.tc_max_depth {
-            cmd.arg("--max-depth").arg(depth.to_string());
+            go_args.push_str(&format!(" depth {}", depth));
         }
         if let Some(fixed) = config.tc_fixed_ms {
-            cmd.arg("--fixed-time").arg(fixed.to_string());
+            go_args.push_str(&format!(" movetime {}", fixed));
         }
         if ply < 8 {
-            cmd.arg("--noise-amp").arg(config.search_noise.to_string());
-        }
-        if config.verbose {
-            cmd.stderr(Stdio::inherit());
+            go_args.push_str(&format!(" noise {}", config.search_noise));
         }
 
         let started = Instant::now();
-        let output = match cmd.output() {
-            Ok(output) => output,
-            Err(_) => {
-                let result = if is_plus_turn {
-                    GameResult::Loss
-                } else {
-                    GameResult::Win
-                };
-                let icn = if moves.is_empty() {
-                    starting.clone()
-                } else {
-                    format!("{} {}", starting, moves.join("|"))
-                };
-                return GameOutcome {
-                    result,
-                    variant_name: variant.to_str().to_string(),
-                    termination_reason: "engine failure".to_string(),
-                    icn,
-                };
-            }
+        let Some(bestmove) = engine
+            .as_mut()
+            .and_then(|engine| engine.go(&subprocess_icn, &go_args))
+        else {
+            // The process is gone; the next game starts a fresh one.
+            *engine = None;
+            let result = if is_plus_turn {
+                GameResult::Loss
+            } else {
+                GameResult::Win
+            };
+            let icn = game_record_icn(&starting, &moves);
+            return GameOutcome {
+                result,
+                variant_name: variant.to_str(),
+                termination_reason: "engine failure".to_string(),
+                icn,
+            };
         };
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let bestmove_icn = stdout
-            .lines()
-            .find(|line| line.starts_with("bestmove"))
-            .and_then(|line| {
-                parse_bestmove_to_icn(line.trim_start_matches("bestmove").trim(), game.turn)
-            });
-        let elapsed = (started.elapsed().as_millis() as u64).saturating_sub(20);
+        let bestmove_icn = parse_bestmove_to_icn(&bestmove, game.turn);
+        let elapsed = started.elapsed().as_millis() as u64;
         let current_clock = if game.turn == PlayerColor::White {
             white_clock
         } else {
@@ -635,14 +828,10 @@ fn play_game(
             } else {
                 GameResult::Win
             };
-            let icn = if moves.is_empty() {
-                starting.clone()
-            } else {
-                format!("{} {}", starting, moves.join("|"))
-            };
+            let icn = game_record_icn(&starting, &moves);
             return GameOutcome {
                 result,
-                variant_name: variant.to_str().to_string(),
+                variant_name: variant.to_str(),
                 termination_reason: "timeout".to_string(),
                 icn,
             };
@@ -650,7 +839,7 @@ fn play_game(
         let remaining_clock = current_clock - elapsed + config.tc_inc_ms;
         if let Some(move_icn) = bestmove_icn {
             moves.push(move_icn);
-            let new_icn = format!("{} {}", starting, moves.join("|"));
+            let new_icn = game_record_icn(&starting, &moves);
             let old_turn = game.turn;
             game = GameState::new();
             game.setup_position_from_icn(&new_icn);
@@ -663,7 +852,7 @@ fn play_game(
                 };
                 return GameOutcome {
                     result,
-                    variant_name: variant.to_str().to_string(),
+                    variant_name: variant.to_str(),
                     termination_reason: "illegal move".to_string(),
                     icn: new_icn,
                 };
@@ -679,28 +868,20 @@ fn play_game(
             } else {
                 GameResult::Win
             };
-            let icn = if moves.is_empty() {
-                starting.clone()
-            } else {
-                format!("{} {}", starting, moves.join("|"))
-            };
+            let icn = game_record_icn(&starting, &moves);
             return GameOutcome {
                 result,
-                variant_name: variant.to_str().to_string(),
+                variant_name: variant.to_str(),
                 termination_reason: "engine failure".to_string(),
                 icn,
             };
         }
     }
 
-    let icn = if moves.is_empty() {
-        starting
-    } else {
-        format!("{} {}", starting, moves.join("|"))
-    };
+    let icn = game_record_icn(&starting, &moves);
     GameOutcome {
         result: GameResult::Draw,
-        variant_name: variant.to_str().to_string(),
+        variant_name: variant.to_str(),
         termination_reason: "max_moves".to_string(),
         icn,
     }
@@ -885,6 +1066,9 @@ fn run_batch(
                                 seeds.push(rand::random::<u64>());
                             }
                             let mut outcomes = Vec::with_capacity(2);
+                            // One process per side for the whole pair.
+                            let mut plus_engine = None;
+                            let mut minus_engine = None;
                             if rand::random::<bool>() {
                                 outcomes.push(play_game(
                                     &config,
@@ -895,6 +1079,8 @@ fn run_batch(
                                     minus_search_json.as_deref(),
                                     plus_eval_json.as_deref(),
                                     minus_eval_json.as_deref(),
+                                    &mut plus_engine,
+                                    &mut minus_engine,
                                 ));
                                 if !STOP.load(Ordering::SeqCst) {
                                     outcomes.push(play_game(
@@ -906,6 +1092,8 @@ fn run_batch(
                                         minus_search_json.as_deref(),
                                         plus_eval_json.as_deref(),
                                         minus_eval_json.as_deref(),
+                                        &mut plus_engine,
+                                        &mut minus_engine,
                                     ));
                                 }
                             } else {
@@ -918,6 +1106,8 @@ fn run_batch(
                                     minus_search_json.as_deref(),
                                     plus_eval_json.as_deref(),
                                     minus_eval_json.as_deref(),
+                                    &mut plus_engine,
+                                    &mut minus_engine,
                                 ));
                                 if !STOP.load(Ordering::SeqCst) {
                                     outcomes.push(play_game(
@@ -929,6 +1119,8 @@ fn run_batch(
                                         minus_search_json.as_deref(),
                                         plus_eval_json.as_deref(),
                                         minus_eval_json.as_deref(),
+                                        &mut plus_engine,
+                                        &mut minus_engine,
                                     ));
                                 }
                             }
@@ -1287,7 +1479,7 @@ fn main() {
                 tc_fixed_ms: None,
                 tc_max_depth: None,
                 concurrency,
-                variants: variants.split(',').map(Variant::parse).collect(),
+                variants: apeiron::variant::select(&variants).unwrap_or_else(|e| panic!("{}", e)),
                 adjudication_threshold: adjudication,
                 max_moves,
                 search_noise,
@@ -1325,40 +1517,41 @@ fn main() {
             search_params_json,
             eval_params_json,
         }) => {
-            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                if let Some(json) = search_params_json.as_deref() {
-                    let _ = params::set_search_params_from_json(json);
-                }
-                if let Some(json) = eval_params_json.as_deref() {
-                    let _ = evaluation::set_eval_params_from_json(json);
-                }
-                let mut engine = Engine::from_icn_native(icn.as_str(), None);
-                engine.set_clock(wtime, btime, winc, binc);
-                engine.game_mut().variant = Some(Variant::parse(&variant));
-                if terminal_reason(engine.game_mut()).is_some() {
-                    println!("bestmove none");
-                    return;
-                }
-                let search_res = if let Some(ft) = fixed_time {
-                    engine.search_native(ft, max_depth, true, noise_amp, seed)
-                } else {
-                    engine.search_native(0, max_depth, true, noise_amp, seed)
-                };
-                if let Some((m, score, stats)) = search_res {
-                    println!("bestmove {}", move_to_string(&m));
-                    let pv = engine.current_pv_native(max_depth.unwrap_or(50));
-                    if pv.is_empty() {
-                        eprintln!("info score {} nodes {}", score, stats.nodes);
-                    } else {
-                        eprintln!("info score {} nodes {} pv {}", score, stats.nodes, pv);
-                    }
-                } else {
-                    println!("bestmove none");
-                }
-            }));
-            if result.is_err() {
-                println!("bestmove none");
+            if let Some(json) = search_params_json.as_deref() {
+                let _ = params::set_search_params_from_json(json);
+            }
+            if let Some(json) = eval_params_json.as_deref() {
+                let _ = evaluation::set_eval_params_from_json(json);
+            }
+            let mut engine = Engine::new_native(&icn);
+            let limits = GoLimits {
+                wtime,
+                btime,
+                winc,
+                binc,
+                max_depth,
+                fixed_time,
+                noise_amp,
+                seed,
+            };
+            let (bestmove, info) =
+                search_position(&mut engine, &icn, Variant::parse(&variant), &limits);
+            println!("bestmove {}", bestmove);
+            if let Some(info) = info {
+                eprintln!("{}", info);
+            }
+        }
+        Some(Commands::Engine {
+            search_params_json,
+            eval_params_json,
+        }) => {
+            if let Some(json) = search_params_json.as_deref() {
+                let _ = params::set_search_params_from_json(json);
+            }
+            if let Some(json) = eval_params_json.as_deref() {
+                let _ = evaluation::set_eval_params_from_json(json);
             }
+            run_engine_loop();
         }
         Some(Commands::Apply { input }) => {
             let path = input
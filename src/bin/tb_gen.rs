@@ -0,0 +1,68 @@
+//! Generates endgame tablebases.
+//!
+//! Solves one pawnless ending (`KQvK`, `KRRvK`, `KMvK`, ...) on a bounded board or in a
+//! window around the defending king, along with every smaller ending its captures lead
+//! into, and writes each table to the output directory for `load_tablebase` and
+//! `Tablebase::from_bytes`.
+
+use apeiron::tablebase::{Geometry, Material, generate};
+use std::env;
+use std::path::PathBuf;
+use std::time::Instant;
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let mut geometry = None;
+    let mut material = None;
+    let mut output = PathBuf::from(".");
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--board" => {
+                let (width, height) = args
+                    .next()
+                    .and_then(|v| {
+                        let (w, h) = v.split_once('x')?;
+                        Some((w.parse().ok()?, h.parse().ok()?))
+                    })
+                    .expect("--board takes WIDTHxHEIGHT, e.g. 8x8");
+                geometry = Some(Geometry::Bounded { width, height });
+            }
+            "--window" => {
+                let radius = args
+                    .next()
+                    .and_then(|v| v.parse().ok())
+                    .expect("--window takes a radius");
+                geometry = Some(Geometry::Window { radius });
+            }
+            "-o" | "--output" => output = args.next().expect("-o takes a directory").into(),
+            _ => material = Some(Material::parse(&arg).expect("Material looks like KQvK")),
+        }
+    }
+
+    let (Some(geometry), Some(material)) = (geometry, material) else {
+        println!(
+            "Usage: cargo run --release --bin tb_gen -- <MATERIAL> (--board WxH | --window R) [-o DIR]"
+        );
+        return;
+    };
+
+    let start = Instant::now();
+    let tables = generate(geometry, &material).unwrap_or_else(|e| panic!("{}", e));
+    let suffix = match geometry {
+        Geometry::Bounded { width, height } => format!("{}x{}", width, height),
+        Geometry::Window { radius } => format!("r{}", radius),
+    };
+    for table in &tables {
+        let path = output.join(format!("{}-{}.tb", table.material().name(), suffix));
+        std::fs::write(&path, table.to_bytes())
+            .unwrap_or_else(|e| panic!("Failed to write '{}': {}", path.display(), e));
+        println!(
+            "{}: {} positions, longest mate {} plies",
+            path.display(),
+            table.len(),
+            table.longest_win()
+        );
+    }
+    println!("Done in {:.1}s", start.elapsed().as_secs_f64());
+}
@@ -0,0 +1,290 @@
+//! Texel tuner for the evaluation parameters.
+//!
+//! Loads positions labelled with game results from `sprt --games` JSON, `game_gen` output
+//! (`generated_games.txt`) or `gen_nnue_data` files, fits the sigmoid scale K and runs Adam
+//! over `TUNABLE_EVAL_PARAM_SPECS` (see `evaluation::texel`). The result JSON has the same
+//! `theta` map as `spsa`, so `spsa apply --input` writes it back into the source constants;
+//! `--apply` does that directly.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+
+use apeiron::evaluation::TUNABLE_EVAL_PARAM_SPECS;
+use apeiron::evaluation::texel::{self, Position, Tuner};
+use apeiron::search::GameRecord;
+use clap::Parser;
+use rayon::prelude::*;
+use serde::Serialize;
+
+static STOP: AtomicBool = AtomicBool::new(false);
+
+const DEFAULT_RESULTS_PATH: &str = "sprt/texel_final.json";
+const EVAL_BASE_RS_PATH: &str = "src/evaluation/base.rs";
+
+#[derive(Parser, Debug)]
+#[command(author, version, about)]
+struct Cli {
+    /// Game files (`sprt --games` JSON or `generated_games.txt`) and `gen_nnue_data` `.bin`
+    /// files, which need their `.icn` position file alongside.
+    #[arg(required = true)]
+    data: Vec<PathBuf>,
+    /// Parameter selection: `eval` (all of them), `piece-values`, or comma-separated names.
+    #[arg(long, default_value = "eval")]
+    params: String,
+    /// Number of Adam epochs (full passes over the positions).
+    #[arg(long, default_value_t = 300)]
+    epochs: usize,
+    /// Adam learning rate, roughly the largest step in centipawns per epoch.
+    #[arg(long, default_value_t = 1.0)]
+    lr: f64,
+    /// Rebuild the linear model from the real evaluation every N epochs. 0 never does.
+    #[arg(long, default_value_t = 50)]
+    relinearize: usize,
+    /// Sigmoid scale. Fitted to the data at the default parameters when omitted.
+    #[arg(long)]
+    k: Option<f64>,
+    /// Plies skipped at the start of every game.
+    #[arg(long, default_value_t = 8)]
+    skip_plies: usize,
+    /// Take every Nth position of a game after the skipped plies.
+    #[arg(long, default_value_t = 1)]
+    every: usize,
+    /// Worker threads. Defaults to one per core.
+    #[arg(long)]
+    threads: Option<usize>,
+    /// Output path for the result JSON.
+    #[arg(long, default_value = DEFAULT_RESULTS_PATH)]
+    results: PathBuf,
+    /// Also write the tuned values into the Rust source constants.
+    #[arg(long, default_value_t = false)]
+    apply: bool,
+}
+
+#[derive(Serialize)]
+struct EpochRecord {
+    epoch: usize,
+    loss: f64,
+}
+
+#[derive(Serialize)]
+struct Results {
+    theta: BTreeMap<String, i64>,
+    k: f64,
+    baseline_loss: f64,
+    loss: f64,
+    positions: usize,
+    history: Vec<EpochRecord>,
+    selected: Vec<String>,
+}
+
+fn piece_value_names() -> &'static [&'static str] {
+    &[
+        "knight",
+        "bishop",
+        "rook",
+        "guard",
+        "centaur",
+        "compound_bonus",
+        "camel",
+        "giraffe",
+        "zebra",
+        "knightrider",
+        "hawk",
+        "archbishop",
+        "rose",
+        "huygen",
+        "chancellor_bonus",
+    ]
+}
+
+fn select_specs(selector: &str) -> Vec<apeiron::evaluation::EvalParamSpec> {
+    let names: Vec<&str> = match selector.trim() {
+        "eval" | "all" => return TUNABLE_EVAL_PARAM_SPECS.to_vec(),
+        "piece-values" | "material" => piece_value_names().to_vec(),
+        other => other
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .collect(),
+    };
+    names
+        .into_iter()
+        .map(|name| {
+            *TUNABLE_EVAL_PARAM_SPECS
+                .iter()
+                .find(|spec| spec.name == name)
+                .unwrap_or_else(|| panic!("unknown eval parameter: {}. Use `spsa list --params eval` to inspect valid names.", name))
+        })
+        .collect()
+}
+
+fn load_positions(path: &Path, skip: usize, every: usize) -> Vec<Position> {
+    if path.extension().is_some_and(|ext| ext == "bin") {
+        return texel::nnue_data_positions(path).unwrap_or_else(|e| panic!("{}", e));
+    }
+
+    let content = fs::read_to_string(path)
+        .unwrap_or_else(|e| panic!("Failed to read '{}': {}", path.display(), e));
+    // sprt saves a JSON array of ICN strings; game_gen writes one game per line.
+    let (games, from_icn): (Vec<String>, bool) = if content.trim_start().starts_with('[') {
+        let games = serde_json::from_str(&content)
+            .unwrap_or_else(|e| panic!("Failed to parse '{}': {}", path.display(), e));
+        (games, true)
+    } else {
+        let lines = content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(String::from)
+            .collect();
+        (lines, false)
+    };
+
+    let per_game: Vec<Result<Vec<Position>, String>> = games
+        .par_iter()
+        .map(|game| {
+            let record = if from_icn {
+                GameRecord::from_icn(game)?
+            } else {
+                GameRecord::from_generated(game)?
+            };
+            texel::game_positions(record, skip, every)
+        })
+        .collect();
+    let skipped = per_game.iter().filter(|r| r.is_err()).count();
+    if skipped > 0 {
+        println!("{}: {} games skipped", path.display(), skipped);
+    }
+    per_game.into_iter().flatten().flatten().collect()
+}
+
+fn eval_const_name(name: &str) -> String {
+    format!("DEFAULT_EVAL_{}", name.to_ascii_uppercase())
+}
+
+fn apply_constants(path: &str, values: &BTreeMap<String, i64>) {
+    let content = fs::read_to_string(path).expect("read constants file");
+    let mut out = Vec::new();
+    for line in content.lines() {
+        let trimmed = line.trim();
+        let replacement = values.iter().find_map(|(name, value)| {
+            let rest = trimmed.strip_prefix("pub const ")?;
+            let (name_part, _) = rest.split_once(':')?;
+            let eq_idx = line.find('=')?;
+            let semi_idx = line.find(';')?;
+            (name_part.trim() == eval_const_name(name) && eq_idx < semi_idx)
+                .then(|| format!("{} {}{}", &line[..eq_idx + 1], value, &line[semi_idx..]))
+        });
+        out.push(replacement.unwrap_or_else(|| line.to_string()));
+    }
+    let mut content = out.join("\n");
+    content.push('\n');
+    fs::write(path, content).expect("write constants file");
+}
+
+fn main() {
+    let cli = Cli::parse();
+    if cfg!(debug_assertions) {
+        println!("Warning: debug build. Use --release for real tuning throughput.");
+    }
+    if let Some(threads) = cli.threads {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build_global()
+            .expect("build thread pool");
+    }
+    ctrlc::set_handler(|| STOP.store(true, Ordering::SeqCst)).expect("install ctrl-c handler");
+
+    let specs = select_specs(&cli.params);
+    println!(
+        "Tuning {} params: {}",
+        specs.len(),
+        specs.iter().map(|s| s.name).collect::<Vec<_>>().join(", ")
+    );
+
+    let started = Instant::now();
+    let mut positions = Vec::new();
+    for path in &cli.data {
+        let loaded = load_positions(path, cli.skip_plies, cli.every);
+        println!("{}: {} positions", path.display(), loaded.len());
+        positions.extend(loaded);
+    }
+    if positions.is_empty() {
+        println!("No positions to tune on.");
+        return;
+    }
+    println!(
+        "Loaded {} quiet positions in {:.1}s",
+        positions.len(),
+        started.elapsed().as_secs_f64()
+    );
+
+    let selected: Vec<String> = specs.iter().map(|s| s.name.to_string()).collect();
+    let mut tuner = Tuner::new(specs, positions);
+    tuner.linearize();
+    match cli.k {
+        Some(k) => tuner.set_k(k),
+        None => {
+            tuner.fit_k();
+        }
+    }
+    let baseline_loss = tuner.loss();
+    println!("K = {:.4} | baseline loss {:.6}", tuner.k(), baseline_loss);
+
+    let mut history = Vec::new();
+    for epoch in 1..=cli.epochs {
+        if STOP.load(Ordering::SeqCst) {
+            println!("Stopped by user");
+            break;
+        }
+        tuner.step(cli.lr);
+        if cli.relinearize > 0 && epoch % cli.relinearize == 0 && epoch < cli.epochs {
+            tuner.linearize();
+        }
+        let loss = tuner.loss();
+        if epoch % 10 == 0 || epoch == cli.epochs {
+            println!("epoch {:>5} | loss {:.6}", epoch, loss);
+        }
+        history.push(EpochRecord { epoch, loss });
+    }
+
+    // Report the real loss at the final values rather than the model's.
+    tuner.linearize();
+    let loss = tuner.loss();
+    println!("Final loss {:.6} (baseline {:.6})", loss, baseline_loss);
+
+    let theta: BTreeMap<String, i64> = tuner
+        .values()
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value))
+        .collect();
+    for (name, value) in &theta {
+        println!("{:<28} {:>6}", name, value);
+    }
+
+    let results = Results {
+        theta,
+        k: tuner.k(),
+        baseline_loss,
+        loss,
+        positions: tuner.len(),
+        history,
+        selected,
+    };
+    if let Some(parent) = cli.results.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    fs::write(
+        &cli.results,
+        serde_json::to_string_pretty(&results).expect("serialize results"),
+    )
+    .expect("write results");
+    println!("Saved {}", cli.results.display());
+
+    if cli.apply {
+        apply_constants(EVAL_BASE_RS_PATH, &results.theta);
+        println!("Applied to {}", EVAL_BASE_RS_PATH);
+    }
+}
@@ -1,16 +1,57 @@
-//! UCI protocol bridge for the infinite-chess engine, constrained to standard 8x8 chess.
+//! UCI protocol bridge for the infinite-chess engine.
 //!
-//! Coordinate mapping:
+//! Two kinds of positions are accepted:
+//!   `position startpos` / `position fen <FEN>`  standard 8x8 chess, moves in UCI
+//!                                               long algebraic notation (`e2e4`, `e7e8q`)
+//!   `position icn <ICN>`                        any position, moves as site tokens
+//!                                               (`1,2>1,4`, `5,7>5,8=Q`)
+//!
+//! Both take an optional `moves ...` list in their own notation, and `bestmove`/`pv`
+//! are written back in the notation of the current position. Site-token coordinates
+//! are plain 64-bit integers, so positions far from the origin work unchanged.
+//!
+//! `setoption name UCI_Variant value <name>` makes `position startpos` set up that
+//! variant instead of standard chess (site-token notation unless the variant is Chess).
+//!
+//! The search runs on its own thread, so `stop`, `isready` and `ponderhit` are answered
+//! while it is thinking. `go ponder` searches untimed until `ponderhit` switches it to the
+//! clock given in the same `go` command. Every completed depth is reported as one `info`
+//! line per PV (`setoption name MultiPV value <n>` for more than one).
+//!
+//! Options: `Hash`, `Threads` (Lazy SMP helpers), `MultiPV`, `Skill Level` (the site's
+//! strength levels), `Move Overhead`, `Ponder`, `UCI_Variant`, `UCI_Chess960`,
+//! `SyzygyPath` (Syzygy tables, probed in 8x8 chess positions), `Variants` (a JSON file of
+//! extra variant definitions, see `apeiron::variant`) and `EvalProfiles` (a JSON file of
+//! per-variant eval parameters, used from the next `position`). Built with
+//! `search_tuning`, every tunable search parameter is also a spin option.
+//!
+//! With `UCI_Chess960` on, FEN castling fields may name rook files (Shredder-FEN `HAha`
+//! or X-FEN), castling is Fischer-random and castles are written as the king taking its
+//! own rook (`e1h1`).
+//!
+//! Coordinate mapping for algebraic notation:
 //!   UCI file a-h  <->  internal x 1-8
 //!   UCI rank 1-8  <->  internal y 1-8
-//!
-//! The world border is fixed at (1, 8, 1, 8) and the Chess variant starting ICN is used.
 
 use apeiron::board::PieceType;
+use apeiron::evaluation::{EvalProfiles, profiles};
 use apeiron::game::GameState;
-use apeiron::moves::set_world_bounds;
+use apeiron::moves::Move;
 use apeiron::search;
+#[cfg(feature = "search_tuning")]
+use apeiron::search::params;
+use apeiron::search::time::{
+    DEFAULT_MOVE_OVERHEAD_MS, TimeControl, TimeLimits, TimeManager, game_ply,
+};
+use apeiron::search::{MAX_SITE_SKILL, SearchLimits};
+use apeiron::tablebase::syzygy;
+use apeiron::variant::{self, Variant};
 use std::io::{self, BufRead, Write};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
 
 const ENGINE_NAME: &str = "Apeiron";
 const ENGINE_AUTHOR: &str = "FirePlank";
@@ -131,10 +172,7 @@ fn fen_to_icn(fen: &str) -> Result<String, String> {
     // In chess, rooks and kings on their starting squares get special rights (castling)
     // and pawns on their starting ranks get double-push rights.
     // We approximate this from FEN castling availability.
-    let white_ks = castling.contains('K');
-    let white_qs = castling.contains('Q');
-    let black_ks = castling.contains('k');
-    let black_qs = castling.contains('q');
+    let castling_rooks = castling_rook_squares(castling, &fen_ranks)?;
 
     // En passant square
     let ep_sq: Option<(i64, i64)> = if en_passant_str == "-" {
@@ -158,17 +196,8 @@ fn fen_to_icn(fen: &str) -> Result<String, String> {
                     (ch, false)
                 };
 
-                let (icn_code, has_special_right) = fen_char_to_icn(
-                    piece_char,
-                    is_white,
-                    x,
-                    y,
-                    white_ks,
-                    white_qs,
-                    black_ks,
-                    black_qs,
-                    ep_sq,
-                );
+                let (icn_code, has_special_right) =
+                    fen_char_to_icn(piece_char, is_white, x, y, &castling_rooks, ep_sq);
 
                 let token = if has_special_right {
                     format!("{}{},{}+", icn_code, x, y)
@@ -211,6 +240,67 @@ fn fen_to_icn(fen: &str) -> Result<String, String> {
     Ok(icn)
 }
 
+/// The squares of the rooks a FEN castling field grants rights to.
+///
+/// Accepts standard `KQkq`, X-FEN (`K`/`Q` name the outermost rook on that side of the
+/// king) and Shredder-FEN (`A`-`H`/`a`-`h` name the rook's file), so Chess960 positions
+/// keep castling with whichever rooks the field names.
+fn castling_rook_squares(castling: &str, fen_ranks: &[&str]) -> Result<Vec<(i64, i64)>, String> {
+    let back_rank = |rank_str: &str| -> Vec<char> {
+        let mut squares = Vec::with_capacity(8);
+        for ch in rank_str.chars() {
+            match ch.to_digit(10) {
+                Some(n) => squares.extend(std::iter::repeat_n(' ', n as usize)),
+                None => squares.push(ch),
+            }
+        }
+        squares
+    };
+    let white_rank = back_rank(fen_ranks[7]);
+    let black_rank = back_rank(fen_ranks[0]);
+
+    let mut rooks = Vec::new();
+    for c in castling.chars().filter(|&c| c != '-') {
+        let is_white = c.is_ascii_uppercase();
+        let (rank, y) = if is_white {
+            (&white_rank, 1)
+        } else {
+            (&black_rank, 8)
+        };
+        let (king, rook) = if is_white { ('K', 'R') } else { ('k', 'r') };
+        let is_rook = |x: i64| rank.get(x as usize - 1) == Some(&rook);
+
+        // A right whose king or rook is missing grants nothing, as with plain KQkq.
+        let x = match c.to_ascii_lowercase() {
+            side @ ('k' | 'q') => {
+                let Some(king_x) = rank.iter().position(|&p| p == king).map(|i| i as i64 + 1)
+                else {
+                    continue;
+                };
+                let outermost = if side == 'k' {
+                    (king_x + 1..=8).rev().find(|&x| is_rook(x))
+                } else {
+                    (1..king_x).find(|&x| is_rook(x))
+                };
+                match outermost {
+                    Some(x) => x,
+                    None => continue,
+                }
+            }
+            file @ 'a'..='h' => {
+                let x = file as i64 - 'a' as i64 + 1;
+                if !is_rook(x) {
+                    continue;
+                }
+                x
+            }
+            _ => return Err(format!("Invalid castling field: {}", castling)),
+        };
+        rooks.push((x, y));
+    }
+    Ok(rooks)
+}
+
 /// Map a FEN piece character + context to ICN piece code and whether it has special rights.
 /// Returns (icn_code_string, has_special_right).
 fn fen_char_to_icn(
@@ -218,10 +308,7 @@ fn fen_char_to_icn(
     is_white: bool,
     x: i64,
     y: i64,
-    white_ks: bool,
-    white_qs: bool,
-    black_ks: bool,
-    black_qs: bool,
+    castling_rooks: &[(i64, i64)],
     ep_sq: Option<(i64, i64)>,
 ) -> (String, bool) {
     let code = match piece_char {
@@ -281,22 +368,11 @@ fn fen_char_to_icn(
         }
         'k' => {
             // King has castling right if any castling right for that color exists
-            if is_white {
-                white_ks || white_qs
-            } else {
-                black_ks || black_qs
-            }
-        }
-        'r' => {
-            // Rook has castling right based on position
-            if is_white && y == 1 {
-                (x == 1 && white_qs) || (x == 8 && white_ks)
-            } else if !is_white && y == 8 {
-                (x == 1 && black_qs) || (x == 8 && black_ks)
-            } else {
-                false
-            }
+            let back_rank = if is_white { 1 } else { 8 };
+            castling_rooks.iter().any(|&(_, ry)| ry == back_rank)
         }
+        // Rook has castling right if the castling field names it
+        'r' => castling_rooks.contains(&(x, y)),
         _ => false,
     };
 
@@ -308,21 +384,136 @@ fn fen_char_to_icn(
 // State
 // ---------------------------------------------------------------------------
 
+/// How moves are read from `position ... moves` and written in `bestmove`/`pv`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Notation {
+    /// UCI long algebraic (`e2e4`), for 8x8 chess positions.
+    Algebraic,
+    /// Long algebraic with castling written as the king taking its own rook
+    /// (`e1h1`), for 8x8 positions under `UCI_Chess960`.
+    Chess960,
+    /// Site tokens (`5,2>5,4`, `5,7>5,8=Q`), for ICN positions.
+    Site,
+}
+
+impl Notation {
+    /// Write a move in this notation.
+    fn format(self, m: &Move) -> String {
+        match self {
+            Notation::Algebraic | Notation::Chess960 => {
+                let to = match m.rook_coord {
+                    Some(rook) if self == Notation::Chess960 => rook,
+                    _ => m.to,
+                };
+                let promo = m.promotion.map(promo_to_uci).map(String::from);
+                format!(
+                    "{}{}{}",
+                    xy_to_uci_sq(m.from.x, m.from.y),
+                    xy_to_uci_sq(to.x, to.y),
+                    promo.unwrap_or_default()
+                )
+            }
+            Notation::Site => apeiron::move_to_site_token(m),
+        }
+    }
+
+    /// The legal move of `game` written as `token` in this notation, if any.
+    fn find_move(self, game: &mut GameState, token: &str) -> Option<Move> {
+        match self {
+            Notation::Algebraic => {
+                let token = token.trim();
+                let (from_x, from_y) = uci_sq_to_xy(token.get(0..2)?)?;
+                let (to_x, to_y) = uci_sq_to_xy(token.get(2..4)?)?;
+                let promo = match token.chars().nth(4) {
+                    Some(c) => format!("={}", uci_promo_to_piece(c)?.to_site_code()),
+                    None => String::new(),
+                };
+                game.find_site_move(&format!("{},{}>{},{}{}", from_x, from_y, to_x, to_y, promo))
+            }
+            Notation::Chess960 => {
+                let token = token.trim();
+                let (from_x, from_y) = uci_sq_to_xy(token.get(0..2)?)?;
+                let (to_x, to_y) = uci_sq_to_xy(token.get(2..4)?)?;
+                let promotion = match token.chars().nth(4) {
+                    Some(c) => Some(uci_promo_to_piece(c)?),
+                    None => None,
+                };
+                // A king "taking" its own rook is a castle; anything else names its
+                // destination, and never a castle that happens to land there.
+                game.find_legal_move(|m| {
+                    (m.from.x, m.from.y) == (from_x, from_y)
+                        && m.promotion == promotion
+                        && match m.rook_coord {
+                            Some(rook) => (rook.x, rook.y) == (to_x, to_y),
+                            None => (m.to.x, m.to.y) == (to_x, to_y),
+                        }
+                })
+            }
+            Notation::Site => game.find_site_move(token),
+        }
+    }
+}
+
+/// Option values read by every `go`.
+#[derive(Clone, Copy)]
+struct SearchOptions {
+    /// Number of PV lines reported per depth (`MultiPV`).
+    multi_pv: usize,
+    /// Site skill level (`Skill Level`); below the maximum, moves come from the
+    /// strength-limited search.
+    skill: u32,
+    /// Per-move allowance for GUI latency (`Move Overhead`).
+    move_overhead_ms: u64,
+}
+
 struct UciState {
     game: GameState,
+    /// Variant set up by `position startpos` (the `UCI_Variant` option).
+    variant: Variant,
+    notation: Notation,
+    options: SearchOptions,
+    /// Fischer-random castling for FEN and `startpos` positions (`UCI_Chess960`).
+    chess960: bool,
 }
 
 impl UciState {
     fn new() -> Self {
-        set_world_bounds(1, 8, 1, 8);
-        let mut game = GameState::new();
-        game.setup_position_from_icn(CHESS_START_ICN);
-        UciState { game }
+        let mut state = UciState {
+            game: GameState::new(),
+            variant: Variant::Chess,
+            notation: Notation::Algebraic,
+            options: SearchOptions {
+                multi_pv: 1,
+                skill: MAX_SITE_SKILL,
+                move_overhead_ms: DEFAULT_MOVE_OVERHEAD_MS,
+            },
+            chess960: false,
+        };
+        state.reset_to_startpos();
+        state
+    }
+
+    /// Castling rules and move notation for an 8x8 position just set up.
+    fn use_algebraic(&mut self) {
+        self.game.game_rules.chess960 = self.chess960;
+        self.notation = if self.chess960 {
+            Notation::Chess960
+        } else {
+            Notation::Algebraic
+        };
     }
 
     fn reset_to_startpos(&mut self) {
         self.game = GameState::new();
-        self.game.setup_position_from_icn(CHESS_START_ICN);
+        if self.variant == Variant::Chess {
+            self.game.setup_position_from_icn(CHESS_START_ICN);
+            self.use_algebraic();
+        } else {
+            // Set before setup so the variant's eval profile is picked.
+            self.game.variant = Some(self.variant);
+            self.game.setup_position_from_icn(&self.variant.starting_icn());
+            self.notation = Notation::Site;
+        }
     }
 
     fn set_fen(&mut self, fen: &str) {
@@ -330,6 +521,7 @@ impl UciState {
             Ok(icn) => {
                 self.game = GameState::new();
                 self.game.setup_position_from_icn(&icn);
+                self.use_algebraic();
             }
             Err(e) => {
                 eprintln!("info string FEN parse error: {}", e);
@@ -337,8 +529,42 @@ impl UciState {
         }
     }
 
-    /// Apply a sequence of UCI moves (e.g. ["e2e4", "e7e5"]) to the current position.
+    /// Set up an arbitrary ICN position. The ICN is validated strictly, so a typo
+    /// is reported instead of silently producing a different position.
+    fn set_icn(&mut self, icn: &str) {
+        match GameState::try_from_icn(icn) {
+            Ok(game) => {
+                self.game = game;
+                self.notation = Notation::Site;
+            }
+            Err(e) => {
+                eprintln!("info string ICN parse error: {}", e);
+            }
+        }
+    }
+
+    /// Apply a sequence of moves in the notation of the current position.
     fn apply_moves(&mut self, moves: &[&str]) {
+        match self.notation {
+            Notation::Algebraic => self.apply_uci_moves(moves),
+            notation => {
+                for token in moves {
+                    match notation.find_move(&mut self.game, token) {
+                        Some(m) => {
+                            self.game.make_move(&m);
+                        }
+                        None => {
+                            eprintln!("info string illegal move: {}", token);
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Apply a sequence of UCI moves (e.g. ["e2e4", "e7e5"]) to the current position.
+    fn apply_uci_moves(&mut self, moves: &[&str]) {
         for mv_str in moves {
             let mv_str = mv_str.trim();
             if mv_str.len() < 4 {
@@ -387,10 +613,34 @@ struct GoParams {
     winc: Option<u64>,
     binc: Option<u64>,
     movetime: Option<u64>,
+    movestogo: Option<u32>,
     depth: Option<usize>,
+    nodes: Option<u64>,
+    /// `go mate N`: stop once a mate in at most N moves is found.
+    mate: Option<u32>,
+    /// `go searchmoves ...`: restrict the root to these moves (in the position's notation).
+    search_moves: Vec<String>,
     infinite: bool,
+    /// `go ponder`: search the predicted position until `ponderhit` or `stop`.
+    ponder: bool,
 }
 
+/// Every keyword of `go`, which ends a `searchmoves` list.
+const GO_KEYWORDS: &[&str] = &[
+    "searchmoves",
+    "ponder",
+    "wtime",
+    "btime",
+    "winc",
+    "binc",
+    "movestogo",
+    "depth",
+    "nodes",
+    "mate",
+    "movetime",
+    "infinite",
+];
+
 impl GoParams {
     fn parse(tokens: &[&str]) -> Self {
         let mut p = GoParams {
@@ -399,8 +649,13 @@ impl GoParams {
             winc: None,
             binc: None,
             movetime: None,
+            movestogo: None,
             depth: None,
+            nodes: None,
+            mate: None,
+            search_moves: Vec::new(),
             infinite: false,
+            ponder: false,
         };
         let mut i = 0;
         while i < tokens.len() {
@@ -425,13 +680,34 @@ impl GoParams {
                     i += 1;
                     p.movetime = tokens.get(i).and_then(|s| s.parse().ok());
                 }
+                "movestogo" => {
+                    i += 1;
+                    p.movestogo = tokens.get(i).and_then(|s| s.parse().ok());
+                }
                 "depth" => {
                     i += 1;
                     p.depth = tokens.get(i).and_then(|s| s.parse().ok());
                 }
+                "nodes" => {
+                    i += 1;
+                    p.nodes = tokens.get(i).and_then(|s| s.parse().ok());
+                }
+                "mate" => {
+                    i += 1;
+                    p.mate = tokens.get(i).and_then(|s| s.parse().ok());
+                }
+                "searchmoves" => {
+                    while let Some(mv) = tokens.get(i + 1).filter(|t| !GO_KEYWORDS.contains(*t)) {
+                        p.search_moves.push(mv.to_string());
+                        i += 1;
+                    }
+                }
                 "infinite" => {
                     p.infinite = true;
                 }
+                "ponder" => {
+                    p.ponder = true;
+                }
                 _ => {}
             }
             i += 1;
@@ -440,125 +716,178 @@ impl GoParams {
     }
 }
 
-fn run_go(state: &mut UciState, params: GoParams) {
-    use apeiron::board::PlayerColor;
+/// Thinking time for `params`, allocated by the shared [`TimeManager`] for clocked moves.
+fn time_limits(game: &GameState, params: &GoParams, move_overhead_ms: u64) -> TimeLimits {
+    if params.infinite {
+        TimeLimits::INFINITE
+    } else if let Some(mt) = params.movetime {
+        TimeLimits::movetime(mt as u128)
+    } else if (params.depth.is_some() || params.nodes.is_some() || params.mate.is_some())
+        && params.wtime.is_none()
+        && params.btime.is_none()
+    {
+        // Fixed depth/nodes/mate, no clock: treat as infinite time
+        TimeLimits::INFINITE
+    } else {
+        TimeControl::for_side(
+            game.turn,
+            params.wtime.unwrap_or(0),
+            params.btime.unwrap_or(0),
+            params.winc.unwrap_or(0),
+            params.binc.unwrap_or(0),
+            params.movestogo,
+        )
+        .and_then(|tc| TimeManager::allocate(&tc, game_ply(game), move_overhead_ms))
+        .unwrap_or(TimeLimits::fixed(5000)) // fallback: 5 seconds
+    }
+}
+
+/// Formats a search score as a UCI `score` value (`cp N` or `mate N`).
+fn uci_score(eval: i32) -> String {
+    if eval > search::MATE_SCORE {
+        let mate_in = (search::MATE_VALUE - eval + 1) / 2;
+        format!("mate {}", mate_in)
+    } else if eval < -search::MATE_SCORE {
+        let mate_in = (search::MATE_VALUE + eval + 1) / 2;
+        format!("mate -{}", mate_in)
+    } else {
+        format!("cp {}", eval)
+    }
+}
 
-    // Set clock on a temporary Engine wrapper via search directly.
-    // We call search_native equivalent by building time limits ourselves.
+/// Where protocol replies go: stdout in the binary, a buffer in the tests.
+#[derive(Clone)]
+struct Output(Arc<Mutex<dyn Write + Send>>);
+
+impl Output {
+    fn stdout() -> Self {
+        Output(Arc::new(Mutex::new(io::stdout())))
+    }
+
+    /// Writes one reply line and flushes it.
+    fn line(&self, line: impl std::fmt::Display) {
+        let mut out = self.0.lock().unwrap();
+        let _ = writeln!(out, "{}", line);
+        let _ = out.flush();
+    }
+}
+
+/// Writes one `info` line per PV line of a completed depth.
+fn print_depth_info(info: &search::DepthInfo, notation: Notation, out: &Output) {
+    for (idx, line) in info.lines.iter().enumerate() {
+        let pv = line
+            .pv
+            .iter()
+            .map(|m| notation.format(m))
+            .collect::<Vec<_>>()
+            .join(" ");
+        out.line(format_args!(
+            "info depth {} seldepth {} multipv {} score {} nodes {} nps {} hashfull {} time {} pv {}",
+            info.depth,
+            info.seldepth.max(info.depth),
+            idx + 1,
+            uci_score(line.score),
+            info.nodes,
+            info.nps,
+            info.hashfull,
+            info.time_ms,
+            pv,
+        ));
+    }
+}
+
+/// Searches `game`, streaming `info` lines per completed depth, then writes `bestmove`.
+/// While `signals` holds the result back (pondering / `go infinite`), `bestmove` waits
+/// for `ponderhit` or `stop`.
+fn run_go(
+    game: &mut GameState,
+    notation: Notation,
+    options: SearchOptions,
+    params: &GoParams,
+    signals: &Signals,
+    out: &Output,
+) {
     let max_depth = params.depth.unwrap_or(50).clamp(1, 100);
 
-    // Compute time limits (mirrors Engine::effective_time_limit_ms logic, simplified).
-    let (opt_ms, max_ms, is_soft): (u128, u128, bool) = if params.infinite {
-        (u128::MAX, u128::MAX, true)
-    } else if let Some(mt) = params.movetime {
-        let ms = mt as u128;
-        (ms, ms, false)
-    } else if params.depth.is_some() && params.wtime.is_none() && params.btime.is_none() {
-        // Fixed depth, no clock: treat as infinite time
-        (u128::MAX, u128::MAX, true)
+    // A ponder search runs untimed; ponderhit schedules the stop (see `Session::handle`).
+    let limits = if params.ponder {
+        TimeLimits::INFINITE
     } else {
-        // Clock-based time allocation
-        let (remaining_ms_raw, inc_ms_raw) = match state.game.turn {
-            PlayerColor::White => (
-                params.wtime.unwrap_or(0),
-                params.winc.unwrap_or(0),
-            ),
-            PlayerColor::Black => (
-                params.btime.unwrap_or(0),
-                params.binc.unwrap_or(0),
-            ),
-            PlayerColor::Neutral => (0, 0),
-        };
-
-        if remaining_ms_raw == 0 && inc_ms_raw == 0 {
-            (5000, 5000, true) // fallback: 5 seconds
-        } else {
-            let move_overhead: u64 = 50;
-            let remaining_ms = if remaining_ms_raw > 0 {
-                remaining_ms_raw
-            } else {
-                inc_ms_raw.max(500)
-            };
-            let scaled_time = remaining_ms.saturating_sub(move_overhead);
-            let centi_mtg: i64 = if scaled_time >= 1000 { 5051 } else { (scaled_time as f64 * 5.051) as i64 }.max(100);
-            let time_left = (remaining_ms as i64
-                + (inc_ms_raw as i64 * (centi_mtg - 100) - move_overhead as i64 * (200 + centi_mtg)) / 100)
-                .max(1) as f64;
-            let log_adj = (0.3128 * time_left.max(1.0).log10() - 0.4354).max(0.1);
-            let log_time_sec = (scaled_time as f64 / 1000.0).max(0.001).log10();
-            let opt_constant = (0.0032116 + 0.000321123 * log_time_sec).min(0.00508017);
-            let max_constant = (3.3977 + 3.0395 * log_time_sec).max(2.94761);
-            let ply = state.game.fullmove_number.saturating_sub(1).saturating_mul(2) as f64
-                + if state.game.turn == PlayerColor::Black { 1.0 } else { 0.0 };
-            let opt_scale = ((0.0121431 + (ply + 2.94693_f64).powf(0.461073) * opt_constant)
-                .min(0.213035 * remaining_ms as f64 / time_left))
-                * log_adj;
-            let max_scale = max_constant.min(6.67704) + ply / 11.9847;
-            let optimum = (opt_scale * time_left) as u64;
-            let maximum = ((max_scale * optimum as f64)
-                .min(0.825179 * remaining_ms as f64 - move_overhead as f64) - 10.0)
-                .max(0.0) as u64;
-            let min_think: u64 = 10;
-            let optimum = optimum.max(min_think);
-            let maximum = maximum.max(optimum);
-            let abs_cap = ((remaining_ms as f64) * 0.825 - move_overhead as f64) as u64;
-            let optimum = optimum.min(abs_cap.max(min_think));
-            let maximum = maximum.min(abs_cap.max(min_think));
-            (optimum as u128, maximum as u128, false)
-        }
+        time_limits(game, params, options.move_overhead_ms)
     };
+    let (opt_ms, max_ms, is_soft) = limits.as_tuple();
 
     // Initialize randomness
     search::set_global_params(get_random_seed(), None);
 
-    let result = search::get_best_move_parallel(
-        &mut state.game,
+    if options.skill < MAX_SITE_SKILL {
+        // The weakened search picks among its root lines once it is done, so there are no
+        // per-depth lines to stream and the node/mate/searchmoves limits don't apply.
+        let result = search::with_global_search(|search| {
+            search.get_best_move_weakened(
+                game,
+                max_depth,
+                opt_ms,
+                max_ms,
+                options.skill,
+                true,
+                is_soft,
+            )
+        });
+        signals.wait_for_release();
+        match result {
+            Some((m, score, stats)) => {
+                out.line(format_args!(
+                    "info score {} nodes {}",
+                    uci_score(score),
+                    stats.nodes
+                ));
+                out.line(format_args!("bestmove {}", notation.format(&m)));
+            }
+            None => out.line("bestmove 0000"),
+        }
+        return;
+    }
+
+    let mut search_moves = Vec::new();
+    for token in &params.search_moves {
+        match notation.find_move(game, token) {
+            Some(m) => search_moves.push(m),
+            None => eprintln!("info string illegal searchmove: {}", token),
+        }
+    }
+    let search_limits = SearchLimits {
+        nodes: params.nodes,
+        mate: params.mate,
+        search_moves,
+    };
+
+    let result = search::search_streamed(
+        game,
         max_depth,
         opt_ms,
         max_ms,
-        true, // silent: we emit UCI info ourselves to stdout
+        options.multi_pv,
         is_soft,
+        &search_limits,
+        &mut |info: &search::DepthInfo| print_depth_info(info, notation, out),
     );
 
-    match result {
-        Some((best_move, eval, stats)) => {
-            let completed_depth = search::get_completed_depth().max(1);
-
-            let score_str = if eval > search::MATE_SCORE {
-                let mate_in = (search::MATE_VALUE - eval + 1) / 2;
-                format!("mate {}", mate_in)
-            } else if eval < -search::MATE_SCORE {
-                let mate_in = (search::MATE_VALUE + eval + 1) / 2;
-                format!("mate -{}", mate_in)
-            } else {
-                format!("cp {}", eval)
-            };
-
-            let from_uci = xy_to_uci_sq(best_move.from.x, best_move.from.y);
-            let to_uci = xy_to_uci_sq(best_move.to.x, best_move.to.y);
-            let promo_str = best_move
-                .promotion
-                .map(promo_to_uci)
-                .map(|c| c.to_string())
-                .unwrap_or_default();
-            let bm_uci = format!("{}{}{}", from_uci, to_uci, promo_str);
-
-            println!(
-                "info depth {} score {} nodes {} hashfull {} pv {}",
-                completed_depth,
-                score_str,
-                stats.nodes,
-                stats.tt_fill_permille,
-                bm_uci,
-            );
-            println!("bestmove {}", bm_uci);
-        }
+    signals.wait_for_release();
+    match result.lines.first() {
+        Some(best) => match best.pv.get(1) {
+            Some(reply) => out.line(format_args!(
+                "bestmove {} ponder {}",
+                notation.format(&best.mv),
+                notation.format(reply)
+            )),
+            None => out.line(format_args!("bestmove {}", notation.format(&best.mv))),
+        },
         None => {
             // No legal moves (checkmate / stalemate)
-            println!("bestmove 0000");
+            out.line("bestmove 0000");
         }
     }
-    let _ = io::stdout().flush();
 }
 
 fn get_random_seed() -> u64 {
@@ -569,69 +898,471 @@ fn get_random_seed() -> u64 {
         .as_nanos() as u64
 }
 
+// ---------------------------------------------------------------------------
+// Search thread
+// ---------------------------------------------------------------------------
+
+/// Work for the search thread. The free search functions keep their searcher and TT in
+/// thread-local storage, so everything that touches them runs on that one thread.
+enum Job {
+    Go {
+        game: Box<GameState>,
+        notation: Notation,
+        options: SearchOptions,
+        params: GoParams,
+    },
+    NewGame,
+    SetHash(usize),
+    SetThreads(usize),
+    /// Writes `readyok` once every earlier job has been handled.
+    Ready,
+}
+
+/// Search status shared between the stdin loop and the search thread.
+///
+/// The stop flag is only ever cleared in [`Signals::start_search`], before the search is
+/// handed to the search thread, so a single stop request always reaches it.
+struct Signals {
+    /// Set from `go` until its `bestmove` has been written.
+    searching: AtomicBool,
+    /// Bumped by every `go`, so a pending ponderhit deadline can't stop a later search.
+    search_id: AtomicU64,
+    /// While set, `bestmove` is held back: after `go ponder` until `ponderhit` or `stop`,
+    /// after `go infinite` until `stop`. The lock also orders every change of `searching`,
+    /// `search_id` and the stop flag.
+    hold: Mutex<bool>,
+    /// Notified when the hold is lifted and when a search finishes.
+    released: Condvar,
+}
+
+impl Signals {
+    fn new() -> Self {
+        Signals {
+            searching: AtomicBool::new(false),
+            search_id: AtomicU64::new(0),
+            hold: Mutex::new(false),
+            released: Condvar::new(),
+        }
+    }
+
+    /// Readies a new search: withdraws the last stop request and marks the search as
+    /// running. Called before the search is handed to the search thread, so a `stop`
+    /// right after `go` can't be withdrawn again.
+    fn start_search(&self, hold: bool) {
+        let mut held = self.hold.lock().unwrap();
+        search::global_context().clear_stop();
+        *held = hold;
+        self.search_id.fetch_add(1, Ordering::AcqRel);
+        self.searching.store(true, Ordering::Release);
+    }
+
+    /// Marks the running search as finished, once its `bestmove` is written.
+    fn finish_search(&self) {
+        let _held = self.hold.lock().unwrap();
+        self.searching.store(false, Ordering::Release);
+        self.released.notify_all();
+    }
+
+    fn release(&self) {
+        *self.hold.lock().unwrap() = false;
+        self.released.notify_all();
+    }
+
+    fn wait_for_release(&self) {
+        let mut hold = self.hold.lock().unwrap();
+        while *hold {
+            hold = self.released.wait(hold).unwrap();
+        }
+    }
+
+    fn is_searching(&self) -> bool {
+        self.searching.load(Ordering::Acquire)
+    }
+
+    /// Stops the running search, if any, and waits until its `bestmove` is written.
+    fn stop_search(&self) {
+        let mut hold = self.hold.lock().unwrap();
+        if !self.is_searching() {
+            return;
+        }
+        *hold = false;
+        self.released.notify_all();
+        search::global_context().request_stop();
+        while self.is_searching() {
+            hold = self.released.wait(hold).unwrap();
+        }
+    }
+
+    /// Stops search `id` if it is still the one running (the ponderhit deadline).
+    fn stop_if_current(&self, id: u64) {
+        let _held = self.hold.lock().unwrap();
+        if self.is_searching() && self.search_id.load(Ordering::Acquire) == id {
+            search::global_context().request_stop();
+        }
+    }
+}
+
+fn search_thread(jobs: Receiver<Job>, signals: Arc<Signals>, out: Output) {
+    for job in jobs {
+        match job {
+            Job::Go {
+                mut game,
+                notation,
+                options,
+                params,
+            } => {
+                run_go(&mut game, notation, options, &params, &signals, &out);
+                signals.finish_search();
+            }
+            Job::NewGame => search::reset_search_state(),
+            Job::SetHash(mb) => search::set_tt_size_mb(mb),
+            Job::SetThreads(threads) => search::set_threads(threads),
+            Job::Ready => out.line("readyok"),
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Main loop
 // ---------------------------------------------------------------------------
 
-fn main() {
-    let stdin = io::stdin();
-    let mut state = UciState::new();
+/// The stdin side of the protocol: the position being set up and the search thread's
+/// job queue and signals.
+struct Session {
+    state: UciState,
+    signals: Arc<Signals>,
+    jobs: Sender<Job>,
+    out: Output,
+    /// Time budget to apply once the running ponder search gets a `ponderhit`.
+    ponder_budget_ms: Option<u128>,
+}
 
-    for line in stdin.lock().lines() {
-        let line = match line {
-            Ok(l) => l,
-            Err(_) => break,
-        };
-        let line = line.trim().to_string();
-        if line.is_empty() {
-            continue;
+impl Session {
+    fn new(signals: Arc<Signals>, jobs: Sender<Job>, out: Output) -> Self {
+        Session {
+            state: UciState::new(),
+            signals,
+            jobs,
+            out,
+            ponder_budget_ms: None,
         }
+    }
 
+    /// Handles one command line. Returns false on `quit`.
+    fn handle(&mut self, line: &str) -> bool {
         let tokens: Vec<&str> = line.split_whitespace().collect();
-        if tokens.is_empty() {
-            continue;
-        }
+        let Some(&command) = tokens.first() else {
+            return true;
+        };
 
-        match tokens[0] {
+        match command {
             "uci" => {
-                println!("id name {}", ENGINE_NAME);
-                println!("id author {}", ENGINE_AUTHOR);
-                println!("option name Hash type spin default 64 min 1 max 65536");
-                println!("uciok");
-                let _ = io::stdout().flush();
+                self.out.line(format_args!("id name {}", ENGINE_NAME));
+                self.out.line(format_args!("id author {}", ENGINE_AUTHOR));
+                print_options(&self.out);
+                self.out.line("uciok");
             }
             "isready" => {
-                println!("readyok");
-                let _ = io::stdout().flush();
+                if self.signals.is_searching() {
+                    // The search thread is busy; the stdin loop itself is ready.
+                    self.out.line("readyok");
+                } else {
+                    let _ = self.jobs.send(Job::Ready);
+                }
             }
             "ucinewgame" => {
-                search::reset_search_state();
-                state.reset_to_startpos();
+                self.signals.stop_search();
+                let _ = self.jobs.send(Job::NewGame);
+                self.state.reset_to_startpos();
+            }
+            "setoption" => {
+                handle_setoption(&mut self.state, &self.jobs, &tokens[1..]);
             }
             "position" => {
-                handle_position(&mut state, &tokens[1..]);
+                handle_position(&mut self.state, &tokens[1..]);
             }
             "go" => {
+                self.signals.stop_search();
                 let params = GoParams::parse(&tokens[1..]);
-                run_go(&mut state, params);
+                let state = &self.state;
+                self.ponder_budget_ms = params.ponder.then(|| {
+                    time_limits(&state.game, &params, state.options.move_overhead_ms).optimum_ms
+                });
+                self.signals.start_search(params.ponder || params.infinite);
+                let _ = self.jobs.send(Job::Go {
+                    game: Box::new(self.state.game.clone()),
+                    notation: self.state.notation,
+                    options: self.state.options,
+                    params,
+                });
             }
-            "stop" => {
-                // Signal the search to stop (if running in a thread in the future)
-                // For now the search is synchronous so this is a no-op.
+            "ponderhit" => {
+                // The predicted move was played: keep searching, now on the clock.
+                if let Some(budget_ms) = self.ponder_budget_ms.take() {
+                    self.signals.release();
+                    if budget_ms != u128::MAX {
+                        let signals = Arc::clone(&self.signals);
+                        let id = signals.search_id.load(Ordering::Acquire);
+                        thread::spawn(move || {
+                            thread::sleep(Duration::from_millis(budget_ms as u64));
+                            signals.stop_if_current(id);
+                        });
+                    }
+                }
             }
-            "quit" => {
-                break;
+            "stop" => {
+                self.ponder_budget_ms = None;
+                self.signals.stop_search();
             }
+            "quit" => return false,
             _ => {
                 // Unknown command - ignore per UCI spec
             }
         }
+        true
+    }
+}
+
+fn main() {
+    let out = Output::stdout();
+    let signals = Arc::new(Signals::new());
+    let (jobs, job_rx) = mpsc::channel();
+    let worker = {
+        let signals = Arc::clone(&signals);
+        let out = out.clone();
+        thread::spawn(move || search_thread(job_rx, signals, out))
+    };
+    let mut session = Session::new(Arc::clone(&signals), jobs, out);
+
+    for line in io::stdin().lock().lines() {
+        let Ok(line) = line else { break };
+        if !session.handle(line.trim()) {
+            break;
+        }
+    }
+
+    signals.stop_search();
+    drop(session);
+    let _ = worker.join();
+}
+
+// ---------------------------------------------------------------------------
+// Options
+// ---------------------------------------------------------------------------
+
+/// Type and default of a UCI option, as advertised in reply to `uci`.
+enum OptionKind {
+    Spin { default: i64, min: i64, max: i64 },
+    Check { default: bool },
+    String { default: &'static str },
+}
+
+struct UciOption {
+    name: &'static str,
+    kind: OptionKind,
+}
+
+/// Every option the bridge understands. `setoption` matches names case-insensitively.
+const OPTIONS: &[UciOption] = &[
+    UciOption {
+        name: "Hash",
+        kind: OptionKind::Spin {
+            default: 64,
+            min: 1,
+            max: 64,
+        },
+    },
+    UciOption {
+        name: "Threads",
+        kind: OptionKind::Spin {
+            default: 1,
+            min: 1,
+            max: search::MAX_SEARCH_THREADS as i64,
+        },
+    },
+    UciOption {
+        name: "MultiPV",
+        kind: OptionKind::Spin {
+            default: 1,
+            min: 1,
+            max: 256,
+        },
+    },
+    UciOption {
+        name: "Skill Level",
+        kind: OptionKind::Spin {
+            default: MAX_SITE_SKILL as i64,
+            min: 1,
+            max: MAX_SITE_SKILL as i64,
+        },
+    },
+    UciOption {
+        name: "Move Overhead",
+        kind: OptionKind::Spin {
+            default: DEFAULT_MOVE_OVERHEAD_MS as i64,
+            min: 0,
+            max: 5000,
+        },
+    },
+    UciOption {
+        name: "Ponder",
+        kind: OptionKind::Check { default: false },
+    },
+    UciOption {
+        name: "UCI_Variant",
+        kind: OptionKind::String { default: "Chess" },
+    },
+    UciOption {
+        name: "UCI_Chess960",
+        kind: OptionKind::Check { default: false },
+    },
+    UciOption {
+        name: "SyzygyPath",
+        kind: OptionKind::String { default: "<empty>" },
+    },
+    UciOption {
+        name: "Variants",
+        kind: OptionKind::String { default: "<empty>" },
+    },
+    UciOption {
+        name: "EvalProfiles",
+        kind: OptionKind::String { default: "<empty>" },
+    },
+];
+
+/// Writes an `option` line per entry of [`OPTIONS`], plus one per search parameter when
+/// built with `search_tuning`, so a tuner can drive this binary directly.
+fn print_options(out: &Output) {
+    for option in OPTIONS {
+        match option.kind {
+            OptionKind::Spin { default, min, max } => out.line(format_args!(
+                "option name {} type spin default {} min {} max {}",
+                option.name, default, min, max
+            )),
+            OptionKind::Check { default } => out.line(format_args!(
+                "option name {} type check default {}",
+                option.name, default
+            )),
+            OptionKind::String { default } => out.line(format_args!(
+                "option name {} type string default {}",
+                option.name, default
+            )),
+        }
+    }
+    #[cfg(feature = "search_tuning")]
+    for spec in params::TUNABLE_PARAM_SPECS {
+        out.line(format_args!(
+            "option name {} type spin default {} min {} max {}",
+            spec.name, spec.default, spec.min, spec.max
+        ));
+    }
+}
+
+fn handle_setoption(state: &mut UciState, jobs: &Sender<Job>, tokens: &[&str]) {
+    // Syntax: setoption name <id> [value <x>]  (ids and values may contain spaces)
+    let name_idx = tokens.iter().position(|&t| t == "name");
+    let value_idx = tokens.iter().position(|&t| t == "value");
+    let Some(name_idx) = name_idx else {
+        return;
+    };
+    let name_end = value_idx.unwrap_or(tokens.len());
+    let name = tokens[name_idx + 1..name_end.max(name_idx + 1)].join(" ");
+    let value = value_idx.map(|i| tokens[i + 1..].join(" ")).unwrap_or_default();
+
+    let Some(option) = OPTIONS.iter().find(|o| o.name.eq_ignore_ascii_case(&name)) else {
+        #[cfg(feature = "search_tuning")]
+        if let Some(spec) = params::TUNABLE_PARAM_SPECS
+            .iter()
+            .find(|spec| spec.name.eq_ignore_ascii_case(&name))
+        {
+            match value.parse::<i64>() {
+                Ok(v) => {
+                    params::set_search_param(spec.name, v);
+                }
+                Err(_) => eprintln!("info string invalid {} value: {}", name, value),
+            }
+            return;
+        }
+        eprintln!("info string unknown option: {}", name);
+        return;
+    };
+
+    // Spin values are clamped into the advertised range.
+    let spin = match option.kind {
+        OptionKind::Spin { min, max, .. } => match value.parse::<i64>() {
+            Ok(v) => v.clamp(min, max),
+            Err(_) => {
+                eprintln!("info string invalid {} value: {}", option.name, value);
+                return;
+            }
+        },
+        _ => 0,
+    };
+
+    match option.name {
+        "Hash" => {
+            let _ = jobs.send(Job::SetHash(spin as usize));
+        }
+        "Threads" => {
+            let _ = jobs.send(Job::SetThreads(spin as usize));
+        }
+        "MultiPV" => state.options.multi_pv = spin as usize,
+        "Skill Level" => state.options.skill = spin as u32,
+        "Move Overhead" => state.options.move_overhead_ms = spin as u64,
+        // The GUI only sends `go ponder` when pondering is on; nothing to configure here.
+        "Ponder" => {}
+        "UCI_Variant" => match Variant::try_parse(&value) {
+            Some(variant) => {
+                state.variant = variant;
+                state.reset_to_startpos();
+            }
+            None => eprintln!("info string unknown variant: {}", value),
+        },
+        // Takes effect from the next `position` command.
+        "UCI_Chess960" => state.chess960 = value.eq_ignore_ascii_case("true"),
+        "SyzygyPath" => {
+            let found = syzygy::set_path(&value);
+            eprintln!("info string found {} Syzygy files", found);
+        }
+        "Variants" => {
+            let path = value.trim();
+            if path.is_empty() || path == "<empty>" {
+                return;
+            }
+            match variant::load_file(path) {
+                Ok(added) => eprintln!("info string registered {} variants", added.len()),
+                Err(e) => eprintln!("info string invalid variants {}: {}", path, e),
+            }
+        }
+        "EvalProfiles" => {
+            let path = value.trim();
+            if path.is_empty() || path == "<empty>" {
+                profiles::clear();
+                return;
+            }
+            match std::fs::read_to_string(path)
+                .map_err(|e| e.to_string())
+                .and_then(|json| EvalProfiles::from_json(&json))
+            {
+                Ok(loaded) => {
+                    eprintln!(
+                        "info string loaded {} variant and {} kind eval profiles",
+                        loaded.variants.len(),
+                        loaded.kinds.len()
+                    );
+                    profiles::load(loaded);
+                }
+                Err(e) => eprintln!("info string invalid eval profiles {}: {}", path, e),
+            }
+        }
+        _ => eprintln!("info string option {} has no handler", option.name),
     }
 }
 
 fn handle_position(state: &mut UciState, tokens: &[&str]) {
     // Syntax: position startpos [moves m1 m2 ...]
     //         position fen <fen_string> [moves m1 m2 ...]
+    //         position icn <icn_string> [moves t1 t2 ...]
     if tokens.is_empty() {
         return;
     }
@@ -648,6 +1379,11 @@ fn handle_position(state: &mut UciState, tokens: &[&str]) {
             let fen_str = tokens[1..fen_end].join(" ");
             state.set_fen(&fen_str);
         }
+        "icn" => {
+            // The ICN occupies tokens[1..moves_idx] (or tokens[1..] if no moves keyword)
+            let icn_end = moves_idx.unwrap_or(tokens.len());
+            state.set_icn(&tokens[1..icn_end].join(" "));
+        }
         _ => {
             // Try treating the whole thing as a FEN
             let fen_end = moves_idx.unwrap_or(tokens.len());
@@ -661,3 +1397,354 @@ fn handle_position(state: &mut UciState, tokens: &[&str]) {
         state.apply_moves(move_list);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{MutexGuard, PoisonError};
+    use std::time::Instant;
+
+    /// Sessions search on the process-wide stop flag, so the tests run them one at a time.
+    static SERIAL: Mutex<()> = Mutex::new(());
+
+    /// Sends every reply line down a channel.
+    struct Lines {
+        pending: Vec<u8>,
+        tx: Sender<String>,
+    }
+
+    impl Write for Lines {
+        fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+            self.pending.extend_from_slice(data);
+            while let Some(end) = self.pending.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = self.pending.drain(..=end).collect();
+                let _ = self
+                    .tx
+                    .send(String::from_utf8_lossy(&line[..end]).into_owned());
+            }
+            Ok(data.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// A session wired to its own search thread, as in `main`.
+    struct Harness {
+        session: Session,
+        replies: Receiver<String>,
+        worker: Option<thread::JoinHandle<()>>,
+        _serial: MutexGuard<'static, ()>,
+    }
+
+    impl Harness {
+        fn new() -> Self {
+            let serial = SERIAL.lock().unwrap_or_else(PoisonError::into_inner);
+            let (tx, replies) = mpsc::channel();
+            let out = Output(Arc::new(Mutex::new(Lines {
+                pending: Vec::new(),
+                tx,
+            })));
+            let signals = Arc::new(Signals::new());
+            let (jobs, job_rx) = mpsc::channel();
+            let worker = {
+                let signals = Arc::clone(&signals);
+                let out = out.clone();
+                thread::spawn(move || search_thread(job_rx, signals, out))
+            };
+            Harness {
+                session: Session::new(signals, jobs, out),
+                replies,
+                worker: Some(worker),
+                _serial: serial,
+            }
+        }
+
+        fn send(&mut self, commands: &[&str]) {
+            for command in commands {
+                assert!(self.session.handle(command));
+            }
+        }
+
+        /// Replies written so far, without waiting.
+        fn replies_now(&self) -> Vec<String> {
+            self.replies.try_iter().collect()
+        }
+
+        /// Replies up to the first `bestmove`, which must come within `timeout`.
+        fn replies_until_bestmove(&self, timeout: Duration) -> Vec<String> {
+            let deadline = Instant::now() + timeout;
+            let mut lines = Vec::new();
+            loop {
+                let left = deadline.saturating_duration_since(Instant::now());
+                let line = self
+                    .replies
+                    .recv_timeout(left)
+                    .expect("no bestmove in time");
+                let done = line.starts_with("bestmove ");
+                lines.push(line);
+                if done {
+                    return lines;
+                }
+            }
+        }
+
+        /// Every reply until the search thread is idle, checked with an `isready` queued
+        /// behind all earlier jobs.
+        fn replies_until_idle(&mut self) -> Vec<String> {
+            let deadline = Instant::now() + Duration::from_secs(30);
+            while self.session.signals.is_searching() {
+                assert!(Instant::now() < deadline, "the search never finished");
+                thread::sleep(Duration::from_millis(5));
+            }
+            self.send(&["isready"]);
+            let mut lines = Vec::new();
+            loop {
+                let line = self
+                    .replies
+                    .recv_timeout(Duration::from_secs(30))
+                    .expect("no readyok");
+                if line == "readyok" {
+                    return lines;
+                }
+                lines.push(line);
+            }
+        }
+    }
+
+    impl Drop for Harness {
+        fn drop(&mut self) {
+            assert!(!self.session.handle("quit"));
+            self.session.signals.stop_search();
+            // Dropping the job queue ends the search thread.
+            self.session.jobs = mpsc::channel().0;
+            if let Some(worker) = self.worker.take() {
+                let _ = worker.join();
+            }
+        }
+    }
+
+    fn bestmoves(lines: &[String]) -> Vec<&str> {
+        lines
+            .iter()
+            .filter(|line| line.starts_with("bestmove "))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Applies one `setoption` line to `state`, returning the jobs it queued.
+    fn set_option(state: &mut UciState, line: &str) -> Vec<Job> {
+        let (jobs, queued) = mpsc::channel();
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        handle_setoption(state, &jobs, &tokens);
+        queued.try_iter().collect()
+    }
+
+    fn hash_jobs(jobs: &[Job]) -> Vec<usize> {
+        jobs.iter()
+            .filter_map(|job| match job {
+                Job::SetHash(mb) => Some(*mb),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_spin_options_are_clamped() {
+        let mut state = UciState::new();
+        assert_eq!(
+            hash_jobs(&set_option(&mut state, "name Hash value 32")),
+            [32]
+        );
+        assert_eq!(hash_jobs(&set_option(&mut state, "name Hash value 0")), [1]);
+        assert_eq!(
+            hash_jobs(&set_option(&mut state, "name Hash value 100000")),
+            [64]
+        );
+        assert_eq!(
+            hash_jobs(&set_option(&mut state, "name hash value -5")),
+            [1]
+        );
+        // Not a number: reported, nothing queued.
+        assert!(set_option(&mut state, "name Hash value lots").is_empty());
+        assert!(set_option(&mut state, "name Hash").is_empty());
+
+        set_option(&mut state, "name MultiPV value 0");
+        assert_eq!(state.options.multi_pv, 1);
+        set_option(&mut state, "name multipv value 1000");
+        assert_eq!(state.options.multi_pv, 256);
+        set_option(&mut state, "name Skill Level value 3");
+        assert_eq!(state.options.skill, 3);
+        set_option(&mut state, "name Skill Level value 99");
+        assert_eq!(state.options.skill, MAX_SITE_SKILL);
+        set_option(&mut state, "name Move Overhead value 7000");
+        assert_eq!(state.options.move_overhead_ms, 5000);
+        set_option(&mut state, "name Move Overhead value 25");
+        assert_eq!(state.options.move_overhead_ms, 25);
+
+        let threads = set_option(&mut state, "name Threads value 0");
+        assert!(matches!(threads[..], [Job::SetThreads(1)]));
+    }
+
+    #[test]
+    fn test_check_and_string_options() {
+        let mut state = UciState::new();
+        set_option(&mut state, "name UCI_Chess960 value true");
+        assert!(state.chess960);
+        set_option(&mut state, "name uci_chess960 value false");
+        assert!(!state.chess960);
+
+        // String values keep their spaces and case-insensitive names still match.
+        set_option(&mut state, "name UCI_Variant value Classical");
+        assert_eq!(state.variant, Variant::Classical);
+        assert!(state.notation == Notation::Site);
+        set_option(&mut state, "name UCI_Variant value No Such Variant");
+        assert_eq!(state.variant, Variant::Classical);
+        set_option(&mut state, "name uci_variant value Chess");
+        assert_eq!(state.variant, Variant::Chess);
+        assert!(state.notation == Notation::Algebraic);
+    }
+
+    #[test]
+    fn test_unknown_and_malformed_options_change_nothing() {
+        let mut state = UciState::new();
+        for line in [
+            "name Contempt value 20",
+            "name",
+            "value 20",
+            "",
+            "name Skill value 3",
+            "name Skill Level Extra value 3",
+        ] {
+            assert!(set_option(&mut state, line).is_empty(), "{}", line);
+            assert_eq!(state.options.skill, MAX_SITE_SKILL, "{}", line);
+        }
+    }
+
+    #[test]
+    fn test_every_option_takes_its_default() {
+        let mut state = UciState::new();
+        for option in OPTIONS {
+            let value = match option.kind {
+                OptionKind::Spin { default, min, max } => {
+                    assert!((min..=max).contains(&default), "{}", option.name);
+                    default.to_string()
+                }
+                OptionKind::Check { default } => default.to_string(),
+                OptionKind::String { default } => default.to_string(),
+            };
+            set_option(&mut state, &format!("name {} value {}", option.name, value));
+        }
+        assert_eq!(state.options.multi_pv, 1);
+        assert_eq!(state.options.skill, MAX_SITE_SKILL);
+        assert_eq!(state.options.move_overhead_ms, DEFAULT_MOVE_OVERHEAD_MS);
+        assert_eq!(state.variant, Variant::Chess);
+        assert!(!state.chess960);
+    }
+
+    #[test]
+    fn test_one_bestmove_per_go() {
+        let mut uci = Harness::new();
+        uci.send(&["position startpos", "go depth 2"]);
+        let lines = uci.replies_until_idle();
+        assert_eq!(bestmoves(&lines).len(), 1);
+        assert!(lines.last().unwrap().starts_with("bestmove "));
+        assert!(lines.iter().any(|line| line.starts_with("info depth 2 ")));
+
+        // A `go` while searching ends the running search first.
+        uci.send(&["go infinite", "go depth 1", "go depth 1"]);
+        let lines = uci.replies_until_idle();
+        assert_eq!(bestmoves(&lines).len(), 3);
+    }
+
+    #[test]
+    fn test_infinite_search_waits_for_stop() {
+        let mut uci = Harness::new();
+        uci.send(&["position startpos", "go infinite depth 1"]);
+        thread::sleep(Duration::from_millis(200));
+        assert!(bestmoves(&uci.replies_now()).is_empty());
+
+        uci.send(&["stop"]);
+        let lines = uci.replies_until_idle();
+        assert_eq!(bestmoves(&lines).len(), 1);
+        // A second stop has no search to end.
+        uci.send(&["stop"]);
+        assert!(uci.replies_until_idle().is_empty());
+    }
+
+    #[test]
+    fn test_stop_right_after_go_is_not_lost() {
+        let mut uci = Harness::new();
+        // The streamed search at full strength, then the weakened one, which can't finish
+        // an infinite search at this skill on its own in time.
+        let skills = [MAX_SITE_SKILL, MAX_SITE_SKILL - 1];
+        for skill in skills {
+            uci.send(&[
+                &format!("setoption name Skill Level value {}", skill),
+                "position startpos",
+            ]);
+            for _ in 0..10 {
+                // `stop` returns once the bestmove is written.
+                let start = Instant::now();
+                uci.send(&["go infinite", "stop"]);
+                assert!(start.elapsed() < Duration::from_secs(5), "skill {}", skill);
+            }
+            let lines = uci.replies_until_idle();
+            let moves = bestmoves(&lines);
+            assert_eq!(moves.len(), 10, "skill {}", skill);
+            assert!(
+                moves.iter().all(|m| *m != "bestmove 0000"),
+                "skill {}",
+                skill
+            );
+        }
+    }
+
+    #[test]
+    fn test_ponderhit_switches_to_the_clock() {
+        let mut uci = Harness::new();
+        uci.send(&["position startpos", "go ponder wtime 2000 btime 2000"]);
+        thread::sleep(Duration::from_millis(200));
+        assert!(bestmoves(&uci.replies_now()).is_empty());
+
+        uci.send(&["ponderhit"]);
+        let lines = uci.replies_until_bestmove(Duration::from_secs(10));
+        assert_eq!(bestmoves(&lines).len(), 1);
+        // The search has already ended, so neither a late stop nor ponderhit adds a move.
+        uci.send(&["stop", "ponderhit"]);
+        assert!(bestmoves(&uci.replies_until_idle()).is_empty());
+    }
+
+    #[test]
+    fn test_stop_ends_a_ponder_search() {
+        let mut uci = Harness::new();
+        uci.send(&[
+            "position startpos",
+            "go ponder wtime 2000 btime 2000",
+            "stop",
+        ]);
+        let lines = uci.replies_until_idle();
+        assert_eq!(bestmoves(&lines).len(), 1);
+        // The ponder search is over, so ponderhit has nothing to schedule.
+        uci.send(&["ponderhit"]);
+        assert!(uci.replies_until_idle().is_empty());
+    }
+
+    #[test]
+    fn test_ponderhit_deadline_spares_the_next_search() {
+        let mut uci = Harness::new();
+        uci.send(&[
+            "position startpos",
+            "go ponder wtime 2000 btime 2000",
+            "ponderhit",
+            "go infinite",
+        ]);
+        // Longer than the ponder search's budget on this clock.
+        thread::sleep(Duration::from_millis(1000));
+        assert_eq!(bestmoves(&uci.replies_now()).len(), 1);
+
+        uci.send(&["stop"]);
+        assert_eq!(bestmoves(&uci.replies_until_idle()).len(), 1);
+    }
+}
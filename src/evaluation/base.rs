@@ -2,14 +2,11 @@
 use crate::game::{GameState, WinCondition};
 
 use smallvec::SmallVec;
-use std::cell::UnsafeCell;
+use std::cell::{Cell, UnsafeCell};
 
 use crate::search::params::{
-    archbishop, bishop, camel, centaur, chancellor_bonus, eg_bishop_pair_bonus,
-    eg_doubled_pawn_penalty, eg_outpost_bonus, giraffe, guard, hawk, huygen, knight, knightrider,
-    mg_bishop_pair_bonus, mg_doubled_pawn_penalty, mg_outpost_bonus, pawn, queen_open_file_bonus,
-    queen_semi_open_file_bonus, queen_value, rook, rook_open_file_bonus, rook_semi_open_file_bonus,
-    rose, zebra,
+    EvalParams, archbishop, bishop, camel, centaur, chancellor_bonus, giraffe, guard, hawk, huygen,
+    knight, knightrider, pawn, queen_value, rook, rose, zebra,
 };
 
 // 2-Bucket LRU pawn structure cache
@@ -45,6 +42,10 @@ impl Default for PawnCacheBucket {
 
 thread_local! {
     static PAWN_CACHE: UnsafeCell<Vec<PawnCacheBucket>> = UnsafeCell::new(vec![PawnCacheBucket::default(); PAWN_CACHE_SIZE]);
+    // Doubled pawn penalties (mg, eg) the cached pawn scores were computed with
+    static PAWN_CACHE_PENALTIES: Cell<(i32, i32)> = const {
+        Cell::new((DEFAULT_EVAL_MG_DOUBLED_PAWN_PENALTY, DEFAULT_EVAL_EG_DOUBLED_PAWN_PENALTY))
+    };
     // Reusable buffer for piece list to avoid allocation
     pub(crate) static EVAL_PIECE_LIST: UnsafeCell<SmallVec<[(i64, i64, Piece); 128]>> = UnsafeCell::new(SmallVec::new());
     pub(crate) static EVAL_WHITE_PAWNS: UnsafeCell<SmallVec<[(i64, i64); 64]>> = UnsafeCell::new(SmallVec::new());
@@ -254,6 +255,31 @@ pub fn get_piece_value_base(piece_type: PieceType) -> i32 {
     }
 }
 
+/// [`get_piece_value_base`] under an eval profile's `params` instead of the live values.
+pub fn piece_value_with(params: &EvalParams, piece_type: PieceType) -> i32 {
+    let queen = params.rook * 2 + params.compound_bonus;
+    match piece_type {
+        PieceType::Void | PieceType::Obstacle => 0,
+        PieceType::Pawn => params.pawn,
+        PieceType::Knight => params.knight,
+        PieceType::Bishop => params.bishop,
+        PieceType::Rook => params.rook,
+        PieceType::Queen | PieceType::RoyalQueen => queen,
+        PieceType::Guard | PieceType::King => params.guard,
+        PieceType::Camel => params.camel,
+        PieceType::Giraffe => params.giraffe,
+        PieceType::Zebra => params.zebra,
+        PieceType::Knightrider => params.knightrider,
+        PieceType::Amazon => queen + params.knight,
+        PieceType::Hawk => params.hawk,
+        PieceType::Chancellor => params.rook + params.knight + params.chancellor_bonus,
+        PieceType::Archbishop => params.archbishop,
+        PieceType::Centaur | PieceType::RoyalCentaur => params.centaur,
+        PieceType::Rose => params.rose,
+        PieceType::Huygen => params.huygen,
+    }
+}
+
 pub fn get_centrality_weight(piece_type: PieceType) -> i64 {
     match piece_type {
         PieceType::King => 2000,
@@ -661,7 +687,7 @@ pub fn evaluate_inner_traced<T: EvaluationTracer>(game: &GameState, tracer: &mut
                                 let packed = tile.piece[idx];
                                 let piece = crate::board::Piece::from_packed(packed);
                                 let pt = piece.piece_type();
-                                let piece_val = get_piece_value_base(pt);
+                                let piece_val = game.get_piece_value(pt, piece.color());
                                 let is_white = piece.color() == PlayerColor::White;
                                 let is_neutral = pt.is_neutral_type();
                                 let x = cx * 8 + (idx % 8) as i64;
@@ -943,7 +969,10 @@ pub fn evaluate_inner_traced<T: EvaluationTracer>(game: &GameState, tracer: &mut
                                             dy,
                                         ) && target.color() == enemy
                                         {
-                                            let tv = get_piece_value_base(target.piece_type());
+                                            let tv = game.get_piece_value(
+                                                target.piece_type(),
+                                                target.color(),
+                                            );
                                             if tv >= 600 {
                                                 if is_white {
                                                     w_pawn_threats += PAWN_THREATENS_QUEEN;
@@ -986,7 +1015,10 @@ pub fn evaluate_inner_traced<T: EvaluationTracer>(game: &GameState, tracer: &mut
                                             dy,
                                         ) && target.color() == enemy
                                         {
-                                            let tv = get_piece_value_base(target.piece_type());
+                                            let tv = game.get_piece_value(
+                                                target.piece_type(),
+                                                target.color(),
+                                            );
                                             let mv = piece_val;
                                             if tv >= 600 && mv < 600 {
                                                 if is_white {
@@ -1196,7 +1228,7 @@ pub fn evaluate_inner_traced<T: EvaluationTracer>(game: &GameState, tracer: &mut
                         for &(px, py, ppiece) in piece_list.iter() {
                             let ppt = ppiece.piece_type();
                             let piece_val = if !ppt.is_royal() && ppt != PieceType::Pawn {
-                                get_piece_value_base(ppt)
+                                game.get_piece_value(ppt, ppiece.color())
                             } else {
                                 0
                             };
@@ -1561,6 +1593,7 @@ fn evaluate_pieces_processed<T: EvaluationTracer>(
                 black_pawns,
             ),
             PieceType::Knight => evaluate_knight(
+                game,
                 x,
                 y,
                 piece.color(),
@@ -1575,6 +1608,7 @@ fn evaluate_pieces_processed<T: EvaluationTracer>(
             | PieceType::Camel
             | PieceType::Giraffe
             | PieceType::Zebra => evaluate_leaper_positioning(
+                game,
                 x,
                 y,
                 piece.color(),
@@ -1585,6 +1619,7 @@ fn evaluate_pieces_processed<T: EvaluationTracer>(
             ),
             PieceType::Centaur | PieceType::RoyalCentaur => {
                 let leaper_eval = evaluate_leaper_positioning(
+                    game,
                     x,
                     y,
                     piece.color(),
@@ -1596,6 +1631,7 @@ fn evaluate_pieces_processed<T: EvaluationTracer>(
                 leaper_eval * CENTAUR_GUARD_SCALE / 100
             }
             PieceType::Huygen => evaluate_leaper_positioning(
+                game,
                 x,
                 y,
                 piece.color(),
@@ -1605,6 +1641,7 @@ fn evaluate_pieces_processed<T: EvaluationTracer>(
                 phase,
             ),
             PieceType::Guard => evaluate_leaper_positioning(
+                game,
                 x,
                 y,
                 piece.color(),
@@ -1617,6 +1654,7 @@ fn evaluate_pieces_processed<T: EvaluationTracer>(
             // reach is unbounded so mobility-counting is meaningless. Use the
             // board-aware cloud-proximity/density shaping like the other riders.
             PieceType::Knightrider => evaluate_leaper_positioning(
+                game,
                 x,
                 y,
                 piece.color(),
@@ -1627,7 +1665,7 @@ fn evaluate_pieces_processed<T: EvaluationTracer>(
             ),
             _ => 0,
         };
-        let piece_val = get_piece_value_base(pt);
+        let piece_val = game.get_piece_value(pt, piece.color());
 
         if let Some(center) = &cloud_center {
             let dx = (x - center.x).abs();
@@ -1742,15 +1780,16 @@ fn evaluate_pieces_processed<T: EvaluationTracer>(
     let mut w_pair_bonus = 0;
     let mut b_pair_bonus = 0;
 
+    let params = game.eval_params();
     if metrics.white_bishops >= 2 {
-        w_pair_bonus += taper(mg_bishop_pair_bonus(), eg_bishop_pair_bonus());
+        w_pair_bonus += taper(params.mg_bishop_pair_bonus, params.eg_bishop_pair_bonus);
         bump_feat!(bishop_pair_bonus, 1);
         if metrics.white_bishop_colors.0 && metrics.white_bishop_colors.1 {
             w_pair_bonus += 20;
         }
     }
     if metrics.black_bishops >= 2 {
-        b_pair_bonus += taper(mg_bishop_pair_bonus(), eg_bishop_pair_bonus());
+        b_pair_bonus += taper(params.mg_bishop_pair_bonus, params.eg_bishop_pair_bonus);
         bump_feat!(bishop_pair_bonus, -1);
         if metrics.black_bishop_colors.0 && metrics.black_bishop_colors.1 {
             b_pair_bonus += 20;
@@ -2090,10 +2129,10 @@ pub fn evaluate_rook(
 
         if !has_enemy_pawns {
             // Open file
-            bonus += rook_open_file_bonus();
+            bonus += game.eval_params().rook_open_file_bonus;
         } else {
             // Semi-open file
-            bonus += rook_semi_open_file_bonus();
+            bonus += game.eval_params().rook_semi_open_file_bonus;
         }
     }
 
@@ -2203,10 +2242,10 @@ pub fn evaluate_queen(
 
         if !has_enemy_pawns {
             // Open file
-            bonus += queen_open_file_bonus();
+            bonus += game.eval_params().queen_open_file_bonus;
         } else {
             // Semi-open file
-            bonus += queen_semi_open_file_bonus();
+            bonus += game.eval_params().queen_semi_open_file_bonus;
         }
     }
 
@@ -2301,13 +2340,16 @@ pub fn evaluate_bishop(
     let has_right_support = my_pawns.binary_search(&(x + 1, support_y)).is_ok();
 
     if has_left_support || has_right_support {
-        bonus += taper(mg_outpost_bonus(), eg_outpost_bonus());
+        let params = game.eval_params();
+        bonus += taper(params.mg_outpost_bonus, params.eg_outpost_bonus);
     }
 
     bonus
 }
 
+#[allow(clippy::too_many_arguments)]
 fn evaluate_knight(
+    game: &GameState,
     x: i64,
     y: i64,
     color: PlayerColor,
@@ -2320,6 +2362,7 @@ fn evaluate_knight(
     let taper =
         |mg: i32, eg: i32| -> i32 { ((mg * phase) + (eg * (MAX_PHASE - phase))) / MAX_PHASE };
     let mut bonus = evaluate_leaper_positioning(
+        game,
         x,
         y,
         color,
@@ -2349,7 +2392,8 @@ fn evaluate_knight(
     let has_right_support = my_pawns.binary_search(&(x + 1, support_y)).is_ok();
 
     if has_left_support || has_right_support {
-        bonus += taper(mg_outpost_bonus(), eg_outpost_bonus());
+        let params = game.eval_params();
+        bonus += taper(params.mg_outpost_bonus, params.eg_outpost_bonus);
     }
 
     bonus
@@ -2362,10 +2406,12 @@ fn evaluate_knight(
 /// 2. **Density bonus** – leapers gain value when pieces cluster together
 /// 3. **Phase taper** – short-range leapers become relatively more valuable in the
 ///    endgame as the board empties
+#[allow(clippy::too_many_arguments)]
 fn evaluate_leaper_positioning(
+    game: &GameState,
     x: i64,
     y: i64,
-    _color: PlayerColor,
+    color: PlayerColor,
     cloud_center: Option<&Coordinate>,
     piece_type: PieceType,
     cloud_avg_spread: i32,
@@ -2373,7 +2419,7 @@ fn evaluate_leaper_positioning(
 ) -> i32 {
     let taper =
         |mg: i32, eg: i32| -> i32 { ((mg * phase) + (eg * (MAX_PHASE - phase))) / MAX_PHASE };
-    let piece_value = get_piece_value_base(piece_type);
+    let piece_value = game.get_piece_value(piece_type, color);
     let mut bonus: i32 = 0;
 
     // 1. CLOUD PROXIMITY: reward being near the piece cloud center
@@ -2424,7 +2470,7 @@ fn evaluate_leaper_positioning(
 
 #[allow(clippy::too_many_arguments)]
 fn evaluate_king_shelter(
-    _game: &GameState,
+    game: &GameState,
     king: &Coordinate,
     color: PlayerColor,
     phase: i32,
@@ -2506,7 +2552,7 @@ fn evaluate_king_shelter(
     };
 
     // Bounds for world border check (treat as friendly blocker)
-    let (min_x, max_x, min_y, max_y) = crate::moves::get_coord_bounds();
+    let (min_x, max_x, min_y, max_y) = game.game_rules.world_bounds.as_tuple();
 
     let get_border_dist = |dx: i64, dy: i64| -> i32 {
         let mut d = i64::MAX;
@@ -2710,6 +2756,16 @@ pub fn evaluate_pawn_structure_traced<T: EvaluationTracer>(
         );
     }
 
+    // Scores cached under other doubled pawn penalties (another eval profile) are stale
+    let params = game.eval_params();
+    let penalties = (
+        params.mg_doubled_pawn_penalty,
+        params.eg_doubled_pawn_penalty,
+    );
+    if PAWN_CACHE_PENALTIES.with(|p| p.replace(penalties)) != penalties {
+        clear_pawn_cache();
+    }
+
     // Fast 2-Bucket cache probe using bitwise mask
     let idx = (pawn_hash as usize) & (PAWN_CACHE_SIZE - 1);
     let cached = PAWN_CACHE.with(|cache| {
@@ -2769,6 +2825,11 @@ fn compute_pawn_structure_traced<T: EvaluationTracer>(
 ) -> i32 {
     let taper =
         |mg: i32, eg: i32| -> i32 { ((mg * phase) + (eg * (MAX_PHASE - phase))) / MAX_PHASE };
+    let params = game.eval_params();
+    let doubled_penalty = taper(
+        params.mg_doubled_pawn_penalty,
+        params.eg_doubled_pawn_penalty,
+    );
     let mut w_doubled = 0;
     let mut b_doubled = 0;
     let mut w_passed_score = 0;
@@ -2793,7 +2854,7 @@ fn compute_pawn_structure_traced<T: EvaluationTracer>(
             j += 1;
         }
         if count > 1 {
-            w_doubled -= (count - 1) * taper(mg_doubled_pawn_penalty(), eg_doubled_pawn_penalty());
+            w_doubled -= (count - 1) * doubled_penalty;
         }
         i = j;
     }
@@ -2809,7 +2870,7 @@ fn compute_pawn_structure_traced<T: EvaluationTracer>(
             j += 1;
         }
         if count > 1 {
-            b_doubled -= (count - 1) * taper(mg_doubled_pawn_penalty(), eg_doubled_pawn_penalty());
+            b_doubled -= (count - 1) * doubled_penalty;
         }
         i = j;
     }
@@ -3797,7 +3858,7 @@ mod tests {
         let icn_no_support = "w (8;q|1;q) K0,0|k0,10|N4,4";
         game.setup_position_from_icn(icn_no_support);
 
-        let score_no_support = evaluate_knight(4, 4, PlayerColor::White, None, 8, 0, &[], &[]);
+        let score_no_support = evaluate_knight(&game, 4, 4, PlayerColor::White, None, 8, 0, &[], &[]);
 
         // Case 2: Support from pawn at (3,3) (White pawn at y-1 supports y)
         let icn_supported = "w (8;q|1;q) K0,0|k0,10|N4,4|P3,3";
@@ -3805,7 +3866,7 @@ mod tests {
         // Mock pawn list
         let white_pawns = vec![(3, 3)];
 
-        let score_supported = evaluate_knight(4, 4, PlayerColor::White, None, 8, 0, &white_pawns, &[]);
+        let score_supported = evaluate_knight(&game, 4, 4, PlayerColor::White, None, 8, 0, &white_pawns, &[]);
 
         println!(
             "No Support: {}, Supported: {}",
@@ -3817,8 +3878,8 @@ mod tests {
         );
         assert_eq!(
             score_supported - score_no_support,
-            eg_outpost_bonus(),
-            "Bonus should match eg_outpost_bonus() in endgame"
+            game.eval_params().eg_outpost_bonus,
+            "Bonus should match eg_outpost_bonus in endgame"
         );
     }
 
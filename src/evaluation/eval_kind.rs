@@ -50,15 +50,33 @@ pub enum EvalKind {
     Generic,
 }
 
+impl EvalKind {
+    /// Parses a kind name as written in eval profiles (`"Obstocean"`, `"pawn_horde"`),
+    /// ignoring case and `_`/space separators.
+    pub fn try_parse(s: &str) -> Option<Self> {
+        let normalized: String = s
+            .chars()
+            .filter(|c| *c != '_' && *c != ' ')
+            .collect::<String>()
+            .to_lowercase();
+        match normalized.as_str() {
+            "chess" => Some(EvalKind::Chess),
+            "pawnhorde" => Some(EvalKind::PawnHorde),
+            "obstocean" => Some(EvalKind::Obstocean),
+            "generic" => Some(EvalKind::Generic),
+            _ => None,
+        }
+    }
+}
+
 /// Derive the evaluator from position characteristics. Called once per received
-/// position; never on the search hot path. Reads the current world bounds.
+/// position; never on the search hot path. Reads the game's world bounds.
 pub fn detect(game: &GameState) -> EvalKind {
-    detect_in_region(game, crate::moves::get_coord_bounds())
+    detect_in_region(game, game.game_rules.world_bounds.as_tuple())
 }
 
 /// Core of [`detect`], parameterized on the board region `(min_x, max_x, min_y,
-/// max_y)` so it never touches the world-bounds global — keeps the logic pure
-/// and unit-testable without mutating process-wide state.
+/// max_y)` so tests can probe any region without rebuilding the game's bounds.
 fn detect_in_region(game: &GameState, region: (i64, i64, i64, i64)) -> EvalKind {
     let (min_x, max_x, min_y, max_y) = region;
     // Single board pass: obstacle count + per-side orthodox composition, plus a
@@ -526,7 +526,7 @@ pub fn evaluate_insufficient_material(game: &crate::game::GameState) -> bool {
 
 #[inline(always)]
 fn compute(game: &crate::game::GameState) -> bool {
-    let bordered = crate::moves::get_world_size() <= 200;
+    let bordered = game.game_rules.world_bounds.size() <= 200;
     let (w, b) = count_both(&game.board, &game.game_rules);
 
     let w_nr = w.non_royal();
@@ -959,7 +959,7 @@ fn is_helpmate_only_combo(a: &Mat, b: &Mat, bordered: bool) -> bool {
 /// despite both sides being individually insufficient.
 #[inline(always)]
 fn compute_game_handler(game: &crate::game::GameState) -> bool {
-    let bordered = crate::moves::get_world_size() <= 200;
+    let bordered = game.game_rules.world_bounds.size() <= 200;
     let (w, b) = count_both(&game.board, &game.game_rules);
 
     let w_nr = w.non_royal();
@@ -1334,6 +1334,11 @@ mod tests {
             white_win_condition: crate::game::WinCondition::Checkmate,
             black_win_condition: crate::game::WinCondition::Checkmate,
             variant: None,
+            world_bounds: Default::default(),
+            chess960: false,
+            eval_params: None,
+            piece_values: Default::default(),
+            koth_squares: Vec::new(),
         };
 
         assert!(can_pawn_promote(5, PlayerColor::White, &rules));
@@ -9,13 +9,19 @@ pub mod eval_kind;
 pub mod helpers;
 pub mod insufficient_material;
 pub mod mop_up;
+pub mod profiles;
+#[cfg(all(feature = "param_tuning", not(target_arch = "wasm32")))]
+pub mod texel;
 pub mod variants;
 
-use crate::board::PlayerColor;
-use crate::game::GameState;
+use crate::board::{Coordinate, PieceType, PlayerColor};
+use crate::game::{GameState, WinCondition};
 use eval_kind::EvalKind;
+use serde::Serialize;
 
+pub use crate::search::params::EvalParams;
 pub use base::{calculate_initial_material, get_piece_phase, get_piece_value_base};
+pub use profiles::EvalProfiles;
 
 /// Largest slice of the evaluation the halfmove-clock damping may take away.
 /// This is the conversion urgency: large enough that the winning side always
@@ -25,7 +31,7 @@ const RULE50_DAMP_CAP: i32 = 700;
 
 #[cfg(any(feature = "param_tuning", feature = "eval_tuning"))]
 pub use crate::search::params::{
-    EVAL_PARAMS, EvalParamSpec, EvalParams, TUNABLE_EVAL_PARAM_SPECS, get_eval_params_as_json,
+    EVAL_PARAMS, EvalParamSpec, TUNABLE_EVAL_PARAM_SPECS, get_eval_params_as_json,
     set_eval_params_from_json,
 };
 #[cfg(any(feature = "param_tuning", feature = "eval_tuning"))]
@@ -45,10 +51,58 @@ fn compute_mop_up_term(game: &GameState) -> i32 {
     if game.turn == PlayerColor::Black { -raw } else { raw }
 }
 
+/// King of the Hill bonus by the king's Chebyshev distance to the nearest hill
+/// square, flat from 7 on. Distance 0 never reaches the evaluation: search
+/// scores a king on the hill as mate.
+const KOTH_DISTANCE_BONUS: [i32; 8] = [0, 300, 180, 110, 70, 40, 20, 10];
+
+/// Returns the hill-distance bonus from the side-to-move's perspective, for each
+/// side whose win condition is `Koth`.
+#[inline]
+fn compute_koth_term(game: &GameState) -> i32 {
+    let rules = &game.game_rules;
+    if rules.koth_squares.is_empty() {
+        return 0;
+    }
+    let mut raw = 0;
+    if rules.white_win_condition == WinCondition::Koth {
+        raw += koth_king_bonus(game, &game.white_royals);
+    }
+    if rules.black_win_condition == WinCondition::Koth {
+        raw -= koth_king_bonus(game, &game.black_royals);
+    }
+    if game.turn == PlayerColor::Black {
+        -raw
+    } else {
+        raw
+    }
+}
+
+/// Bonus for the side's king closest to the hill (royals that are not kings
+/// cannot take it).
+fn koth_king_bonus(game: &GameState, royals: &[Coordinate]) -> i32 {
+    royals
+        .iter()
+        .filter(|c| {
+            game.board
+                .get_piece(c.x, c.y)
+                .is_some_and(|p| p.piece_type() == PieceType::King)
+        })
+        .filter_map(|k| {
+            game.game_rules
+                .koth_squares
+                .iter()
+                .map(|h| k.x.abs_diff(h.x).max(k.y.abs_diff(h.y)))
+                .min()
+        })
+        .min()
+        .map_or(0, |d| KOTH_DISTANCE_BONUS[d.min(7) as usize])
+}
+
 /// Bounded-board rook/minor endings that are drawn with correct defense are
 /// scaled hard toward the draw.
 fn apply_bounded_drawish_scale(game: &GameState, eval: i32) -> i32 {
-    bounded_drawish_scale_inner(game, eval, crate::moves::get_world_size())
+    bounded_drawish_scale_inner(game, eval, game.game_rules.world_bounds.size())
 }
 
 fn bounded_drawish_scale_inner(game: &GameState, eval: i32, world_size: i64) -> i32 {
@@ -77,7 +131,7 @@ fn bounded_drawish_scale_inner(game: &GameState, eval: i32, world_size: i64) ->
         let white = piece.color() == PlayerColor::White;
         match pt {
             PieceType::Rook | PieceType::Knight | PieceType::Bishop => {
-                let v = get_piece_value_base(pt);
+                let v = game.get_piece_value(pt, piece.color());
                 if white {
                     w_npm += v;
                 } else {
@@ -116,7 +170,12 @@ fn bounded_drawish_scale_inner(game: &GameState, eval: i32, world_size: i64) ->
         return eval;
     }
     // Bare piece edge (≤ a minor) with no pawns cannot force mate: fortress.
-    if strong_npm - weak_npm <= get_piece_value_base(PieceType::Bishop) {
+    let strong = if strong_is_white {
+        PlayerColor::White
+    } else {
+        PlayerColor::Black
+    };
+    if strong_npm - weak_npm <= game.get_piece_value(PieceType::Bishop, strong) {
         eval / 8
     } else {
         eval
@@ -170,10 +229,11 @@ pub fn evaluate(game: &GameState, nnue_state: Option<&crate::nnue::NnueState>) -
         }
     };
     let mop_up = compute_mop_up_term(game);
+    let koth = compute_koth_term(game);
 
     apply_rule50_damping(
         game,
-        apply_bounded_drawish_scale(game, raw_eval + mop_up),
+        apply_bounded_drawish_scale(game, raw_eval + mop_up + koth),
         mop_up != 0,
     )
 }
@@ -192,14 +252,107 @@ pub fn evaluate(game: &GameState) -> i32 {
         EvalKind::Generic => base::evaluate(game),
     };
     let mop_up = compute_mop_up_term(game);
+    let koth = compute_koth_term(game);
 
     apply_rule50_damping(
         game,
-        apply_bounded_drawish_scale(game, raw_eval + mop_up),
+        apply_bounded_drawish_scale(game, raw_eval + mop_up + koth),
         mop_up != 0,
     )
 }
 
+/// One traced evaluation term, in centipawns for each side.
+#[derive(Clone, Debug, Serialize)]
+pub struct ExplainedTerm {
+    pub term: String,
+    pub white: i32,
+    pub black: i32,
+}
+
+/// How `evaluate` arrives at its score, step by step. Scores are from the side to move's
+/// point of view unless named for a colour.
+#[derive(Clone, Debug, Serialize)]
+pub struct EvalExplanation {
+    /// The evaluator that ran: `Chess`, `PawnHorde`, `Obstocean`, `Generic` (the base
+    /// HCE) or `NNUE`.
+    pub evaluator: &'static str,
+    /// Material balance, White minus Black.
+    pub material: i32,
+    /// The base HCE's terms beyond material. Empty for the variant evaluators and NNUE,
+    /// which only report a total.
+    pub terms: Vec<ExplainedTerm>,
+    /// A dead draw on material; every score below is then zero.
+    pub insufficient_material: bool,
+    /// The evaluator's own score.
+    pub raw: i32,
+    /// Mop-up bonus for driving a lone king to mate.
+    pub mop_up: i32,
+    /// King of the Hill bonus for the kings' distance to the hill.
+    pub koth: i32,
+    /// `raw + mop_up + koth` after scaling drawn rook/minor endings on bounded boards.
+    pub drawish_scaled: i32,
+    /// Taken off by the halfmove-clock damping.
+    pub rule50_damping: i32,
+    /// The final score, as `evaluate` returns it.
+    pub eval: i32,
+}
+
+/// Runs the same steps as [`evaluate`] and reports each of them. NNUE is evaluated from
+/// scratch rather than from a search's accumulators.
+pub fn explain(game: &GameState) -> EvalExplanation {
+    let mut explanation = EvalExplanation {
+        evaluator: match game.eval_kind {
+            EvalKind::Chess => "Chess",
+            EvalKind::PawnHorde => "PawnHorde",
+            EvalKind::Obstocean => "Obstocean",
+            EvalKind::Generic => "Generic",
+        },
+        material: game.material_score,
+        terms: Vec::new(),
+        insufficient_material: insufficient_material::evaluate_insufficient_material(game),
+        raw: 0,
+        mop_up: 0,
+        koth: 0,
+        drawish_scaled: 0,
+        rule50_damping: 0,
+        eval: 0,
+    };
+    if explanation.insufficient_material {
+        return explanation;
+    }
+
+    explanation.raw = match game.eval_kind {
+        EvalKind::Chess => variants::chess::evaluate(game),
+        EvalKind::Obstocean => variants::obstocean::evaluate(game),
+        EvalKind::PawnHorde => variants::pawn_horde::evaluate(game),
+        #[cfg(feature = "nnue")]
+        EvalKind::Generic if crate::nnue::is_applicable(game) => {
+            explanation.evaluator = "NNUE";
+            crate::nnue::evaluate(game)
+        }
+        EvalKind::Generic => {
+            let mut trace = base::ActiveTrace::default();
+            let raw = base::evaluate_inner_traced(game, &mut trace);
+            explanation.terms = trace
+                .rows
+                .into_iter()
+                .map(|(term, white, black)| ExplainedTerm { term, white, black })
+                .collect();
+            raw
+        }
+    };
+    explanation.mop_up = compute_mop_up_term(game);
+    explanation.koth = compute_koth_term(game);
+    explanation.drawish_scaled = apply_bounded_drawish_scale(
+        game,
+        explanation.raw + explanation.mop_up + explanation.koth,
+    );
+    explanation.eval =
+        apply_rule50_damping(game, explanation.drawish_scaled, explanation.mop_up != 0);
+    explanation.rule50_damping = explanation.drawish_scaled - explanation.eval;
+    explanation
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -224,6 +377,22 @@ mod tests {
         return evaluate(game);
     }
 
+    #[test]
+    fn test_koth_term_rewards_the_king_nearer_the_hill() {
+        // White's king is one step from the hill, Black's far away.
+        let near = create_test_game_from_icn("w koth K3,3|R1,1|k20,20|r30,30");
+        assert_eq!(compute_koth_term(&near), 300 - 10);
+        let near_black_to_move = create_test_game_from_icn("b koth K3,3|R1,1|k20,20|r30,30");
+        assert_eq!(compute_koth_term(&near_black_to_move), -(300 - 10));
+
+        let farther = create_test_game_from_icn("w koth K2,2|R1,1|k20,20|r30,30");
+        assert!(compute_koth_term(&farther) < compute_koth_term(&near));
+
+        // No hill, no term.
+        let plain = create_test_game_from_icn("w K3,3|R1,1|k20,20|r30,30");
+        assert_eq!(compute_koth_term(&plain), 0);
+    }
+
     #[test]
     fn test_bounded_drawish_endings_scaled() {
         // R+minor vs R and R vs lone minor are draws with correct defense on
@@ -264,6 +433,12 @@ mod tests {
             400,
             "unbounded boards are handled by insufficient-material, not scaling"
         );
+
+        // The game's own piece values decide what counts as a bare minor edge.
+        let heavy_knight = create_test_game_from_icn(
+            "[PieceValues \"N=900\"] w (8;q|1;q) K2,2|R4,4|N5,5|k7,7|r7,1",
+        );
+        assert_eq!(bounded_drawish_scale_inner(&heavy_knight, 400, 8), 400);
     }
 
     #[test]
@@ -343,6 +518,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_explain_matches_evaluate() {
+        let mut game = create_test_game_from_icn("w (8;q|1;q) K5,1|k5,8|Q4,4|r1,8|P2,2|p7,7");
+        game.game_rules.move_rule_limit = Some(100);
+        game.halfmove_clock = 30;
+        let explanation = explain(&game);
+        assert_eq!(explanation.eval, evaluate_wrapper(&game));
+        assert_eq!(explanation.material, game.material_score);
+        assert!(explanation.rule50_damping != 0);
+        assert_eq!(
+            explanation.eval + explanation.rule50_damping,
+            explanation.drawish_scaled
+        );
+        if explanation.evaluator == "Generic" {
+            assert!(explanation.terms.iter().any(|t| t.term == "Pawn: Passed"));
+        }
+
+        // Lone king against two queens: mop-up does the driving.
+        let game = create_test_game_from_icn("w (8;q|1;q) K5,1|Q4,4|Q3,3|k5,8");
+        let explanation = explain(&game);
+        assert_eq!(explanation.eval, evaluate_wrapper(&game));
+        assert!(explanation.mop_up != 0, "{:?}", explanation);
+
+        let game = create_test_game_from_icn("w (8;Q,R,B,N|1;q,r,b,n) 1,8,1,8 K5,1|R1,1|k5,8");
+        let explanation = explain(&game);
+        assert_eq!(explanation.evaluator, "Chess");
+        assert!(explanation.terms.is_empty());
+        assert_eq!(explanation.eval, evaluate_wrapper(&game));
+
+        let game = create_test_game_from_icn("w (8;q|1;q) K5,1|N3,3|k5,8");
+        let explanation = explain(&game);
+        assert!(explanation.insufficient_material);
+        assert_eq!(explanation.eval, 0);
+    }
+
     #[test]
     fn test_get_piece_value() {
         // Test piece values are reasonable
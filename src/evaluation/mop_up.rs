@@ -7,7 +7,7 @@
 
 use crate::board::{Board, Coordinate, PieceType, PlayerColor};
 use crate::game::GameState;
-use crate::moves::{SpatialIndices, is_square_attacked};
+use crate::moves::{SpatialIndices, WorldBounds, is_square_attacked};
 use crate::utils::is_prime_fast;
 
 /// A defender pawn's weight in the net-leakiness term: pawns barely obstruct
@@ -261,7 +261,8 @@ fn gcd_i64(a: i64, b: i64) -> i64 {
 
 /// Evaluates the "wall" bit for each not-yet-evaluated `x` set in `need` on row
 /// `local_y` of the cage window, recording results in `forbidden`/`computed`.
-/// A wall is an out-of-bounds, our-attacked, or our-occupied square.
+/// A wall is an unplayable (outside the border or in a region hole),
+/// our-attacked, or our-occupied square.
 #[inline]
 #[allow(clippy::too_many_arguments)]
 fn cage_eval_walls(
@@ -270,7 +271,7 @@ fn cage_eval_walls(
     our_color: PlayerColor,
     origin_x: i64,
     origin_y: i64,
-    bounds: (i64, i64, i64, i64),
+    bounds: &WorldBounds,
     local_y: usize,
     need: u32,
     forbidden: &mut [u32; 32],
@@ -281,17 +282,13 @@ fn cage_eval_walls(
         return;
     }
     computed[local_y] |= need;
-    let (min_x, max_x, min_y, max_y) = bounds;
     let abs_y = origin_y + local_y as i64;
     let mut bits = todo;
     while bits != 0 {
         let local_x = bits.trailing_zeros() as usize;
         bits &= bits - 1;
         let abs_x = origin_x + local_x as i64;
-        let wall = abs_x < min_x
-            || abs_x > max_x
-            || abs_y < min_y
-            || abs_y > max_y
+        let wall = !bounds.contains(abs_x, abs_y)
             || is_square_attacked(board, &Coordinate::new(abs_x, abs_y), our_color, indices)
             || board.is_occupied_by_color(abs_x, abs_y, our_color);
         if wall {
@@ -304,20 +301,20 @@ fn cage_eval_walls(
 /// Returns whether a cage exists and the total reachable area for the king.
 ///
 /// Floods an enemy-king-centered 32x32 window away from the king, treating
-/// out-of-bounds / our-attacked / our-occupied squares as walls. Wall squares
+/// unplayable / our-attacked / our-occupied squares as walls. Wall squares
 /// are evaluated lazily — only for cells the flood actually reaches — so a small
 /// contained cage costs a handful of `is_square_attacked` calls rather than 1024.
 #[inline]
 fn find_bitboard_cage(
     board: &Board,
     indices: &SpatialIndices,
+    bounds: &WorldBounds,
     enemy_king: &Coordinate,
     our_color: PlayerColor,
 ) -> (bool, u32) {
     // 32x32 local window: indices 0..31 map to king_coord - 16 .. king_coord + 15.
     let origin_x = enemy_king.x - 16;
     let origin_y = enemy_king.y - 16;
-    let bounds = crate::moves::get_coord_bounds();
 
     let mut forbidden = [0u32; 32];
     let mut computed = [0u32; 32];
@@ -443,7 +440,7 @@ pub fn calculate_mop_up_scale(game: &GameState, losing_color: PlayerColor) -> Op
         if pt.is_royal() {
             continue;
         }
-        let value = super::base::get_piece_value_base(pt);
+        let value = game.get_piece_value(pt, piece.color());
         defender_material += value;
         defense += if pt == PieceType::Pawn {
             defender_has_pawns = true;
@@ -462,7 +459,7 @@ pub fn calculate_mop_up_scale(game: &GameState, losing_color: PlayerColor) -> Op
         if pt.is_royal() {
             continue;
         }
-        attacker_material += super::base::get_piece_value_base(pt);
+        attacker_material += game.get_piece_value(pt, piece.color());
     }
 
     let surplus = attacker_material - defender_material;
@@ -563,7 +560,7 @@ fn bounded_lone_king_mop_up(
     enemy_king: &Coordinate,
     winning_color: PlayerColor,
 ) -> i32 {
-    let (min_x, max_x, min_y, max_y) = crate::moves::get_coord_bounds();
+    let (min_x, max_x, min_y, max_y) = game.game_rules.world_bounds.as_tuple();
     let ex = enemy_king.x;
     let ey = enemy_king.y;
 
@@ -1180,7 +1177,7 @@ fn evaluate_mating_net(
     let near_scale = (NEAR_GATE_DIST + 4 - kr.king_dist.max(NEAR_GATE_DIST)).clamp(0, 4) as i32;
 
     // ---- Tier 1: confinement ----
-    let (min_x, max_x, min_y, max_y) = crate::moves::get_coord_bounds();
+    let (min_x, max_x, min_y, max_y) = game.game_rules.world_bounds.as_tuple();
 
     // Wall distance per direction: nearest fence line or the board edge
     // (a wall one square past the last rank/file), whichever is closer.
@@ -1353,6 +1350,7 @@ fn evaluate_mating_net(
         let (caged, area) = find_bitboard_cage(
             &game.board,
             &game.spatial_indices,
+            &game.game_rules.world_bounds,
             enemy_king,
             winning_color,
         );
@@ -1579,7 +1577,7 @@ fn evaluate_mop_up_core(
     enemy_king: &Coordinate,
     winning_color: PlayerColor,
 ) -> i32 {
-    let bounded = crate::moves::get_world_size() <= MOP_UP_BOUNDED_MAX;
+    let bounded = game.game_rules.world_bounds.size() <= MOP_UP_BOUNDED_MAX;
     let losing_color = winning_color.opponent();
     let bareish = defender_is_bareish(game, losing_color);
 
@@ -2198,6 +2196,7 @@ mod tests {
         let (_is_caged, area) = find_bitboard_cage(
             &game.board,
             &game.spatial_indices,
+            &game.game_rules.world_bounds,
             &enemy_king,
             PlayerColor::White,
         );
@@ -2209,23 +2208,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_find_bitboard_cage_respects_region_holes() {
+        // A ring of holes around the king leaves it a 3x3 pocket.
+        let game = create_test_game_from_icn(
+            "w (8;q|1;q) -20,20,-20,20~2,6,2,2~2,6,6,6~2,2,3,5~6,6,3,5 k4,4|K10,10|R-10,-10",
+        );
+        let (is_caged, area) = find_bitboard_cage(
+            &game.board,
+            &game.spatial_indices,
+            &game.game_rules.world_bounds,
+            &Coordinate::new(4, 4),
+            PlayerColor::White,
+        );
+        assert!(is_caged);
+        assert_eq!(area, 9);
+    }
+
     /// Straightforward full-window cage computation: evaluates every cell's wall
     /// bit up front. Used only to verify the lazy `find_bitboard_cage` matches it.
     fn cage_eager_reference(
         board: &Board,
         indices: &SpatialIndices,
+        bounds: &WorldBounds,
         enemy_king: &Coordinate,
         our_color: PlayerColor,
     ) -> (bool, u32) {
         let mut forbidden = [0u32; 32];
         let origin_x = enemy_king.x - 16;
         let origin_y = enemy_king.y - 16;
-        let (min_x, max_x, min_y, max_y) = crate::moves::get_coord_bounds();
         for (local_y, fr) in forbidden.iter_mut().enumerate() {
             let abs_y = origin_y + local_y as i64;
             for local_x in 0..32 {
                 let abs_x = origin_x + local_x as i64;
-                if abs_x < min_x || abs_x > max_x || abs_y < min_y || abs_y > max_y {
+                if !bounds.contains(abs_x, abs_y) {
                     *fr |= 1 << local_x;
                     continue;
                 }
@@ -2295,8 +2311,21 @@ mod tests {
         for (icn, kx, ky) in cases {
             let game = create_test_game_from_icn(icn);
             let ek = Coordinate::new(kx, ky);
-            let got = find_bitboard_cage(&game.board, &game.spatial_indices, &ek, PlayerColor::White);
-            let want = cage_eager_reference(&game.board, &game.spatial_indices, &ek, PlayerColor::White);
+            let bounds = &game.game_rules.world_bounds;
+            let got = find_bitboard_cage(
+                &game.board,
+                &game.spatial_indices,
+                bounds,
+                &ek,
+                PlayerColor::White,
+            );
+            let want = cage_eager_reference(
+                &game.board,
+                &game.spatial_indices,
+                bounds,
+                &ek,
+                PlayerColor::White,
+            );
             assert_eq!(got, want, "cage mismatch for icn: {}", icn);
         }
     }
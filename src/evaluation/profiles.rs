@@ -0,0 +1,226 @@
+// Per-variant evaluation parameter profiles.
+//
+// The compile-time `DEFAULT_EVAL_*` values are one compromise across every
+// variant, while the best piece values clearly differ between e.g. Knightline,
+// Palace and Space. A profile set, loaded from JSON at runtime, gives variants
+// and eval kinds their own `EvalParams`. `GameState::finalize_setup` stores the
+// profile that fits the position on its `GameRules`, so each game keeps its own
+// parameters no matter what other games are set up alongside it.
+//
+// Format (every section and every parameter is optional; missing parameters
+// take the compile-time defaults):
+//
+//     {
+//       "default":  { "knight": 260 },
+//       "variants": { "Knightline": { "knight": 310, "pawn": 110 } },
+//       "kinds":    { "Obstocean": { "rook": 600 } }
+//     }
+//
+// A variant entry wins over a kind entry, which wins over `default`. Variant
+// names are read like the `[Variant "…"]` tag, kinds are `EvalKind` names.
+
+use std::sync::{PoisonError, RwLock};
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::Variant;
+use crate::evaluation::eval_kind::{self, EvalKind};
+use crate::game::GameState;
+use crate::search::params::EvalParams;
+
+/// A parsed profile file. See the module comment for the JSON format.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EvalProfiles {
+    pub default: Option<EvalParams>,
+    pub variants: Vec<(Variant, EvalParams)>,
+    pub kinds: Vec<(EvalKind, EvalParams)>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct RawProfiles {
+    default: Option<Value>,
+    variants: Map<String, Value>,
+    kinds: Map<String, Value>,
+}
+
+/// Parses one profile entry, rejecting names that are not eval parameters so a typo
+/// does not silently fall back to the default value.
+fn parse_params(entry: &str, value: Value) -> Result<EvalParams, String> {
+    let Value::Object(fields) = &value else {
+        return Err(format!("{}: expected an object of eval parameters", entry));
+    };
+    let known = serde_json::to_value(EvalParams::DEFAULT).map_err(|e| e.to_string())?;
+    if let Some(unknown) = fields
+        .keys()
+        .find(|name| known.get(name.as_str()).is_none())
+    {
+        return Err(format!("{}: unknown eval parameter '{}'", entry, unknown));
+    }
+    serde_json::from_value(value).map_err(|e| format!("{}: {}", entry, e))
+}
+
+impl EvalProfiles {
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let raw: RawProfiles = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        let default = raw
+            .default
+            .map(|value| parse_params("default", value))
+            .transpose()?;
+        let variants = raw
+            .variants
+            .into_iter()
+            .map(|(name, value)| {
+                let variant = Variant::try_parse(&name)
+                    .ok_or_else(|| format!("unknown variant '{}'", name))?;
+                Ok((variant, parse_params(&name, value)?))
+            })
+            .collect::<Result<_, String>>()?;
+        let kinds = raw
+            .kinds
+            .into_iter()
+            .map(|(name, value)| {
+                let kind = EvalKind::try_parse(&name)
+                    .ok_or_else(|| format!("unknown eval kind '{}'", name))?;
+                Ok((kind, parse_params(&name, value)?))
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(Self {
+            default,
+            variants,
+            kinds,
+        })
+    }
+
+    /// The parameters a position of `variant` (if known) and `kind` plays with.
+    pub fn params_for(&self, variant: Option<Variant>, kind: EvalKind) -> EvalParams {
+        let by_variant = variant
+            .and_then(|v| self.variants.iter().find(|(pv, _)| *pv == v))
+            .map(|(_, params)| *params);
+        let by_kind = || {
+            self.kinds
+                .iter()
+                .find(|(pk, _)| *pk == kind)
+                .map(|(_, params)| *params)
+        };
+        by_variant
+            .or_else(by_kind)
+            .or(self.default)
+            .unwrap_or(EvalParams::DEFAULT)
+    }
+}
+
+static PROFILES: RwLock<Option<EvalProfiles>> = RwLock::new(None);
+
+/// Makes `profiles` the set [`params_for_game`] picks from, replacing any earlier one.
+/// Takes effect from the next position set up.
+pub fn load(profiles: EvalProfiles) {
+    *PROFILES.write().unwrap_or_else(PoisonError::into_inner) = Some(profiles);
+}
+
+/// Unloads the profiles. Positions set up from now on play with the built-in parameters.
+pub fn clear() {
+    *PROFILES.write().unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+/// The loaded profile that fits `game`, or `None` without loaded profiles, which leaves
+/// the game on the live parameters (and so keeps `set_eval_params` overrides working).
+pub fn params_for_game(game: &GameState) -> Option<EvalParams> {
+    PROFILES
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_ref()
+        .map(|profiles| profiles.params_for(game.variant, eval_kind::detect(game)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{PieceType, PlayerColor};
+
+    const PROFILES_JSON: &str = r#"{
+        "default": { "knight": 260 },
+        "variants": { "Knightline": { "knight": 310, "pawn": 110 } },
+        "kinds": { "obstocean": { "rook": 600 } }
+    }"#;
+
+    #[test]
+    fn test_profiles_pick_variant_then_kind_then_default() {
+        let profiles = EvalProfiles::from_json(PROFILES_JSON).unwrap();
+
+        let knightline = profiles.params_for(Some(Variant::Knightline), EvalKind::Generic);
+        assert_eq!(knightline.knight, 310);
+        assert_eq!(knightline.pawn, 110);
+        // Parameters an entry leaves out keep the compile-time value, not `default`'s.
+        assert_eq!(knightline.rook, EvalParams::DEFAULT.rook);
+
+        let obstocean = profiles.params_for(Some(Variant::Obstocean), EvalKind::Obstocean);
+        assert_eq!(obstocean.rook, 600);
+        assert_eq!(obstocean.knight, EvalParams::DEFAULT.knight);
+
+        let palace = profiles.params_for(Some(Variant::Palace), EvalKind::Generic);
+        assert_eq!(palace.knight, 260);
+
+        assert_eq!(
+            EvalProfiles::default().params_for(None, EvalKind::Chess),
+            EvalParams::DEFAULT
+        );
+    }
+
+    #[test]
+    fn test_games_keep_their_own_profile() {
+        let profiles = EvalProfiles::from_json(PROFILES_JSON).unwrap();
+        let setup = |variant: Variant| {
+            let mut game = GameState::new();
+            game.setup_position_from_icn(&variant.starting_icn());
+            game.game_rules.eval_params =
+                Some(profiles.params_for(Some(variant), EvalKind::Generic));
+            game.recompute_material_score();
+            game
+        };
+        let knightline = setup(Variant::Knightline);
+        let palace = setup(Variant::Palace);
+        let unprofiled = GameState::new();
+
+        // Setting up one game leaves the other's parameters alone.
+        assert_eq!(
+            knightline.get_piece_value(PieceType::Knight, PlayerColor::White),
+            310
+        );
+        assert_eq!(
+            palace.get_piece_value(PieceType::Knight, PlayerColor::White),
+            260
+        );
+        assert_eq!(
+            unprofiled.get_piece_value(PieceType::Knight, PlayerColor::White),
+            crate::evaluation::get_piece_value_base(PieceType::Knight)
+        );
+        assert_eq!(knightline.eval_params().pawn, 110);
+    }
+
+    #[test]
+    fn test_profiles_reject_unknown_names() {
+        let unknown_param = r#"{ "variants": { "Palace": { "knigth": 300 } } }"#;
+        assert!(
+            EvalProfiles::from_json(unknown_param)
+                .unwrap_err()
+                .contains("knigth")
+        );
+
+        let unknown_variant = r#"{ "variants": { "Atlantis": { "knight": 300 } } }"#;
+        assert!(
+            EvalProfiles::from_json(unknown_variant)
+                .unwrap_err()
+                .contains("Atlantis")
+        );
+
+        let unknown_section = r#"{ "variant": {} }"#;
+        assert!(EvalProfiles::from_json(unknown_section).is_err());
+
+        assert_eq!(
+            EvalProfiles::from_json("{}").unwrap(),
+            EvalProfiles::default()
+        );
+    }
+}
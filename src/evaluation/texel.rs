@@ -0,0 +1,583 @@
+//! Texel tuning of the evaluation parameters.
+//!
+//! Samples are positions labelled with the result of the game they came from. Each one is
+//! first resolved with a capture-only quiescence search, so the tuner only ever sees quiet
+//! positions. The tuner then minimises the mean squared error between the results and
+//! `sigmoid(K * eval)` over the parameters in [`TUNABLE_EVAL_PARAM_SPECS`].
+//!
+//! Gradients come from a linear model of the evaluation: every position is evaluated with
+//! each parameter nudged up and down (central differences through the real `evaluate`, so
+//! a parameter is seen by every term it feeds), and Adam runs full-batch on that model. The
+//! model is rebuilt from the real evaluation every few epochs as the parameters move away
+//! from where it was taken.
+//!
+//! [`TUNABLE_EVAL_PARAM_SPECS`]: crate::search::params::TUNABLE_EVAL_PARAM_SPECS
+
+use super::{base, insufficient_material};
+use crate::board::PlayerColor;
+use crate::game::GameState;
+use crate::moves::{Move, MoveGenContext, MoveList, get_quiescence_captures};
+use crate::search::GameRecord;
+use crate::search::params::{EvalParamSpec, get_eval_params_as_json, set_eval_params_from_json};
+use rayon::prelude::*;
+use serde_json::Value;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+
+/// Positions parsed and evaluated at once while building the model; bounds the memory
+/// held by live games.
+const CHUNK: usize = 1 << 15;
+/// Captures followed from a sample before it stands pat.
+const MAX_RESOLVE_DEPTH: usize = 8;
+/// Score for a side whose royal has been captured during resolution.
+const LOST: i32 = 100_000;
+/// Resolved positions scored past this are already decided and say little about the
+/// parameters, so they are not sampled.
+const MAX_SAMPLE_EVAL: i32 = 3000;
+
+/// `gen_nnue_data` file header.
+const NNUE_DATA_MAGIC: &[u8; 8] = b"INNUE1\0\0";
+
+const ADAM_BETA1: f64 = 0.9;
+const ADAM_BETA2: f64 = 0.999;
+const ADAM_EPSILON: f64 = 1e-8;
+
+/// A quiet position and the result of its game from White's side: 1, 0.5 or 0.
+#[derive(Clone, Debug)]
+pub struct Position {
+    pub icn: String,
+    pub result: f32,
+}
+
+#[inline]
+fn evaluate(game: &GameState) -> i32 {
+    #[cfg(feature = "nnue")]
+    return super::evaluate(game, None);
+    #[cfg(not(feature = "nnue"))]
+    super::evaluate(game)
+}
+
+/// Plays out the captures in `game` and returns the quiet position at the end of the
+/// principal variation as ICN. Positions that make poor samples give `None`: the side to
+/// move in check or out of moves, a leaf in check, or a score past `MAX_SAMPLE_EVAL`.
+/// `game` is left as it was.
+pub fn resolve(game: &mut GameState) -> Option<String> {
+    if game.is_in_check() || game.outcome().is_some() {
+        return None;
+    }
+    let mut pv = Vec::new();
+    if qsearch(game, -LOST - 1, LOST + 1, 0, &mut pv).abs() > MAX_SAMPLE_EVAL {
+        return None;
+    }
+
+    let undos: Vec<_> = pv.iter().map(|m| game.make_move(m)).collect();
+    let icn = (!game.is_in_check()).then(|| game.to_icn());
+    for (m, undo) in pv.iter().zip(undos).rev() {
+        game.undo_move(m, undo);
+    }
+    icn
+}
+
+fn qsearch(
+    game: &mut GameState,
+    mut alpha: i32,
+    beta: i32,
+    depth: usize,
+    pv: &mut Vec<Move>,
+) -> i32 {
+    pv.clear();
+    if game.has_lost_by_royal_capture() || game.has_lost_by_koth() {
+        return -LOST;
+    }
+    let stand_pat = evaluate(game);
+    if stand_pat >= beta || depth == MAX_RESOLVE_DEPTH {
+        return stand_pat;
+    }
+    alpha = alpha.max(stand_pat);
+
+    let mut moves: Vec<(i32, Move)> = captures(game)
+        .into_iter()
+        .map(|m| (crate::search::static_exchange_eval(game, &m), m))
+        .filter(|&(see, _)| see >= 0)
+        .collect();
+    moves.sort_by_key(|&(see, _)| std::cmp::Reverse(see));
+
+    let mut best = stand_pat;
+    let mut child = Vec::new();
+    for (_, m) in moves {
+        let undo = game.make_move(&m);
+        if game.is_move_illegal() {
+            game.undo_move(&m, undo);
+            continue;
+        }
+        let score = -qsearch(game, -beta, -alpha, depth + 1, &mut child);
+        game.undo_move(&m, undo);
+
+        if score > best {
+            best = score;
+            if score > alpha {
+                alpha = score;
+                pv.clear();
+                pv.push(m);
+                pv.extend_from_slice(&child);
+            }
+            if score >= beta {
+                break;
+            }
+        }
+    }
+    best
+}
+
+fn captures(game: &GameState) -> MoveList {
+    let king = match game.turn {
+        PlayerColor::White => game.white_royals.first(),
+        _ => game.black_royals.first(),
+    };
+    let pinned = king
+        .map(|k| game.compute_pins(k, game.turn))
+        .unwrap_or_default();
+    let ctx = MoveGenContext {
+        pinned: &pinned,
+        special_rights: &game.special_rights,
+        en_passant: &game.en_passant,
+        game_rules: &game.game_rules,
+        indices: &game.spatial_indices,
+        enemy_king_pos: game.enemy_king_pos(),
+    };
+    let mut moves = MoveList::new();
+    get_quiescence_captures(&game.board, game.turn, &ctx, &mut moves);
+    moves
+}
+
+/// The resolved positions of a finished game, skipping the first `skip` plies (usually
+/// book moves) and then taking every `every`th position.
+pub fn game_positions(
+    record: GameRecord,
+    skip: usize,
+    every: usize,
+) -> Result<Vec<Position>, String> {
+    let result = match record.winner {
+        Some(PlayerColor::White) => 1.0,
+        Some(_) => 0.0,
+        None => 0.5,
+    };
+    let mut game = record.start;
+    let mut positions = Vec::new();
+    for (ply, token) in record.moves.iter().enumerate() {
+        if ply >= skip
+            && (ply - skip).is_multiple_of(every.max(1))
+            && let Some(icn) = resolve(&mut game)
+        {
+            positions.push(Position { icn, result });
+        }
+        let m = game
+            .find_site_move(token)
+            .ok_or_else(|| format!("illegal move: {token}"))?;
+        game.make_move(&m);
+    }
+    Ok(positions)
+}
+
+/// The resolved positions of a `gen_nnue_data` file. Its records only hold NNUE features
+/// and the result, so the positions themselves come from the `.icn` file written next to
+/// it, one line per record. Files without that sidecar, or whose sidecar does not hold
+/// exactly one position per record, are refused.
+pub fn nnue_data_positions(path: &Path) -> Result<Vec<Position>, String> {
+    let read_err = |e: std::io::Error| format!("{}: {}", path.display(), e);
+    let mut data = BufReader::new(std::fs::File::open(path).map_err(read_err)?);
+
+    let mut header = [0u8; 20];
+    data.read_exact(&mut header).map_err(read_err)?;
+    if &header[..8] != NNUE_DATA_MAGIC {
+        return Err(format!("{}: not a gen_nnue_data file", path.display()));
+    }
+    let records = u64::from_le_bytes(header[12..20].try_into().unwrap());
+
+    let icn_path = path.with_extension("icn");
+    let icn_file = std::fs::File::open(&icn_path).map_err(|e| {
+        format!(
+            "{}: {} (data written before gen_nnue_data saved positions cannot be tuned on)",
+            icn_path.display(),
+            e
+        )
+    })?;
+    let icns = BufReader::new(icn_file)
+        .lines()
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("{}: {}", icn_path.display(), e))?;
+    if icns.len() as u64 != records {
+        return Err(format!(
+            "{} has {} positions for the {} records in {}; regenerate the data",
+            icn_path.display(),
+            icns.len(),
+            records,
+            path.display()
+        ));
+    }
+
+    let mut raw = Vec::with_capacity(icns.len());
+    for icn in icns {
+        let mut lengths = [0u8; 9];
+        data.read_exact(&mut lengths).map_err(read_err)?;
+        let features: usize = lengths[1..]
+            .chunks(2)
+            .map(|n| u16::from_le_bytes([n[0], n[1]]) as usize)
+            .sum();
+        let mut rest = vec![0u8; features * 4 + 3];
+        data.read_exact(&mut rest).map_err(read_err)?;
+
+        // The result is stored from the side to move's point of view.
+        let wdl = rest[rest.len() - 1] as i8;
+        let white_wdl = if lengths[0] == 0 { wdl } else { -wdl };
+        raw.push((icn, (white_wdl as f32 + 1.0) / 2.0));
+    }
+    if data.read(&mut [0u8]).map_err(read_err)? != 0 {
+        return Err(format!(
+            "{} has more records than its header counts",
+            path.display()
+        ));
+    }
+
+    Ok(raw
+        .into_par_iter()
+        .filter_map(|(icn, result)| {
+            let mut game = GameState::new();
+            game.setup_position_from_icn(&icn);
+            resolve(&mut game).map(|icn| Position { icn, result })
+        })
+        .collect())
+}
+
+/// Makes `values` the live evaluation parameters, on every thread.
+fn set_params(specs: &[EvalParamSpec], values: &[i64]) {
+    let mut params: serde_json::Map<String, Value> =
+        serde_json::from_str(&get_eval_params_as_json()).unwrap_or_default();
+    for (spec, &value) in specs.iter().zip(values) {
+        params.insert(spec.name.to_string(), value.into());
+    }
+    set_eval_params_from_json(&Value::Object(params).to_string());
+
+    // Both caches are per thread and keyed by the position alone.
+    let clear = || {
+        base::clear_pawn_cache();
+        insufficient_material::clear_material_cache();
+    };
+    rayon::broadcast(|_| clear());
+    clear();
+}
+
+/// Evaluations from White's side under the live parameters.
+fn white_evals(games: &mut [GameState]) -> Vec<i32> {
+    games
+        .par_iter_mut()
+        .map(|game| {
+            game.recompute_material_score();
+            let eval = evaluate(game);
+            if game.turn == PlayerColor::Black {
+                -eval
+            } else {
+                eval
+            }
+        })
+        .collect()
+}
+
+#[inline]
+fn sigmoid(k: f64, eval: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-k * eval / 400.0))
+}
+
+/// One position's evaluation from White's side, linearised around the parameters the
+/// model was built at: `base + sum(slope * (theta - anchor))`.
+#[derive(Clone, Debug, Default)]
+struct Row {
+    base: f32,
+    slopes: Vec<(u16, f32)>,
+}
+
+/// Adam over a linear model of the evaluation. See the module docs.
+pub struct Tuner {
+    specs: Vec<EvalParamSpec>,
+    positions: Vec<Position>,
+    rows: Vec<Row>,
+    theta: Vec<f64>,
+    anchor: Vec<f64>,
+    k: f64,
+    m: Vec<f64>,
+    v: Vec<f64>,
+    t: i32,
+}
+
+impl Tuner {
+    /// A tuner over `specs`, starting from their defaults. Call [`Tuner::linearize`]
+    /// before anything else.
+    pub fn new(specs: Vec<EvalParamSpec>, positions: Vec<Position>) -> Self {
+        let theta: Vec<f64> = specs.iter().map(|s| s.default as f64).collect();
+        let n = specs.len();
+        Tuner {
+            specs,
+            positions,
+            rows: Vec::new(),
+            anchor: theta.clone(),
+            theta,
+            k: 1.0,
+            m: vec![0.0; n],
+            v: vec![0.0; n],
+            t: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    pub fn k(&self) -> f64 {
+        self.k
+    }
+
+    pub fn set_k(&mut self, k: f64) {
+        self.k = k;
+    }
+
+    /// The current parameter values, rounded to what the engine can use.
+    pub fn values(&self) -> Vec<(&'static str, i64)> {
+        self.specs
+            .iter()
+            .zip(&self.theta)
+            .map(|(spec, &value)| (spec.name, spec.clamp_value(value.round() as i64)))
+            .collect()
+    }
+
+    /// Rebuilds the linear model from the real evaluation at the current (rounded)
+    /// parameters and leaves those parameters live. Afterwards [`Tuner::loss`] is the
+    /// exact loss.
+    pub fn linearize(&mut self) {
+        let anchor: Vec<i64> = self.values().into_iter().map(|(_, v)| v).collect();
+        let steps: Vec<i64> = self
+            .specs
+            .iter()
+            .map(|s| (s.c_end.round() as i64).max(1))
+            .collect();
+
+        let mut rows = Vec::with_capacity(self.positions.len());
+        for chunk in self.positions.chunks(CHUNK) {
+            let mut games: Vec<GameState> = chunk
+                .par_iter()
+                .map(|p| {
+                    let mut game = GameState::new();
+                    game.setup_position_from_icn(&p.icn);
+                    game
+                })
+                .collect();
+
+            set_params(&self.specs, &anchor);
+            let mut chunk_rows: Vec<Row> = white_evals(&mut games)
+                .into_iter()
+                .map(|base| Row {
+                    base: base as f32,
+                    slopes: Vec::new(),
+                })
+                .collect();
+
+            let mut values = anchor.clone();
+            for (i, &step) in steps.iter().enumerate() {
+                values[i] = anchor[i] + step;
+                set_params(&self.specs, &values);
+                let plus = white_evals(&mut games);
+                values[i] = anchor[i] - step;
+                set_params(&self.specs, &values);
+                let minus = white_evals(&mut games);
+                values[i] = anchor[i];
+
+                for (row, (p, m)) in chunk_rows.iter_mut().zip(plus.iter().zip(&minus)) {
+                    if p != m {
+                        let slope = (p - m) as f32 / (2 * step) as f32;
+                        row.slopes.push((i as u16, slope));
+                    }
+                }
+            }
+            rows.extend(chunk_rows);
+        }
+
+        set_params(&self.specs, &anchor);
+        self.anchor = anchor.into_iter().map(|v| v as f64).collect();
+        self.theta.clone_from(&self.anchor);
+        self.rows = rows;
+    }
+
+    #[inline]
+    fn eval(&self, row: &Row) -> f64 {
+        row.base as f64
+            + row
+                .slopes
+                .iter()
+                .map(|&(i, slope)| {
+                    slope as f64 * (self.theta[i as usize] - self.anchor[i as usize])
+                })
+                .sum::<f64>()
+    }
+
+    /// Mean squared error of the model at the current parameters.
+    pub fn loss(&self) -> f64 {
+        let total: f64 = self
+            .rows
+            .par_iter()
+            .zip(self.positions.par_iter())
+            .map(|(row, p)| (p.result as f64 - sigmoid(self.k, self.eval(row))).powi(2))
+            .sum();
+        total / self.rows.len().max(1) as f64
+    }
+
+    /// Sets K to the scale that best fits the current evaluations to the results.
+    pub fn fit_k(&mut self) -> f64 {
+        let (mut lo, mut hi) = (0.0, 10.0);
+        for _ in 0..60 {
+            let a = lo + (hi - lo) / 3.0;
+            let b = hi - (hi - lo) / 3.0;
+            self.k = a;
+            let loss_a = self.loss();
+            self.k = b;
+            if loss_a < self.loss() {
+                hi = b;
+            } else {
+                lo = a;
+            }
+        }
+        self.k = (lo + hi) / 2.0;
+        self.k
+    }
+
+    fn gradient(&self) -> Vec<f64> {
+        let n = self.specs.len();
+        let scale = self.k * std::f64::consts::LN_10 / 400.0;
+        let sum = self
+            .rows
+            .par_iter()
+            .zip(self.positions.par_iter())
+            .fold(
+                || vec![0.0; n],
+                |mut acc, (row, p)| {
+                    let s = sigmoid(self.k, self.eval(row));
+                    let d = 2.0 * (s - p.result as f64) * s * (1.0 - s) * scale;
+                    for &(i, slope) in &row.slopes {
+                        acc[i as usize] += d * slope as f64;
+                    }
+                    acc
+                },
+            )
+            .reduce(
+                || vec![0.0; n],
+                |mut a, b| {
+                    a.iter_mut().zip(b).for_each(|(x, y)| *x += y);
+                    a
+                },
+            );
+        let len = self.rows.len().max(1) as f64;
+        sum.into_iter().map(|g| g / len).collect()
+    }
+
+    /// One full-batch Adam step of at most about `lr` centipawns per parameter.
+    pub fn step(&mut self, lr: f64) {
+        let gradient = self.gradient();
+        self.t += 1;
+        let m_scale = 1.0 - ADAM_BETA1.powi(self.t);
+        let v_scale = 1.0 - ADAM_BETA2.powi(self.t);
+        for (i, g) in gradient.into_iter().enumerate() {
+            self.m[i] = ADAM_BETA1 * self.m[i] + (1.0 - ADAM_BETA1) * g;
+            self.v[i] = ADAM_BETA2 * self.v[i] + (1.0 - ADAM_BETA2) * g * g;
+            let update = lr * (self.m[i] / m_scale) / ((self.v[i] / v_scale).sqrt() + ADAM_EPSILON);
+            let spec = &self.specs[i];
+            self.theta[i] = (self.theta[i] - update).clamp(spec.min as f64, spec.max as f64);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(icn: &str) -> GameState {
+        let mut game = GameState::new();
+        game.setup_position_from_icn(icn);
+        game
+    }
+
+    #[test]
+    fn test_resolve_plays_out_captures() {
+        let mut g = game("w 0/100 1 (8|1) K1,1|R5,1|P2,2|k8,8|q5,6|r7,7|p1,7");
+        let hash = g.hash;
+        let leaf = resolve(&mut g).unwrap();
+        assert_eq!(g.hash, hash);
+        assert!(leaf.contains("R5,6"), "{leaf}");
+        assert!(!leaf.contains("q5,6"), "{leaf}");
+
+        // Already quiet: the position itself.
+        let mut quiet = game("w 0/100 1 (8|1) K1,1|R5,1|P2,2|k8,8|q4,6|r7,7|p1,7");
+        assert_eq!(resolve(&mut quiet), Some(quiet.to_icn()));
+
+        // In check: not a sample.
+        assert_eq!(resolve(&mut game("w 0/100 1 (8|1) K1,1|k8,8|q1,5")), None);
+    }
+
+    #[test]
+    fn test_nnue_data_needs_one_position_per_record() {
+        let path = std::env::temp_dir().join(format!("apeiron-texel-{}.bin", std::process::id()));
+        let mut data = NNUE_DATA_MAGIC.to_vec();
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(&1u64.to_le_bytes());
+        // White to move, no features, teacher score 0, White won.
+        data.extend_from_slice(&[0; 11]);
+        data.push(1);
+        std::fs::write(&path, data).unwrap();
+
+        assert!(nnue_data_positions(&path).is_err(), "no position file");
+
+        let icn_path = path.with_extension("icn");
+        let quiet = "w 0/100 1 (8|1) K1,1|R5,1|P2,2|k8,8|q4,6|r7,7|p1,7";
+        std::fs::write(&icn_path, format!("{quiet}\n{quiet}\n")).unwrap();
+        assert!(nnue_data_positions(&path).is_err(), "one position too many");
+
+        std::fs::write(&icn_path, format!("{quiet}\n")).unwrap();
+        let positions = nnue_data_positions(&path).unwrap();
+        assert_eq!(positions.len(), 1);
+        assert_eq!(positions[0].result, 1.0);
+
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(&icn_path);
+    }
+
+    #[test]
+    fn test_adam_fits_a_linear_model() {
+        // One parameter, eval = pieces * value; results follow the sigmoid at 300.
+        let spec = EvalParamSpec::new("knight", 200, 100, 500, 4.0, 0.002, "Knight value");
+        let positions: Vec<Position> = (-3..=3)
+            .map(|pieces| Position {
+                icn: String::new(),
+                result: sigmoid(1.0, pieces as f64 * 300.0) as f32,
+            })
+            .collect();
+        let mut tuner = Tuner::new(vec![spec], positions);
+        tuner.rows = (-3..=3)
+            .map(|pieces| Row {
+                base: pieces as f32 * 200.0,
+                slopes: vec![(0, pieces as f32)],
+            })
+            .collect();
+
+        let start = tuner.loss();
+        for _ in 0..300 {
+            tuner.step(2.0);
+        }
+        assert!(tuner.loss() < start);
+        assert!(
+            (tuner.values()[0].1 - 300).abs() <= 2,
+            "{:?}",
+            tuner.values()
+        );
+    }
+}
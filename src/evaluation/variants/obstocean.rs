@@ -363,12 +363,14 @@ fn psqt_value(pt: PieceType, x: i64, y: i64, color: PlayerColor, phase: i32) ->
 
 /// Count available knight moves on full Obstocean board.
 #[inline]
-fn count_knight_mobility(board: &crate::board::Board, x: i64, y: i64, piece: crate::board::Piece) -> i32 {
+fn count_knight_mobility(game: &GameState, x: i64, y: i64, piece: crate::board::Piece) -> i32 {
+    let board = &game.board;
+    let bounds = &game.game_rules.world_bounds;
     let our_color = piece.color();
     let mut count = 0i32;
     for (dx, dy) in [(2, 1), (2, -1), (-2, 1), (-2, -1), (1, 2), (1, -2), (-1, 2), (-1, -2)] {
         let (tx, ty) = (x + dx, y + dy);
-        if !crate::moves::in_bounds(tx, ty) {
+        if !crate::moves::in_bounds(bounds, tx, ty) {
             continue;
         }
         match board.get_piece(tx, ty) {
@@ -446,7 +448,7 @@ fn eval_knight(
     my_pawns: &[(i64, i64)],
     promo_rank: i64,
 ) -> i32 {
-    let mob = count_knight_mobility(&game.board, x, y, piece);
+    let mob = count_knight_mobility(game, x, y, piece);
     let mob_idx = (mob as usize).min(8);
     let mg_mob = KNIGHT_MOB_MG[mob_idx];
     let eg_mob = KNIGHT_MOB_EG[mob_idx];
@@ -839,7 +841,7 @@ mod tests {
         let game = create_obstocean_game_from_icn("w (8;q|1;q) K5,1|k5,8|N0,4");
         for (x, y, p) in game.board.iter() {
             if p.piece_type() == PieceType::Knight && p.color() == PlayerColor::White {
-                let mob = count_knight_mobility(&game.board, x, y, p);
+                let mob = count_knight_mobility(&game, x, y, p);
                 assert!(mob > 0, "Knight should have mobility");
                 let score = eval_knight(&game, x, y, p, base::MAX_PHASE / 2, &[], game.white_promo_rank);
                 assert!(score.abs() < 500, "Knight eval should be reasonable");
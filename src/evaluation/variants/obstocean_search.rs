@@ -69,6 +69,7 @@ fn generate_captures_for_piece(
     let special_rights = ctx.special_rights;
     let en_passant = ctx.en_passant;
     let game_rules = ctx.game_rules;
+    let bounds = &game_rules.world_bounds;
     let indices = ctx.indices;
     match piece.piece_type() {
         PieceType::Void | PieceType::Obstacle => {}
@@ -89,29 +90,74 @@ fn generate_captures_for_piece(
 
         // Knight-like leapers
         PieceType::Knight => {
-            generate_leaper_moves_into(board, from, piece, 1, 2, MoveGenType::Captures, out);
+            generate_leaper_moves_into(
+                board,
+                from,
+                piece,
+                1,
+                2,
+                MoveGenType::Captures,
+                bounds,
+                out,
+            );
         }
         PieceType::Camel => {
-            generate_leaper_moves_into(board, from, piece, 1, 3, MoveGenType::Captures, out);
+            generate_leaper_moves_into(
+                board,
+                from,
+                piece,
+                1,
+                3,
+                MoveGenType::Captures,
+                bounds,
+                out,
+            );
         }
         PieceType::Giraffe => {
-            generate_leaper_moves_into(board, from, piece, 1, 4, MoveGenType::Captures, out);
+            generate_leaper_moves_into(
+                board,
+                from,
+                piece,
+                1,
+                4,
+                MoveGenType::Captures,
+                bounds,
+                out,
+            );
         }
         PieceType::Zebra => {
-            generate_leaper_moves_into(board, from, piece, 2, 3, MoveGenType::Captures, out);
+            generate_leaper_moves_into(
+                board,
+                from,
+                piece,
+                2,
+                3,
+                MoveGenType::Captures,
+                bounds,
+                out,
+            );
         }
 
         // King/Guard/Centaur/RoyalCentaur/Hawk: use compass moves, then filter captures
         PieceType::King | PieceType::Guard => {
-            generate_compass_moves_into(board, from, piece, 1, MoveGenType::Captures, out);
+            generate_compass_moves_into(board, from, piece, 1, MoveGenType::Captures, bounds, out);
         }
         PieceType::Centaur | PieceType::RoyalCentaur => {
-            generate_compass_moves_into(board, from, piece, 1, MoveGenType::Captures, out);
-            generate_leaper_moves_into(board, from, piece, 1, 2, MoveGenType::Captures, out);
+            generate_compass_moves_into(board, from, piece, 1, MoveGenType::Captures, bounds, out);
+            generate_leaper_moves_into(
+                board,
+                from,
+                piece,
+                1,
+                2,
+                MoveGenType::Captures,
+                bounds,
+                out,
+            );
         }
         PieceType::Hawk => {
-            generate_compass_moves_into(board, from, piece, 2, MoveGenType::Captures, out);
-            generate_compass_moves_into(board, from, piece, 3, MoveGenType::Captures, out);
+            generate_compass_moves_into(board, from, piece, 2, MoveGenType::Captures, bounds, out);
+            generate_compass_moves_into(board, from, piece, 3, MoveGenType::Captures, bounds, out);
         }
 
         // Standard sliders and slider-leaper compounds
@@ -128,33 +174,68 @@ fn generate_captures_for_piece(
         PieceType::Chancellor => {
             // Rook + knight
             generate_sliding_capture_moves(board, from, piece, &[(1, 0), (0, 1)], indices, out);
-            generate_leaper_moves_into(board, from, piece, 1, 2, MoveGenType::Captures, out);
+            generate_leaper_moves_into(
+                board,
+                from,
+                piece,
+                1,
+                2,
+                MoveGenType::Captures,
+                bounds,
+                out,
+            );
         }
         PieceType::Archbishop => {
             // Bishop + knight
             generate_sliding_capture_moves(board, from, piece, &[(1, 1), (1, -1)], indices, out);
-            generate_leaper_moves_into(board, from, piece, 1, 2, MoveGenType::Captures, out);
+            generate_leaper_moves_into(
+                board,
+                from,
+                piece,
+                1,
+                2,
+                MoveGenType::Captures,
+                bounds,
+                out,
+            );
         }
         PieceType::Amazon => {
             // Queen + knight
             generate_sliding_capture_moves(board, from, piece, &[(1, 0), (0, 1)], indices, out);
             generate_sliding_capture_moves(board, from, piece, &[(1, 1), (1, -1)], indices, out);
-            generate_leaper_moves_into(board, from, piece, 1, 2, MoveGenType::Captures, out);
+            generate_leaper_moves_into(
+                board,
+                from,
+                piece,
+                1,
+                2,
+                MoveGenType::Captures,
+                bounds,
+                out,
+            );
         }
 
         // Knightrider: sliding along knight vectors
         PieceType::Knightrider => {
-            generate_knightrider_moves_into(board, from, piece, MoveGenType::Captures, out);
+            generate_knightrider_moves_into(board, from, piece, MoveGenType::Captures, bounds, out);
         }
 
         // Huygen: use existing generator and keep only captures
         PieceType::Huygen => {
-            generate_huygen_moves_into(board, from, piece, indices, MoveGenType::Captures, out);
+            generate_huygen_moves_into(
+                board,
+                from,
+                piece,
+                indices,
+                MoveGenType::Captures,
+                bounds,
+                out,
+            );
         }
 
         // Rose: use existing generator and keep only captures
         PieceType::Rose => {
-            generate_rose_moves_into(board, from, piece, MoveGenType::Captures, out);
+            generate_rose_moves_into(board, from, piece, MoveGenType::Captures, bounds, out);
         }
     }
 }
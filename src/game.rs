@@ -3,7 +3,7 @@ use crate::Variant;
 use crate::board::{Board, Coordinate, Piece, PieceType, PlayerColor};
 use crate::evaluation::get_piece_phase;
 use crate::moves::{
-    Move, MoveList, SpatialIndices, get_legal_moves, get_legal_moves_into,
+    Move, MoveList, SpatialIndices, WorldBounds, get_legal_moves, get_legal_moves_into,
     get_pseudo_legal_moves_for_piece_into, is_square_attacked,
 };
 use crate::utils::{PRIMES_UNDER_128, is_prime_fast, is_prime_i64};
@@ -12,11 +12,16 @@ use rustc_hash::FxHashSet;
 use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
 
+mod icn;
+pub use icn::{IcnError, IcnErrorKind};
+
 /// Win conditions for a player. Determines how they win the game.
 /// - Checkmate: Standard - win by checkmating the opponent
 /// - RoyalCapture: Win by capturing one of the opponent's royal pieces
 /// - AllRoyalsCaptured: Win when all of the opponent's royal pieces are captured
 /// - AllPiecesCaptured: Win when all of the opponent's pieces are captured
+/// - Koth: King of the Hill - win by checkmate or by moving a king onto one of
+///   the hill squares (`GameRules::koth_squares`)
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub enum WinCondition {
     #[default]
@@ -24,6 +29,7 @@ pub enum WinCondition {
     RoyalCapture,
     AllRoyalsCaptured,
     AllPiecesCaptured,
+    Koth,
 }
 
 impl std::str::FromStr for WinCondition {
@@ -36,6 +42,7 @@ impl std::str::FromStr for WinCondition {
             "royalcapture" => Ok(WinCondition::RoyalCapture),
             "allroyalscaptured" => Ok(WinCondition::AllRoyalsCaptured),
             "allpiecescaptured" => Ok(WinCondition::AllPiecesCaptured),
+            "koth" => Ok(WinCondition::Koth),
             _ => Err(()),
         }
     }
@@ -43,12 +50,15 @@ impl std::str::FromStr for WinCondition {
 
 impl WinCondition {
     /// Select the most appropriate win condition from a list based on priority.
-    /// Priority: Checkmate > RoyalCapture > AllRoyalsCaptured > AllPiecesCaptured.
+    /// Priority: Koth > Checkmate > RoyalCapture > AllRoyalsCaptured > AllPiecesCaptured.
+    /// Koth goes first because it includes checkmate.
     pub fn select(conditions: &[WinCondition], opponent_has_royal: bool) -> Self {
         if !opponent_has_royal {
             return WinCondition::AllPiecesCaptured;
         }
-        if conditions.contains(&WinCondition::Checkmate) {
+        if conditions.contains(&WinCondition::Koth) {
+            WinCondition::Koth
+        } else if conditions.contains(&WinCondition::Checkmate) {
             WinCondition::Checkmate
         } else if conditions.contains(&WinCondition::RoyalCapture) {
             WinCondition::RoyalCapture
@@ -63,11 +73,22 @@ impl WinCondition {
 }
 
 impl WinCondition {
+    /// ICN spelling of this win condition (inverse of `FromStr`).
+    pub fn as_icn_str(&self) -> &'static str {
+        match self {
+            WinCondition::Checkmate => "checkmate",
+            WinCondition::RoyalCapture => "royalcapture",
+            WinCondition::AllRoyalsCaptured => "allroyalscaptured",
+            WinCondition::AllPiecesCaptured => "allpiecescaptured",
+            WinCondition::Koth => "koth",
+        }
+    }
+
     /// Returns true if this win condition requires the opponent to respond to check.
     /// For Checkmate, checks must be addressed. For capture-based conditions, king can be taken.
     #[inline]
     pub fn requires_check_evasion(&self) -> bool {
-        matches!(self, WinCondition::Checkmate)
+        matches!(self, WinCondition::Checkmate | WinCondition::Koth)
     }
 
     /// Returns true if this win condition is based on capturing royal pieces.
@@ -81,6 +102,102 @@ impl WinCondition {
     }
 }
 
+/// Default hill for the `koth` win condition: the four centre squares of the
+/// standard board (d4, e4, d5, e5), as on infinitechess.org.
+pub const KOTH_SQUARES: [Coordinate; 4] = [
+    Coordinate { x: 4, y: 4 },
+    Coordinate { x: 5, y: 4 },
+    Coordinate { x: 4, y: 5 },
+    Coordinate { x: 5, y: 5 },
+];
+
+/// Parses a hill as written inside the ICN `koth(...)` form: `x,y` squares
+/// separated by `;`.
+pub fn parse_koth_squares(s: &str) -> Option<Vec<Coordinate>> {
+    s.split(';')
+        .map(|square| {
+            let (x, y) = square.split_once(',')?;
+            Some(Coordinate::new(
+                x.trim().parse().ok()?,
+                y.trim().parse().ok()?,
+            ))
+        })
+        .collect()
+}
+
+/// Splits a win-condition token naming its own hill (`checkmate,koth(4,4;5,5)`)
+/// into the plain condition list (`checkmate,koth`) and the hill squares.
+fn split_koth_squares(token: &str) -> Option<(String, Vec<Coordinate>)> {
+    let start = token.find("koth(")?;
+    let len = token[start..].find(')')?;
+    let squares = parse_koth_squares(&token[start + 5..start + len])?;
+    let conditions = format!("{}koth{}", &token[..start], &token[start + len + 1..]);
+    Some((conditions, squares))
+}
+
+/// Why a game ended (see `GameState::outcome`).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameEndReason {
+    Checkmate,
+    Stalemate,
+    RoyalCapture,
+    AllRoyalsCaptured,
+    AllPiecesCaptured,
+    Koth,
+    Threefold,
+    MoveRule,
+    InsufficientMaterial,
+}
+
+impl GameEndReason {
+    /// Termination string used in match logs and PGN-style reports.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GameEndReason::Checkmate => "checkmate",
+            GameEndReason::Stalemate => "stalemate",
+            GameEndReason::RoyalCapture => "royalcapture",
+            GameEndReason::AllRoyalsCaptured => "allroyalscaptured",
+            GameEndReason::AllPiecesCaptured => "allpiecescaptured",
+            GameEndReason::Koth => "koth",
+            GameEndReason::Threefold => "threefold repetition",
+            GameEndReason::MoveRule => "fifty-move rule",
+            GameEndReason::InsufficientMaterial => "insufficient_material",
+        }
+    }
+}
+
+/// Result of a finished game: the winner (`None` for a draw) and the reason.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameOutcome {
+    pub winner: Option<PlayerColor>,
+    pub reason: GameEndReason,
+}
+
+impl GameOutcome {
+    fn win(winner: PlayerColor, reason: GameEndReason) -> Self {
+        GameOutcome {
+            winner: Some(winner),
+            reason,
+        }
+    }
+
+    fn draw(reason: GameEndReason) -> Self {
+        GameOutcome {
+            winner: None,
+            reason,
+        }
+    }
+
+    /// Result string from White's perspective: "1-0", "0-1" or "1/2-1/2".
+    pub fn result_str(&self) -> &'static str {
+        match self.winner {
+            Some(PlayerColor::White) => "1-0",
+            Some(PlayerColor::Black) => "0-1",
+            _ => "1/2-1/2",
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub struct EnPassantState {
     pub square: Coordinate,
@@ -122,6 +239,26 @@ pub struct GameRules {
     /// Game variant used for move generation in specific variants.
     #[serde(skip)]
     pub variant: Option<crate::Variant>,
+    /// World border, set from the ICN bounds token (unbounded if absent).
+    #[serde(skip)]
+    pub world_bounds: WorldBounds,
+    /// Fischer-random castling: the king lands on the g- or c-file of the
+    /// bounded board and its partner beside it, wherever both started.
+    #[serde(skip)]
+    pub chess960: bool,
+    /// Eval parameters from the loaded profile that fits this game, picked at setup.
+    /// `None` plays with the live parameters.
+    #[serde(skip)]
+    pub eval_params: Option<crate::search::params::EvalParams>,
+    /// Piece values replacing the engine's own for this game, from the ICN
+    /// `[PieceValues "..."]` tag or the JS engine config.
+    #[serde(skip)]
+    pub piece_values: PieceValues,
+    /// Hill squares for the `koth` win condition: a king moving onto one wins.
+    /// Set from the ICN `koth(x,y;…)` form, or to the four centre squares when
+    /// the ICN names a plain `koth`; hosts may replace them after setup.
+    #[serde(skip)]
+    pub koth_squares: Vec<Coordinate>,
 }
 
 impl GameRules {
@@ -138,6 +275,67 @@ impl GameRules {
     }
 }
 
+/// Per-game piece values overriding `get_piece_value_base` for the piece types
+/// they set. Material, SEE, capture ordering and mop-up all read them through
+/// [`GameState::get_piece_value`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PieceValues([Option<i32>; PieceValues::SLOTS]);
+
+impl PieceValues {
+    const SLOTS: usize = PieceType::Pawn as usize + 1;
+
+    #[inline(always)]
+    pub fn get(&self, pt: PieceType) -> Option<i32> {
+        self.0[pt as usize]
+    }
+
+    pub fn set(&mut self, pt: PieceType, value: i32) {
+        self.0[pt as usize] = Some(value);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(Option::is_none)
+    }
+
+    /// Takes every value `other` sets, keeping the rest.
+    pub fn merge(&mut self, other: &PieceValues) {
+        for (slot, value) in self.0.iter_mut().zip(other.0) {
+            if value.is_some() {
+                *slot = value;
+            }
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (PieceType, i32)> + '_ {
+        self.0
+            .iter()
+            .enumerate()
+            .filter_map(|(i, v)| v.map(|v| (PieceType::from_u8(i as u8), v)))
+    }
+
+    /// Parses comma-separated `<code>=<value>` pairs in ICN piece codes, e.g.
+    /// `RO=650,NR=720`, as written in the `[PieceValues "..."]` tag.
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut values = PieceValues::default();
+        for pair in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (code, value) = pair.split_once('=')?;
+            values.set(
+                PieceType::parse_promotion_code(code.trim())?,
+                value.trim().parse().ok()?,
+            );
+        }
+        Some(values)
+    }
+
+    /// The `[PieceValues "..."]` tag body [`PieceValues::parse`] reads back.
+    pub fn to_icn(&self) -> String {
+        self.iter()
+            .map(|(pt, v)| format!("{}={}", pt.to_site_code(), v))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
 /// Entry in move history for repetition detection.
 /// Stores move details to check if a reversal would repeat a position.
 #[derive(Clone, Copy, Debug)]
@@ -579,8 +777,23 @@ impl GameState {
         }
     }
 
+    /// The eval parameters this game plays with: its profile if one was picked at setup,
+    /// otherwise the live ones.
+    #[inline]
+    pub fn eval_params(&self) -> crate::search::params::EvalParams {
+        self.game_rules
+            .eval_params
+            .unwrap_or_else(crate::search::params::live_eval_params)
+    }
+
     pub fn get_piece_value(&self, pt: PieceType, color: PlayerColor) -> i32 {
-        let base = crate::evaluation::base::get_piece_value_base(pt);
+        let base = match self.game_rules.piece_values.get(pt) {
+            Some(value) => value,
+            None => match &self.game_rules.eval_params {
+                Some(params) => crate::evaluation::base::piece_value_with(params, pt),
+                None => crate::evaluation::base::get_piece_value_base(pt),
+            },
+        };
         if pt.is_royal() {
             match color {
                 PlayerColor::White => base + self.white_royal_bonus,
@@ -669,6 +882,7 @@ impl GameState {
 
         // Rebuild spatial indices from current board
         self.spatial_indices = SpatialIndices::new(&self.board);
+        self.spatial_indices.region = self.game_rules.world_bounds.region.clone();
         self.recompute_castling_state();
         // Recompute check squares for O(1) check detection
         self.recompute_check_squares();
@@ -928,7 +1142,8 @@ impl GameState {
 
     /// Find the first blocker on a ray from (start_x, start_y) in direction (dx, dy).
     /// Uses spatial indices for O(1) lookup per direction.
-    /// Returns Some((x, y)) if a blocker exists, None if ray is infinite.
+    /// Returns Some((x, y)) if a blocker exists, None if the ray is infinite or
+    /// ends at a hole in the playable region.
     #[inline]
     fn find_first_blocker_on_ray(
         &self,
@@ -937,56 +1152,9 @@ impl GameState {
         dx: i64,
         dy: i64,
     ) -> Option<(i64, i64)> {
-        // Use spatial indices to find nearest piece in direction
-        // SpatialIndices stores (coord, packed_piece) tuples sorted by coord
-        // Field names: cols (x -> list of y), rows (y -> list of x),
-        //              diag1 (x-y -> list of x), diag2 (x+y -> list of x)
-
-        if dx == 0 {
-            // Vertical ray (N or S) - use cols[start_x] to get all y coords
-            if let Some(col_vec) = self.spatial_indices.cols.get(&start_x)
-                && let Some((found_y, _packed)) = col_vec.find_nearest(start_y, dy)
-            {
-                return Some((start_x, found_y));
-            }
-        } else if dy == 0 {
-            // Horizontal ray (E or W) - use rows[start_y] to get all x coords
-            if let Some((found_x, _packed)) = self
-                .spatial_indices
-                .rows
-                .get(&start_y)
-                .and_then(|row_vec| row_vec.find_nearest(start_x, dx))
-            {
-                return Some((found_x, start_y));
-            }
-        } else {
-            // Diagonal rays
-            let diag_key = start_x - start_y;
-            let anti_key = start_x + start_y;
-
-            if dx == dy {
-                // Main diagonal (NE or SW: dx == dy) - use diag1
-                if let Some(diag_vec) = self.spatial_indices.diag1.get(&diag_key) {
-                    // diag1 is indexed by x, so search for x in direction dx
-                    if let Some((found_x, _packed)) = diag_vec.find_nearest(start_x, dx) {
-                        // Reconstruct y from x: on main diagonal, x - y = diag_key
-                        let found_y = found_x - diag_key;
-                        return Some((found_x, found_y));
-                    }
-                }
-            } else {
-                // Anti-diagonal (NW or SE: dx != dy) - use diag2
-                if let Some(anti_vec) = self.spatial_indices.diag2.get(&anti_key) {
-                    // diag2 is indexed by x, search in direction dx
-                    if let Some((found_x, _packed)) = anti_vec.find_nearest(start_x, dx) {
-                        // Reconstruct y from x: on anti-diagonal, x + y = anti_key
-                        let found_y = anti_key - found_x;
-                        return Some((found_x, found_y));
-                    }
-                }
-            }
-        }
-        None // Infinite ray
+        self.spatial_indices
+            .find_first_blocker(start_x, start_y, dx, dy)
+            .map(|(x, y, _)| (x, y))
     }
 
     fn is_on_check_ray(
@@ -1068,6 +1236,13 @@ impl GameState {
         let step_x = dx.signum();
         let step_y = dy.signum();
         let dist = dx.abs().max(dy.abs());
+        if self
+            .spatial_indices
+            .region_gap(from.x, from.y, step_x, step_y, dist)
+            .is_some()
+        {
+            return false;
+        }
         if dist <= 1 {
             // Target is adjacent, check if occupied by friendly
             return !self.board.is_occupied_by_color(to.x, to.y, self.turn);
@@ -1103,6 +1278,13 @@ impl GameState {
         let step_x = dx.signum();
         let step_y = dy.signum();
         let dist = dx.abs();
+        if self
+            .spatial_indices
+            .region_gap(from.x, from.y, step_x, step_y, dist)
+            .is_some()
+        {
+            return false;
+        }
         if dist <= 1 {
             return !self.board.is_occupied_by_color(to.x, to.y, self.turn);
         }
@@ -1350,6 +1532,26 @@ impl GameState {
         false
     }
 
+    /// Check if the side-to-move has lost because the opponent (who just moved)
+    /// has a king on one of the hill squares. Only relevant for the Koth win
+    /// condition; the win-condition compare keeps it free for everything else.
+    #[inline]
+    pub fn has_lost_by_koth(&self) -> bool {
+        let (opponent_win_condition, opponent_royals) = match self.turn {
+            PlayerColor::White => (self.game_rules.black_win_condition, &self.black_royals),
+            PlayerColor::Black => (self.game_rules.white_win_condition, &self.white_royals),
+            PlayerColor::Neutral => return false,
+        };
+        opponent_win_condition == WinCondition::Koth
+            && opponent_royals.iter().any(|c| {
+                self.game_rules.koth_squares.contains(c)
+                    && self
+                        .board
+                        .get_piece(c.x, c.y)
+                        .is_some_and(|p| p.piece_type() == PieceType::King)
+            })
+    }
+
     /// Repetition detection for search.
     /// Returns true if the current position should be treated as a draw due to repetition.
     ///
@@ -1570,6 +1772,77 @@ impl GameState {
         }
     }
 
+    /// True if the side to move has at least one fully legal move.
+    pub fn has_legal_move(&self) -> bool {
+        self.get_legal_moves().iter().any(|m| {
+            let mut copy = self.clone();
+            copy.make_move(m);
+            !copy.is_move_illegal()
+        })
+    }
+
+    /// Decides whether the game is over in the current position, and how.
+    /// Wins are checked before draws, so a mate delivered on the move that
+    /// also hits the move-rule limit counts as a mate.
+    pub fn outcome(&self) -> Option<GameOutcome> {
+        // How the opponent (who just moved) beats the side to move.
+        let opp_wc = match self.turn {
+            PlayerColor::White => self.game_rules.black_win_condition,
+            PlayerColor::Black => self.game_rules.white_win_condition,
+            PlayerColor::Neutral => return None,
+        };
+        let opponent = self.turn.opponent();
+
+        if self.has_lost_by_koth() {
+            return Some(GameOutcome::win(opponent, GameEndReason::Koth));
+        }
+
+        // AllPiecesCaptured must come before royal capture: taking the last
+        // piece (a king) also empties the royals.
+        if opp_wc == WinCondition::AllPiecesCaptured {
+            let has_royals = match self.turn {
+                PlayerColor::White => !self.white_royals.is_empty(),
+                _ => !self.black_royals.is_empty(),
+            };
+            if !self.has_pieces(self.turn) && !has_royals {
+                return Some(GameOutcome::win(opponent, GameEndReason::AllPiecesCaptured));
+            }
+        } else if self.has_lost_by_royal_capture() {
+            match opp_wc {
+                WinCondition::RoyalCapture => {
+                    return Some(GameOutcome::win(opponent, GameEndReason::RoyalCapture));
+                }
+                WinCondition::AllRoyalsCaptured => {
+                    return Some(GameOutcome::win(opponent, GameEndReason::AllRoyalsCaptured));
+                }
+                _ => {}
+            }
+        }
+
+        if !self.has_legal_move() {
+            if self.is_in_check() && self.must_escape_check() {
+                return Some(GameOutcome::win(opponent, GameEndReason::Checkmate));
+            }
+            if !self.has_pieces(self.turn) {
+                return Some(GameOutcome::win(opponent, GameEndReason::AllPiecesCaptured));
+            }
+            return Some(GameOutcome::draw(GameEndReason::Stalemate));
+        }
+
+        if crate::evaluation::insufficient_material::evaluate_insufficient_material_game_handler(
+            self,
+        ) {
+            return Some(GameOutcome::draw(GameEndReason::InsufficientMaterial));
+        }
+        if self.null_moves == 0 && self.repetition < 0 {
+            return Some(GameOutcome::draw(GameEndReason::Threefold));
+        }
+        if self.is_fifty() {
+            return Some(GameOutcome::draw(GameEndReason::MoveRule));
+        }
+        None
+    }
+
     /// Make a null move (just flip turn, for null move pruning)
     pub fn make_null_move(&mut self) {
         use crate::search::zobrist::{REP_SIDE_KEY, SIDE_KEY, en_passant_key, rep_en_passant_key};
@@ -1852,7 +2125,15 @@ impl GameState {
             let mut illegal = false;
             let pt = m.piece.piece_type();
 
-            if pt.is_royal() {
+            if pt.is_royal() && m.rook_coord.is_some() && self.game_rules.chess960 {
+                // A Fischer-random partner can be what shields the king's
+                // destination, so the castle is checked on the resulting position.
+                let mut s_mut = self.clone();
+                let _undo = s_mut.make_move(&m);
+                if s_mut.is_move_illegal() {
+                    illegal = true;
+                }
+            } else if pt.is_royal() {
                 // King moves: destination must not be attacked
                 use crate::moves::is_square_attacked;
                 if is_square_attacked(
@@ -2967,6 +3248,17 @@ impl GameState {
                 process_piece(self, Coordinate::new(ax, ay), &p, out);
             }
         }
+
+        // A slider that reaches the checker is found by the ray intercept, the direct
+        // capture and the pseudo-legal scan alike; keep one copy of each move.
+        let mut i = 0;
+        while i < out.len() {
+            if out[..i].contains(&out[i]) {
+                out.remove(i);
+            } else {
+                i += 1;
+            }
+        }
     }
 
     /// Ultra-fast legality check (C1 Optimization).
@@ -3040,6 +3332,23 @@ impl GameState {
         }
     }
 
+    /// The first fully legal move matching `pred`: pseudo-legal moves from
+    /// [`get_legal_moves`](Self::get_legal_moves) are tried with make/undo and kept only if
+    /// [`is_move_illegal`](Self::is_move_illegal) passes.
+    pub fn find_legal_move(&mut self, pred: impl Fn(&Move) -> bool) -> Option<Move> {
+        let candidates = self.get_legal_moves();
+        candidates
+            .iter()
+            .filter(|&m| pred(m))
+            .find(|m| {
+                let undo = self.make_move(m);
+                let legal = !self.is_move_illegal();
+                self.undo_move(m, undo);
+                legal
+            })
+            .copied()
+    }
+
     /// Check if the side that just moved left their royal piece(s) in check (illegal move).
     /// Call this AFTER make_move to verify legality.
     /// Checks all royal pieces: King, RoyalQueen, RoyalCentaur
@@ -3275,6 +3584,10 @@ impl GameState {
 
         let from_coord = Coordinate::new(m.from.x, m.from.y);
 
+        // Castling key of the pre-move position. Taken before the board changes:
+        // the precise form hashes each rights holder still standing on its square.
+        let (old_castle_hash, old_castle_rep_hash) = self.castling_hash_pair();
+
         let piece = self.board.remove_piece(&m.from.x, &m.from.y).unwrap();
         // Update spatial indices: remove moving piece from source square
         self.spatial_indices.remove(m.from.x, m.from.y);
@@ -3299,6 +3612,17 @@ impl GameState {
             }
         }
 
+        // Lift a castling partner before the destination is read: a Fischer-random
+        // king may land on its own partner's square, which is not a capture.
+        let mut castling_rook = None;
+        if piece.piece_type().is_royal()
+            && let Some(rook_coord) = m.rook_coord
+            && let Some(rook) = self.board.remove_piece(&rook_coord.x, &rook_coord.y)
+        {
+            self.spatial_indices.remove(rook_coord.x, rook_coord.y);
+            castling_rook = Some((rook_coord, rook));
+        }
+
         let mut undo_info = UndoMove {
             captured_piece: self.board.get_piece(m.to.x, m.to.y),
             old_en_passant: self.en_passant,
@@ -3344,9 +3668,13 @@ impl GameState {
         let is_capture = undo_info.captured_piece.is_some();
 
         if let Some(captured) = &undo_info.captured_piece {
-            // Remove captured piece (XOR works for both neutral and non-neutral)
-            self.hash ^= piece_key(captured.piece_type(), captured.color(), m.to.x, m.to.y);
-            self.rep_hash ^= rep_piece_key(captured.piece_type(), captured.color(), m.to.x, m.to.y);
+            // Remove captured piece. Neutral pieces are never hashed (see
+            // recompute_hash), so capturing one leaves the piece keys untouched.
+            if captured.color() != PlayerColor::Neutral {
+                self.hash ^= piece_key(captured.piece_type(), captured.color(), m.to.x, m.to.y);
+                self.rep_hash ^=
+                    rep_piece_key(captured.piece_type(), captured.color(), m.to.x, m.to.y);
+            }
 
             // Update correction hashes incrementally for captured piece
             if captured.piece_type() == PieceType::Pawn {
@@ -3497,7 +3825,6 @@ impl GameState {
             self.rep_hash ^= rep_en_passant_key(ep.square.x, ep.square.y);
         }
 
-        let (old_castle_hash, old_castle_rep_hash) = self.castling_hash_pair();
         self.hash ^= old_castle_hash;
         self.rep_hash ^= old_castle_rep_hash;
         let mut castling_state_dirty = false;
@@ -3527,14 +3854,9 @@ impl GameState {
             }
         }
 
-        // Handle Castling Move (Royal moves exactly 2 squares)
-        if piece.piece_type().is_royal()
-            && (m.to.x - m.from.x).abs() == 2
-            && let Some(rook_coord) = &m.rook_coord
-            && let Some(rook) = self.board.remove_piece(&rook_coord.x, &rook_coord.y)
-        {
-            let dx = m.to.x - m.from.x;
-            let direction = if dx > 0 { 1 } else { -1 };
+        // Handle Castling Move (partner lands on the king's inner side)
+        if let Some((rook_coord, rook)) = castling_rook {
+            let direction = if rook_coord.x > m.from.x { 1 } else { -1 };
             let rook_to_x = m.to.x - direction;
             // Move rook in castling
             self.hash ^= piece_key(rook.piece_type(), rook.color(), rook_coord.x, rook_coord.y);
@@ -3554,13 +3876,12 @@ impl GameState {
                     piece_key(rook.piece_type(), rook.color(), rook_to_x, m.from.y);
             }
             self.board.set_piece(rook_to_x, m.from.y, rook);
-            // Update spatial indices for rook move
-            self.spatial_indices.remove(rook_coord.x, rook_coord.y);
+            // Update spatial indices for rook move (source already lifted above)
             self.spatial_indices.add(rook_to_x, m.from.y, rook.packed());
 
             // Rook also loses special rights
-            if self.special_rights.remove(rook_coord) {
-                undo_info.special_rights_removed.push(*rook_coord);
+            if self.special_rights.remove(&rook_coord) {
+                undo_info.special_rights_removed.push(rook_coord);
                 castling_state_dirty = true;
             }
         }
@@ -3737,6 +4058,43 @@ impl GameState {
             }
         }
 
+        // Handle Castling Revert. Runs before the king returns to 'from', which
+        // a Fischer-random partner may have landed on.
+        if piece.piece_type().is_royal()
+            && let Some(rook_coord) = &m.rook_coord
+        {
+            let direction = if rook_coord.x > m.from.x { 1 } else { -1 };
+            let rook_to_x = m.to.x - direction;
+            if let Some(rook) = self.board.remove_piece(&rook_to_x, &m.from.y) {
+                self.board.set_piece(rook_coord.x, rook_coord.y, rook);
+                // Update spatial indices for rook moved back
+                self.spatial_indices.remove(rook_to_x, m.from.y);
+                self.spatial_indices
+                    .add(rook_coord.x, rook_coord.y, rook.packed());
+
+                // Revert non-pawn hash for rook
+                if rook.color() == PlayerColor::White {
+                    self.white_nonpawn_hash ^=
+                        piece_key(rook.piece_type(), rook.color(), rook_to_x, m.from.y);
+                    self.white_nonpawn_hash ^= piece_key(
+                        rook.piece_type(),
+                        rook.color(),
+                        rook_coord.x,
+                        rook_coord.y,
+                    );
+                } else {
+                    self.black_nonpawn_hash ^=
+                        piece_key(rook.piece_type(), rook.color(), rook_to_x, m.from.y);
+                    self.black_nonpawn_hash ^= piece_key(
+                        rook.piece_type(),
+                        rook.color(),
+                        rook_coord.x,
+                        rook_coord.y,
+                    );
+                }
+            }
+        }
+
         // Handle Promotion Revert
         if m.promotion.is_some() {
             // Convert back to pawn: Remove promo type, Add pawn type
@@ -3874,46 +4232,6 @@ impl GameState {
             }
         }
 
-        // Handle Castling Revert
-        if piece.piece_type().is_royal() {
-            let dx = m.to.x - m.from.x;
-            if dx.abs() == 2 {
-                // Castling was performed. Move rook back.
-                if let Some(rook_coord) = &m.rook_coord {
-                    let direction = if dx > 0 { 1 } else { -1 };
-                    let rook_to_x = m.to.x - direction;
-                    if let Some(rook) = self.board.remove_piece(&rook_to_x, &m.from.y) {
-                        self.board.set_piece(rook_coord.x, rook_coord.y, rook);
-                        // Update spatial indices for rook moved back
-                        self.spatial_indices.remove(rook_to_x, m.from.y);
-                        self.spatial_indices
-                            .add(rook_coord.x, rook_coord.y, rook.packed());
-
-                        // Revert non-pawn hash for rook
-                        if rook.color() == PlayerColor::White {
-                            self.white_nonpawn_hash ^=
-                                piece_key(rook.piece_type(), rook.color(), rook_to_x, m.from.y);
-                            self.white_nonpawn_hash ^= piece_key(
-                                rook.piece_type(),
-                                rook.color(),
-                                rook_coord.x,
-                                rook_coord.y,
-                            );
-                        } else {
-                            self.black_nonpawn_hash ^=
-                                piece_key(rook.piece_type(), rook.color(), rook_to_x, m.from.y);
-                            self.black_nonpawn_hash ^= piece_key(
-                                rook.piece_type(),
-                                rook.color(),
-                                rook_coord.x,
-                                rook_coord.y,
-                            );
-                        }
-                    }
-                }
-            }
-        }
-
         // Restore state
         self.en_passant = undo.old_en_passant;
         // Re-insert removed special rights instead of restoring entire HashSet
@@ -3954,6 +4272,10 @@ impl GameState {
         if depth == 0 {
             return 1;
         }
+        // A king on the hill ends the game: the position has no moves.
+        if self.has_lost_by_koth() {
+            return 0;
+        }
 
         bufs[ply].clear();
         self.get_legal_moves_into(&mut bufs[ply]);
@@ -3969,6 +4291,130 @@ impl GameState {
         nodes
     }
 
+    /// Serializes the current position to ICN, in the same token layout that
+    /// `setup_position_from_icn` reads back:
+    /// `[Variant "..."] [PieceValues "..."] turn [ep] halfmove/limit fullmove
+    /// (promotions) bounds [wins] pieces`.
+    ///
+    /// Only the position is written (no move list), so re-parsing the output
+    /// yields a game with the same `hash` but an empty repetition history.
+    pub fn to_icn(&self) -> String {
+        let mut tokens: Vec<String> = Vec::new();
+
+        if let Some(v) = self.variant {
+            tokens.push(format!("[Variant \"{}\"]", v.to_str()));
+        }
+        if !self.game_rules.piece_values.is_empty() {
+            tokens.push(format!(
+                "[PieceValues \"{}\"]",
+                self.game_rules.piece_values.to_icn()
+            ));
+        }
+
+        let turn = if self.turn == PlayerColor::Black {
+            "b"
+        } else {
+            "w"
+        };
+        tokens.push(turn.to_string());
+
+        // The parser derives the pawn square from the side to move, so only the
+        // target square is written (and it must follow the turn token).
+        if let Some(ep) = &self.en_passant {
+            tokens.push(format!("{},{}", ep.square.x, ep.square.y));
+        }
+
+        // "-" keeps the token recognisable as a clock when there is no move rule.
+        let limit = self
+            .game_rules
+            .move_rule_limit
+            .map_or_else(|| "-".to_string(), |l| l.to_string());
+        tokens.push(format!("{}/{}", self.halfmove_clock, limit));
+        tokens.push(self.fullmove_number.to_string());
+
+        let ranks = &self.game_rules.promotion_ranks;
+        if !ranks.white.is_empty() || !ranks.black.is_empty() {
+            let promos = self
+                .game_rules
+                .promotions_allowed
+                .as_ref()
+                .map(|allowed| {
+                    allowed
+                        .iter()
+                        .map(|s| s.to_lowercase())
+                        .collect::<Vec<_>>()
+                        .join(",")
+                })
+                .filter(|s| !s.is_empty());
+            let side = |rank: Option<&i64>| {
+                let rank = rank.map(|r| r.to_string()).unwrap_or_default();
+                match &promos {
+                    Some(p) => format!("{};{}", rank, p),
+                    None => rank,
+                }
+            };
+            tokens.push(format!(
+                "({}|{})",
+                side(ranks.white.first()),
+                side(ranks.black.first())
+            ));
+        }
+
+        let bounds = &self.game_rules.world_bounds;
+        if *bounds != WorldBounds::UNBOUNDED {
+            tokens.push(bounds.to_icn());
+        }
+
+        // The parser reads the win conditions as one shared list and re-selects
+        // per side from royal presence, so write the distinct set.
+        let white_wc = self.game_rules.white_win_condition;
+        let black_wc = self.game_rules.black_win_condition;
+        if white_wc != WinCondition::Checkmate || black_wc != WinCondition::Checkmate {
+            let hill = &self.game_rules.koth_squares;
+            let icn_str = |wc: WinCondition| {
+                if wc == WinCondition::Koth && hill[..] != KOTH_SQUARES {
+                    let squares: Vec<String> =
+                        hill.iter().map(|c| format!("{},{}", c.x, c.y)).collect();
+                    format!("koth({})", squares.join(";"))
+                } else {
+                    wc.as_icn_str().to_string()
+                }
+            };
+            let mut wcs = vec![icn_str(white_wc)];
+            if black_wc != white_wc {
+                wcs.push(icn_str(black_wc));
+            }
+            tokens.push(wcs.join(","));
+        }
+
+        let mut pieces: Vec<_> = self.board.iter().collect();
+        // Sort by Y descending, then X ascending
+        pieces.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        if !pieces.is_empty() {
+            let pieces_str = pieces
+                .iter()
+                .map(|(x, y, piece)| {
+                    let code = piece.piece_type().to_site_code();
+                    let code = if piece.color() == PlayerColor::White {
+                        code.to_string()
+                    } else {
+                        code.to_lowercase()
+                    };
+                    let rights = if self.has_special_right(&Coordinate::new(*x, *y)) {
+                        "+"
+                    } else {
+                        ""
+                    };
+                    format!("{}{},{}{}", code, x, y, rights)
+                })
+                .collect::<Vec<_>>()
+                .join("|");
+            tokens.push(pieces_str);
+        }
+
+        tokens.join(" ")
+    }
+
     pub fn setup_position_from_icn(&mut self, position_icn: &str) {
         self.board = Board::new();
         self.special_rights.clear();
@@ -3980,12 +4426,14 @@ impl GameState {
 
         self.game_rules.promotion_ranks.white.clear();
         self.game_rules.promotion_ranks.black.clear();
+        self.game_rules.world_bounds = WorldBounds::UNBOUNDED;
         self.white_promo_rank = i64::MIN;
         self.black_promo_rank = i64::MAX;
 
         let mut content = position_icn.trim();
 
-        // Scan for Variant Tag [Variant "Name"]
+        // Scan for tags: [Variant "Name"], [PieceValues "RO=650,..."]
+        self.game_rules.piece_values = PieceValues::default();
         while content.starts_with('[') {
             if let Some(end) = content.find(']') {
                 let tag = &content[1..end];
@@ -3995,6 +4443,10 @@ impl GameState {
                     let v_name = &tag[9..9 + v_end];
                     self.variant = Some(Variant::parse(v_name));
                     self.game_rules.variant = self.variant;
+                } else if let Some(rest) = tag.strip_prefix("PieceValues \"")
+                    && let Some(values) = rest.strip_suffix('"').and_then(PieceValues::parse)
+                {
+                    self.game_rules.piece_values = values;
                 }
                 content = content[end + 1..].trim();
             } else {
@@ -4010,6 +4462,7 @@ impl GameState {
         let tokens: Vec<&str> = content.split_whitespace().collect();
         let mut moves_to_play = Vec::new();
         let mut wc_list = Vec::new();
+        let mut koth_squares = None;
         let mut pieces_token = None;
 
         // Handle the case where it's just pieces
@@ -4025,121 +4478,120 @@ impl GameState {
                 continue;
             }
 
-            if token.contains('>') || token.contains('x') {
-                for m in token.split('|') {
-                    if !m.is_empty() {
-                        moves_to_play.push(m);
-                    }
-                }
-                continue;
-            }
-
             // Identify token by structure
-            if token == "w" {
-                self.turn = PlayerColor::White;
-            } else if token == "b" {
-                self.turn = PlayerColor::Black;
-            } else if token.contains('/')
-                && token.chars().next().is_some_and(|c| c.is_ascii_digit())
-            {
-                // Clocks: halfmove/limit
-                let parts: Vec<&str> = token.split('/').collect();
-                if let Some(hm_str) = parts.first() {
-                    self.halfmove_clock = hm_str.parse().unwrap_or(0);
+            match icn::IcnField::of(token) {
+                icn::IcnField::Moves => {
+                    for m in token.split('|') {
+                        if !m.is_empty() {
+                            moves_to_play.push(m);
+                        }
+                    }
                 }
-                if parts.len() > 1 {
-                    self.game_rules.move_rule_limit = parts[1].parse::<u32>().ok();
+                icn::IcnField::Turn => {
+                    self.turn = if token == "w" {
+                        PlayerColor::White
+                    } else {
+                        PlayerColor::Black
+                    };
                 }
-            } else if token.starts_with('(') && token.ends_with(')') {
-                // Promotion Rules: (w_rank;w_pieces|b_rank;b_pieces)
-                let inner = &token[1..token.len() - 1];
-                let sides: Vec<&str> = inner.split('|').collect();
-                let mut promo_types = Vec::new();
-                let mut seen_promos = FxHashSet::default();
-
-                for (idx, side_str) in sides.iter().enumerate() {
-                    let parts: Vec<&str> = side_str.split(';').collect();
-                    if parts.is_empty() {
-                        continue;
+                icn::IcnField::Clock => {
+                    // Clocks: halfmove/limit
+                    let parts: Vec<&str> = token.split('/').collect();
+                    if let Some(hm_str) = parts.first() {
+                        self.halfmove_clock = hm_str.parse().unwrap_or(0);
                     }
-                    // Parse promotion logic
-                    if let Ok(rank) = parts[0].parse::<i64>() {
-                        if idx == 0 {
-                            self.white_promo_rank = rank;
-                        } else {
-                            self.black_promo_rank = rank;
+                    if parts.len() > 1 {
+                        self.game_rules.move_rule_limit = parts[1].parse::<u32>().ok();
+                    }
+                }
+                icn::IcnField::Promotion => {
+                    // Promotion Rules: (w_rank;w_pieces|b_rank;b_pieces)
+                    let inner = &token[1..token.len() - 1];
+                    let sides: Vec<&str> = inner.split('|').collect();
+                    let mut promo_types = Vec::new();
+                    let mut seen_promos = FxHashSet::default();
+
+                    for (idx, side_str) in sides.iter().enumerate() {
+                        let parts: Vec<&str> = side_str.split(';').collect();
+                        if parts.is_empty() {
+                            continue;
                         }
+                        // Parse promotion logic
+                        if let Ok(rank) = parts[0].parse::<i64>() {
+                            if idx == 0 {
+                                self.white_promo_rank = rank;
+                            } else {
+                                self.black_promo_rank = rank;
+                            }
 
-                        self.game_rules.promotion_ranks.white = if idx == 0 {
-                            vec![rank]
-                        } else {
-                            self.game_rules.promotion_ranks.white.clone()
-                        };
-                        self.game_rules.promotion_ranks.black = if idx != 0 {
-                            vec![rank]
-                        } else {
-                            self.game_rules.promotion_ranks.black.clone()
-                        };
-                    }
+                            self.game_rules.promotion_ranks.white = if idx == 0 {
+                                vec![rank]
+                            } else {
+                                self.game_rules.promotion_ranks.white.clone()
+                            };
+                            self.game_rules.promotion_ranks.black = if idx != 0 {
+                                vec![rank]
+                            } else {
+                                self.game_rules.promotion_ranks.black.clone()
+                            };
+                        }
 
-                    if parts.len() > 1 {
-                        let types: Vec<&str> = parts[1].split(',').collect();
-                        for t in &types {
-                            let pt = PieceType::from_site_code(&t.to_uppercase());
-                            if pt != PieceType::Void && seen_promos.insert(pt) {
-                                promo_types.push(pt);
+                        if parts.len() > 1 {
+                            let types: Vec<&str> = parts[1].split(',').collect();
+                            for t in &types {
+                                let pt = PieceType::from_site_code(&t.to_uppercase());
+                                if pt != PieceType::Void && seen_promos.insert(pt) {
+                                    promo_types.push(pt);
+                                }
                             }
+                            self.game_rules.promotions_allowed =
+                                Some(types.iter().map(|s| s.to_string()).collect());
+                            self.game_rules.promotion_types = Some(promo_types.clone());
                         }
-                        self.game_rules.promotions_allowed =
-                            Some(types.iter().map(|s| s.to_string()).collect());
-                        self.game_rules.promotion_types = Some(promo_types.clone());
                     }
                 }
-            } else if token.split(',').count() == 4
-                && token
-                    .chars()
-                    .all(|c| c.is_ascii_digit() || c == ',' || c == '-')
-            {
-                // World Border: left,right,bottom,top
-                let bounds: Vec<&str> = token.split(',').collect();
-                if let (Ok(l), Ok(r), Ok(b), Ok(t)) = (
-                    bounds[0].parse::<i64>(),
-                    bounds[1].parse::<i64>(),
-                    bounds[2].parse::<i64>(),
-                    bounds[3].parse::<i64>(),
-                ) {
-                    crate::moves::set_world_bounds(l, r, b, t);
+                icn::IcnField::Bounds => {
+                    // World Border: left,right,bottom,top, plus optional +box / ~hole parts
+                    if let Some(bounds) = WorldBounds::from_icn(token) {
+                        self.game_rules.world_bounds = bounds;
+                    }
                 }
-            } else if token.contains('|')
-                || (token.contains(',') && token.chars().any(|c| c.is_ascii_uppercase()))
-            {
-                // Pieces segment typically contains '|' or pieces like 'P1,2' (uppercase P)
-                pieces_token = Some(token);
-            } else if token.contains(',')
-                && token.split(',').count() == 2
-                && !token.chars().any(|c| c.is_ascii_alphabetic())
-            {
-                // En Passant: x,y
-                let parts: Vec<&str> = token.split(',').collect();
-                if let (Ok(x), Ok(y)) = (parts[0].parse::<i64>(), parts[1].parse::<i64>()) {
-                    let pawn_y = if self.turn == PlayerColor::White {
-                        y - 1
-                    } else {
-                        y + 1
-                    };
-                    self.en_passant = Some(EnPassantState {
-                        square: Coordinate::new(x, y),
-                        pawn_square: Coordinate::new(x, pawn_y),
-                    });
+                icn::IcnField::Pieces => pieces_token = Some(token),
+                icn::IcnField::EnPassant => {
+                    // En Passant: x,y
+                    let parts: Vec<&str> = token.split(',').collect();
+                    if let (Ok(x), Ok(y)) = (parts[0].parse::<i64>(), parts[1].parse::<i64>()) {
+                        let pawn_y = if self.turn == PlayerColor::White {
+                            y - 1
+                        } else {
+                            y + 1
+                        };
+                        self.en_passant = Some(EnPassantState {
+                            square: Coordinate::new(x, y),
+                            pawn_square: Coordinate::new(x, pawn_y),
+                        });
+                    }
                 }
-            } else if let Ok(val) = token.parse::<u32>() {
-                // Fullmove number
-                self.fullmove_number = val;
-            } else {
-                // Check if it's a win condition list
-                for wc_str in token.split(',') {
-                    if let Ok(wc) = wc_str.parse::<WinCondition>() {
-                        wc_list.push(wc);
+                icn::IcnField::Fullmove => {
+                    if let Ok(val) = token.parse::<u32>() {
+                        self.fullmove_number = val;
+                    }
+                }
+                icn::IcnField::WinConditions => {
+                    if let Some((conditions, squares)) = split_koth_squares(token) {
+                        // Win conditions with their own hill: checkmate,koth(4,4;5,5)
+                        wc_list.extend(
+                            conditions
+                                .split(',')
+                                .filter_map(|wc| wc.parse::<WinCondition>().ok()),
+                        );
+                        koth_squares = Some(squares);
+                    } else {
+                        wc_list.extend(
+                            token
+                                .split(',')
+                                .filter_map(|wc| wc.parse::<WinCondition>().ok()),
+                        );
                     }
                 }
             }
@@ -4169,6 +4621,11 @@ impl GameState {
 
         self.game_rules.white_win_condition = WinCondition::select(&wc_list, black_has_royal);
         self.game_rules.black_win_condition = WinCondition::select(&wc_list, white_has_royal);
+        self.game_rules.koth_squares = if wc_list.contains(&WinCondition::Koth) {
+            koth_squares.unwrap_or_else(|| KOTH_SQUARES.to_vec())
+        } else {
+            Vec::new()
+        };
 
         self.finalize_setup();
 
@@ -4274,6 +4731,35 @@ impl GameState {
         }
     }
 
+    /// Rebuilds `material_score` from the board. Make/undo keep it up to date, so this is
+    /// only needed at setup or after the piece values change under eval tuning.
+    pub fn recompute_material_score(&mut self) {
+        self.material_score = 0;
+        for &coord in &self.white_pieces {
+            if let Some(piece) = self.board.get_piece(coord.0, coord.1) {
+                self.material_score += self.get_piece_value(piece.piece_type(), piece.color());
+            }
+        }
+        for &coord in &self.black_pieces {
+            if let Some(piece) = self.board.get_piece(coord.0, coord.1) {
+                self.material_score -= self.get_piece_value(piece.piece_type(), piece.color());
+            }
+        }
+    }
+
+    /// Replaces the hill squares of the `koth` win condition. Has no effect
+    /// unless a side plays for `koth`.
+    pub fn set_koth_squares(&mut self, squares: Vec<Coordinate>) {
+        self.game_rules.koth_squares = squares;
+    }
+
+    /// Overrides piece values on top of any the ICN set and rescores material
+    /// with them.
+    pub fn apply_piece_values(&mut self, values: &PieceValues) {
+        self.game_rules.piece_values.merge(values);
+        self.recompute_material_score();
+    }
+
     fn finalize_setup(&mut self) {
         // 1. Rebuild piece lists and counts to find royals
         self.recompute_piece_counts();
@@ -4312,19 +4798,9 @@ impl GameState {
         };
 
         // 4. Calculate initial material score now that we have bonuses
-        self.material_score = 0;
-        for &coord in &self.white_pieces {
-            if let Some(piece) = self.board.get_piece(coord.0, coord.1) {
-                self.material_score += self.get_piece_value(piece.piece_type(), piece.color());
-            }
-        }
-        for &coord in &self.black_pieces {
-            if let Some(piece) = self.board.get_piece(coord.0, coord.1) {
-                self.material_score -= self.get_piece_value(piece.piece_type(), piece.color());
-            }
-        }
+        self.recompute_material_score();
 
-        let (min_x, max_x, min_y, max_y) = crate::moves::get_coord_bounds();
+        let (min_x, max_x, min_y, max_y) = self.game_rules.world_bounds.as_tuple();
 
         // Filter special rights outside world bounds
         self.special_rights
@@ -4388,11 +4864,19 @@ impl GameState {
 
         // Rebuild spatial indices
         self.spatial_indices = SpatialIndices::new(&self.board);
+        self.spatial_indices.region = self.game_rules.world_bounds.region.clone();
+
+        // Play with the loaded eval profile that fits this variant, rescoring material with it
+        let params = crate::evaluation::profiles::params_for_game(self);
+        if params != self.game_rules.eval_params {
+            self.game_rules.eval_params = params;
+            self.recompute_material_score();
+        }
     }
 
     #[cfg(any(test, not(target_arch = "wasm32"), feature = "parallel_solver"))]
     pub fn setup_variant(&mut self, variant: Variant) {
-        self.setup_position_from_icn(variant.starting_icn());
+        self.setup_position_from_icn(&variant.starting_icn());
     }
 
     #[cfg(any(test, not(target_arch = "wasm32"), feature = "parallel_solver"))]
@@ -4404,92 +4888,409 @@ impl GameState {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::sync::Mutex;
-    use std::sync::OnceLock;
+    /// Helper to create a minimal game state for testing
+    fn create_test_game() -> GameState {
+        create_test_game_from_icn("w (8;q|1;q) K5,1|k5,8")
+    }
+
+    fn create_test_game_from_icn(icn: &str) -> GameState {
+        let mut game = GameState::new();
+        game.setup_position_from_icn(icn);
+        game
+    }
+
+    #[test]
+    fn test_parse_icn_full() {
+        let icn = "[Event \"Complex Game\"] w 10,3 5/100 1 (8;am,q|1;am,q) -100,500,-35,100 checkmate,royalcapture,allroyalscaptured,allpiecescaptured K5,1+|k5,8+";
+        let mut game = GameState::new();
+        game.setup_position_from_icn(icn);
+
+        // Check header info
+        assert_eq!(game.turn, PlayerColor::White);
+        assert_eq!(game.halfmove_clock, 5);
+        assert_eq!(game.game_rules.move_rule_limit, Some(100));
+        assert_eq!(game.fullmove_number, 1);
+        assert_eq!(game.white_promo_rank, 8);
+        assert_eq!(game.black_promo_rank, 1);
+
+        // En passant square (10,3). White turn, so Black pawn just moved 10,4->10,2.
+        // Pawn being captured is at 10,2.
+        let ep = game.en_passant.unwrap();
+        assert_eq!(ep.square, Coordinate::new(10, 3));
+        assert_eq!(ep.pawn_square, Coordinate::new(10, 2));
+
+        // Check world bounds
+        let (min_x, max_x, min_y, max_y) = game.game_rules.world_bounds.as_tuple();
+        assert_eq!(min_x, -100);
+        assert_eq!(max_x, 500);
+        assert_eq!(min_y, -35);
+        assert_eq!(max_y, 100);
+
+        // Check win conditions
+        // Priority: Checkmate
+        assert_eq!(game.game_rules.white_win_condition, WinCondition::Checkmate);
+        assert_eq!(game.game_rules.black_win_condition, WinCondition::Checkmate);
+
+        // Check allowed promotions
+        let allowed = game.game_rules.promotions_allowed.as_ref().unwrap();
+        assert!(allowed.contains(&"am".to_string()));
+        assert!(allowed.contains(&"q".to_string()));
+
+        // Check pieces
+        let k = game.board.get_piece(5, 1).unwrap();
+        assert_eq!(k.piece_type(), PieceType::King);
+    }
+
+    #[test]
+    fn test_outcome_reports_winner_and_reason() {
+        let outcome = |icn: &str| create_test_game_from_icn(icn).outcome();
+        let win = |color, reason| {
+            Some(GameOutcome {
+                winner: Some(color),
+                reason,
+            })
+        };
+        let draw = |reason| {
+            Some(GameOutcome {
+                winner: None,
+                reason,
+            })
+        };
 
-    static BOUNDS_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        assert_eq!(outcome(&crate::Variant::Classical.starting_icn()), None);
+        assert_eq!(
+            outcome("b 1,8,1,8 K6,6|k8,8|Q7,7"),
+            win(PlayerColor::White, GameEndReason::Checkmate)
+        );
+        assert_eq!(
+            outcome("b 1,8,1,8 K6,7|k8,8|Q7,6"),
+            draw(GameEndReason::Stalemate)
+        );
+        assert_eq!(
+            outcome("w royalcapture K1,1|k5,8|Q5,2 5,2>5,8"),
+            win(PlayerColor::White, GameEndReason::RoyalCapture)
+        );
+        assert_eq!(
+            outcome("w K1,1|Q2,2|n2,3 2,2>2,3"),
+            win(PlayerColor::White, GameEndReason::AllPiecesCaptured)
+        );
+        assert_eq!(
+            outcome("w 1,8,1,8 K1,1|k8,8"),
+            draw(GameEndReason::InsufficientMaterial)
+        );
+        assert_eq!(
+            outcome("w 100/100 1 K1,1|Q4,4|R5,5|k20,20"),
+            draw(GameEndReason::MoveRule)
+        );
+        assert_eq!(
+            outcome("w koth K3,3|k8,8|r1,8 3,3>4,4"),
+            win(PlayerColor::White, GameEndReason::Koth)
+        );
+        assert_eq!(outcome("w koth K3,3|k8,8|r1,8"), None);
+        let shuffle = "2,1>3,3|19,20>18,18|3,3>2,1|18,18>19,20";
+        assert_eq!(
+            outcome(&format!(
+                "w 0/100 1 K1,1|R1,2|N2,1|k20,20|r20,19|n19,20 {shuffle}|{shuffle}"
+            )),
+            draw(GameEndReason::Threefold)
+        );
 
-    fn get_bounds_lock() -> &'static Mutex<()> {
-        BOUNDS_LOCK.get_or_init(|| Mutex::new(()))
+        let mate = outcome("b 1,8,1,8 K6,6|k8,8|Q7,7").unwrap();
+        assert_eq!(mate.result_str(), "1-0");
+        assert_eq!(mate.reason.as_str(), "checkmate");
     }
 
-    /// Helper to reset world bounds to defaults
-    fn reset_world_bounds() {
-        crate::moves::set_world_bounds(
-            -1_000_000_000_000_000,
-            1_000_000_000_000_000,
-            -1_000_000_000_000_000,
-            1_000_000_000_000_000,
+    #[test]
+    fn test_playable_region_icn_round_trip() {
+        let icn = "w 0/100 1 (8;q|1;q) -8,18,-8,17+19,30,0,5~0,2,0,2 K-8,-8|k30,5";
+        let game = GameState::try_from_icn(icn).unwrap();
+        let bounds = &game.game_rules.world_bounds;
+        assert!(bounds.region.is_some());
+        assert!(!bounds.contains(1, 1));
+        assert!(bounds.contains(25, 3));
+        assert!(!bounds.contains(25, 6));
+        assert_eq!(
+            game.spatial_indices.region, bounds.region,
+            "indices must see the same region"
+        );
+
+        let again = GameState::try_from_icn(&game.to_icn()).unwrap();
+        assert_eq!(again.game_rules.world_bounds, *bounds);
+
+        assert_eq!(
+            icn_error("w 1,8,1,8~3,4,3,4 K1,1|k3,3").kind,
+            IcnErrorKind::OutOfBounds
+        );
+        assert_eq!(
+            icn_error("w 1,8,1,8~4,3,3,4 K1,1|k8,8").kind,
+            IcnErrorKind::BadBounds
         );
     }
 
-    /// Helper to acquire bounds lock for tests that modify bounds
-    fn with_bounds_lock<F, R>(f: F) -> R
-    where
-        F: FnOnce() -> R,
-    {
-        let _guard = get_bounds_lock().lock().unwrap_or_else(|e| e.into_inner());
-        f()
+    #[test]
+    fn test_world_bounds_are_per_game() {
+        let chess = create_test_game_from_icn(&crate::Variant::Chess.starting_icn());
+        let classical = create_test_game_from_icn(&crate::Variant::Classical.starting_icn());
+
+        assert_eq!(chess.game_rules.world_bounds, WorldBounds::new(1, 8, 1, 8));
+        assert_eq!(classical.game_rules.world_bounds, WorldBounds::UNBOUNDED);
+
+        // Re-parsing into a bounded game must not keep the old border.
+        let mut reused = chess.clone();
+        reused.setup_position_from_icn(&crate::Variant::Classical.starting_icn());
+        assert_eq!(reused.game_rules.world_bounds, WorldBounds::UNBOUNDED);
+
+        // Knight on (2,1): (0,2) is off the Chess board but playable in Classical.
+        let off_board = |g: &GameState| {
+            g.get_legal_moves()
+                .iter()
+                .any(|m| m.from == Coordinate::new(2, 1) && m.to == Coordinate::new(0, 2))
+        };
+        assert!(!off_board(&chess));
+        assert!(off_board(&classical));
     }
 
-    /// Helper to create a minimal game state for testing
-    fn create_test_game() -> GameState {
-        reset_world_bounds();
-        create_test_game_from_icn("w (8;q|1;q) K5,1|k5,8")
+    fn assert_icn_round_trip(game: &GameState) {
+        let icn = game.to_icn();
+        let mut parsed = GameState::new();
+        parsed.setup_position_from_icn(&icn);
+
+        assert_eq!(parsed.hash, game.hash, "hash mismatch for {}", icn);
+        assert_eq!(parsed.turn, game.turn);
+        assert_eq!(parsed.halfmove_clock, game.halfmove_clock);
+        assert_eq!(parsed.fullmove_number, game.fullmove_number);
+        assert_eq!(
+            parsed.game_rules.move_rule_limit,
+            game.game_rules.move_rule_limit
+        );
+        assert_eq!(parsed.special_rights, game.special_rights);
+        assert_eq!(
+            parsed.en_passant.map(|ep| (ep.square, ep.pawn_square)),
+            game.en_passant.map(|ep| (ep.square, ep.pawn_square))
+        );
+        assert_eq!(
+            parsed.game_rules.white_win_condition,
+            game.game_rules.white_win_condition
+        );
+        assert_eq!(
+            parsed.game_rules.black_win_condition,
+            game.game_rules.black_win_condition
+        );
+        assert_eq!(parsed.white_promo_rank, game.white_promo_rank);
+        assert_eq!(parsed.black_promo_rank, game.black_promo_rank);
+        assert_eq!(
+            parsed.game_rules.promotion_types,
+            game.game_rules.promotion_types
+        );
+        assert_eq!(parsed.variant, game.variant);
+        assert_eq!(parsed.game_rules.piece_values, game.game_rules.piece_values);
+        assert_eq!(parsed.to_icn(), icn);
     }
 
-    fn create_test_game_from_icn(icn: &str) -> GameState {
-        reset_world_bounds();
+    #[test]
+    fn test_to_icn_round_trip_full() {
+        let icn = "[Variant \"Classical\"] w 10,3 5/100 7 (8;am,q|1;am,q) -100,500,-35,100 checkmate,royalcapture K5,1+|k5,8+|p10,2|R1,1+";
         let mut game = GameState::new();
         game.setup_position_from_icn(icn);
-        game
+        assert_icn_round_trip(&game);
+        assert!(game.to_icn().contains("-100,500,-35,100"));
     }
 
     #[test]
-    fn test_parse_icn_full() {
-        with_bounds_lock(|| {
-            reset_world_bounds();
-            let icn = "[Event \"Complex Game\"] w 10,3 5/100 1 (8;am,q|1;am,q) -100,500,-35,100 checkmate,royalcapture,allroyalscaptured,allpiecescaptured K5,1+|k5,8+";
+    fn test_to_icn_round_trip_variants_after_moves() {
+        use crate::Variant;
+        let variants = [
+            Variant::Classical,
+            Variant::ConfinedClassical,
+            Variant::CoaIP,
+            Variant::Palace,
+            Variant::PawnHorde,
+            Variant::Obstocean,
+            Variant::Chess,
+            Variant::ScatteredLeapers,
+            Variant::DoubleKingClassical,
+            Variant::TripleKingMaze,
+        ];
+        for variant in variants {
             let mut game = GameState::new();
-            game.setup_position_from_icn(icn);
-
-            // Check header info
-            assert_eq!(game.turn, PlayerColor::White);
-            assert_eq!(game.halfmove_clock, 5);
-            assert_eq!(game.game_rules.move_rule_limit, Some(100));
-            assert_eq!(game.fullmove_number, 1);
-            assert_eq!(game.white_promo_rank, 8);
-            assert_eq!(game.black_promo_rank, 1);
-
-            // En passant square (10,3). White turn, so Black pawn just moved 10,4->10,2.
-            // Pawn being captured is at 10,2.
-            let ep = game.en_passant.unwrap();
-            assert_eq!(ep.square, Coordinate::new(10, 3));
-            assert_eq!(ep.pawn_square, Coordinate::new(10, 2));
-
-            // Check world bounds
-            let (min_x, max_x, min_y, max_y) = crate::moves::get_coord_bounds();
-            assert_eq!(min_x, -100);
-            assert_eq!(max_x, 500);
-            assert_eq!(min_y, -35);
-            assert_eq!(max_y, 100);
-
-            // Check win conditions
-            // Priority: Checkmate
-            assert_eq!(game.game_rules.white_win_condition, WinCondition::Checkmate);
-            assert_eq!(game.game_rules.black_win_condition, WinCondition::Checkmate);
-
-            // Check allowed promotions
-            let allowed = game.game_rules.promotions_allowed.as_ref().unwrap();
-            assert!(allowed.contains(&"am".to_string()));
-            assert!(allowed.contains(&"q".to_string()));
-
-            // Check pieces
-            let k = game.board.get_piece(5, 1).unwrap();
-            assert_eq!(k.piece_type(), PieceType::King);
-            
-            reset_world_bounds();
-        });
+            game.setup_variant(variant);
+            assert_icn_round_trip(&game);
+
+            // Play a few legal moves so the turn, clocks, special rights and
+            // (for double pawn pushes) en passant all move away from the defaults.
+            for ply in 0..4 {
+                let legal: Vec<Move> = game
+                    .get_legal_moves()
+                    .iter()
+                    .copied()
+                    .filter(|m| {
+                        let undo = game.make_move(m);
+                        let legal = !game.is_move_illegal();
+                        game.undo_move(m, undo);
+                        legal
+                    })
+                    .collect();
+                let double_push = legal.iter().copied().find(|m| {
+                    m.piece.piece_type() == PieceType::Pawn && (m.to.y - m.from.y).abs() == 2
+                });
+                let Some(m) = double_push
+                    .filter(|_| ply % 2 == 1)
+                    .or_else(|| legal.first().copied())
+                else {
+                    break;
+                };
+                game.make_move(&m);
+                assert_icn_round_trip(&game);
+            }
+        }
+    }
+
+    fn icn_error(icn: &str) -> IcnError {
+        match GameState::try_from_icn(icn) {
+            Ok(_) => panic!("expected {} to be rejected", icn),
+            Err(e) => e,
+        }
+    }
+
+    #[test]
+    fn test_try_from_icn_matches_lenient_parser() {
+        use crate::Variant;
+        for variant in [
+            Variant::Classical,
+            Variant::CoaIP,
+            Variant::PawnHorde,
+            Variant::Obstocean,
+            Variant::Chess,
+        ] {
+            let strict = GameState::try_from_icn(&variant.starting_icn()).unwrap();
+            let lenient = create_test_game_from_icn(&variant.starting_icn());
+            assert_eq!(strict.hash, lenient.hash, "{:?}", variant);
+            assert_eq!(strict.eval_kind, lenient.eval_kind, "{:?}", variant);
+        }
+
+        let icn = "[Variant \"Classical\"] w 0/100 1 (8|1) K5,1+|R8,1+|P5,2+|k5,8|p4,7+ P5,2>5,4|p4,7>4,5|P5,4x4,5|k5,8>5,7|K5,1>7,1";
+        let strict = GameState::try_from_icn(icn).unwrap();
+        let lenient = create_test_game_from_icn(icn);
+        assert_eq!(strict.hash, lenient.hash);
+        assert_eq!(
+            strict.board.get_piece(6, 1).unwrap().piece_type(),
+            PieceType::Rook
+        );
+    }
+
+    #[test]
+    fn test_try_from_icn_reports_token_offset_and_reason() {
+        let icn = "w 0/100 1 K5,1|k5,8|Z3,3";
+        let err = icn_error(icn);
+        assert_eq!(err.kind, IcnErrorKind::UnknownPiece);
+        assert_eq!(err.token, "Z3,3");
+        assert_eq!(&icn[err.offset..err.offset + err.token.len()], "Z3,3");
+
+        let err = icn_error("w 0/100 1 K5,1|kq,8");
+        assert_eq!(err.kind, IcnErrorKind::BadPiece);
+        assert_eq!(err.token, "kq,8");
+
+        let err = icn_error("w 0/abc 1 K5,1|k5,8");
+        assert_eq!(err.kind, IcnErrorKind::BadClock);
+        assert_eq!(err.offset, 2);
+
+        let err = icn_error("w 0/100 1 banana K5,1|k5,8");
+        assert_eq!(err.kind, IcnErrorKind::UnknownToken);
+        assert_eq!(err.token, "banana");
+
+        let err = icn_error("[Variant \"Nonsense\"] w K5,1|k5,8");
+        assert_eq!(err.kind, IcnErrorKind::UnknownVariant);
+        assert_eq!(err.offset, 0);
+
+        let err = icn_error("[PieceValues \"RO=lots\"] w K5,1|k5,8");
+        assert_eq!(err.kind, IcnErrorKind::BadPieceValues);
+
+        let err = icn_error("[Event \"x\" w K5,1|k5,8");
+        assert_eq!(err.kind, IcnErrorKind::UnterminatedTag);
+
+        assert_eq!(
+            icn_error("w b K5,1|k5,8").kind,
+            IcnErrorKind::DuplicateField
+        );
+        assert_eq!(
+            icn_error("w K5,1|k5,8|K5,1").kind,
+            IcnErrorKind::DuplicateSquare
+        );
+        assert_eq!(
+            icn_error("w (8;q,zz|1;q) K5,1|k5,8").kind,
+            IcnErrorKind::BadPromotion
+        );
+        assert_eq!(
+            icn_error("w 8,1,1,8 K5,1|k5,8").kind,
+            IcnErrorKind::BadBounds
+        );
+        assert_eq!(
+            icn_error("w 1,8,1,8 K5,1|k5,9").kind,
+            IcnErrorKind::OutOfBounds
+        );
+        assert_eq!(icn_error("w 0/100 1").kind, IcnErrorKind::MissingPieces);
+    }
+
+    #[test]
+    fn test_try_from_icn_rejects_illegal_moves() {
+        let icn = "w 0/100 1 (8|1) K5,1+|P5,2+|k5,8 5,2>5,4|5,8>5,7|5,4>5,6";
+        let err = icn_error(icn);
+        assert_eq!(err.kind, IcnErrorKind::IllegalMove);
+        assert_eq!(err.token, "5,4>5,6");
+        assert_eq!(err.offset, icn.find("5,4>5,6").unwrap());
+
+        let err = icn_error("w 0/100 1 K5,1|k5,8 5,1>5,q");
+        assert_eq!(err.kind, IcnErrorKind::BadMove);
+
+        // Promotion must be spelled out when the move promotes.
+        let err = icn_error("w 0/100 1 (8;q|1;q) K1,1|P5,7|k8,3 5,7>5,8");
+        assert_eq!(err.kind, IcnErrorKind::IllegalMove);
+        let game = GameState::try_from_icn("w 0/100 1 (8;q|1;q) K1,1|P5,7|k8,3 5,7>5,8=Q").unwrap();
+        assert_eq!(
+            game.board.get_piece(5, 8).unwrap().piece_type(),
+            PieceType::Queen
+        );
+    }
+
+    #[test]
+    fn test_find_site_move_matches_legal_moves_only() {
+        let mut game = create_test_game_from_icn("w 0/100 1 (8;q|1;q) K1,1|P5,7|k8,3");
+        let m = game.find_site_move("5,7>5,8=Q").unwrap();
+        assert_eq!(m.promotion, Some(PieceType::Queen));
+        assert_eq!(crate::move_to_site_token(&m), "5,7>5,8=Q");
+
+        assert!(game.find_site_move("5,7>5,8").is_none());
+        assert!(game.find_site_move("5,7>5,9=Q").is_none());
+        assert!(game.find_site_move("1,1>1,").is_none());
+
+        // Moving into check is not legal.
+        let mut game = create_test_game_from_icn("w 0/100 1 K5,1|r1,2|k5,8");
+        assert!(game.find_site_move("5,1>5,2").is_none());
+        assert!(game.find_site_move("5,1>4,1").is_some());
+    }
+
+    #[test]
+    fn test_to_icn_lone_black_piece_and_no_move_rule() {
+        let game = create_test_game_from_icn("b 3/- 12 (8|1) k5,8");
+        assert_eq!(game.board.len(), 1);
+        assert_eq!(game.game_rules.move_rule_limit, None);
+        assert!(game.to_icn().ends_with(" k5,8"));
+        assert_icn_round_trip(&game);
+    }
+
+    #[test]
+    fn test_try_from_icn_reads_to_icn_lone_black_piece() {
+        let game = create_test_game_from_icn("w 0/100 1 (8|1) checkmate,koth(4,4;5,5) k5,8");
+        let icn = game.to_icn();
+        let strict = GameState::try_from_icn(&icn).unwrap_or_else(|e| panic!("{}: {}", icn, e));
+        assert_eq!(strict.board.len(), 1);
+        assert_eq!(
+            strict.board.get_piece(5, 8).unwrap().piece_type(),
+            PieceType::King
+        );
+        assert_eq!(strict.hash, game.hash);
+        assert_eq!(strict.game_rules.koth_squares, game.game_rules.koth_squares);
+        assert_eq!(strict.to_icn(), icn);
     }
 
     // ======================== 50-Move Rule Tests ========================
@@ -4889,6 +5690,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_incremental_hash_after_partner_move_and_neutral_capture() {
+        fn play(game: &mut GameState, from: (i64, i64), to: (i64, i64)) {
+            let m = game
+                .get_legal_moves()
+                .iter()
+                .find(|m| (m.from.x, m.from.y) == from && (m.to.x, m.to.y) == to)
+                .cloned()
+                .expect("move should be legal");
+            game.make_move(&m);
+        }
+        fn assert_hash_in_step(game: &mut GameState) {
+            let (hash, rep_hash) = (game.hash, game.rep_hash);
+            game.recompute_hash();
+            assert_eq!(game.hash, hash);
+            assert_eq!(game.rep_hash, rep_hash);
+        }
+
+        // Two rooks on one side need the precise castling key, which hashes every
+        // rights holder still on its square: the one leaving must count as before.
+        let mut game = GameState::new();
+        game.setup_position_from_icn("w 0/100 1 (8|1) K5,1+|R7,1+|R8,1+|k5,8|ob3,3");
+        play(&mut game, (7, 1), (7, 3));
+        assert_hash_in_step(&mut game);
+
+        // Neutral pieces are not hashed, so neither is taking one.
+        play(&mut game, (5, 8), (5, 7));
+        play(&mut game, (7, 3), (3, 3));
+        assert_hash_in_step(&mut game);
+    }
+
     #[test]
     fn test_rep_hash_restored_on_unmake() {
         let mut game = GameState::new();
@@ -5038,32 +5870,29 @@ mod tests {
 
     #[test]
     fn test_setup_standard_chess() {
-        with_bounds_lock(|| {
-            reset_world_bounds();
-            let mut game = GameState::new();
-            game.setup_standard_chess();
+        let mut game = GameState::new();
+        game.setup_standard_chess();
 
-            // Check piece counts
-            assert_eq!(game.white_piece_count, 16);
-            assert_eq!(game.black_piece_count, 16);
+        // Check piece counts
+        assert_eq!(game.white_piece_count, 16);
+        assert_eq!(game.black_piece_count, 16);
 
-            // Check king positions
-            assert_eq!(
-                game.white_royals.first().copied(),
-                Some(Coordinate::new(5, 1))
-            );
-            assert_eq!(
-                game.black_royals.first().copied(),
-                Some(Coordinate::new(5, 8))
-            );
+        // Check king positions
+        assert_eq!(
+            game.white_royals.first().copied(),
+            Some(Coordinate::new(5, 1))
+        );
+        assert_eq!(
+            game.black_royals.first().copied(),
+            Some(Coordinate::new(5, 8))
+        );
 
-            // Check it's white's turn
-            assert_eq!(game.turn, PlayerColor::White);
+        // Check it's white's turn
+        assert_eq!(game.turn, PlayerColor::White);
 
-            // Check promotion ranks set
-            assert_eq!(game.white_promo_rank, 8);
-            assert_eq!(game.black_promo_rank, 1);
-        });
+        // Check promotion ranks set
+        assert_eq!(game.white_promo_rank, 8);
+        assert_eq!(game.black_promo_rank, 1);
     }
 
     #[test]
@@ -5346,6 +6175,44 @@ mod tests {
         assert!(queen_value > pawn_value);
     }
 
+    #[test]
+    fn test_piece_values_tag_and_config_override_material() {
+        let mut game = create_test_game_from_icn(
+            "[PieceValues \"RO=900,N=100\"] w (8;q|1;q) K5,1|RO2,2|k5,8|n7,7",
+        );
+        assert_eq!(
+            game.get_piece_value(PieceType::Rose, PlayerColor::White),
+            900
+        );
+        assert_eq!(
+            game.get_piece_value(PieceType::Knight, PlayerColor::Black),
+            100
+        );
+        assert_eq!(
+            game.get_piece_value(PieceType::Rook, PlayerColor::White),
+            crate::evaluation::get_piece_value_base(PieceType::Rook)
+        );
+        assert_eq!(game.material_score, 800);
+        assert_icn_round_trip(&game);
+
+        // Config values replace the tag's for the types they set and rescore material.
+        let mut config = PieceValues::default();
+        config.set(PieceType::Knight, 300);
+        game.apply_piece_values(&config);
+        assert_eq!(
+            game.get_piece_value(PieceType::Rose, PlayerColor::White),
+            900
+        );
+        assert_eq!(game.material_score, 600);
+
+        assert_eq!(
+            PieceValues::parse("RO=650, nr = 720").unwrap().to_icn(),
+            "NR=720,RO=650"
+        );
+        assert!(PieceValues::parse("RO=lots").is_none());
+        assert!(PieceValues::parse("ZZ=100").is_none());
+    }
+
     #[test]
     fn test_has_pieces_true() {
         let game = create_test_game_from_icn("w (8;q|1;q) K5,1|k5,8");
@@ -5432,6 +6299,54 @@ mod tests {
         assert!(!game.has_lost_by_royal_capture());
     }
 
+    #[test]
+    fn test_koth_parses_and_round_trips() {
+        let game = create_test_game_from_icn("w 0/100 1 checkmate,koth K3,3|k8,8");
+        assert_eq!(game.game_rules.white_win_condition, WinCondition::Koth);
+        assert_eq!(game.game_rules.black_win_condition, WinCondition::Koth);
+        assert_eq!(game.game_rules.koth_squares, KOTH_SQUARES.to_vec());
+        assert!(game.must_escape_check());
+
+        let reparsed = create_test_game_from_icn(&game.to_icn());
+        assert_eq!(reparsed.game_rules.white_win_condition, WinCondition::Koth);
+        assert_eq!(reparsed.game_rules.koth_squares, KOTH_SQUARES.to_vec());
+
+        let plain = create_test_game_from_icn("w K3,3|k8,8");
+        assert!(plain.game_rules.koth_squares.is_empty());
+        assert!(!plain.has_lost_by_koth());
+    }
+
+    #[test]
+    fn test_koth_custom_squares() {
+        let mut game = create_test_game_from_icn("w koth(2,2;9,9) K3,3|k8,8|r1,8");
+        assert_eq!(
+            game.game_rules.koth_squares,
+            vec![Coordinate::new(2, 2), Coordinate::new(9, 9)]
+        );
+        assert!(game.to_icn().contains(" koth(2,2;9,9) "));
+        assert!(GameState::try_from_icn(&game.to_icn()).is_ok());
+
+        let m = game
+            .get_legal_moves()
+            .into_iter()
+            .find(|m| m.to == Coordinate::new(2, 2))
+            .unwrap();
+        game.make_move(&m);
+        assert!(game.has_lost_by_koth());
+    }
+
+    #[test]
+    fn test_perft_stops_on_the_hill() {
+        // Of the king's eight moves, the one onto d4 ends the game; the other
+        // seven each leave Black's cornered king three replies.
+        let mut koth = create_test_game_from_icn("w 1,8,1,8 koth K3,3|k8,8");
+        assert_eq!(koth.perft(1), 8);
+        assert_eq!(koth.perft(2), 21);
+
+        let mut plain = create_test_game_from_icn("w 1,8,1,8 K3,3|k8,8");
+        assert_eq!(plain.perft(2), 24);
+    }
+
     #[test]
     fn test_perft_depth_zero() {
         let mut game = GameState::new();
@@ -5452,6 +6367,32 @@ mod tests {
         assert!(count > 0);
     }
 
+    #[test]
+    fn test_chess960_castle_onto_partner_square_round_trips() {
+        // King f1, castling rook g1: kingside the king lands on g1 and the rook on f1.
+        let mut game = create_test_game_from_icn("w 0/100 1 (8;q|1;q) 1,8,1,8 K6,1+|R7,1+|k5,8");
+        game.game_rules.chess960 = true;
+        let hash_before = game.hash;
+
+        let castle = game
+            .get_legal_moves()
+            .into_iter()
+            .find(|m| m.rook_coord == Some(Coordinate::new(7, 1)))
+            .expect("castling with the g1 rook should be generated");
+        assert_eq!(castle.to, Coordinate::new(7, 1));
+
+        let undo = game.make_move(&castle);
+        assert!(undo.captured_piece.is_none(), "castling is not a capture");
+        assert_eq!(game.board.get_piece(7, 1).map(|p| p.piece_type()), Some(PieceType::King));
+        assert_eq!(game.board.get_piece(6, 1).map(|p| p.piece_type()), Some(PieceType::Rook));
+        assert!(!game.special_rights.contains(&Coordinate::new(7, 1)));
+
+        game.undo_move(&castle, undo);
+        assert_eq!(game.board.get_piece(6, 1).map(|p| p.piece_type()), Some(PieceType::King));
+        assert_eq!(game.board.get_piece(7, 1).map(|p| p.piece_type()), Some(PieceType::Rook));
+        assert_eq!(game.hash, hash_before);
+    }
+
     #[test]
     fn test_compute_pins_structure() {
         let game = create_test_game_from_icn("w (8;q|1;q) K5,1|k5,8");
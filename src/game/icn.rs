@@ -0,0 +1,453 @@
+//! Strict ICN parsing.
+//!
+//! `GameState::setup_position_from_icn` is deliberately forgiving: malformed
+//! coordinates become 0, unknown codes become voids, unknown tokens are dropped
+//! and moves in the move segment are played without a legality check. That is
+//! fine for our own tools, which only ever feed it ICN they generated, but
+//! positions coming from outside need to be rejected instead of silently
+//! repaired. [`GameState::try_from_icn`] validates every token with the same
+//! token classification the lenient parser uses, builds the position through
+//! it, then replays the move segment against the legal move list.
+
+use std::fmt;
+
+use rustc_hash::FxHashSet;
+
+use super::{GameState, PieceValues, WinCondition};
+use crate::Variant;
+use crate::board::{Coordinate, PieceType};
+use crate::moves::{Move, WorldBounds};
+
+/// Why an ICN string was rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IcnErrorKind {
+    /// A `[` tag without its closing `]`.
+    UnterminatedTag,
+    /// A `[Variant "..."]` or `[PieceValues "..."]` tag without its closing quote.
+    MalformedTag,
+    /// A variant name that `Variant::try_parse` does not recognise.
+    UnknownVariant,
+    /// A `[PieceValues "..."]` list that is not `<code>=<value>` pairs.
+    BadPieceValues,
+    /// A token that matches none of the ICN fields.
+    UnknownToken,
+    /// A field (turn, clock, bounds, ...) given more than once.
+    DuplicateField,
+    /// A `halfmove/limit` clock that does not parse.
+    BadClock,
+    /// A `(w_rank;pieces|b_rank;pieces)` promotion rule that does not parse.
+    BadPromotion,
+    /// A `left,right,bottom,top` bounds token (or one of its `+`/`~` boxes)
+    /// that does not parse or is empty.
+    BadBounds,
+    /// An en passant `x,y` square that does not parse.
+    BadEnPassant,
+    /// A piece entry that is not `<code><x>,<y>[+]`.
+    BadPiece,
+    /// A piece entry with an unknown piece code.
+    UnknownPiece,
+    /// Two piece entries on the same square.
+    DuplicateSquare,
+    /// A piece placed outside the world bounds.
+    OutOfBounds,
+    /// No piece list in the string.
+    MissingPieces,
+    /// A move in the move segment that does not parse.
+    BadMove,
+    /// A move in the move segment that is not legal in its position.
+    IllegalMove,
+}
+
+impl fmt::Display for IcnErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            IcnErrorKind::UnterminatedTag => "unterminated tag",
+            IcnErrorKind::MalformedTag => "malformed tag",
+            IcnErrorKind::UnknownVariant => "unknown variant",
+            IcnErrorKind::BadPieceValues => "invalid piece values",
+            IcnErrorKind::UnknownToken => "unrecognised token",
+            IcnErrorKind::DuplicateField => "field given more than once",
+            IcnErrorKind::BadClock => "invalid halfmove/limit clock",
+            IcnErrorKind::BadPromotion => "invalid promotion rule",
+            IcnErrorKind::BadBounds => "invalid world bounds",
+            IcnErrorKind::BadEnPassant => "invalid en passant square",
+            IcnErrorKind::BadPiece => "malformed piece",
+            IcnErrorKind::UnknownPiece => "unknown piece code",
+            IcnErrorKind::DuplicateSquare => "two pieces on one square",
+            IcnErrorKind::OutOfBounds => "piece outside the world bounds",
+            IcnErrorKind::MissingPieces => "no piece list",
+            IcnErrorKind::BadMove => "malformed move",
+            IcnErrorKind::IllegalMove => "illegal move",
+        };
+        f.write_str(reason)
+    }
+}
+
+/// An ICN parse failure: the offending token, its byte offset in the input
+/// and the reason it was rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IcnError {
+    pub token: String,
+    pub offset: usize,
+    pub kind: IcnErrorKind,
+}
+
+impl fmt::Display for IcnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at byte {}: '{}'", self.kind, self.offset, self.token)
+    }
+}
+
+impl std::error::Error for IcnError {}
+
+/// A move from the move segment, already split into coordinates.
+struct IcnMove<'a> {
+    text: &'a str,
+    from: Coordinate,
+    to: Coordinate,
+    promotion: Option<PieceType>,
+}
+
+/// Tracks which single-occurrence fields have been seen.
+#[derive(Default)]
+struct Seen {
+    turn: bool,
+    en_passant: bool,
+    clock: bool,
+    fullmove: bool,
+    promotion: bool,
+    bounds: bool,
+    win_conditions: bool,
+    pieces: bool,
+}
+
+impl GameState {
+    /// Parses an ICN string, rejecting anything `setup_position_from_icn` would
+    /// skip, default or silently repair. On success the result is identical to
+    /// the lenient parser's; every move in the move segment is also checked to
+    /// be fully legal before it is played.
+    pub fn try_from_icn(icn: &str) -> Result<GameState, IcnError> {
+        let error = |part: &str, kind: IcnErrorKind| IcnError {
+            token: part.to_string(),
+            offset: part.as_ptr() as usize - icn.as_ptr() as usize,
+            kind,
+        };
+
+        // Tokens for the lenient parser, with move tokens blanked out to "-" so
+        // its token count (and therefore its single-token shortcut) is unchanged.
+        let mut setup: Vec<&str> = Vec::new();
+        let mut content = icn.trim();
+
+        while content.starts_with('[') {
+            let Some(end) = content.find(']') else {
+                return Err(error(content, IcnErrorKind::UnterminatedTag));
+            };
+            let tag = &content[..=end];
+            if let Some(rest) = tag[1..end].strip_prefix("Variant \"") {
+                let Some(name_end) = rest.find('"') else {
+                    return Err(error(tag, IcnErrorKind::MalformedTag));
+                };
+                if Variant::try_parse(&rest[..name_end]).is_none() {
+                    return Err(error(tag, IcnErrorKind::UnknownVariant));
+                }
+            } else if let Some(rest) = tag[1..end].strip_prefix("PieceValues \"") {
+                let Some(values) = rest.strip_suffix('"') else {
+                    return Err(error(tag, IcnErrorKind::MalformedTag));
+                };
+                if PieceValues::parse(values).is_none() {
+                    return Err(error(tag, IcnErrorKind::BadPieceValues));
+                }
+            }
+            setup.push(tag);
+            content = content[end + 1..].trim_start();
+        }
+
+        let tokens: Vec<&str> = content.split_whitespace().collect();
+        let mut seen = Seen::default();
+        let mut moves: Vec<IcnMove> = Vec::new();
+        let mut pieces: Vec<(&str, Coordinate)> = Vec::new();
+
+        let once = |flag: &mut bool, token: &str| {
+            if std::mem::replace(flag, true) {
+                Err(error(token, IcnErrorKind::DuplicateField))
+            } else {
+                Ok(())
+            }
+        };
+
+        for &token in &tokens {
+            if tokens.len() == 1 && !token.contains('>') {
+                // A bare piece list, as the lenient parser accepts it.
+                once(&mut seen.pieces, token)?;
+                parse_pieces(token, &mut pieces).map_err(|(p, k)| error(p, k))?;
+                setup.push(token);
+                continue;
+            }
+
+            if token == "-" {
+                setup.push(token);
+                continue;
+            }
+
+            match IcnField::of(token) {
+                IcnField::Moves => {
+                    for m in token.split('|').filter(|m| !m.is_empty()) {
+                        moves.push(
+                            parse_move(m).ok_or_else(|| error(m, IcnErrorKind::BadMove))?,
+                        );
+                    }
+                    setup.push("-");
+                    continue;
+                }
+                IcnField::Turn => once(&mut seen.turn, token)?,
+                IcnField::Clock => {
+                    once(&mut seen.clock, token)?;
+                    if !is_valid_clock(token) {
+                        return Err(error(token, IcnErrorKind::BadClock));
+                    }
+                }
+                IcnField::Promotion => {
+                    once(&mut seen.promotion, token)?;
+                    if !is_valid_promotion(&token[1..token.len() - 1]) {
+                        return Err(error(token, IcnErrorKind::BadPromotion));
+                    }
+                }
+                IcnField::Bounds => {
+                    once(&mut seen.bounds, token)?;
+                    if !is_valid_bounds(token) {
+                        return Err(error(token, IcnErrorKind::BadBounds));
+                    }
+                }
+                IcnField::Pieces => {
+                    once(&mut seen.pieces, token)?;
+                    parse_pieces(token, &mut pieces).map_err(|(p, k)| error(p, k))?;
+                }
+                IcnField::EnPassant => {
+                    once(&mut seen.en_passant, token)?;
+                    if parse_coord(token).is_none() {
+                        return Err(error(token, IcnErrorKind::BadEnPassant));
+                    }
+                }
+                IcnField::Fullmove => once(&mut seen.fullmove, token)?,
+                IcnField::WinConditions => {
+                    let conditions = super::split_koth_squares(token).map(|(wcs, _)| wcs);
+                if conditions
+                    .as_deref()
+                    .unwrap_or(token)
+                        .split(',')
+                        .any(|wc| wc.parse::<WinCondition>().is_err())
+                    {
+                        return Err(error(token, IcnErrorKind::UnknownToken));
+                    }
+                    once(&mut seen.win_conditions, token)?;
+                }
+            }
+            setup.push(token);
+        }
+
+        if !seen.pieces {
+            return Err(error(&icn[icn.len()..], IcnErrorKind::MissingPieces));
+        }
+
+        let mut game = GameState::new();
+        game.setup_position_from_icn(&setup.join(" "));
+
+        let bounds = &game.game_rules.world_bounds;
+        if let Some((part, _)) = pieces.iter().find(|(_, c)| !bounds.contains(c.x, c.y)) {
+            return Err(error(part, IcnErrorKind::OutOfBounds));
+        }
+
+        for m in &moves {
+            if !game.play_checked_move(m) {
+                return Err(error(m.text, IcnErrorKind::IllegalMove));
+            }
+        }
+        if !moves.is_empty() {
+            game.eval_kind = crate::evaluation::eval_kind::detect(&game);
+        }
+
+        Ok(game)
+    }
+
+    /// Finds the fully legal move written as a site token (`x,y>x,y[=promo]`,
+    /// the same syntax as the ICN move segment). Returns `None` if the token
+    /// doesn't parse or no legal move matches it.
+    pub fn find_site_move(&mut self, token: &str) -> Option<Move> {
+        let m = parse_move(token.trim())?;
+        self.find_checked_move(&m)
+    }
+
+    /// The fully legal move matching `m` in the current position, if any.
+    fn find_checked_move(&mut self, m: &IcnMove) -> Option<Move> {
+        self.find_legal_move(|c| c.from == m.from && c.to == m.to && c.promotion == m.promotion)
+    }
+
+    /// Plays `m` if it matches a fully legal move in the current position.
+    fn play_checked_move(&mut self, m: &IcnMove) -> bool {
+        match self.find_checked_move(m) {
+            Some(legal) => {
+                self.make_move(&legal);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// The field an ICN setup token fills, judged by its shape alone. Both parsers
+/// classify tokens through [`IcnField::of`], so they always agree on what a token is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum IcnField {
+    /// The move segment: `x,y>x,y` moves separated by `|`.
+    Moves,
+    Turn,
+    /// `halfmove/limit`.
+    Clock,
+    /// `(w_rank;codes|b_rank;codes)`.
+    Promotion,
+    /// `left,right,bottom,top`, plus optional `+box` / `~hole` parts.
+    Bounds,
+    /// `checkmate,koth(4,4;5,5)` and other win condition lists.
+    WinConditions,
+    /// The piece list, including a lone piece of either color.
+    Pieces,
+    /// `x,y`.
+    EnPassant,
+    Fullmove,
+}
+
+impl IcnField {
+    pub(super) fn of(token: &str) -> IcnField {
+        if token.contains('>') || token.contains('x') {
+            IcnField::Moves
+        } else if token == "w" || token == "b" {
+            IcnField::Turn
+        } else if token.contains('/') && token.starts_with(|c: char| c.is_ascii_digit()) {
+            IcnField::Clock
+        } else if token.starts_with('(') && token.ends_with(')') {
+            IcnField::Promotion
+        } else if WorldBounds::is_icn_token(token) {
+            IcnField::Bounds
+        } else if token.contains("koth(") {
+            // Hill squares have digits and commas, but never a piece code.
+            IcnField::WinConditions
+        } else if token.contains('|')
+            || (token.contains(',') && token.chars().any(|c| c.is_ascii_uppercase()))
+            || (token.contains(',')
+                && token.starts_with(|c: char| c.is_ascii_lowercase())
+                && token.chars().any(|c| c.is_ascii_digit()))
+        {
+            // A lone black piece ('k5,8') has digits, which win conditions never do.
+            IcnField::Pieces
+        } else if token.contains(',')
+            && token.split(',').count() == 2
+            && !token.chars().any(|c| c.is_ascii_alphabetic())
+        {
+            IcnField::EnPassant
+        } else if token.parse::<u32>().is_ok() {
+            IcnField::Fullmove
+        } else {
+            IcnField::WinConditions
+        }
+    }
+}
+
+fn parse_coord(s: &str) -> Option<Coordinate> {
+    let (x, y) = s.split_once(',')?;
+    Some(Coordinate::new(x.parse().ok()?, y.parse().ok()?))
+}
+
+fn is_valid_clock(token: &str) -> bool {
+    let Some((halfmove, limit)) = token.split_once('/') else {
+        return false;
+    };
+    halfmove.parse::<u32>().is_ok() && (limit == "-" || limit.parse::<u32>().is_ok())
+}
+
+fn is_valid_promotion(inner: &str) -> bool {
+    let sides: Vec<&str> = inner.split('|').collect();
+    sides.len() <= 2
+        && sides.iter().all(|side| {
+            let (rank, promos) = match side.split_once(';') {
+                Some((rank, promos)) => (rank, Some(promos)),
+                None => (*side, None),
+            };
+            (rank.is_empty() || rank.parse::<i64>().is_ok())
+                && promos.is_none_or(|p| {
+                    p.split(',').all(|code| {
+                        PieceType::from_site_code(&code.to_uppercase()) != PieceType::Void
+                    })
+                })
+        })
+}
+
+fn is_valid_bounds(token: &str) -> bool {
+    token.split(['+', '~']).all(|rect| {
+        let values: Vec<Option<i64>> = rect.split(',').map(|v| v.parse().ok()).collect();
+        match values[..] {
+            [Some(left), Some(right), Some(bottom), Some(top)] => left <= right && bottom <= top,
+            _ => false,
+        }
+    })
+}
+
+/// Validates a `|`-separated piece list, collecting each piece's square.
+/// Errors carry the offending piece entry rather than the whole segment.
+fn parse_pieces<'a>(
+    segment: &'a str,
+    pieces: &mut Vec<(&'a str, Coordinate)>,
+) -> Result<(), (&'a str, IcnErrorKind)> {
+    let mut squares: FxHashSet<Coordinate> = FxHashSet::default();
+    for def in segment.split('|').filter(|d| !d.is_empty()) {
+        let Some((head, y)) = def.split_once(',') else {
+            return Err((def, IcnErrorKind::BadPiece));
+        };
+        let split_idx = head
+            .find(|c: char| c.is_ascii_digit() || c == '-')
+            .unwrap_or(head.len());
+        let (code, x) = head.split_at(split_idx);
+        let x = x.strip_suffix('+').unwrap_or(x);
+        let y = y.strip_suffix('+').unwrap_or(y);
+        let (Ok(x), Ok(y)) = (x.parse::<i64>(), y.parse::<i64>()) else {
+            return Err((def, IcnErrorKind::BadPiece));
+        };
+        if code.is_empty() {
+            return Err((def, IcnErrorKind::BadPiece));
+        }
+
+        let upper = code.to_uppercase();
+        if PieceType::from_site_code(&upper) == PieceType::Void && upper != "VO" {
+            return Err((def, IcnErrorKind::UnknownPiece));
+        }
+
+        let square = Coordinate::new(x, y);
+        if !squares.insert(square) {
+            return Err((def, IcnErrorKind::DuplicateSquare));
+        }
+        pieces.push((def, square));
+    }
+    Ok(())
+}
+
+/// Parses one move of the move segment (`[piece]x,y>x,y[=promo]`, `x` for
+/// captures, annotation suffixes allowed) the same way the lenient parser does.
+fn parse_move(text: &str) -> Option<IcnMove<'_>> {
+    let clean = text
+        .trim_start_matches(|c: char| c.is_alphabetic() && c != 'x')
+        .trim_end_matches(['+', '!', '#', '?']);
+    let (from, to) = if clean.contains('>') {
+        clean.split_once('>')?
+    } else {
+        clean.split_once('x')?
+    };
+    let (to, promotion) = match to.split_once('=') {
+        Some((to, promo)) => (to, Some(PieceType::parse_promotion_code(promo)?)),
+        None => (to, None),
+    };
+    Some(IcnMove {
+        text,
+        from: parse_coord(from)?,
+        to: parse_coord(to)?,
+        promotion,
+    })
+}
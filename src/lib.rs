@@ -9,8 +9,10 @@ pub mod moves;
 pub mod nnue;
 pub mod search;
 pub mod simd;
+pub mod tablebase;
 pub mod tiles;
 mod utils;
+pub mod variant;
 
 // Initialize panic hook for better error messages in WASM
 #[cfg(feature = "debug")]
@@ -21,193 +23,7 @@ pub fn init_panic_hook() {
 
 use board::PlayerColor;
 use game::GameState;
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum Variant {
-    Classical,
-    ConfinedClassical,
-    ClassicalPlus,
-    CoaIP,
-    CoaIPHO,
-    CoaIPRO,
-    CoaIPNO,
-    Palace,
-    Pawndard,
-    Core,
-    Standarch,
-    SpaceClassic,
-    Space,
-    Abundance,
-    PawnHorde,
-    Knightline,
-    Obstocean,
-    Chess,
-    // Custom variant that uses all fairy leapers
-    ScatteredLeapers,
-    // Custom variants that uses more than 1 king
-    DoubleKingClassical,
-    DoubleKingChess,
-    TripleKingMaze, // variant created by Nikita
-    AllPiecesClassical, // Classical setup, allpiecescaptured win condition (tests base eval)
-}
-
-impl Variant {
-    #[cfg(any(test, not(target_arch = "wasm32")))]
-    pub fn starting_icn(&self) -> &'static str {
-        match self {
-            Variant::Classical => {
-                "w 0/100 1 (8|1) P1,2+|P2,2+|P3,2+|P4,2+|P5,2+|P6,2+|P7,2+|P8,2+|p1,7+|p2,7+|p3,7+|p4,7+|p5,7+|p6,7+|p7,7+|p8,7+|R1,1+|R8,1+|r1,8+|r8,8+|N2,1|N7,1|n2,8|n7,8|B3,1|B6,1|b3,8|b6,8|Q4,1|q4,8|K5,1+|k5,8+"
-            }
-            Variant::ConfinedClassical => {
-                "w 0/100 1 (8|1) -1000000000000000,1000000000000009,-1000000000000000,1000000000000009 P1,2+|P2,2+|P3,2+|P4,2+|P5,2+|P6,2+|P7,2+|P8,2+|p1,7+|p2,7+|p3,7+|p4,7+|p5,7+|p6,7+|p7,7+|p8,7+|R1,1+|R8,1+|r1,8+|r8,8+|N2,1|N7,1|n2,8|n7,8|B3,1|B6,1|b3,8|b6,8|Q4,1|q4,8|K5,1+|k5,8+|ob0,0|ob0,1|ob0,2|ob0,7|ob0,8|ob0,9|ob9,0|ob9,1|ob9,2|ob9,7|ob9,8|ob9,9|ob1,0|ob2,0|ob3,0|ob4,0|ob5,0|ob6,0|ob7,0|ob8,0|ob1,9|ob2,9|ob3,9|ob4,9|ob5,9|ob6,9|ob7,9|ob8,9"
-            }
-            Variant::ClassicalPlus => {
-                "w 0/100 1 (8|1) p1,9+|p2,9+|p3,9+|p6,9+|p7,9+|p8,9+|p0,8+|r1,8+|n2,8|b3,8|q4,8|k5,8+|b6,8|n7,8|r8,8+|p9,8+|p1,7+|p2,7+|p3,7+|p4,7+|p5,7+|p6,7+|p7,7+|p8,7+|p3,5+|p6,5+|P3,4+|P6,4+|P1,2+|P2,2+|P3,2+|P4,2+|P5,2+|P6,2+|P7,2+|P8,2+|P0,1+|R1,1+|N2,1|B3,1|Q4,1|K5,1+|B6,1|N7,1|R8,1+|P9,1+|P1,0+|P2,0+|P3,0+|P6,0+|P7,0+|P8,0+"
-            }
-            Variant::CoaIP => {
-                "w 0/100 1 (8;n,b,r,q,gu,ch,ha|1;n,b,r,q,gu,ch,ha) P-2,1+|P-1,2+|P0,2+|P1,2+|P2,2+|P3,2+|P4,2+|P5,2+|P6,2+|P7,2+|P8,2+|P9,2+|P10,2+|P11,1+|P-4,-6+|P-3,-5+|P-2,-4+|P-1,-5+|P0,-6+|P9,-6+|P10,-5+|P11,-4+|P12,-5+|P13,-6+|p-2,8+|p-1,7+|p0,7+|p1,7+|p2,7+|p3,7+|p4,7+|p5,7+|p6,7+|p7,7+|p8,7+|p9,7+|p10,7+|p11,8+|p-4,15+|p-3,14+|p-2,13+|p-1,14+|p0,15+|p9,15+|p10,14+|p11,13+|p12,14+|p13,15+|HA-2,-6|HA11,-6|ha-2,15|ha11,15|R-1,1|R10,1|r-1,8|r10,8|CH0,1|CH9,1|ch0,8|ch9,8|GU1,1+|GU8,1+|gu1,8+|gu8,8+|N2,1|N7,1|n2,8|n7,8|B3,1|B6,1|b3,8|b6,8|Q4,1|q4,8|K5,1+|k5,8+"
-            }
-            Variant::CoaIPHO => {
-                "w 0/100 1 (8;n,b,r,q,gu,ch,ha,hu|1;n,b,r,q,gu,ch,ha,hu) p-4,14+|ha-2,14|p0,14+|p9,14+|ha11,14|p13,14+|p-3,13+|p-1,13+|p10,13+|p12,13+|p-2,12+|p11,12+|gu-1,9|hu0,9|ch1,9|ch8,9|hu9,9|gu10,9|p-1,8+|p0,8+|r1,8+|n2,8|b3,8|q4,8|k5,8+|b6,8|n7,8|r8,8+|p9,8+|p10,8+|p1,7+|p2,7+|p3,7+|p4,7+|p5,7+|p6,7+|p7,7+|p8,7+|P1,2+|P2,2+|P3,2+|P4,2+|P5,2+|P6,2+|P7,2+|P8,2+|P-1,1+|P0,1+|R1,1+|N2,1|B3,1|Q4,1|K5,1+|B6,1|N7,1|R8,1+|P9,1+|P10,1+|GU-1,0|HU0,0|CH1,0|CH8,0|HU9,0|GU10,0|P-2,-3+|P11,-3+|P-3,-4+|P-1,-4+|P10,-4+|P12,-4+|P-4,-5+|HA-2,-5|P0,-5+|P9,-5+|HA11,-5|P13,-5+"
-            }
-            Variant::CoaIPRO => {
-                "w 0/100 1 (8;n,b,r,q,gu,ch,ro|1;n,b,r,q,gu,ch,ro) P-2,1+|P-1,2+|P0,2+|P1,2+|P2,2+|P3,2+|P4,2+|P5,2+|P6,2+|P7,2+|P8,2+|P9,2+|P10,2+|P11,1+|P-4,-6+|P-3,-5+|P-2,-4+|P-1,-5+|P0,-6+|P9,-6+|P10,-5+|P11,-4+|P12,-5+|P13,-6+|p-2,8+|p-1,7+|p0,7+|p1,7+|p2,7+|p3,7+|p4,7+|p5,7+|p6,7+|p7,7+|p8,7+|p9,7+|p10,7+|p11,8+|p-4,15+|p-3,14+|p-2,13+|p-1,14+|p0,15+|p9,15+|p10,14+|p11,13+|p12,14+|p13,15+|R-1,1|R10,1|r-1,8|r10,8|CH0,1|CH9,1|ch0,8|ch9,8|GU1,1+|GU8,1+|gu1,8+|gu8,8+|N2,1|N7,1|n2,8|n7,8|B3,1|B6,1|b3,8|b6,8|Q4,1|q4,8|K5,1+|k5,8+|RO-2,-6|RO11,-6|ro-2,15|ro11,15"
-            }
-            Variant::CoaIPNO => {
-                "w 0/100 1 (8;n,b,r,q,gu,ch,nr|1;n,b,r,q,gu,ch,nr) P-2,1+|P-1,2+|P0,2+|P1,2+|P2,2+|P3,2+|P4,2+|P5,2+|P6,2+|P7,2+|P8,2+|P9,2+|P10,2+|P11,1+|P-4,-6+|P-3,-5+|P-2,-4+|P-1,-5+|P0,-6+|P9,-6+|P10,-5+|P11,-4+|P12,-5+|P13,-6+|p-2,8+|p-1,7+|p0,7+|p1,7+|p2,7+|p3,7+|p4,7+|p5,7+|p6,7+|p7,7+|p8,7+|p9,7+|p10,7+|p11,8+|p-4,15+|p-3,14+|p-2,13+|p-1,14+|p0,15+|p9,15+|p10,14+|p11,13+|p12,14+|p13,15+|R-1,1|R10,1|r-1,8|r10,8|CH0,1|CH9,1|ch0,8|ch9,8|GU1,1+|GU8,1+|gu1,8+|gu8,8+|N2,1|N7,1|n2,8|n7,8|B3,1|B6,1|b3,8|b6,8|Q4,1|q4,8|K5,1+|k5,8+|nr-2,16|nr11,16|NR-2,-7|NR11,-7"
-            }
-            Variant::Palace => {
-                "w 0/100 1 (4;n,b,r,q,am|2;n,b,r,q,am) K4,1|Q5,1|P6,2+|P5,2+|P4,2+|P3,2+|P2,2+|P1,2+|p1,4+|p2,4+|p3,4+|p4,4+|p5,4+|p6,4+|N6,1|AM3,1|Q2,1|N1,1|n1,5|n6,5|k4,5|q5,5|q2,5|am3,5|P6,-1+|P7,-1+|P8,-1+|P9,-1+|P1,-1+|P0,-1+|P-1,-1+|P-2,-1+|P2,-2+|P-3,-2+|P5,-2+|P10,-2+|p7,7+|p6,7+|p8,7+|p9,7+|p1,7+|p0,7+|p-1,7+|p-2,7+|p-3,8+|p2,8+|p5,8+|p10,8+|r-1,8|r-2,8|r8,8|r9,8|R8,-2|R9,-2|R-1,-2|R-2,-2|B0,-2|B1,-2|B7,-2|B6,-2|b0,8|b1,8|b7,8|b6,8"
-            }
-            Variant::Pawndard => {
-                "w 0/100 1 (8|1) b4,14|b5,14|r4,12|r5,12|p2,10+|p3,10+|p6,10+|p7,10+|p1,9+|p8,9+|p0,8+|n2,8|n3,8|k4,8|q5,8|n6,8|n7,8|p9,8+|p1,7+|p2,7+|p3,7+|p4,7+|p5,7+|p6,7+|p7,7+|p8,7+|P1,5+|p2,5+|P3,5+|p6,5+|P7,5+|p8,5+|p1,4+|P2,4+|p3,4+|P6,4+|p7,4+|P8,4+|P1,2+|P2,2+|P3,2+|P4,2+|P5,2+|P6,2+|P7,2+|P8,2+|P0,1+|N2,1|N3,1|Q4,1|K5,1|N6,1|N7,1|P9,1+|P1,0+|P8,0+|P2,-1+|P3,-1+|P6,-1+|P7,-1+|R4,-3|R5,-3|B4,-5|B5,-5"
-            }
-            Variant::Core => {
-                "w 0/100 1 (8|1) p-1,10+|p3,10+|p4,10+|p5,10+|p6,10+|p10,10+|p0,9+|p9,9+|n0,8|r1,8+|n2,8|b3,8|q4,8|k5,8+|b6,8|n7,8|r8,8+|n9,8|p-2,7+|p1,7+|p2,7+|p3,7+|p4,7+|p5,7+|p6,7+|p7,7+|p8,7+|p11,7+|p-3,6+|p12,6+|p1,5+|P2,5+|P7,5+|p8,5+|P1,4+|p2,4+|p7,4+|P8,4+|P-3,3+|P12,3+|P-2,2+|P1,2+|P2,2+|P3,2+|P4,2+|P5,2+|P6,2+|P7,2+|P8,2+|P11,2+|N0,1|R1,1+|N2,1|B3,1|Q4,1|K5,1+|B6,1|N7,1|R8,1+|N9,1|P0,0+|P9,0+|P-1,-1+|P3,-1+|P4,-1+|P5,-1+|P6,-1+|P10,-1+"
-            }
-            Variant::Standarch => {
-                "w 0/100 1 (8;n,b,r,q,ch,ar|1;n,b,r,q,ch,ar) p4,11+|p5,11+|p1,10+|p2,10+|p3,10+|p6,10+|p7,10+|p8,10+|p0,9+|ar4,9|ch5,9|p9,9+|p0,8+|r1,8+|n2,8|b3,8|q4,8|k5,8+|b6,8|n7,8|r8,8+|p9,8+|p0,7+|p1,7+|p2,7+|p3,7+|p4,7+|p5,7+|p6,7+|p7,7+|p8,7+|p9,7+|P0,2+|P1,2+|P2,2+|P3,2+|P4,2+|P5,2+|P6,2+|P7,2+|P8,2+|P9,2+|P0,1+|R1,1+|N2,1|B3,1|Q4,1|K5,1+|B6,1|N7,1|R8,1+|P9,1+|P0,0+|AR4,0|CH5,0|P9,0+|P1,-1+|P2,-1+|P3,-1+|P6,-1+|P7,-1+|P8,-1+|P4,-2+|P5,-2+"
-            }
-            Variant::SpaceClassic => {
-                "w 0/100 1 (8|1) p-3,18+|r2,18|b4,18|b5,18|r7,18|p12,18+|p-4,17+|p13,17+|p-5,16+|p14,16+|p3,9+|p4,9+|p5,9+|p6,9+|n3,8|k4,8|q5,8|n6,8|p-6,7+|p1,7+|p2,7+|p3,7+|p4,7+|p5,7+|p6,7+|p7,7+|p8,7+|p-8,6+|p-7,6+|p16,6+|p17,6+|p-9,5+|p18,5+|P-9,4+|P18,4+|P-8,3+|P-7,3+|P16,3+|P17,3+|P1,2+|P2,2+|P3,2+|P4,2+|P5,2+|P6,2+|P7,2+|P8,2+|P15,2+|N3,1|K4,1|Q5,1|N6,1|P3,0+|P4,0+|P5,0+|P6,0+|P-5,-7+|P14,-7+|P-4,-8+|P13,-8+|P-3,-9+|R2,-9|B4,-9|B5,-9|R7,-9|P12,-9+"
-            }
-            Variant::Space => {
-                "w 0/100 1 (4;n,b,r,q,ha,ce,ar,ch|-3;n,b,r,q,ha,ce,ar,ch) q4,31|ch4,23|p-12,18+|b4,18|p20,18+|p-11,17+|ar-10,17|p0,17+|b4,17|p8,17+|ar18,17|p19,17+|p-11,16+|p-10,16+|p-1,16+|p9,16+|p18,16+|p19,16+|p-1,15+|r0,15|ha4,15|r8,15|p9,15+|p3,6+|p4,6+|p5,6+|p2,5+|k4,5|p6,5+|n1,4|ce4,4|n7,4|p-10,3+|p-1,3+|p0,3+|p2,3+|p3,3+|p4,3+|p5,3+|p6,3+|p8,3+|p9,3+|p-12,2+|p-11,2+|p19,2+|p20,2+|p-13,1+|p21,1+|P-13,0+|P21,0+|P-12,-1+|P-11,-1+|P19,-1+|P20,-1+|P-1,-2+|P0,-2+|P2,-2+|P3,-2+|P4,-2+|P5,-2+|P6,-2+|P8,-2+|P9,-2+|P18,-2+|N1,-3|CE4,-3|N7,-3|P2,-4+|K4,-4|P6,-4+|P3,-5+|P4,-5+|P5,-5+|P-1,-14+|R0,-14|HA4,-14|R8,-14|P9,-14+|P-11,-15+|P-10,-15+|P-1,-15+|P9,-15+|P18,-15+|P19,-15+|P-11,-16+|AR-10,-16|P0,-16+|B4,-16|P8,-16+|AR18,-16|P19,-16+|P-12,-17+|B4,-17|P20,-17+|CH4,-22|Q4,-30"
-            }
-            Variant::Abundance => {
-                "w 0/100 1 (6;n,b,r,q,gu,ha,ch|-6;n,b,r,q,gu,ha,ch) p-3,10+|ha-2,10|ha-1,10|r0,10|ha1,10|ha2,10|p3,10+|p-2,9+|p-1,9+|p1,9+|p2,9+|p-5,6+|gu-4,6|r-3,6+|b-2,6|b-1,6|k0,6+|b1,6|b2,6|r3,6+|gu4,6|p5,6+|p-4,5+|gu-3,5|n-1,5|q0,5|n1,5|gu3,5|p4,5+|p-3,4+|p-2,4+|gu-1,4|ch0,4|gu1,4|p2,4+|p3,4+|p-1,3+|p0,3+|p1,3+|P-1,-3+|P0,-3+|P1,-3+|P-3,-4+|P-2,-4+|GU-1,-4|CH0,-4|GU1,-4|P2,-4+|P3,-4+|P-4,-5+|GU-3,-5|N-1,-5|Q0,-5|N1,-5|GU3,-5|P4,-5+|P-5,-6+|GU-4,-6|R-3,-6+|B-2,-6|B-1,-6|K0,-6+|B1,-6|B2,-6|R3,-6+|GU4,-6|P5,-6+|P-2,-9+|P-1,-9+|P1,-9+|P2,-9+|P-3,-10+|HA-2,-10|HA-1,-10|R0,-10|HA1,-10|HA2,-10|P3,-10+"
-            }
-            Variant::PawnHorde => {
-                "w 0/100 1 (2|-7) checkmate,allpiecescaptured k5,2+|q4,2|r1,2+|n7,2|n2,2|r8,2+|b3,2|b6,2|P2,-1+|P3,-1+|P6,-1+|P7,-1+|P1,-2+|P2,-2+|P4,-2+|P5,-2+|P6,-2+|P7,-2+|P8,-2+|P1,-3+|P2,-3+|P4,-3+|P5,-3+|P6,-3+|P7,-3+|P8,-3+|P1,-4+|P2,-4+|P4,-4+|P5,-4+|P6,-4+|P7,-4+|P8,-4+|P1,-5+|P2,-5+|P4,-5+|P5,-5+|P6,-5+|P7,-5+|P8,-5+|P1,-6+|P2,-6+|P4,-6+|P5,-6+|P6,-6+|P7,-6+|P8,-6+|P3,-2+|P3,-3+|P3,-4+|P3,-5+|P3,-6+|P1,-7+|P2,-7+|P3,-7+|P4,-7+|P5,-7+|P6,-7+|P7,-7+|P8,-7+|P0,-6+|P0,-7+|P9,-6+|P9,-7+|p9,2+|p1,1+|p2,1+|p3,1+|p4,1+|p5,1+|p6,1+|p7,1+|p8,1+|p0,2+"
-            }
-            Variant::Knightline => {
-                "w 0/100 1 (8;n,q|1;n,q) k5,8|n3,8|n4,8|n6,8|n7,8|p-5,7+|p-4,7+|p-3,7+|p-2,7+|p-1,7+|p0,7+|p1,7+|p2,7+|p3,7+|p4,7+|p5,7+|p6,7+|p7,7+|p8,7+|p9,7+|p10,7+|p11,7+|p12,7+|p13,7+|p14,7+|p15,7+|K5,1|N3,1|N4,1|N6,1|N7,1|P-5,2+|P-4,2+|P-3,2+|P-2,2+|P-1,2+|P0,2+|P1,2+|P2,2+|P3,2+|P4,2+|P5,2+|P6,2+|P7,2+|P8,2+|P9,2+|P10,2+|P11,2+|P12,2+|P13,2+|P14,2+|P15,2+"
-            }
-            Variant::Obstocean => {
-                "w 0/100 1 (8|1) -6,15,-3,12 ob-6,12|ob-5,12|ob-4,12|ob-3,12|ob-2,12|ob-1,12|ob0,12|ob1,12|ob2,12|ob3,12|ob4,12|ob5,12|ob6,12|ob7,12|ob8,12|ob9,12|ob10,12|ob11,12|ob12,12|ob13,12|ob14,12|ob15,12|ob-6,11|ob-5,11|ob-4,11|ob-3,11|ob-2,11|ob-1,11|ob0,11|ob1,11|ob2,11|ob3,11|ob4,11|ob5,11|ob6,11|ob7,11|ob8,11|ob9,11|ob10,11|ob11,11|ob12,11|ob13,11|ob14,11|ob15,11|ob-6,10|ob-5,10|ob-4,10|ob-3,10|ob-2,10|ob-1,10|ob0,10|ob1,10|ob2,10|ob3,10|ob4,10|ob5,10|ob6,10|ob7,10|ob8,10|ob9,10|ob10,10|ob11,10|ob12,10|ob13,10|ob14,10|ob15,10|ob-6,9|ob-5,9|ob-4,9|ob-3,9|ob-2,9|ob-1,9|ob0,9|ob1,9|ob2,9|ob3,9|ob4,9|ob5,9|ob6,9|ob7,9|ob8,9|ob9,9|ob10,9|ob11,9|ob12,9|ob13,9|ob14,9|ob15,9|ob-6,8|ob-5,8|ob-4,8|ob-3,8|ob-2,8|ob-1,8|ob0,8|r1,8+|n2,8|b3,8|q4,8|k5,8+|b6,8|n7,8|r8,8+|ob9,8|ob10,8|ob11,8|ob12,8|ob13,8|ob14,8|ob15,8|ob-6,7|ob-5,7|ob-4,7|ob-3,7|ob-2,7|ob-1,7|ob0,7|p1,7+|p2,7+|p3,7+|p4,7+|p5,7+|p6,7+|p7,7+|p8,7+|ob9,7|ob10,7|ob11,7|ob12,7|ob13,7|ob14,7|ob15,7|ob-6,6|ob-5,6|ob-4,6|ob-3,6|ob-2,6|ob-1,6|ob0,6|ob1,6|ob2,6|ob3,6|ob4,6|ob5,6|ob6,6|ob7,6|ob8,6|ob9,6|ob10,6|ob11,6|ob12,6|ob13,6|ob14,6|ob15,6|ob-6,5|ob-5,5|ob-4,5|ob-3,5|ob-2,5|ob-1,5|ob0,5|ob1,5|ob2,5|ob3,5|ob4,5|ob5,5|ob6,5|ob7,5|ob8,5|ob9,5|ob10,5|ob11,5|ob12,5|ob13,5|ob14,5|ob15,5|ob-6,4|ob-5,4|ob-4,4|ob-3,4|ob-2,4|ob-1,4|ob0,4|ob1,4|ob2,4|ob3,4|ob4,4|ob5,4|ob6,4|ob7,4|ob8,4|ob9,4|ob10,4|ob11,4|ob12,4|ob13,4|ob14,4|ob15,4|ob-6,3|ob-5,3|ob-4,3|ob-3,3|ob-2,3|ob-1,3|ob0,3|ob1,3|ob2,3|ob3,3|ob4,3|ob5,3|ob6,3|ob7,3|ob8,3|ob9,3|ob10,3|ob11,3|ob12,3|ob13,3|ob14,3|ob15,3|ob-6,2|ob-5,2|ob-4,2|ob-3,2|ob-2,2|ob-1,2|ob0,2|P1,2+|P2,2+|P3,2+|P4,2+|P5,2+|P6,2+|P7,2+|P8,2+|ob9,2|ob10,2|ob11,2|ob12,2|ob13,2|ob14,2|ob15,2|ob-6,1|ob-5,1|ob-4,1|ob-3,1|ob-2,1|ob-1,1|ob0,1|R1,1+|N2,1|B3,1|Q4,1|K5,1+|B6,1|N7,1|R8,1+|ob9,1|ob10,1|ob11,1|ob12,1|ob13,1|ob14,1|ob15,1|ob-6,0|ob-5,0|ob-4,0|ob-3,0|ob-2,0|ob-1,0|ob0,0|ob1,0|ob2,0|ob3,0|ob4,0|ob5,0|ob6,0|ob7,0|ob8,0|ob9,0|ob10,0|ob11,0|ob12,0|ob13,0|ob14,0|ob15,0|ob-6,-1|ob-5,-1|ob-4,-1|ob-3,-1|ob-2,-1|ob-1,-1|ob0,-1|ob1,-1|ob2,-1|ob3,-1|ob4,-1|ob5,-1|ob6,-1|ob7,-1|ob8,-1|ob9,-1|ob10,-1|ob11,-1|ob12,-1|ob13,-1|ob14,-1|ob15,-1|ob-6,-2|ob-5,-2|ob-4,-2|ob-3,-2|ob-2,-2|ob-1,-2|ob0,-2|ob1,-2|ob2,-2|ob3,-2|ob4,-2|ob5,-2|ob6,-2|ob7,-2|ob8,-2|ob9,-2|ob10,-2|ob11,-2|ob12,-2|ob13,-2|ob14,-2|ob15,-2|ob-6,-3|ob-5,-3|ob-4,-3|ob-3,-3|ob-2,-3|ob-1,-3|ob0,-3|ob1,-3|ob2,-3|ob3,-3|ob4,-3|ob5,-3|ob6,-3|ob7,-3|ob8,-3|ob9,-3|ob10,-3|ob11,-3|ob12,-3|ob13,-3|ob14,-3|ob15,-3"
-            }
-            Variant::Chess => {
-                "w 0/100 1 (8|1) 1,8,1,8 P1,2+|P2,2+|P3,2+|P4,2+|P5,2+|P6,2+|P7,2+|P8,2+|p1,7+|p2,7+|p3,7+|p4,7+|p5,7+|p6,7+|p7,7+|p8,7+|R1,1+|R8,1+|r1,8+|r8,8+|N2,1|N7,1|n2,8|n7,8|B3,1|B6,1|b3,8|b6,8|Q4,1|q4,8|K5,1+|k5,8+"
-            }
-            Variant::ScatteredLeapers => {
-                "w 0/100 1 (8|1) P1,2+|P2,2+|P3,2+|P4,2+|P5,2+|P7,2+|P8,2+|p1,7+|p2,7+|p3,7+|p4,7+|p5,7+|p6,7+|p7,7+|R1,1+|r1,8+|r8,8+|B3,1|B6,1|b3,8|b6,8|GU2,1|gu2,8|K5,1+|k5,8+|gu7,8|P11,1+|P-2,1+|P-5,0+|P14,0+|p-2,8+|p-5,9+|p11,8+|p14,9+|nr4,9|NR4,0|CA9,-2|ca9,11|ca0,11|ze-3,12|ze12,12|ZE12,-3|GI-5,-6|GI14,-6|gi14,15|gi-5,15|ha-1,14|ha10,14|P6,2+|RO7,-6|p8,7+|ZE-3,-3|CA0,-2|GU7,1|R8,1+|HA10,-5|HA-1,-5|ro7,15"
-            }
-            Variant::DoubleKingClassical => {
-                "w 0/100 1 (8|1) allroyalscaptured,allroyalscaptured k5,8+|k4,8+|n2,8|n7,8|r1,8+|r8,8+|b3,8|b6,8|p1,7+|p2,7+|p3,7+|p4,7+|p5,7+|p6,7+|p7,7+|p8,7+|K5,1+|K4,1+|N2,1|N7,1|R1,1+|R8,1+|B3,1|B6,1|P1,2+|P2,2+|P3,2+|P4,2+|P5,2+|P6,2+|P7,2+|P8,2+"
-            }
-            Variant::DoubleKingChess => {
-                "w 0/100 1 (8|1) 1,8,1,8 k5,8+|k4,8+|n2,8|n7,8|r1,8+|r8,8+|b3,8|b6,8|p1,7+|p2,7+|p3,7+|p4,7+|p5,7+|p6,7+|p7,7+|p8,7+|K5,1+|K4,1+|N2,1|N7,1|R1,1+|R8,1+|B3,1|B6,1|P1,2+|P2,2+|P3,2+|P4,2+|P5,2+|P6,2+|P7,2+|P8,2+"
-            }
-            Variant::AllPiecesClassical => {
-                "w 0/100 1 (8|1) allpiecescaptured,allpiecescaptured P1,2+|P2,2+|P3,2+|P4,2+|P5,2+|P6,2+|P7,2+|P8,2+|p1,7+|p2,7+|p3,7+|p4,7+|p5,7+|p6,7+|p7,7+|p8,7+|R1,1+|R8,1+|r1,8+|r8,8+|N2,1|N7,1|n2,8|n7,8|B3,1|B6,1|b3,8|b6,8|Q4,1|q4,8|K5,1+|k5,8+"
-            }
-            Variant::TripleKingMaze => {
-                "w 0/200 1 (8|1) -8,18,-8,17 checkmate,allroyalscaptured vo0,0|vo1,0|vo2,0|vo3,0|vo4,0|vo5,0|vo10,0|vo0,9|vo10,1|vo10,2|vo10,9|vo9,9|vo6,9|vo8,9|vo7,9|vo0,8|vo0,7|vo0,4|vo0,3|vo10,6|vo10,5|vo3,9|vo3,6|vo3,5|vo3,4|vo3,3|vo7,5|vo7,4|vo7,3|vo4,9|vo5,9|vo6,0|vo7,0|vo-2,7|vo-1,7|vo11,2|vo12,2|vo-6,12|vo-6,11|vo-6,6|vo-6,5|vo-6,2|vo-6,-1|vo-6,-2|vo-6,-3|vo16,-3|vo16,-2|vo16,-1|vo16,2|vo16,4|vo16,7|vo16,10|vo16,11|vo16,12|vo-3,4|vo-3,3|vo-3,2|vo-3,1|vo-3,-3|vo-3,0|vo-3,7|vo-3,9|vo-3,8|vo13,2|vo13,1|vo13,0|vo13,5|vo13,6|vo13,7|vo13,8|vo13,9|vo0,-3|vo1,-3|vo2,-3|vo5,-3|vo8,12|vo9,12|vo10,12|vo-3,12|vo0,12|vo10,-3|vo13,-3|vo13,12|vo0,14|vo0,13|vo10,-4|vo10,-5|vo15,7|vo-5,2|vo-4,2|vo-3,-4|vo-3,-5|vo13,14|vo13,13|vo-6,10|vo16,3|vo-6,7|vo6,-3|vo7,-3|vo3,12|vo4,12|vo5,12|vo-8,-6|vo-7,-6|vo-6,-6|vo-3,-6|vo13,15|vo16,15|vo17,15|vo18,15|vo-2,-6|vo-1,-6|vo0,-6|vo1,-6|vo2,-6|vo3,-6|vo7,-6|vo8,-6|vo9,-6|vo10,-6|vo0,15|vo1,15|vo2,15|vo3,15|vo7,15|vo8,15|vo9,15|vo10,15|vo11,15|vo12,15|vo11,-6|vo12,-6|vo13,-6|vo16,-6|vo17,-6|vo18,-6|vo-3,15|vo-2,15|vo-1,15|vo-8,15|vo-7,15|vo-6,15|vo7,6|vo14,7|k5,7|k-8,17|k18,17|q5,17|n14,14|n-4,14|n14,8|n0,11|n0,10|n10,11|n10,10|r-8,14|r-7,13|r-2,8|r-1,8|r17,13|r18,14|r5,16|b5,6|b4,13|b6,13|b-7,17|b-7,16|b17,17|b17,16|b7,7|b3,7|p4,8+|p5,8+|p6,8+|p15,6+|p-5,8+|p1,9+|p2,9+|p8,8+|p9,8+|p-8,8+|p-7,8+|p17,4+|p18,4+|K5,2|K-8,-8|K18,-8|Q5,-8|N14,-5|N-4,-5|N-4,1|N10,-1|N10,-2|N0,-1|N0,-2|R17,-4|R11,1|R12,1|R18,-5|R-8,-5|R-7,-4|R5,-7|B5,3|B4,-4|B6,-4|B5,-2|B-7,-7|B-7,-8|B17,-7|B17,-8|B7,2|B3,2|P4,1+|P5,1+|P6,1+|P15,1+|P-5,3+|P8,0+|P9,0+|P1,1+|P2,1+|P17,1+|P18,1+|P-8,5+|P-7,5+|b5,11"
-            }
-        }
-    }
-
-    pub fn to_str(&self) -> &'static str {
-        match self {
-            Variant::Classical => "Classical",
-            Variant::ConfinedClassical => "Confined_Classical",
-            Variant::ClassicalPlus => "Classical_Plus",
-            Variant::CoaIP => "CoaIP",
-            Variant::CoaIPHO => "CoaIP_HO",
-            Variant::CoaIPRO => "CoaIP_RO",
-            Variant::CoaIPNO => "CoaIP_NO",
-            Variant::Palace => "Palace",
-            Variant::Pawndard => "Pawndard",
-            Variant::Core => "Core",
-            Variant::Standarch => "Standarch",
-            Variant::SpaceClassic => "Space_Classic",
-            Variant::Space => "Space",
-            Variant::Abundance => "Abundance",
-            Variant::PawnHorde => "Pawn_Horde",
-            Variant::Knightline => "Knightline",
-            Variant::Obstocean => "Obstocean",
-            Variant::Chess => "Chess",
-            Variant::ScatteredLeapers => "Scattered_Leapers",
-            Variant::DoubleKingClassical => "Double_King_Classical",
-            Variant::DoubleKingChess => "Double_King_Chess",
-            Variant::TripleKingMaze => "Triple_King_Maze",
-            Variant::AllPiecesClassical => "All_Pieces_Classical",
-        }
-    }
-
-    pub fn parse(s: &str) -> Self {
-        let normalized = s.to_lowercase().replace(' ', "_");
-        match normalized.as_str() {
-            "classical" => Variant::Classical,
-            "confined_classical" => Variant::ConfinedClassical,
-            "classical_plus" | "classical+" => Variant::ClassicalPlus,
-            "coaip" | "chess_on_an_infinite_plane" => Variant::CoaIP,
-            "coaip_ho" | "chess_on_an_infinite_plane_-_huygens_option" => Variant::CoaIPHO,
-            "coaip_ro" | "chess_on_an_infinite_plane_-_roses_option" => Variant::CoaIPRO,
-            "coaip_no" | "chess_on_an_infinite_plane_-_knightriders_option" => Variant::CoaIPNO,
-            "palace" => Variant::Palace,
-            "pawndard" => Variant::Pawndard,
-            "core" => Variant::Core,
-            "standarch" => Variant::Standarch,
-            "space_classic" => Variant::SpaceClassic,
-            "space" => Variant::Space,
-            "abundance" => Variant::Abundance,
-            "pawn_horde" => Variant::PawnHorde,
-            "knightline" => Variant::Knightline,
-            "obstocean" => Variant::Obstocean,
-            "chess" => Variant::Chess,
-            "scattered_leapers" => Variant::ScatteredLeapers,
-            "double_king_classical" => Variant::DoubleKingClassical,
-            "double_king_chess" => Variant::DoubleKingChess,
-            "triple_king_maze" => Variant::TripleKingMaze,
-            "all_pieces_classical" => Variant::AllPiecesClassical,
-            _ => Variant::Classical, // Default fallback
-        }
-    }
-
-    pub fn get_default_bounds(&self) -> (i64, i64, i64, i64) {
-        match self {
-            Variant::Chess => (1, 8, 1, 8),
-            Variant::Obstocean => (-6, 15, -3, 12),
-            Variant::DoubleKingChess => (1, 8, 1, 8),
-            Variant::TripleKingMaze => (-8, 18, -8, 17),
-            _ => (
-                -1_000_000_000_000_000,
-                1_000_000_000_000_000,
-                -1_000_000_000_000_000,
-                1_000_000_000_000_000,
-            ),
-        }
-    }
-}
-
-impl std::str::FromStr for Variant {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Variant::parse(s))
-    }
-}
+pub use variant::Variant;
 
 #[wasm_bindgen]
 extern "C" {
@@ -220,6 +36,9 @@ extern "C" {
     pub fn groupEnd();
 }
 
+/// Clears the worker-wide searcher and transposition table, which every engine searches
+/// on unless it was created with `own_search` (those clear their own tables with
+/// [`Engine::reset_search`]).
 #[wasm_bindgen]
 pub fn reset_engine_state() {
     #[cfg(feature = "multithreading")]
@@ -242,9 +61,12 @@ pub fn stop_analysis_helpers() {
 /// thread can abort an in-flight search instantly by writing a non-zero byte at
 /// this address (`new Uint8Array(memory.buffer)[ptr] = 1`). The search polls the
 /// flag every node batch. Each new search clears it.
+///
+/// This is the worker-wide flag, which stops every engine except those created with
+/// `own_search`; they have their own flag at [`Engine::stop_flag_ptr`].
 #[wasm_bindgen]
 pub fn stop_flag_ptr() -> u32 {
-    &crate::search::GLOBAL_STOP as *const _ as u32
+    crate::search::global_context().stop_flag_ptr() as u32
 }
 
 /// Sets the transposition table size in MB (clamped to 1..=64).
@@ -255,6 +77,46 @@ pub fn set_hash_size(mb: u32) {
     crate::search::set_tt_size_mb(mb as usize);
 }
 
+/// Loads an endgame table written by `tb_gen` (see `Tablebase::from_bytes`). Every search
+/// then scores positions the table covers with their exact distance to mate.
+#[wasm_bindgen]
+pub fn load_tablebase(data: &[u8]) -> Result<(), JsValue> {
+    let table = tablebase::Tablebase::from_bytes(data).map_err(js_sys::Error::new)?;
+    tablebase::register(table);
+    Ok(())
+}
+
+/// Unloads every table loaded with [`load_tablebase`].
+#[wasm_bindgen]
+pub fn clear_tablebases() {
+    tablebase::clear();
+}
+
+/// Loads per-variant evaluation parameter profiles from JSON (see `evaluation::profiles`
+/// for the format), replacing any loaded earlier. Each position set up afterwards plays
+/// with the profile for its variant.
+#[wasm_bindgen]
+pub fn load_eval_profiles(json: &str) -> Result<(), JsValue> {
+    let profiles = evaluation::EvalProfiles::from_json(json).map_err(|e| js_sys::Error::new(&e))?;
+    evaluation::profiles::load(profiles);
+    Ok(())
+}
+
+/// Unloads the profiles loaded with [`load_eval_profiles`]. Positions set up afterwards
+/// play with the built-in evaluation parameters.
+#[wasm_bindgen]
+pub fn clear_eval_profiles() {
+    evaluation::profiles::clear();
+}
+
+/// Registers the variants of a JSON definition file (see `variant` for the format), all
+/// or none. Positions can then name them in the `[Variant "..."]` tag.
+#[wasm_bindgen]
+pub fn register_variants(json: &str) -> Result<(), JsValue> {
+    variant::load_json(json).map_err(|e| js_sys::Error::new(&e))?;
+    Ok(())
+}
+
 /// Returns the engine's version — read from the `version` field
 /// in Cargo.toml at compile time via Cargo's `CARGO_PKG_VERSION`.
 #[wasm_bindgen]
@@ -282,6 +144,14 @@ pub struct JsMoveWithEval {
     pub depth: usize, // depth reached
 }
 
+/// Finished-game summary returned by `Engine::game_outcome`.
+#[derive(Serialize, Deserialize)]
+pub struct JsGameOutcome {
+    pub winner: Option<String>, // "w" | "b", null for a draw
+    pub reason: String,         // e.g. "checkmate", "stalemate", "threefold repetition"
+    pub result: String,         // "1-0" | "0-1" | "1/2-1/2"
+}
+
 #[cfg(any(feature = "param_tuning", feature = "eval_tuning"))]
 #[derive(Serialize)]
 pub struct JsEvalWithFeatures {
@@ -307,6 +177,33 @@ pub struct JsEngineConfig {
     pub btime: Option<u64>,
     pub winc: Option<u64>,
     pub binc: Option<u64>,
+    /// Moves until the next time control (`movestogo`), if the clock has one.
+    pub moves_to_go: Option<u32>,
+    /// Per-move allowance for communication latency, in ms (defaults to 50).
+    pub move_overhead: Option<u64>,
+    /// Give this engine its own searcher, transposition table and stop flag instead of
+    /// the worker-wide ones, so several engines in one worker don't share tables. The
+    /// global [`reset_engine_state`] and [`stop_flag_ptr`] don't reach such an engine
+    /// (use [`Engine::reset_search`] and [`Engine::stop_flag_ptr`]), and it only stays
+    /// warm across moves if it is kept alive (see `set_position`).
+    pub own_search: Option<bool>,
+    /// Piece values for this game keyed by ICN piece code (`{ "RO": 650, "NR": 720 }`),
+    /// replacing the engine's own and any `[PieceValues "..."]` tag in the ICN.
+    pub piece_values: Option<std::collections::HashMap<String, i32>>,
+    /// Hill squares for the `koth` win condition as `"x,y;x,y"`, replacing any
+    /// `koth(...)` hill in the ICN.
+    pub koth_squares: Option<String>,
+}
+
+/// Options for [`Engine::set_search_limits`].
+#[derive(Deserialize)]
+pub struct JsSearchLimits {
+    /// Hard node budget per search.
+    pub nodes: Option<u64>,
+    /// Stop once a mate in at most this many moves is found.
+    pub mate: Option<u32>,
+    /// Only search these root moves, as site tokens legal in the current position.
+    pub search_moves: Option<Vec<String>>,
 }
 
 /// Options for [`Engine::analyse`].
@@ -354,7 +251,7 @@ pub struct JsAnalysisInfo {
 
 /// Formats a move as the site's compact ICN token, casing the promotion
 /// abbreviation by the mover's color (white uppercase, black lowercase).
-fn move_to_site_token(m: &crate::moves::Move) -> String {
+pub fn move_to_site_token(m: &crate::moves::Move) -> String {
     let mut token = format!("{},{}>{},{}", m.from.x, m.from.y, m.to.x, m.to.y);
     if let Some(promotion) = m.promotion {
         let code = promotion.to_site_code();
@@ -408,6 +305,8 @@ pub struct JsClock {
     pub btime: u64,
     pub winc: u64,
     pub binc: u64,
+    /// Moves until the next time control; `None` for sudden death / increment.
+    pub moves_to_go: Option<u32>,
 }
 
 #[cfg(target_arch = "wasm32")]
@@ -425,11 +324,43 @@ fn get_random_seed() -> u64 {
     duration.as_nanos() as u64
 }
 
+/// Converts an ICN parse failure into a JS `Error` whose message is the full
+/// description, with `token`, `offset` and `reason` attached as properties.
+fn icn_error_to_js(err: &game::IcnError) -> JsValue {
+    let js_err = js_sys::Error::new(&err.to_string());
+    let _ = js_sys::Reflect::set(&js_err, &"token".into(), &err.token.as_str().into());
+    let _ = js_sys::Reflect::set(&js_err, &"offset".into(), &(err.offset as f64).into());
+    let _ = js_sys::Reflect::set(&js_err, &"reason".into(), &err.kind.to_string().into());
+    js_err.into()
+}
+
+/// A position plus the search state used to play it.
+///
+/// Engines from [`Engine::from_icn`] search on the worker-wide searcher, transposition
+/// table and stop flag unless created with `own_search`, so [`reset_engine_state`] and
+/// [`stop_flag_ptr`] keep working for them. Engines with `own_search`, and native
+/// engines, own that state, so several engines can share a module without touching each
+/// other's tables; use [`Engine::reset_search`] and [`Engine::stop_flag_ptr`] for them.
 #[wasm_bindgen]
 pub struct Engine {
     game: GameState,
     clock: Option<JsClock>,
     strength_level: Option<u32>,
+    /// Searcher, TT and stop flag owned by this engine; `None` uses the thread's global ones.
+    search: Option<search::SearchInstance>,
+    /// Moves played with [`Engine::make_move`] since the position was set, with their undo info.
+    history: Vec<(crate::moves::Move, game::UndoMove)>,
+    /// Per-move allowance for communication latency, taken off every clocked allocation.
+    move_overhead_ms: u64,
+    /// Node budget, mate target and root moves applied to every search until changed.
+    limits: search::SearchLimits,
+    /// Opening book played from at the root while the position is in it.
+    book: Option<std::sync::Arc<search::Book>>,
+    /// Piece values from the engine config, applied to every position set.
+    piece_values: game::PieceValues,
+    /// Hill squares from the engine config or [`Engine::set_koth_squares`], applied
+    /// to every position set.
+    koth_squares: Option<Vec<board::Coordinate>>,
 }
 
 #[wasm_bindgen]
@@ -438,8 +369,27 @@ impl Engine {
     pub fn from_icn(icn_string: &str, config: JsValue) -> Result<Engine, JsValue> {
         let options: JsEngineConfig = serde_wasm_bindgen::from_value(config)?;
 
-        let mut game = GameState::new();
-        game.setup_position_from_icn(icn_string);
+        let mut game = GameState::try_from_icn(icn_string).map_err(|e| icn_error_to_js(&e))?;
+
+        let mut piece_values = game::PieceValues::default();
+        for (code, value) in options.piece_values.unwrap_or_default() {
+            let pt = board::PieceType::parse_promotion_code(&code)
+                .ok_or_else(|| js_sys::Error::new(&format!("unknown piece code: {}", code)))?;
+            piece_values.set(pt, value);
+        }
+        if !piece_values.is_empty() {
+            game.apply_piece_values(&piece_values);
+        }
+
+        let koth_squares = match options.koth_squares {
+            Some(squares) => Some(game::parse_koth_squares(&squares).ok_or_else(|| {
+                js_sys::Error::new(&format!("invalid koth squares: {}", squares))
+            })?),
+            None => None,
+        };
+        if let Some(squares) = &koth_squares {
+            game.set_koth_squares(squares.clone());
+        }
 
         let strength_level = options.strength_level;
 
@@ -449,17 +399,51 @@ impl Engine {
                 btime,
                 winc: options.winc.unwrap_or(0),
                 binc: options.binc.unwrap_or(0),
+                moves_to_go: options.moves_to_go,
             })
         } else {
             None
         };
 
+        let search = options.own_search.unwrap_or(false).then(search::SearchInstance::new);
+
         Ok(Engine {
             game,
             clock,
             strength_level,
+            search,
+            history: Vec::new(),
+            move_overhead_ms: options
+                .move_overhead
+                .unwrap_or(search::time::DEFAULT_MOVE_OVERHEAD_MS),
+            limits: search::SearchLimits::default(),
+            book: None,
+            piece_values,
+            koth_squares,
         })
     }
+
+    /// Byte offset of this engine's stop flag inside wasm linear memory (the global
+    /// [`stop_flag_ptr`] unless the engine was created with `own_search`).
+    pub fn stop_flag_ptr(&self) -> u32 {
+        match &self.search {
+            Some(search) => search.context().stop_flag_ptr() as u32,
+            None => stop_flag_ptr(),
+        }
+    }
+
+    /// Clears this engine's searcher and transposition table for a new game.
+    /// Engines without `own_search` reset the worker-wide state, like [`reset_engine_state`].
+    pub fn reset_search(&mut self) {
+        match self.search.as_mut() {
+            Some(search) => {
+                #[cfg(feature = "multithreading")]
+                search.context().stop_analysis_helpers();
+                search.reset();
+            }
+            None => reset_engine_state(),
+        }
+    }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -471,6 +455,13 @@ impl Engine {
             game,
             clock: None,
             strength_level: None,
+            search: Some(search::SearchInstance::new()),
+            history: Vec::new(),
+            move_overhead_ms: search::time::DEFAULT_MOVE_OVERHEAD_MS,
+            limits: search::SearchLimits::default(),
+            book: None,
+            piece_values: game::PieceValues::default(),
+            koth_squares: None,
         }
     }
 
@@ -481,18 +472,73 @@ impl Engine {
             game,
             clock: None,
             strength_level,
+            search: Some(search::SearchInstance::new()),
+            history: Vec::new(),
+            move_overhead_ms: search::time::DEFAULT_MOVE_OVERHEAD_MS,
+            limits: search::SearchLimits::default(),
+            book: None,
+            piece_values: game::PieceValues::default(),
+            koth_squares: None,
         }
     }
 
+    /// Makes this engine search on the process-wide searcher, transposition table and
+    /// stop flag instead of its own, like engines created without `own_search`.
+    pub fn with_shared_search(mut self) -> Engine {
+        self.search = None;
+        self
+    }
+
+    /// This engine's own search state, unless it was switched to the shared one with
+    /// [`with_shared_search`](Self::with_shared_search).
+    pub fn search_instance(&self) -> Option<&search::SearchInstance> {
+        self.search.as_ref()
+    }
+
     pub fn set_clock(&mut self, wtime: u64, btime: u64, winc: u64, binc: u64) {
         self.clock = Some(JsClock {
             wtime,
             btime,
             winc,
             binc,
+            moves_to_go: None,
         });
     }
 
+    /// Strength level for the following searches; `None` plays at full strength.
+    pub fn set_strength_level(&mut self, strength_level: Option<u32>) {
+        self.strength_level = strength_level;
+    }
+
+    /// Moves until the next time control (`movestogo`) for the clock set by
+    /// [`Engine::set_clock`]; `None` for sudden death and increment controls.
+    pub fn set_moves_to_go(&mut self, moves_to_go: Option<u32>) {
+        if let Some(clock) = self.clock.as_mut() {
+            clock.moves_to_go = moves_to_go;
+        }
+    }
+
+    /// Native counterpart of [`Engine::set_position`], returning the parse error.
+    pub fn set_position_native(&mut self, icn_string: &str) -> Result<(), game::IcnError> {
+        self.replace_position(icn_string)
+    }
+
+    /// Native counterpart of [`Engine::load_book`]; `None` disables the book.
+    pub fn set_book_native(&mut self, book: Option<std::sync::Arc<search::Book>>) {
+        self.book = book;
+    }
+
+    /// Per-move allowance for communication latency, in ms.
+    pub fn set_move_overhead(&mut self, ms: u64) {
+        self.move_overhead_ms = ms;
+    }
+
+    /// Native counterpart of [`Engine::set_search_limits`]; `search_moves` must be
+    /// moves of the current position.
+    pub fn set_search_limits_native(&mut self, limits: search::SearchLimits) {
+        self.limits = limits;
+    }
+
     pub fn game_mut(&mut self) -> &mut GameState {
         &mut self.game
     }
@@ -513,50 +559,64 @@ impl Engine {
             };
         let depth = max_depth.unwrap_or(50).clamp(1, 100);
         let strength = self.strength_level;
+        let limits = self.limits.clone();
 
         let effective_seed = seed.unwrap_or_else(get_random_seed);
-        crate::search::set_global_params(effective_seed, noise_amp);
 
-        if strength.is_some_and(|s| s < 3) {
-            crate::search::get_best_move_limited(
-                &mut self.game,
-                depth,
-                opt_time,
-                max_time,
-                strength,
-                silent,
-                is_soft_limit,
-            )
-        } else {
-            crate::search::get_best_move_parallel(
-                &mut self.game,
-                depth,
-                opt_time,
-                max_time,
-                silent,
-                is_soft_limit,
-            )
-        }
+        self.with_search(|search, game| {
+            search.set_params(effective_seed, noise_amp);
+
+            if strength.is_some_and(|s| s < 3) {
+                search.get_best_move_limited(
+                    game,
+                    depth,
+                    opt_time,
+                    max_time,
+                    strength,
+                    silent,
+                    is_soft_limit,
+                )
+            } else if !limits.is_unlimited() {
+                search.get_best_move_with_limits(
+                    game,
+                    depth,
+                    opt_time,
+                    max_time,
+                    &limits,
+                    silent,
+                    is_soft_limit,
+                )
+            } else {
+                search.get_best_move_parallel(
+                    game,
+                    depth,
+                    opt_time,
+                    max_time,
+                    silent,
+                    is_soft_limit,
+                )
+            }
+        })
     }
 
     pub fn current_pv_native(&mut self, depth: usize) -> String {
-        crate::search::GLOBAL_SEARCHER.with(|cell| {
-            let opt = cell.borrow();
-            if let Some(searcher) = opt.as_ref() {
-                searcher.format_pv(&mut self.game, depth)
-            } else {
-                String::new()
-            }
+        self.with_search(|search, game| {
+            search
+                .searcher()
+                .map_or_else(String::new, |searcher| searcher.format_pv(game, depth))
         })
     }
 }
 
 #[wasm_bindgen]
 impl Engine {
+    /// Searches the position to depth 50 on this engine's search state (the worker-wide
+    /// one unless created with `own_search`) and returns the best move, or null if there
+    /// is none.
     pub fn get_best_move(&mut self) -> JsValue {
-        if let Some((best_move, _eval, _stats)) =
-            search::get_best_move(&mut self.game, 50, u128::MAX, false, true)
-        {
+        if let Some((best_move, _eval, _stats)) = self.with_search(|search, game| {
+            search.get_best_move_parallel(game, 50, u128::MAX, u128::MAX, false, true)
+        }) {
             let js_move = JsMove {
                 from: format!("{},{}", best_move.from.x, best_move.from.y),
                 to: format!("{},{}", best_move.to.x, best_move.to.y),
@@ -568,6 +628,13 @@ impl Engine {
         }
     }
 
+    /// The current position's evaluation broken down by term and pipeline step (see
+    /// `evaluation::EvalExplanation`), as a plain object.
+    pub fn explain_eval(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&crate::evaluation::explain(&self.game))
+            .unwrap_or(JsValue::NULL)
+    }
+
     #[cfg(any(feature = "param_tuning", feature = "eval_tuning"))]
     #[wasm_bindgen]
     pub fn evaluate_with_features(&mut self) -> JsValue {
@@ -610,130 +677,38 @@ impl Engine {
 
     /// Derive an effective time limit for this move from the current clock and game state.
     fn effective_time_limit_ms(&self, requested_limit_ms: u32) -> (u128, u128, bool) {
-        let Some(clock) = self.clock else {
-            // No clock info: use the fixed per-move limit as a soft limit.
-            // The search can use up to this time freely without flagging risk.
-            let limit = requested_limit_ms as u128;
-            return (limit, limit, true);
-        };
+        use search::time::{TimeControl, TimeLimits, TimeManager, game_ply};
 
-        // Decide which side's clock to use.
-        let (remaining_ms_raw, inc_ms_raw) = match self.game.turn {
-            PlayerColor::White => (clock.wtime, clock.winc),
-            PlayerColor::Black => (clock.btime, clock.binc),
-            // Neutral side-to-move should not happen; fall back to
-            // the requested limit as soft.
-            PlayerColor::Neutral => {
-                let limit = requested_limit_ms as u128;
-                return (limit, limit, true);
-            }
+        // No clock info, a neutral side to move or an empty clock: use the fixed per-move
+        // limit as a soft limit. The search can use up to this time freely without
+        // flagging risk.
+        let fallback = TimeLimits::fixed(requested_limit_ms as u128).as_tuple();
+        let Some(clock) = self.clock else {
+            return fallback;
         };
-
-        // If there is no usable clock information, use requested limit as soft.
-        if remaining_ms_raw == 0 && inc_ms_raw == 0 {
-            let limit = requested_limit_ms as u128;
-            return (limit, limit, true);
-        }
-
-        // Handle zero remaining time with positive increment (increment-only)
-        let remaining_ms = if remaining_ms_raw > 0 {
-            remaining_ms_raw
-        } else {
-            // At least give ourselves a small buffer based on increment.
-            inc_ms_raw.max(500)
+        let Some(tc) = TimeControl::for_side(
+            self.game.turn,
+            clock.wtime,
+            clock.btime,
+            clock.winc,
+            clock.binc,
+            clock.moves_to_go,
+        ) else {
+            return fallback;
         };
-
-        let inc_ms = inc_ms_raw;
-
-        // Dynamic Time Allocation
-        // Calculates optimal and maximum thinking time based on remaining budget.
-
-        // Move overhead for communication latency
-        let move_overhead: u64 = 50;
-
-        // Calculate scaled time (remaining time minus one move overhead)
-        let scaled_time = remaining_ms.saturating_sub(move_overhead);
-
-        // Maximum move horizon (centiMTG = moves to go * 100)
-        // For games without movestogo, assume ~50 moves remaining
-        // If less than 1 second, gradually reduce moves to go estimate
-        let centi_mtg: i64 = if scaled_time >= 1000 {
-            5051 // 50.51 moves * 100
-        } else {
-            ((scaled_time as f64) * 5.051) as i64
+        let Some(mut limits) =
+            TimeManager::allocate(&tc, game_ply(&self.game), self.move_overhead_ms)
+        else {
+            return fallback;
         };
-        let centi_mtg = centi_mtg.max(100); // At least 1 move expected
-
-        // timeLeft: total time we can use considering increment and overhead
-        // Formula: remaining + inc * (MTG - 1) - overhead * (2 + MTG)
-        // Division by 100 because centiMTG is in centimoves
-        let time_left = (remaining_ms as i64
-            + (inc_ms as i64 * (centi_mtg - 100) - move_overhead as i64 * (200 + centi_mtg)) / 100)
-            .max(1) as f64;
-
-        // original_time_adjust: logarithmic scaling factor based on available time
-        // This factor prevents overspending at longer time controls by adjusting the
-        // base allocation according to the total time budget.
-        // For 10s games: log10(10000) = 4, adjust = 0.3128*4 - 0.4354 = 0.816
-        // For 40m games: log10(2400000) = 6.38, adjust = 1.56
-        let original_time_adjust = (0.3128 * time_left.max(1.0).log10() - 0.4354).max(0.1);
-
-        // Log-based time constants for adaptive scaling
-        let log_time_sec = (scaled_time as f64 / 1000.0).max(0.001).log10();
-
-        // Optimum and maximum time constants
-        let opt_constant = (0.0032116 + 0.000321123 * log_time_sec).min(0.00508017);
-        let max_constant = (3.3977 + 3.0395 * log_time_sec).max(2.94761);
-
-        // Game ply estimation based on fullmove number
-        // In chess, typically ply = (fullmove_number - 1) * 2 + (is_black ? 1 : 0)
-        let ply = self
-            .game
-            .fullmove_number
-            .saturating_sub(1)
-            .saturating_mul(2) as f64
-            + if self.game.turn == PlayerColor::Black {
-                1.0
-            } else {
-                0.0
-            };
-
-        // optScale: percentage of timeLeft to use for this move
-        // Multiply by originalTimeAdjust to prevent overspending
-        let opt_scale = ((0.0121431 + (ply + 2.94693_f64).powf(0.461073) * opt_constant)
-            .min(0.213035 * remaining_ms as f64 / time_left))
-            * original_time_adjust;
-
-        // maxScale: multiplier from optimum to maximum
-        let max_scale = max_constant.min(6.67704) + ply / 11.9847;
-
-        // Calculate optimum and maximum allocations
-        let optimum = (opt_scale * time_left) as u64;
-        let maximum = ((max_scale * optimum as f64)
-            .min(0.825179 * remaining_ms as f64 - move_overhead as f64)
-            - 10.0)
-            .max(0.0) as u64;
 
         // Special case: opening (first move) - limit to 5 seconds
-        let mut maximum = maximum;
-        let mut optimum = optimum;
         if self.game.fullmove_number == 1 && self.game.variant.is_some() {
-            maximum = maximum.min(5000);
-            optimum = optimum.min(5000);
+            limits.optimum_ms = limits.optimum_ms.min(5000);
+            limits.maximum_ms = limits.maximum_ms.min(5000);
         }
 
-        // Apply sensible bounds
-        let min_think_ms: u64 = 10;
-        let optimum = optimum.max(min_think_ms);
-        let maximum = maximum.max(optimum); // Maximum should never be less than optimum
-
-        // Final safety cap: never exceed 82.5% of remaining time
-        let absolute_cap = ((remaining_ms as f64) * 0.825 - move_overhead as f64) as u64;
-        let optimum = optimum.min(absolute_cap.max(min_think_ms));
-        let maximum = maximum.min(absolute_cap.max(min_think_ms));
-
-        // Timed games are hard limits (risk of flagging).
-        (optimum as u128, maximum as u128, false)
+        limits.as_tuple()
     }
 
     /// Timed search. This also exposes the search evaluation as an `eval` field alongside the move,
@@ -760,10 +735,11 @@ impl Engine {
         let silent = silent.unwrap_or(false);
         let depth = max_depth.unwrap_or(50).clamp(1, 50);
         let strength = self.strength_level;
+        let limits = self.limits.clone();
 
         #[cfg(target_arch = "wasm32")]
         {
-            let pre_stats = crate::search::get_current_tt_stats();
+            let pre_stats = self.with_search(|search, _| search.tt_stats());
             if !silent {
                 use crate::log;
                 let variant = self
@@ -806,38 +782,47 @@ impl Engine {
 
         // Initialize randomness with global seed logic
         let effective_seed = seed.unwrap_or_else(get_random_seed);
-        search::set_global_params(effective_seed, noise_amp);
-
-        // Choose search path based on strength level.
-        let (best_move, eval) = if strength.is_some_and(|s| s < 3) {
-            // Use strength limited search (uses global seed we just set)
-            if let Some((bm, ev, _stats)) = search::get_best_move_limited(
-                &mut self.game,
-                depth,
-                opt_time,
-                max_time,
-                strength,
-                silent,
-                is_soft_limit,
-            ) {
-                (bm, ev)
-            } else {
-                return JsValue::NULL;
-            }
-        } else {
-            // Normal search: use parallel version (handles both single and multi-threaded)
-            if let Some((bm, ev, _stats)) = search::get_best_move_parallel(
-                &mut self.game,
-                depth,
-                opt_time,
-                max_time,
-                silent,
-                is_soft_limit,
-            ) {
-                (bm, ev)
+
+        let result = self.with_search(|search, game| {
+            search.set_params(effective_seed, noise_amp);
+
+            // Choose search path based on strength level.
+            if strength.is_some_and(|s| s < 3) {
+                // Use strength limited search (uses the seed we just set)
+                search.get_best_move_limited(
+                    game,
+                    depth,
+                    opt_time,
+                    max_time,
+                    strength,
+                    silent,
+                    is_soft_limit,
+                )
+            } else if !limits.is_unlimited() {
+                // Node/mate/searchmoves limits: single-threaded so the budget is exact
+                search.get_best_move_with_limits(
+                    game,
+                    depth,
+                    opt_time,
+                    max_time,
+                    &limits,
+                    silent,
+                    is_soft_limit,
+                )
             } else {
-                return JsValue::NULL;
+                // Normal search: use parallel version (handles both single and multi-threaded)
+                search.get_best_move_parallel(
+                    game,
+                    depth,
+                    opt_time,
+                    max_time,
+                    silent,
+                    is_soft_limit,
+                )
             }
+        });
+        let Some((best_move, eval, _stats)) = result else {
+            return JsValue::NULL;
         };
 
         let js_move = JsMoveWithEval {
@@ -862,15 +847,17 @@ impl Engine {
         let silent = silent.unwrap_or(false);
         let multi_pv = multi_pv.unwrap_or(1).max(1);
 
-        let result = search::get_best_moves_multipv(
-            &mut self.game,
-            50,
-            opt_time,
-            max_time,
-            multi_pv,
-            silent,
-            is_soft_limit,
-        );
+        let result = self.with_search(|search, game| {
+            search.get_best_moves_multipv(
+                game,
+                50,
+                opt_time,
+                max_time,
+                multi_pv,
+                silent,
+                is_soft_limit,
+            )
+        });
 
         // Convert to JS-friendly format
         let js_lines: Vec<JsPVLine> = result
@@ -908,11 +895,94 @@ impl Engine {
     }
 
     /// Replaces the engine's position from an ICN string, keeping the persistent
-    /// searcher (and its transposition table) warm.
-    pub fn set_position(&mut self, icn_string: &str) {
-        let mut game = GameState::new();
-        game.setup_position_from_icn(icn_string);
-        self.game = game;
+    /// searcher (and its transposition table) warm. Throws the same error as
+    /// [`Engine::from_icn`] if the ICN is malformed, leaving the position unchanged.
+    pub fn set_position(&mut self, icn_string: &str) -> Result<(), JsValue> {
+        self.replace_position(icn_string)
+            .map_err(|e| icn_error_to_js(&e))
+    }
+
+    /// Sets the hill squares of the `koth` win condition from `"x,y;x,y"`, for this
+    /// position and every one set after it. Returns false if the squares don't parse.
+    pub fn set_koth_squares(&mut self, squares: &str) -> bool {
+        let Some(squares) = game::parse_koth_squares(squares) else {
+            return false;
+        };
+        self.game.set_koth_squares(squares.clone());
+        self.koth_squares = Some(squares);
+        true
+    }
+
+    /// Sets the node budget, mate target and root moves for the following searches
+    /// (UCI `go nodes` / `mate` / `searchmoves`). Absent fields are cleared. Root moves are
+    /// site tokens of the current position and are dropped whenever the position changes.
+    pub fn set_search_limits(&mut self, options: JsValue) -> Result<(), JsValue> {
+        let options: JsSearchLimits = serde_wasm_bindgen::from_value(options)?;
+        let mut search_moves = Vec::new();
+        for token in options.search_moves.unwrap_or_default() {
+            match self.game.find_site_move(&token) {
+                Some(m) => search_moves.push(m),
+                None => {
+                    return Err(js_sys::Error::new(&format!("illegal search move: {token}")).into());
+                }
+            }
+        }
+        self.limits = search::SearchLimits {
+            nodes: options.nodes,
+            mate: options.mate,
+            search_moves,
+        };
+        Ok(())
+    }
+
+    /// Loads an opening book built by `book_gen` (see `Book::from_bytes`). Searches from
+    /// `get_best_move` / `get_best_move_with_time` then play a weighted book move
+    /// instantly while the position is in book.
+    pub fn load_book(&mut self, data: &[u8]) -> Result<(), JsValue> {
+        let book = search::Book::from_bytes(data).map_err(js_sys::Error::new)?;
+        self.book = Some(std::sync::Arc::new(book));
+        Ok(())
+    }
+
+    /// Stops playing from the book loaded with [`Engine::load_book`].
+    pub fn clear_book(&mut self) {
+        self.book = None;
+    }
+
+    /// Plays a move given as a site token (`"1,2>1,4"`, `"5,7>5,8=Q"`) if it is legal in
+    /// the current position, keeping the game history that repetition detection needs.
+    /// Returns false and leaves the position unchanged otherwise.
+    pub fn make_move(&mut self, token: &str) -> bool {
+        match self.game.find_site_move(token) {
+            Some(m) => {
+                let undo = self.game.make_move(&m);
+                self.history.push((m, undo));
+                self.limits.search_moves.clear();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Takes back the last move played with [`Engine::make_move`]. Returns false if there
+    /// is nothing to take back.
+    pub fn undo_move(&mut self) -> bool {
+        match self.history.pop() {
+            Some((m, undo)) => {
+                self.game.undo_move(&m, undo);
+                self.limits.search_moves.clear();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The moves played with [`Engine::make_move`] since the position was set, as site tokens.
+    pub fn move_history(&self) -> Vec<String> {
+        self.history
+            .iter()
+            .map(|(m, _)| move_to_site_token(m))
+            .collect()
     }
 
     /// Runs one time-sliced MultiPV analysis of the current position.
@@ -920,7 +990,7 @@ impl Engine {
     /// Invokes `on_info` with a `JsAnalysisInfo` after every completed depth, and
     /// returns the final `JsAnalysisInfo` for the slice (lines empty if the position
     /// is terminal). Issue repeated calls to deepen; abort mid-slice by writing to
-    /// [`stop_flag_ptr`] (shared-memory builds only).
+    /// [`Engine::stop_flag_ptr`] (shared-memory builds only).
     pub fn analyse(&mut self, options: JsValue, on_info: js_sys::Function) -> JsValue {
         let options: JsAnalyseOptions = match serde_wasm_bindgen::from_value(options) {
             Ok(o) => o,
@@ -954,44 +1024,32 @@ impl Engine {
             // Helpers = pool size - 1, capped at 3: the site exposes up to 4 analysis threads.
             let num_threads = rayon::current_num_threads().max(1).min(4);
             if num_threads > 1 {
-                search::init_shared_tt();
-                search::USE_SHARED_TT.store(true, std::sync::atomic::Ordering::Relaxed);
-
-                // New position, or a resume with no batch alive (e.g. "go deeper" after the
-                // helpers retired at done): retire any previous batch, launch a fresh one.
-                if start_depth <= 1
-                    || search::HELPERS_LIVE.load(std::sync::atomic::Ordering::Relaxed) == 0
-                {
-                    let epoch = search::HELPER_EPOCH
-                        .fetch_add(1, std::sync::atomic::Ordering::Relaxed)
-                        + 1;
-                    search::GLOBAL_STOP.store(false, std::sync::atomic::Ordering::Relaxed);
-                    for i in 1..num_threads {
-                        let game_clone = self.game.clone();
-                        rayon::spawn(move || search::helper_run(game_clone, epoch, i));
-                    }
-                }
-
-                let result = search::analyse_position(
-                    &mut self.game,
-                    max_depth,
-                    start_depth,
-                    slice_ms,
-                    multi_pv,
-                    &mut callback,
-                );
+                let result = self.with_search(|search, game| {
+                    search.context().clear_stop();
+                    search.ensure_analysis_helpers(game, start_depth, num_threads);
+                    search.analyse_position(
+                        game,
+                        max_depth,
+                        start_depth,
+                        slice_ms,
+                        multi_pv,
+                        &mut callback,
+                    )
+                });
                 return self.analysis_result_to_js(&result);
             }
         }
 
-        let result = search::analyse_position(
-            &mut self.game,
-            max_depth,
-            start_depth,
-            slice_ms,
-            multi_pv,
-            &mut callback,
-        );
+        let result = self.with_search(|search, game| {
+            search.analyse_position(
+                game,
+                max_depth,
+                start_depth,
+                slice_ms,
+                multi_pv,
+                &mut callback,
+            )
+        });
         self.analysis_result_to_js(&result)
     }
 
@@ -1030,9 +1088,64 @@ impl Engine {
     pub fn is_sufficient_material(&self) -> bool {
         !evaluation::insufficient_material::evaluate_insufficient_material_game_handler(&self.game)
     }
+
+    /// Returns the current position as an ICN string (position only, no move list).
+    pub fn to_icn(&self) -> String {
+        self.game.to_icn()
+    }
+
+    /// Returns {winner, reason, result} if the game is over, or null while it is still going.
+    pub fn game_outcome(&self) -> JsValue {
+        match self.game.outcome() {
+            Some(outcome) => serde_wasm_bindgen::to_value(&JsGameOutcome {
+                winner: outcome.winner.map(|c| c.to_str().to_string()),
+                reason: outcome.reason.as_str().to_string(),
+                result: outcome.result_str().to_string(),
+            })
+            .unwrap_or(JsValue::NULL),
+            None => JsValue::NULL,
+        }
+    }
 }
 
 impl Engine {
+    /// Strictly parses `icn_string` and makes it the engine's position, with the engine's
+    /// piece values and hill squares applied.
+    fn replace_position(&mut self, icn_string: &str) -> Result<(), game::IcnError> {
+        let mut game = GameState::try_from_icn(icn_string)?;
+        if !self.piece_values.is_empty() {
+            game.apply_piece_values(&self.piece_values);
+        }
+        if let Some(squares) = &self.koth_squares {
+            game.set_koth_squares(squares.clone());
+        }
+        self.game = game;
+        self.history.clear();
+        self.limits.search_moves.clear();
+        Ok(())
+    }
+
+    /// Runs `f` on this engine's own search state, or on the thread's global one when the
+    /// engine was created without its own.
+    fn with_search<R>(
+        &mut self,
+        f: impl FnOnce(&mut search::SearchInstance, &mut GameState) -> R,
+    ) -> R {
+        // The global instance is shared by every engine on the thread, so the book is
+        // installed on each call rather than once.
+        let book = self.book.clone();
+        match self.search.as_mut() {
+            Some(search) => {
+                search.set_book(book);
+                f(search, &mut self.game)
+            }
+            None => search::with_global_search(|search| {
+                search.set_book(book);
+                f(search, &mut self.game)
+            }),
+        }
+    }
+
     /// Builds the final slice summary returned by [`Engine::analyse`].
     fn analysis_result_to_js(&self, result: &search::MultiPVResult) -> JsValue {
         let depth = result.lines.first().map_or(0, |l| l.depth);
@@ -1062,6 +1175,7 @@ impl Engine {
 mod tests {
     use super::*;
     use crate::board::PlayerColor;
+    use std::sync::Arc;
 
     fn all_variants() -> Vec<Variant> {
         vec![
@@ -1094,7 +1208,7 @@ mod tests {
     fn variant_round_trips_and_starting_positions_exist() {
         for variant in all_variants() {
             let canonical = variant.to_str();
-            assert_eq!(Variant::parse(canonical), variant);
+            assert_eq!(Variant::parse(&canonical), variant);
             assert!(!variant.starting_icn().is_empty());
         }
     }
@@ -1141,16 +1255,45 @@ mod tests {
 
     #[test]
     fn native_engine_search_and_pv_work_on_fixed_depth() {
-        let mut engine = Engine::from_icn_native(Variant::Chess.starting_icn(), Some(2));
+        let mut engine = Engine::from_icn_native(&Variant::Chess.starting_icn(), Some(2));
         let best = engine.search_native(0, Some(1), true, Some(0), Some(1234));
 
         assert!(best.is_some());
         assert!(!engine.current_pv_native(1).is_empty());
     }
 
+    #[test]
+    fn native_engines_do_not_share_tables() {
+        let mut first = Engine::new_native(&Variant::Chess.starting_icn());
+        let second = Engine::new_native(&Variant::Chess.starting_icn());
+
+        let (_, _, stats) = first
+            .search_native(0, Some(4), true, Some(0), Some(7))
+            .expect("start position has legal moves");
+        assert!(stats.tt_used > 0);
+        assert!(!first.current_pv_native(1).is_empty());
+
+        let second_search = second.search_instance().unwrap();
+        assert!(second_search.searcher().is_none());
+        assert!(!Arc::ptr_eq(
+            first.search_instance().unwrap().context(),
+            second_search.context()
+        ));
+
+        // Stopping one engine leaves the other (and the global state) untouched.
+        first.search_instance().unwrap().context().request_stop();
+        assert_ne!(second.stop_flag_ptr(), first.stop_flag_ptr());
+        assert_ne!(second.stop_flag_ptr(), stop_flag_ptr());
+
+        // The shared search is an explicit opt-out.
+        let shared = Engine::new_native(&Variant::Chess.starting_icn()).with_shared_search();
+        assert!(shared.search_instance().is_none());
+        assert_eq!(shared.stop_flag_ptr(), stop_flag_ptr());
+    }
+
     #[test]
     fn native_engine_mutation_and_position_queries_work() {
-        let mut engine = Engine::new_native(Variant::Chess.starting_icn());
+        let mut engine = Engine::new_native(&Variant::Chess.starting_icn());
         assert_eq!(engine.perft(1), 20);
         assert!(engine.is_sufficient_material());
         assert!(!engine.is_in_check());
@@ -1163,15 +1306,80 @@ mod tests {
         assert!(!bare_kings.is_in_check());
     }
 
+    #[test]
+    fn native_engine_plays_and_takes_back_site_token_moves() {
+        let mut engine = Engine::new_native(&Variant::Chess.starting_icn());
+        let start_hash = engine.game.hash;
+
+        assert!(engine.make_move("5,2>5,4"));
+        assert!(!engine.make_move("5,7>5,3"));
+        assert!(engine.make_move("5,7>5,5"));
+        assert_eq!(engine.move_history(), vec!["5,2>5,4", "5,7>5,5"]);
+
+        assert!(engine.undo_move());
+        assert!(engine.undo_move());
+        assert!(!engine.undo_move());
+        assert!(engine.move_history().is_empty());
+        assert_eq!(engine.game.hash, start_hash);
+        assert_eq!(engine.perft(1), 20);
+    }
+
+    #[test]
+    fn native_engine_detects_threefold_across_played_moves() {
+        let mut engine = Engine::new_native(&Variant::Chess.starting_icn());
+        for _ in 0..2 {
+            for token in ["7,1>6,3", "7,8>6,6", "6,3>7,1", "6,6>7,8"] {
+                assert!(engine.make_move(token));
+            }
+        }
+
+        let outcome = engine.game.outcome().expect("threefold repetition ends the game");
+        assert_eq!(outcome.reason, game::GameEndReason::Threefold);
+        assert!(outcome.winner.is_none());
+
+        engine.undo_move();
+        assert!(engine.game.outcome().is_none());
+    }
+
+    #[test]
+    fn native_engine_keeps_koth_squares_across_positions() {
+        let mut engine = Engine::new_native("w koth K3,3|k8,8");
+        assert!(!engine.set_koth_squares("2,2;9"));
+        assert!(engine.set_koth_squares("2,2;9,9"));
+        let hill = vec![board::Coordinate::new(2, 2), board::Coordinate::new(9, 9)];
+        assert_eq!(engine.game.game_rules.koth_squares, hill);
+
+        engine
+            .set_position_native("w checkmate,koth K3,3|k8,8")
+            .unwrap();
+        assert_eq!(engine.game.game_rules.koth_squares, hill);
+        assert!(engine.make_move("3,3>2,2"));
+        assert!(engine.game.has_lost_by_koth());
+    }
+
+    #[test]
+    fn native_engine_rejects_malformed_positions() {
+        let mut engine = Engine::new_native(&Variant::Chess.starting_icn());
+        assert!(engine.make_move("5,2>5,4"));
+        let err = engine.set_position_native("w K5,1|k5,8|Zz3,3").unwrap_err();
+        assert_eq!(err.token, "Zz3,3");
+        // The old position and its history are kept.
+        assert!(engine.undo_move());
+        assert_eq!(engine.perft(1), 20);
+
+        engine.set_position_native("w K5,1|k5,8").unwrap();
+        assert_eq!(engine.perft(1), 8);
+    }
+
     #[test]
     fn effective_time_limit_without_clock_is_soft_limit() {
-        let engine = Engine::new_native(Variant::Chess.starting_icn());
+        let engine = Engine::new_native(&Variant::Chess.starting_icn());
         assert_eq!(engine.effective_time_limit_ms(250), (250, 250, true));
     }
 
     #[test]
     fn effective_time_limit_handles_neutral_and_empty_clock_cases() {
-        let mut engine = Engine::new_native(Variant::Chess.starting_icn());
+        let mut engine = Engine::new_native(&Variant::Chess.starting_icn());
         engine.set_clock(0, 0, 0, 0);
         assert_eq!(engine.effective_time_limit_ms(300), (300, 300, true));
 
@@ -1181,7 +1389,7 @@ mod tests {
 
     #[test]
     fn effective_time_limit_uses_increment_and_opening_cap() {
-        let mut engine = Engine::new_native(Variant::Chess.starting_icn());
+        let mut engine = Engine::new_native(&Variant::Chess.starting_icn());
         engine.set_clock(0, 0, 1500, 0);
 
         let (optimum, maximum, is_soft) = engine.effective_time_limit_ms(0);
@@ -1193,7 +1401,7 @@ mod tests {
 
     #[test]
     fn effective_time_limit_for_black_side_is_a_hard_limit() {
-        let mut engine = Engine::new_native(Variant::Chess.starting_icn());
+        let mut engine = Engine::new_native(&Variant::Chess.starting_icn());
         engine.game.turn = PlayerColor::Black;
         engine.game.fullmove_number = 12;
         engine.set_clock(40_000, 25_000, 250, 500);
@@ -3,6 +3,7 @@ use crate::game::{EnPassantState, GameRules};
 use crate::utils::{PRIMES_UNDER_128, is_prime_fast, is_prime_i64};
 use rustc_hash::{FxHashMap, FxHashSet};
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MoveGenType {
@@ -23,12 +24,266 @@ pub struct MoveGenContext<'a> {
     pub pinned: &'a FxHashMap<Coordinate, (i64, i64)>,
 }
 
-// World border for infinite chess.
-use std::sync::atomic::{AtomicI64, Ordering};
-static COORD_MIN_X: AtomicI64 = AtomicI64::new(-1_000_000_000_000_000);
-static COORD_MAX_X: AtomicI64 = AtomicI64::new(1_000_000_000_000_000);
-static COORD_MIN_Y: AtomicI64 = AtomicI64::new(-1_000_000_000_000_000);
-static COORD_MAX_Y: AtomicI64 = AtomicI64::new(1_000_000_000_000_000);
+/// Axis-aligned rectangle of squares (inclusive on all sides).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rect {
+    pub min_x: i64,
+    pub max_x: i64,
+    pub min_y: i64,
+    pub max_y: i64,
+}
+
+impl Rect {
+    /// Build a rectangle from (left, right, bottom, top), normalising swapped edges.
+    pub fn new(left: i64, right: i64, bottom: i64, top: i64) -> Self {
+        Rect {
+            min_x: left.min(right),
+            max_x: left.max(right),
+            min_y: bottom.min(top),
+            max_y: bottom.max(top),
+        }
+    }
+
+    #[inline(always)]
+    pub fn contains(&self, x: i64, y: i64) -> bool {
+        x >= self.min_x && x <= self.max_x && y >= self.min_y && y <= self.max_y
+    }
+
+    /// Steps `t` in `[1, max]` for which `(x + t*dx, y + t*dy)` lies inside,
+    /// as an inclusive range. `None` if the ray misses the rectangle.
+    fn ray_span(&self, x: i64, y: i64, dx: i64, dy: i64, max: i64) -> Option<(i64, i64)> {
+        // Steps keeping `p + t*d` within [lo, hi].
+        fn axis(p: i64, d: i64, lo: i64, hi: i64) -> Option<(i64, i64)> {
+            match d.signum() {
+                0 => (lo..=hi).contains(&p).then_some((i64::MIN, i64::MAX)),
+                1 => Some((ceil_div(lo - p, d), (hi - p).div_euclid(d))),
+                _ => Some((ceil_div(p - hi, -d), (p - lo).div_euclid(-d))),
+            }
+        }
+        fn ceil_div(a: i64, b: i64) -> i64 {
+            -((-a).div_euclid(b))
+        }
+
+        let (x_lo, x_hi) = axis(x, dx, self.min_x, self.max_x)?;
+        let (y_lo, y_hi) = axis(y, dy, self.min_y, self.max_y)?;
+        let lo = x_lo.max(y_lo).max(1);
+        let hi = x_hi.min(y_hi).min(max);
+        (lo <= hi).then_some((lo, hi))
+    }
+}
+
+/// Playable area built from a union of rectangles minus excluded rectangles.
+/// Lets irregular boards (mazes, holes, several joined boxes) be described
+/// directly instead of walling them off with neutral pieces.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayableRegion {
+    pub include: Vec<Rect>,
+    pub exclude: Vec<Rect>,
+}
+
+impl PlayableRegion {
+    #[inline]
+    pub fn contains(&self, x: i64, y: i64) -> bool {
+        self.include.iter().any(|r| r.contains(x, y))
+            && !self.exclude.iter().any(|r| r.contains(x, y))
+    }
+
+    /// Number of consecutive playable squares along the ray from (x, y) in
+    /// direction (dx, dy), starting one step out and looking at most `max` steps.
+    pub fn ray_limit(&self, x: i64, y: i64, dx: i64, dy: i64, max: i64) -> i64 {
+        if max <= 0 {
+            return 0;
+        }
+
+        // Stop just short of the nearest excluded square.
+        let mut limit = max;
+        for r in &self.exclude {
+            if let Some((lo, _)) = r.ray_span(x, y, dx, dy, limit) {
+                limit = lo - 1;
+            }
+        }
+
+        // Walk the included spans outwards while they stay connected.
+        let mut spans: smallvec::SmallVec<[(i64, i64); 8]> = self
+            .include
+            .iter()
+            .filter_map(|r| r.ray_span(x, y, dx, dy, limit))
+            .collect();
+        spans.sort_unstable();
+        let mut reach = 0;
+        for (lo, hi) in spans {
+            if lo > reach + 1 {
+                break;
+            }
+            reach = reach.max(hi);
+        }
+        reach.min(limit)
+    }
+
+    /// Distance to the first non-playable square on the ray, if within `max` steps.
+    #[inline]
+    pub fn first_gap(&self, x: i64, y: i64, dx: i64, dy: i64, max: i64) -> Option<i64> {
+        let limit = self.ray_limit(x, y, dx, dy, max);
+        (limit < max).then_some(limit + 1)
+    }
+}
+
+/// World border for infinite chess (inclusive on all sides).
+/// Owned by each game's `GameRules`, so games with different borders can be
+/// searched side by side in the same process. The box is the outer limit; an
+/// optional `region` cuts holes and gaps out of it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorldBounds {
+    pub min_x: i64,
+    pub max_x: i64,
+    pub min_y: i64,
+    pub max_y: i64,
+    /// Non-rectangular playable area inside the box, shared between clones.
+    #[serde(skip)]
+    pub region: Option<Arc<PlayableRegion>>,
+}
+
+impl WorldBounds {
+    /// Effectively unbounded board used when no border is given.
+    pub const UNBOUNDED: WorldBounds = WorldBounds {
+        min_x: -1_000_000_000_000_000,
+        max_x: 1_000_000_000_000_000,
+        min_y: -1_000_000_000_000_000,
+        max_y: 1_000_000_000_000_000,
+        region: None,
+    };
+
+    /// Build bounds from a playableRegion (left, right, bottom, top).
+    pub fn new(left: i64, right: i64, bottom: i64, top: i64) -> Self {
+        WorldBounds {
+            min_x: left.min(right),
+            max_x: left.max(right),
+            min_y: bottom.min(top),
+            max_y: bottom.max(top),
+            region: None,
+        }
+    }
+
+    /// Build bounds from a union of `include` rectangles minus `exclude`.
+    /// A single included box with no exclusions is a plain border.
+    pub fn from_region(include: Vec<Rect>, exclude: Vec<Rect>) -> Self {
+        let Some(first) = include.first() else {
+            return WorldBounds::UNBOUNDED;
+        };
+        let mut bounds = WorldBounds::new(first.min_x, first.max_x, first.min_y, first.max_y);
+        for r in &include[1..] {
+            bounds.min_x = bounds.min_x.min(r.min_x);
+            bounds.max_x = bounds.max_x.max(r.max_x);
+            bounds.min_y = bounds.min_y.min(r.min_y);
+            bounds.max_y = bounds.max_y.max(r.max_y);
+        }
+        if include.len() > 1 || !exclude.is_empty() {
+            bounds.region = Some(Arc::new(PlayableRegion { include, exclude }));
+        }
+        bounds
+    }
+
+    /// Parse an ICN bounds token: `left,right,bottom,top`, optionally followed by
+    /// further `+left,right,bottom,top` boxes and `~left,right,bottom,top` holes.
+    pub fn from_icn(token: &str) -> Option<Self> {
+        let mut include = Vec::new();
+        let mut exclude = Vec::new();
+        let mut rest = token;
+        let mut target = &mut include;
+        loop {
+            let end = rest.find(['+', '~']).unwrap_or(rest.len());
+            let v: Vec<i64> = rest[..end]
+                .split(',')
+                .map(|n| n.parse().ok())
+                .collect::<Option<_>>()?;
+            let [l, r, b, t] = v[..] else {
+                return None;
+            };
+            target.push(Rect::new(l, r, b, t));
+            if end == rest.len() {
+                break;
+            }
+            target = if rest.as_bytes()[end] == b'+' {
+                &mut include
+            } else {
+                &mut exclude
+            };
+            rest = &rest[end + 1..];
+        }
+        Some(WorldBounds::from_region(include, exclude))
+    }
+
+    /// True if `token` has the shape of an ICN bounds token (see `from_icn`).
+    pub fn is_icn_token(token: &str) -> bool {
+        token
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, ',' | '-' | '+' | '~'))
+            && token
+                .split(['+', '~'])
+                .all(|part| part.split(',').count() == 4)
+    }
+
+    /// ICN bounds token, the inverse of `from_icn`.
+    pub fn to_icn(&self) -> String {
+        let rect = |r: &Rect| format!("{},{},{},{}", r.min_x, r.max_x, r.min_y, r.max_y);
+        match &self.region {
+            None => rect(&Rect::new(self.min_x, self.max_x, self.min_y, self.max_y)),
+            Some(region) => {
+                let mut out = region
+                    .include
+                    .iter()
+                    .map(rect)
+                    .collect::<Vec<_>>()
+                    .join("+");
+                for r in &region.exclude {
+                    out.push('~');
+                    out.push_str(&rect(r));
+                }
+                out
+            }
+        }
+    }
+
+    #[inline(always)]
+    pub fn contains(&self, x: i64, y: i64) -> bool {
+        x >= self.min_x
+            && x <= self.max_x
+            && y >= self.min_y
+            && y <= self.max_y
+            && self.region.as_ref().is_none_or(|r| r.contains(x, y))
+    }
+
+    /// Distance to the first region hole or gap on the ray from (x, y), if
+    /// within `max` steps. The outer box is left to `ray_border_distance`.
+    #[inline]
+    pub fn region_gap(&self, x: i64, y: i64, dx: i64, dy: i64, max: i64) -> Option<i64> {
+        self.region
+            .as_ref()
+            .and_then(|r| r.first_gap(x, y, dx, dy, max))
+    }
+
+    /// Get the maximum dimension of the border.
+    /// Returns the larger of (max_x - min_x, max_y - min_y).
+    /// Used for determining if standard chess mating patterns apply (bounded board).
+    #[inline]
+    pub fn size(&self) -> i64 {
+        let width = self.max_x.saturating_sub(self.min_x);
+        let height = self.max_y.saturating_sub(self.min_y);
+        width.max(height)
+    }
+
+    /// All coordinate bounds as (min_x, max_x, min_y, max_y).
+    #[inline]
+    pub fn as_tuple(&self) -> (i64, i64, i64, i64) {
+        (self.min_x, self.max_x, self.min_y, self.max_y)
+    }
+}
+
+impl Default for WorldBounds {
+    fn default() -> Self {
+        WorldBounds::UNBOUNDED
+    }
+}
 
 struct CrossRayContext<'a> {
     board: &'a Board,
@@ -50,45 +305,17 @@ pub struct SlidingMoveContext<'a> {
     pub enemy_king_pos: Option<&'a Coordinate>,
     pub visited_targets: Option<&'a std::cell::RefCell<Vec<(Coordinate, u8)>>>,
     pub pinned: &'a FxHashMap<Coordinate, (i64, i64)>,
-}
-
-/// Update world borders from JS playableRegion (left, right, bottom, top).
-pub fn set_world_bounds(left: i64, right: i64, bottom: i64, top: i64) {
-    COORD_MIN_X.store(left.min(right), Ordering::Relaxed);
-    COORD_MAX_X.store(left.max(right), Ordering::Relaxed);
-    COORD_MIN_Y.store(bottom.min(top), Ordering::Relaxed);
-    COORD_MAX_Y.store(bottom.max(top), Ordering::Relaxed);
-}
-
-/// Get the maximum dimension of the current world border.
-/// Returns the larger of (max_x - min_x, max_y - min_y).
-/// Used for determining if standard chess mating patterns apply (bounded board).
-#[inline]
-pub fn get_world_size() -> i64 {
-    let width = COORD_MAX_X
-        .load(Ordering::Relaxed)
-        .saturating_sub(COORD_MIN_X.load(Ordering::Relaxed));
-    let height = COORD_MAX_Y
-        .load(Ordering::Relaxed)
-        .saturating_sub(COORD_MIN_Y.load(Ordering::Relaxed));
-    width.max(height)
-}
-
-/// Get all coordinate bounds (min_x, max_x, min_y, max_y).
-/// Used for cage detection in mop-up evaluation.
-#[inline]
-pub fn get_coord_bounds() -> (i64, i64, i64, i64) {
-    (
-        COORD_MIN_X.load(Ordering::Relaxed),
-        COORD_MAX_X.load(Ordering::Relaxed),
-        COORD_MIN_Y.load(Ordering::Relaxed),
-        COORD_MAX_Y.load(Ordering::Relaxed),
-    )
+    pub bounds: &'a WorldBounds,
 }
 
 /// Generate all pseudo-legal moves for a Knightrider.
 /// A Knightrider slides like a knight repeated along its direction until blocked or out of bounds.
-fn generate_knightrider_moves(board: &Board, from: &Coordinate, piece: &Piece) -> MoveList {
+fn generate_knightrider_moves(
+    board: &Board,
+    from: &Coordinate,
+    piece: &Piece,
+    bounds: &WorldBounds,
+) -> MoveList {
     // All 8 knight directions
     const KR_DIRS: [(i64, i64); 8] = [
         (1, 2),
@@ -172,7 +399,11 @@ fn generate_knightrider_moves(board: &Board, from: &Coordinate, piece: &Piece) -
         if closest_k < i64::MAX && closest_is_enemy && closest_k > KR_STEP_LIMIT {
             let x = from.x + dx * closest_k;
             let y = from.y + dy * closest_k;
-            if in_bounds(x, y) {
+            if in_bounds(bounds, x, y)
+                && bounds
+                    .region_gap(from.x, from.y, dx, dy, closest_k)
+                    .is_none()
+            {
                 moves.push(Move::new(*from, Coordinate::new(x, y), *piece));
             }
         }
@@ -186,7 +417,7 @@ fn generate_knightrider_moves(board: &Board, from: &Coordinate, piece: &Piece) -
             let x = from.x + dx * k;
             let y = from.y + dy * k;
 
-            if !in_bounds(x, y) {
+            if !in_bounds(bounds, x, y) {
                 break;
             }
 
@@ -211,12 +442,8 @@ fn generate_knightrider_moves(board: &Board, from: &Coordinate, piece: &Piece) -
 
 /// Check if a coordinate is within valid bounds (world border)
 #[inline]
-pub fn in_bounds(x: i64, y: i64) -> bool {
-    let min_x = COORD_MIN_X.load(Ordering::Relaxed);
-    let max_x = COORD_MAX_X.load(Ordering::Relaxed);
-    let min_y = COORD_MIN_Y.load(Ordering::Relaxed);
-    let max_y = COORD_MAX_Y.load(Ordering::Relaxed);
-    x >= min_x && x <= max_x && y >= min_y && y <= max_y
+pub fn in_bounds(bounds: &WorldBounds, x: i64, y: i64) -> bool {
+    bounds.contains(x, y)
 }
 
 /// Helper to check if a path is clear between two squares ON THE SAME TILE.
@@ -521,6 +748,12 @@ pub struct SpatialIndices {
     pub has_rose: [bool; 2],
     #[serde(skip)]
     pub has_knightrider: [bool; 2],
+
+    /// Playable region of the game these indices belong to, so ray queries
+    /// stop at holes and gaps. The outer box needs no check: it is convex, so
+    /// a ray between two pieces never leaves it.
+    #[serde(skip)]
+    pub region: Option<Arc<PlayableRegion>>,
 }
 
 impl SpatialIndices {
@@ -579,9 +812,24 @@ impl SpatialIndices {
             has_huygen,
             has_rose,
             has_knightrider,
+            region: None,
         }
     }
 
+    /// True if (x, y) is inside the playable region (always true without one).
+    #[inline(always)]
+    pub fn is_playable(&self, x: i64, y: i64) -> bool {
+        self.region.as_ref().is_none_or(|r| r.contains(x, y))
+    }
+
+    /// Distance to the first region hole or gap on a ray, if within `max` steps.
+    #[inline]
+    pub fn region_gap(&self, x: i64, y: i64, dx: i64, dy: i64, max: i64) -> Option<i64> {
+        self.region
+            .as_ref()
+            .and_then(|r| r.first_gap(x, y, dx, dy, max))
+    }
+
     /// Incrementally add a piece at (x, y) to the indices.
     pub fn add(&mut self, x: i64, y: i64, packed: u8) {
         self.rows.entry(y).or_default().insert(x, packed);
@@ -631,7 +879,7 @@ impl SpatialIndices {
     }
 
     /// Find first blocker on a ray starting from (from_x, from_y) in direction (dx, dy).
-    /// Returns (vx, vy, piece) if found.
+    /// Returns (vx, vy, piece) if found, or None if a region hole cuts the ray first.
     pub fn find_first_blocker(
         &self,
         from_x: i64,
@@ -674,6 +922,10 @@ impl SpatialIndices {
                     (coord, key - coord)
                 };
 
+                let dist = (vx - from_x).abs().max((vy - from_y).abs());
+                if self.region_gap(from_x, from_y, dx, dy, dist).is_some() {
+                    return None;
+                }
                 return Some((vx, vy, piece));
             }
         }
@@ -692,6 +944,7 @@ impl Default for SpatialIndices {
             has_huygen: [false, false],
             has_rose: [false, false],
             has_knightrider: [false, false],
+            region: None,
         }
     }
 }
@@ -844,6 +1097,7 @@ fn generate_captures_for_piece(
     let special_rights = ctx.special_rights;
     let en_passant = ctx.en_passant;
     let game_rules = ctx.game_rules;
+    let bounds = &game_rules.world_bounds;
     let indices = ctx.indices;
     match piece.piece_type() {
         PieceType::Void | PieceType::Obstacle => {}
@@ -864,29 +1118,74 @@ fn generate_captures_for_piece(
 
         // Knight-like leapers
         PieceType::Knight => {
-            generate_leaper_moves_into(board, from, piece, 1, 2, MoveGenType::Captures, out);
+            generate_leaper_moves_into(
+                board,
+                from,
+                piece,
+                1,
+                2,
+                MoveGenType::Captures,
+                bounds,
+                out,
+            );
         }
         PieceType::Camel => {
-            generate_leaper_moves_into(board, from, piece, 1, 3, MoveGenType::Captures, out);
+            generate_leaper_moves_into(
+                board,
+                from,
+                piece,
+                1,
+                3,
+                MoveGenType::Captures,
+                bounds,
+                out,
+            );
         }
         PieceType::Giraffe => {
-            generate_leaper_moves_into(board, from, piece, 1, 4, MoveGenType::Captures, out);
+            generate_leaper_moves_into(
+                board,
+                from,
+                piece,
+                1,
+                4,
+                MoveGenType::Captures,
+                bounds,
+                out,
+            );
         }
         PieceType::Zebra => {
-            generate_leaper_moves_into(board, from, piece, 2, 3, MoveGenType::Captures, out);
+            generate_leaper_moves_into(
+                board,
+                from,
+                piece,
+                2,
+                3,
+                MoveGenType::Captures,
+                bounds,
+                out,
+            );
         }
 
         // King/Guard/Centaur/RoyalCentaur/Hawk: use compass moves, then filter captures
         PieceType::King | PieceType::Guard => {
-            generate_compass_moves_into(board, from, piece, 1, MoveGenType::Captures, out);
+            generate_compass_moves_into(board, from, piece, 1, MoveGenType::Captures, bounds, out);
         }
         PieceType::Centaur | PieceType::RoyalCentaur => {
-            generate_compass_moves_into(board, from, piece, 1, MoveGenType::Captures, out);
-            generate_leaper_moves_into(board, from, piece, 1, 2, MoveGenType::Captures, out);
+            generate_compass_moves_into(board, from, piece, 1, MoveGenType::Captures, bounds, out);
+            generate_leaper_moves_into(
+                board,
+                from,
+                piece,
+                1,
+                2,
+                MoveGenType::Captures,
+                bounds,
+                out,
+            );
         }
         PieceType::Hawk => {
-            generate_compass_moves_into(board, from, piece, 2, MoveGenType::Captures, out);
-            generate_compass_moves_into(board, from, piece, 3, MoveGenType::Captures, out);
+            generate_compass_moves_into(board, from, piece, 2, MoveGenType::Captures, bounds, out);
+            generate_compass_moves_into(board, from, piece, 3, MoveGenType::Captures, bounds, out);
         }
 
         // Standard sliders and slider-leaper compounds
@@ -903,33 +1202,68 @@ fn generate_captures_for_piece(
         PieceType::Chancellor => {
             // Rook + knight
             generate_sliding_capture_moves(board, from, piece, &[(1, 0), (0, 1)], indices, out);
-            generate_leaper_moves_into(board, from, piece, 1, 2, MoveGenType::Captures, out);
+            generate_leaper_moves_into(
+                board,
+                from,
+                piece,
+                1,
+                2,
+                MoveGenType::Captures,
+                bounds,
+                out,
+            );
         }
         PieceType::Archbishop => {
             // Bishop + knight
             generate_sliding_capture_moves(board, from, piece, &[(1, 1), (1, -1)], indices, out);
-            generate_leaper_moves_into(board, from, piece, 1, 2, MoveGenType::Captures, out);
+            generate_leaper_moves_into(
+                board,
+                from,
+                piece,
+                1,
+                2,
+                MoveGenType::Captures,
+                bounds,
+                out,
+            );
         }
         PieceType::Amazon => {
             // Queen + knight
             generate_sliding_capture_moves(board, from, piece, &[(1, 0), (0, 1)], indices, out);
             generate_sliding_capture_moves(board, from, piece, &[(1, 1), (1, -1)], indices, out);
-            generate_leaper_moves_into(board, from, piece, 1, 2, MoveGenType::Captures, out);
+            generate_leaper_moves_into(
+                board,
+                from,
+                piece,
+                1,
+                2,
+                MoveGenType::Captures,
+                bounds,
+                out,
+            );
         }
 
         // Knightrider: sliding along knight vectors
         PieceType::Knightrider => {
-            generate_knightrider_moves_into(board, from, piece, MoveGenType::Captures, out);
+            generate_knightrider_moves_into(board, from, piece, MoveGenType::Captures, bounds, out);
         }
 
         // Huygen: use existing generator and keep only captures
         PieceType::Huygen => {
-            generate_huygen_moves_into(board, from, piece, indices, MoveGenType::Captures, out);
+            generate_huygen_moves_into(
+                board,
+                from,
+                piece,
+                indices,
+                MoveGenType::Captures,
+                bounds,
+                out,
+            );
         }
 
         // Rose: use existing generator and keep only captures
         PieceType::Rose => {
-            generate_rose_moves_into(board, from, piece, MoveGenType::Captures, out);
+            generate_rose_moves_into(board, from, piece, MoveGenType::Captures, bounds, out);
         }
     }
 }
@@ -947,6 +1281,7 @@ pub fn get_pseudo_legal_moves_for_piece_into(
     let special_rights = ctx.special_rights;
     let en_passant = ctx.en_passant;
     let game_rules = ctx.game_rules;
+    let bounds = &game_rules.world_bounds;
     let indices = ctx.indices;
     let enemy_king_pos = ctx.enemy_king_pos;
     match piece.piece_type() {
@@ -964,18 +1299,18 @@ pub fn get_pseudo_legal_moves_for_piece_into(
             );
         }
         PieceType::Knight => {
-            generate_leaper_moves_into(board, from, piece, 1, 2, MoveGenType::All, out)
+            generate_leaper_moves_into(board, from, piece, 1, 2, MoveGenType::All, bounds, out)
         }
         PieceType::Hawk => {
-            generate_compass_moves_into(board, from, piece, 2, MoveGenType::All, out);
-            generate_compass_moves_into(board, from, piece, 3, MoveGenType::All, out);
+            generate_compass_moves_into(board, from, piece, 2, MoveGenType::All, bounds, out);
+            generate_compass_moves_into(board, from, piece, 3, MoveGenType::All, bounds, out);
         }
         PieceType::King => {
-            generate_compass_moves_into(board, from, piece, 1, MoveGenType::All, out);
+            generate_compass_moves_into(board, from, piece, 1, MoveGenType::All, bounds, out);
             generate_castling_moves_into(board, from, piece, special_rights, game_rules, indices, out);
         }
         PieceType::Guard => {
-            generate_compass_moves_into(board, from, piece, 1, MoveGenType::All, out)
+            generate_compass_moves_into(board, from, piece, 1, MoveGenType::All, bounds, out)
         }
         PieceType::Rook => {
             generate_sliding_moves_into(
@@ -988,6 +1323,7 @@ pub fn get_pseudo_legal_moves_for_piece_into(
                     enemy_king_pos,
                     visited_targets: None,
                     pinned: ctx.pinned,
+                    bounds,
                 },
                 out,
             );
@@ -1003,6 +1339,7 @@ pub fn get_pseudo_legal_moves_for_piece_into(
                     enemy_king_pos,
                     visited_targets: None,
                     pinned: ctx.pinned,
+                    bounds,
                 },
                 out,
             );
@@ -1019,6 +1356,7 @@ pub fn get_pseudo_legal_moves_for_piece_into(
                     enemy_king_pos,
                     visited_targets: Some(&visited),
                     pinned: ctx.pinned,
+                    bounds,
                 },
                 out,
             );
@@ -1032,12 +1370,13 @@ pub fn get_pseudo_legal_moves_for_piece_into(
                     enemy_king_pos,
                     visited_targets: Some(&visited),
                     pinned: ctx.pinned,
+                    bounds,
                 },
                 out,
             );
         }
         PieceType::Chancellor => {
-            generate_leaper_moves_into(board, from, piece, 1, 2, MoveGenType::All, out);
+            generate_leaper_moves_into(board, from, piece, 1, 2, MoveGenType::All, bounds, out);
             generate_sliding_moves_into(
                 &SlidingMoveContext {
                     board,
@@ -1048,12 +1387,13 @@ pub fn get_pseudo_legal_moves_for_piece_into(
                     enemy_king_pos,
                     visited_targets: None,
                     pinned: ctx.pinned,
+                    bounds,
                 },
                 out,
             );
         }
         PieceType::Archbishop => {
-            generate_leaper_moves_into(board, from, piece, 1, 2, MoveGenType::All, out);
+            generate_leaper_moves_into(board, from, piece, 1, 2, MoveGenType::All, bounds, out);
             generate_sliding_moves_into(
                 &SlidingMoveContext {
                     board,
@@ -1064,12 +1404,13 @@ pub fn get_pseudo_legal_moves_for_piece_into(
                     enemy_king_pos,
                     visited_targets: None,
                     pinned: ctx.pinned,
+                    bounds,
                 },
                 out,
             );
         }
         PieceType::Amazon => {
-            generate_leaper_moves_into(board, from, piece, 1, 2, MoveGenType::All, out);
+            generate_leaper_moves_into(board, from, piece, 1, 2, MoveGenType::All, bounds, out);
             let visited = std::cell::RefCell::new(Vec::with_capacity(16));
             generate_sliding_moves_into(
                 &SlidingMoveContext {
@@ -1081,6 +1422,7 @@ pub fn get_pseudo_legal_moves_for_piece_into(
                     enemy_king_pos,
                     visited_targets: Some(&visited),
                     pinned: ctx.pinned,
+                    bounds,
                 },
                 out,
             );
@@ -1094,36 +1436,39 @@ pub fn get_pseudo_legal_moves_for_piece_into(
                     enemy_king_pos,
                     visited_targets: Some(&visited),
                     pinned: ctx.pinned,
+                    bounds,
                 },
                 out,
             );
         }
         PieceType::Camel => {
-            generate_leaper_moves_into(board, from, piece, 1, 3, MoveGenType::All, out)
+            generate_leaper_moves_into(board, from, piece, 1, 3, MoveGenType::All, bounds, out)
         }
         PieceType::Giraffe => {
-            generate_leaper_moves_into(board, from, piece, 1, 4, MoveGenType::All, out)
+            generate_leaper_moves_into(board, from, piece, 1, 4, MoveGenType::All, bounds, out)
         }
         PieceType::Zebra => {
-            generate_leaper_moves_into(board, from, piece, 2, 3, MoveGenType::All, out)
+            generate_leaper_moves_into(board, from, piece, 2, 3, MoveGenType::All, bounds, out)
         }
         // Knightrider: slide along all 8 knight directions until blocked
         PieceType::Knightrider => {
-            generate_knightrider_moves_into(board, from, piece, MoveGenType::All, out)
+            generate_knightrider_moves_into(board, from, piece, MoveGenType::All, bounds, out)
         }
         PieceType::Centaur => {
-            generate_compass_moves_into(board, from, piece, 1, MoveGenType::All, out);
-            generate_leaper_moves_into(board, from, piece, 1, 2, MoveGenType::All, out);
+            generate_compass_moves_into(board, from, piece, 1, MoveGenType::All, bounds, out);
+            generate_leaper_moves_into(board, from, piece, 1, 2, MoveGenType::All, bounds, out);
         }
         PieceType::RoyalCentaur => {
-            generate_compass_moves_into(board, from, piece, 1, MoveGenType::All, out);
-            generate_leaper_moves_into(board, from, piece, 1, 2, MoveGenType::All, out);
+            generate_compass_moves_into(board, from, piece, 1, MoveGenType::All, bounds, out);
+            generate_leaper_moves_into(board, from, piece, 1, 2, MoveGenType::All, bounds, out);
             generate_castling_moves_into(board, from, piece, special_rights, game_rules, indices, out);
         }
         PieceType::Huygen => {
-            generate_huygen_moves_into(board, from, piece, indices, MoveGenType::All, out)
+            generate_huygen_moves_into(board, from, piece, indices, MoveGenType::All, bounds, out)
+        }
+        PieceType::Rose => {
+            generate_rose_moves_into(board, from, piece, MoveGenType::All, bounds, out)
         }
-        PieceType::Rose => generate_rose_moves_into(board, from, piece, MoveGenType::All, out),
     }
 }
 
@@ -1251,9 +1596,14 @@ pub fn is_square_attacked(
             let val = if dx == 0 { target.y } else { target.x };
             let step_dir = if dx == 0 { dy } else { dx };
 
-            if let Some((_, packed)) = vec.find_nearest(val, step_dir) {
+            if let Some((coord, packed)) = vec.find_nearest(val, step_dir) {
                 let piece = Piece::from_packed(packed);
-                if piece.color() == attacker_color && matches_mask(piece.piece_type(), type_mask) {
+                if piece.color() == attacker_color
+                    && matches_mask(piece.piece_type(), type_mask)
+                    && indices
+                        .region_gap(target.x, target.y, dx, dy, (coord - val).abs())
+                        .is_none()
+                {
                     return true;
                 }
             }
@@ -1287,6 +1637,9 @@ pub fn is_square_attacked(
             loop {
                 let x = target.x + dx * k;
                 let y = target.y + dy * k;
+                if !indices.is_playable(x, y) {
+                    break;
+                }
                 if let Some(piece) = board.get_piece(x, y) {
                     if piece.color() == attacker_color
                         && piece.piece_type() == PieceType::Knightrider
@@ -1385,6 +1738,22 @@ pub fn is_square_attacked(
                         }
                     }
 
+                    // Region holes at a prime distance block like Void
+                    if !blocked && indices.region.is_some() {
+                        let step = -dist_to_target.signum();
+                        blocked = PRIMES_UNDER_128
+                            .iter()
+                            .take_while(|&&p| p < abs_dist_to_target)
+                            .any(|&p| {
+                                let (hx, hy) = if dx == 0 {
+                                    (target.x, huygen_coord + step * p)
+                                } else {
+                                    (huygen_coord + step * p, target.y)
+                                };
+                                !indices.is_playable(hx, hy)
+                            });
+                    }
+
                     if !blocked {
                         return true; // Huygens attacks the target!
                     }
@@ -1418,7 +1787,9 @@ pub fn is_square_attacked(
                         for &(prev_dx, prev_dy) in spiral.iter().take(hop) {
                             let check_x = target.x - cum_dx + prev_dx;
                             let check_y = target.y - cum_dy + prev_dy;
-                            if board.is_occupied(check_x, check_y) {
+                            if board.is_occupied(check_x, check_y)
+                                || !indices.is_playable(check_x, check_y)
+                            {
                                 blocked = true;
                                 break;
                             }
@@ -1608,6 +1979,19 @@ fn generate_castling_moves(
 ) -> MoveList {
     let mut moves = MoveList::new();
 
+    if game_rules.chess960 {
+        generate_chess960_castling_into(
+            board,
+            from,
+            piece,
+            special_rights,
+            game_rules,
+            indices,
+            &mut moves,
+        );
+        return moves;
+    }
+
     // King must have special rights to castle
     if !special_rights.contains(from) {
         return moves;
@@ -1629,7 +2013,9 @@ fn generate_castling_moves(
                     let dir = if dx > 0 { 1i64 } else { -1i64 };
 
                     // Use spatial indices to check path - O(log n) instead of O(distance)
-                    let mut clear = true;
+                    let mut clear = indices
+                        .region_gap(from.x, from.y, dir, 0, dx.abs())
+                        .is_none();
                     if let Some(row_pieces) = indices.rows.get(&from.y) {
                         // Find nearest piece in direction from king
                         if let Some((nearest_x, _)) = row_pieces.find_nearest(from.x, dir) {
@@ -1778,6 +2164,7 @@ fn generate_quiets_for_piece(
 ) {
     let special_rights = ctx.special_rights;
     let game_rules = ctx.game_rules;
+    let bounds = &game_rules.world_bounds;
     let indices = ctx.indices;
     let enemy_king_pos = ctx.enemy_king_pos;
     match piece.piece_type() {
@@ -1790,41 +2177,41 @@ fn generate_quiets_for_piece(
 
         // Knight-like leapers: filter to empty squares
         PieceType::Knight => {
-            generate_leaper_moves_into(board, from, piece, 1, 2, MoveGenType::Quiets, out);
+            generate_leaper_moves_into(board, from, piece, 1, 2, MoveGenType::Quiets, bounds, out);
         }
         PieceType::Camel => {
-            generate_leaper_moves_into(board, from, piece, 1, 3, MoveGenType::Quiets, out);
+            generate_leaper_moves_into(board, from, piece, 1, 3, MoveGenType::Quiets, bounds, out);
         }
         PieceType::Giraffe => {
-            generate_leaper_moves_into(board, from, piece, 1, 4, MoveGenType::Quiets, out);
+            generate_leaper_moves_into(board, from, piece, 1, 4, MoveGenType::Quiets, bounds, out);
         }
         PieceType::Zebra => {
-            generate_leaper_moves_into(board, from, piece, 2, 3, MoveGenType::Quiets, out);
+            generate_leaper_moves_into(board, from, piece, 2, 3, MoveGenType::Quiets, bounds, out);
         }
 
         // King: compass + castling
         PieceType::King => {
-            generate_compass_moves_into(board, from, piece, 1, MoveGenType::Quiets, out);
+            generate_compass_moves_into(board, from, piece, 1, MoveGenType::Quiets, bounds, out);
             // Castling is always a quiet move
             let castling = generate_castling_moves(board, from, piece, special_rights, game_rules, indices);
             out.extend(castling);
         }
         PieceType::Guard => {
-            generate_compass_moves_into(board, from, piece, 1, MoveGenType::Quiets, out);
+            generate_compass_moves_into(board, from, piece, 1, MoveGenType::Quiets, bounds, out);
         }
         PieceType::Centaur => {
-            generate_compass_moves_into(board, from, piece, 1, MoveGenType::Quiets, out);
-            generate_leaper_moves_into(board, from, piece, 1, 2, MoveGenType::Quiets, out);
+            generate_compass_moves_into(board, from, piece, 1, MoveGenType::Quiets, bounds, out);
+            generate_leaper_moves_into(board, from, piece, 1, 2, MoveGenType::Quiets, bounds, out);
         }
         PieceType::RoyalCentaur => {
-            generate_compass_moves_into(board, from, piece, 1, MoveGenType::Quiets, out);
-            generate_leaper_moves_into(board, from, piece, 1, 2, MoveGenType::Quiets, out);
+            generate_compass_moves_into(board, from, piece, 1, MoveGenType::Quiets, bounds, out);
+            generate_leaper_moves_into(board, from, piece, 1, 2, MoveGenType::Quiets, bounds, out);
             let castling = generate_castling_moves(board, from, piece, special_rights, game_rules, indices);
             out.extend(castling);
         }
         PieceType::Hawk => {
-            generate_compass_moves_into(board, from, piece, 2, MoveGenType::Quiets, out);
-            generate_compass_moves_into(board, from, piece, 3, MoveGenType::Quiets, out);
+            generate_compass_moves_into(board, from, piece, 2, MoveGenType::Quiets, bounds, out);
+            generate_compass_moves_into(board, from, piece, 3, MoveGenType::Quiets, bounds, out);
         }
 
         // Sliders
@@ -1839,6 +2226,7 @@ fn generate_quiets_for_piece(
                     enemy_king_pos,
                     visited_targets: None,
                     pinned: ctx.pinned,
+                    bounds,
                 },
                 out,
             );
@@ -1854,6 +2242,7 @@ fn generate_quiets_for_piece(
                     enemy_king_pos,
                     visited_targets: None,
                     pinned: ctx.pinned,
+                    bounds,
                 },
                 out,
             );
@@ -1870,6 +2259,7 @@ fn generate_quiets_for_piece(
                     enemy_king_pos,
                     visited_targets: Some(&visited),
                     pinned: ctx.pinned,
+                    bounds,
                 },
                 out,
             );
@@ -1883,6 +2273,7 @@ fn generate_quiets_for_piece(
                     enemy_king_pos,
                     visited_targets: Some(&visited),
                     pinned: ctx.pinned,
+                    bounds,
                 },
                 out,
             );
@@ -1899,6 +2290,7 @@ fn generate_quiets_for_piece(
                     enemy_king_pos,
                     visited_targets: Some(&visited),
                     pinned: ctx.pinned,
+                    bounds,
                 },
                 out,
             );
@@ -1912,6 +2304,7 @@ fn generate_quiets_for_piece(
                     enemy_king_pos,
                     visited_targets: Some(&visited),
                     pinned: ctx.pinned,
+                    bounds,
                 },
                 out,
             );
@@ -1920,7 +2313,7 @@ fn generate_quiets_for_piece(
             out.extend(castling);
         }
         PieceType::Chancellor => {
-            generate_leaper_moves_into(board, from, piece, 1, 2, MoveGenType::Quiets, out);
+            generate_leaper_moves_into(board, from, piece, 1, 2, MoveGenType::Quiets, bounds, out);
             generate_sliding_quiets_into(
                 &SlidingMoveContext {
                     board,
@@ -1931,12 +2324,13 @@ fn generate_quiets_for_piece(
                     enemy_king_pos,
                     visited_targets: None,
                     pinned: ctx.pinned,
+                    bounds,
                 },
                 out,
             );
         }
         PieceType::Archbishop => {
-            generate_leaper_moves_into(board, from, piece, 1, 2, MoveGenType::Quiets, out);
+            generate_leaper_moves_into(board, from, piece, 1, 2, MoveGenType::Quiets, bounds, out);
             generate_sliding_quiets_into(
                 &SlidingMoveContext {
                     board,
@@ -1947,12 +2341,13 @@ fn generate_quiets_for_piece(
                     enemy_king_pos,
                     visited_targets: None,
                     pinned: ctx.pinned,
+                    bounds,
                 },
                 out,
             );
         }
         PieceType::Amazon => {
-            generate_leaper_moves_into(board, from, piece, 1, 2, MoveGenType::Quiets, out);
+            generate_leaper_moves_into(board, from, piece, 1, 2, MoveGenType::Quiets, bounds, out);
             let visited = std::cell::RefCell::new(Vec::with_capacity(16));
             generate_sliding_quiets_into(
                 &SlidingMoveContext {
@@ -1964,6 +2359,7 @@ fn generate_quiets_for_piece(
                     enemy_king_pos,
                     visited_targets: Some(&visited),
                     pinned: ctx.pinned,
+                    bounds,
                 },
                 out,
             );
@@ -1977,19 +2373,28 @@ fn generate_quiets_for_piece(
                     enemy_king_pos,
                     visited_targets: Some(&visited),
                     pinned: ctx.pinned,
+                    bounds,
                 },
                 out,
             );
         }
 
         PieceType::Knightrider => {
-            generate_knightrider_moves_into(board, from, piece, MoveGenType::Quiets, out);
+            generate_knightrider_moves_into(board, from, piece, MoveGenType::Quiets, bounds, out);
         }
         PieceType::Huygen => {
-            generate_huygen_moves_into(board, from, piece, indices, MoveGenType::Quiets, out);
+            generate_huygen_moves_into(
+                board,
+                from,
+                piece,
+                indices,
+                MoveGenType::Quiets,
+                bounds,
+                out,
+            );
         }
         PieceType::Rose => {
-            generate_rose_moves_into(board, from, piece, MoveGenType::Quiets, out);
+            generate_rose_moves_into(board, from, piece, MoveGenType::Quiets, bounds, out);
         }
     }
 }
@@ -2030,6 +2435,7 @@ fn generate_pawn_quiet_moves(
 
     // Helper function for promotion moves
     #[inline]
+    #[allow(clippy::too_many_arguments)]
     fn add_pawn_move(
         out: &mut MoveList,
         from: Coordinate,
@@ -2038,8 +2444,9 @@ fn generate_pawn_quiet_moves(
         piece: Piece,
         promotion_ranks: &[i64],
         promotion_pieces: &[PieceType],
+        bounds: &WorldBounds,
     ) {
-        if in_bounds(to_x, to_y) {
+        if in_bounds(bounds, to_x, to_y) {
             if promotion_ranks.contains(&to_y) {
                 for &promo in promotion_pieces {
                     let mut m = Move::new(from, Coordinate::new(to_x, to_y), piece);
@@ -2056,8 +2463,8 @@ fn generate_pawn_quiet_moves(
     let to_y = from.y + direction;
     let to_x = from.x;
 
-    if board.get_piece(to_x, to_y).is_none() {
-        // Square is empty, can push
+    if board.get_piece(to_x, to_y).is_none() && in_bounds(&game_rules.world_bounds, to_x, to_y) {
+        // Square is empty and playable, can push
         add_pawn_move(
             out,
             *from,
@@ -2066,6 +2473,7 @@ fn generate_pawn_quiet_moves(
             *piece,
             promotion_ranks,
             promotion_pieces,
+            &game_rules.world_bounds,
         );
 
         // Double push if pawn has special rights
@@ -2080,6 +2488,7 @@ fn generate_pawn_quiet_moves(
                     *piece,
                     promotion_ranks,
                     promotion_pieces,
+                    &game_rules.world_bounds,
                 );
             }
         }
@@ -2089,6 +2498,7 @@ fn generate_pawn_quiet_moves(
 /// Generate leaper moves directly into an output buffer
 /// gen_type controls which move types to generate: All, Quiets only, or Captures only
 #[inline]
+#[allow(clippy::too_many_arguments)]
 pub fn generate_leaper_moves_into(
     board: &Board,
     from: &Coordinate,
@@ -2096,6 +2506,7 @@ pub fn generate_leaper_moves_into(
     m: i64,
     n: i64,
     gen_type: MoveGenType,
+    bounds: &WorldBounds,
     out: &mut MoveList,
 ) {
     let offsets = [
@@ -2114,7 +2525,7 @@ pub fn generate_leaper_moves_into(
         let to_y = from.y + dy;
 
         // Skip if outside world border
-        if !in_bounds(to_x, to_y) {
+        if !in_bounds(bounds, to_x, to_y) {
             continue;
         }
 
@@ -2142,6 +2553,7 @@ pub fn generate_compass_moves_into(
     piece: &Piece,
     distance: i64,
     gen_type: MoveGenType,
+    bounds: &WorldBounds,
     out: &mut MoveList,
 ) {
     let dist = distance;
@@ -2161,7 +2573,7 @@ pub fn generate_compass_moves_into(
         let to_y = from.y + dy;
 
         // Skip if outside world border
-        if !in_bounds(to_x, to_y) {
+        if !in_bounds(bounds, to_x, to_y) {
             continue;
         }
 
@@ -2181,15 +2593,17 @@ pub fn generate_compass_moves_into(
 }
 
 #[inline]
-fn ray_border_distance(from: &Coordinate, dir_x: i64, dir_y: i64) -> Option<i64> {
+fn ray_border_distance(
+    bounds: &WorldBounds,
+    from: &Coordinate,
+    dir_x: i64,
+    dir_y: i64,
+) -> Option<i64> {
     if dir_x == 0 && dir_y == 0 {
         return None;
     }
 
-    let min_x = COORD_MIN_X.load(Ordering::Relaxed);
-    let max_x = COORD_MAX_X.load(Ordering::Relaxed);
-    let min_y = COORD_MIN_Y.load(Ordering::Relaxed);
-    let max_y = COORD_MAX_Y.load(Ordering::Relaxed);
+    let (min_x, max_x, min_y, max_y) = bounds.as_tuple();
 
     const MAX_INF_DISTANCE: i64 = 256;
 
@@ -2487,6 +2901,7 @@ fn generate_sliding_moves_impl(
     let directions = ctx.directions;
     let indices = ctx.indices;
     let enemy_king_pos = ctx.enemy_king_pos;
+    let bounds = ctx.bounds;
 
     // Original wiggle values - important for tactics
     const ENEMY_WIGGLE: i64 = 2;
@@ -2543,9 +2958,19 @@ fn generate_sliding_moves_impl(
             let is_horizontal = dir_y == 0;
 
             // Use spatial indices for O(log n) blocker finding
-            let (closest_dist, closest_is_enemy) =
+            let (mut closest_dist, closest_is_enemy) =
                 find_blocker_via_indices(board, from, dir_x, dir_y, indices, our_color);
 
+            // Region holes also cut rays that have no piece on them
+            if closest_dist == i64::MAX
+                && let Some(gap) = ray_border_distance(bounds, from, dir_x, dir_y)
+                    .and_then(|d| bounds.region_gap(from.x, from.y, dir_x, dir_y, d))
+            {
+                closest_dist = gap;
+            }
+            let at_wall = closest_dist < i64::MAX
+                && !bounds.contains(from.x + dir_x * closest_dist, from.y + dir_y * closest_dist);
+
             let max_dist = if closest_dist < i64::MAX {
                 if closest_is_enemy {
                     closest_dist
@@ -2553,7 +2978,7 @@ fn generate_sliding_moves_impl(
                     closest_dist - 1
                 }
             } else {
-                match ray_border_distance(from, dir_x, dir_y) {
+                match ray_border_distance(bounds, from, dir_x, dir_y) {
                     Some(d) if d > 0 => d,
                     _ => 0,
                 }
@@ -2577,11 +3002,23 @@ fn generate_sliding_moves_impl(
             };
             let cache_key = (from.x, from.y, dir_index);
 
+            // A ray that hits the world border within short range (a small bounded board)
+            // is cheap to list in full. The interception heuristics would only drop real
+            // moves there, and their cache is keyed by square alone, so skip both.
+            let short_ray = ray_border_distance(bounds, from, dir_x, dir_y)
+                .is_some_and(|d| d <= BASE_INTERCEPTION_DIST);
+
             // Check cache first
-            let cached = indices.slider_cache.borrow().get(&cache_key).cloned();
+            let cached = if short_ray {
+                None
+            } else {
+                indices.slider_cache.borrow().get(&cache_key).cloned()
+            };
             let mut target_dists: Vec<i64> = Vec::with_capacity(64);
 
-            if let Some(cached_dists) = cached {
+            if short_ray {
+                target_dists.extend(1..=max_dist);
+            } else if let Some(cached_dists) = cached {
                 target_dists.extend(cached_dists);
             } else {
                 dist_counts.clear();
@@ -2901,6 +3338,10 @@ fn generate_sliding_moves_impl(
                 if closest_dist < i64::MAX && closest_is_enemy {
                     shared_targets.push(closest_dist);
                 }
+                // The square in front of a region wall
+                if at_wall && closest_dist > 1 {
+                    shared_targets.push(closest_dist - 1);
+                }
 
                 for (&d, &count) in &dist_counts {
                     if d <= BASE_INTERCEPTION_DIST || count >= 2 || royal_dists.contains(&d) {
@@ -2936,7 +3377,7 @@ fn generate_sliding_moves_impl(
 
                 let sq_x = from.x + dir_x * d;
                 let sq_y = from.y + dir_y * d;
-                if in_bounds(sq_x, sq_y) {
+                if in_bounds(bounds, sq_x, sq_y) {
                     out.push(Move::new(*from, Coordinate::new(sq_x, sq_y), *piece));
                 }
             }
@@ -2982,6 +3423,11 @@ fn find_blocker_via_indices(
                 return (i64::MAX, false);
             }
 
+            // A hole in the playable region stops the ray like an uncapturable piece
+            if let Some(gap) = indices.region_gap(from.x, from.y, dir_x, dir_y, dist) {
+                return (gap, false);
+            }
+
             let piece = Piece::from_packed(packed);
             // Obstacles are neutral but capturable - check is_uncapturable()
             let is_enemy = piece.color() != our_color && !piece.piece_type().is_uncapturable();
@@ -2999,6 +3445,7 @@ pub fn generate_huygen_moves_into(
     piece: &Piece,
     indices: &SpatialIndices,
     gen_type: MoveGenType,
+    bounds: &WorldBounds,
     out: &mut MoveList,
 ) {
     let my_color = piece.color();
@@ -3011,9 +3458,20 @@ pub fn generate_huygen_moves_into(
 
     for &(dir_x, dir_y) in &ORTHO_DIRECTIONS {
         // Find the closest blocker at a prime distance in this direction
-        let (blocker_dist, blocker_color) =
+        let (mut blocker_dist, mut blocker_color) =
             find_huygen_blocker(board, from, dir_x, dir_y, indices, my_color);
 
+        // Region holes at a prime distance block like Void
+        if bounds.region.is_some()
+            && let Some(&gap) = PRIMES_UNDER_128
+                .iter()
+                .take_while(|&&p| p < blocker_dist)
+                .find(|&&p| !bounds.contains(from.x + dir_x * p, from.y + dir_y * p))
+        {
+            blocker_dist = gap;
+            blocker_color = Some(my_color);
+        }
+
         if blocker_dist < i64::MAX {
             // CASE 1: Blocker found at prime distance
             // Generate all prime-distance moves up to (and including if capturable) the blocker
@@ -3074,7 +3532,7 @@ pub fn generate_huygen_moves_into(
                         indices.rows.get(&to_y).is_some_and(|v| !v.is_empty())
                     };
 
-                    if in_bounds(to_x, to_y) && (aligned || prime_dist <= 3) {
+                    if in_bounds(bounds, to_x, to_y) && (aligned || prime_dist <= 3) {
                         out.push(Move::new(*from, Coordinate::new(to_x, to_y), *piece));
                     }
                 }
@@ -3223,6 +3681,7 @@ pub fn generate_rose_moves_into(
     from: &Coordinate,
     piece: &Piece,
     gen_type: MoveGenType,
+    bounds: &WorldBounds,
     out: &mut MoveList,
 ) {
     let my_color = piece.color();
@@ -3256,7 +3715,7 @@ pub fn generate_rose_moves_into(
                 let ty = fy + cum_dy;
 
                 // Skip if outside world border
-                if !in_bounds(tx, ty) {
+                if !in_bounds(bounds, tx, ty) {
                     break;
                 }
 
@@ -3327,6 +3786,7 @@ fn generate_pawn_moves_into(
 
     // Helper function for promotion moves
     #[inline]
+    #[allow(clippy::too_many_arguments)]
     fn add_pawn_move(
         out: &mut MoveList,
         from: Coordinate,
@@ -3335,8 +3795,9 @@ fn generate_pawn_moves_into(
         piece: Piece,
         promotion_ranks: &[i64],
         promotion_pieces: &[PieceType],
+        bounds: &WorldBounds,
     ) {
-        if in_bounds(to_x, to_y) {
+        if in_bounds(bounds, to_x, to_y) {
             if promotion_ranks.contains(&to_y) {
                 for &promo in promotion_pieces {
                     let mut m = Move::new(from, Coordinate::new(to_x, to_y), piece);
@@ -3352,7 +3813,8 @@ fn generate_pawn_moves_into(
     // Move forward 1
     let to_y = from.y + direction;
     let to_x = from.x;
-    let forward_blocked = board.is_occupied(to_x, to_y);
+    let forward_blocked =
+        board.is_occupied(to_x, to_y) || !in_bounds(&game_rules.world_bounds, to_x, to_y);
 
     if !forward_blocked {
         add_pawn_move(
@@ -3363,6 +3825,7 @@ fn generate_pawn_moves_into(
             *piece,
             promotion_ranks,
             promotion_pieces,
+            &game_rules.world_bounds,
         );
 
         // Double push (can also result in promotion in some variants)
@@ -3377,6 +3840,7 @@ fn generate_pawn_moves_into(
                     *piece,
                     promotion_ranks,
                     promotion_pieces,
+                    &game_rules.world_bounds,
                 );
             }
         }
@@ -3397,6 +3861,7 @@ fn generate_pawn_moves_into(
                     *piece,
                     promotion_ranks,
                     promotion_pieces,
+                    &game_rules.world_bounds,
                 );
             }
         } else if en_passant
@@ -3411,6 +3876,7 @@ fn generate_pawn_moves_into(
                 *piece,
                 promotion_ranks,
                 promotion_pieces,
+                &game_rules.world_bounds,
             );
         }
     }
@@ -3418,7 +3884,7 @@ fn generate_pawn_moves_into(
 
 /// Generate castling moves directly into an output buffer
 #[inline]
-fn generate_castling_moves_into(
+pub(crate) fn generate_castling_moves_into(
     board: &Board,
     from: &Coordinate,
     piece: &Piece,
@@ -3427,6 +3893,18 @@ fn generate_castling_moves_into(
     indices: &SpatialIndices,
     out: &mut MoveList,
 ) {
+    if game_rules.chess960 {
+        generate_chess960_castling_into(
+            board,
+            from,
+            piece,
+            special_rights,
+            game_rules,
+            indices,
+            out,
+        );
+        return;
+    }
     if !special_rights.contains(from) {
         return;
     }
@@ -3444,7 +3922,9 @@ fn generate_castling_moves_into(
                 let dir = if dx > 0 { 1i64 } else { -1i64 };
 
                 // Use spatial indices to check path - O(log n) instead of O(distance)
-                let mut clear = true;
+                let mut clear = indices
+                    .region_gap(from.x, from.y, dir, 0, dx.abs())
+                    .is_none();
                 if let Some((nearest_x, _)) = indices
                     .rows
                     .get(&from.y)
@@ -3480,6 +3960,83 @@ fn generate_castling_moves_into(
     }
 }
 
+/// Fischer-random castling: the king goes to the g-file (c-file) of the bounded
+/// board and its partner to the f-file (d-file), wherever the two started. Every
+/// square either piece crosses must be empty apart from the two of them, and the
+/// king may not start on, pass through or land on an attacked square. The king
+/// may stay put or land on its partner's square, so `to` can equal `from`.
+fn generate_chess960_castling_into(
+    board: &Board,
+    from: &Coordinate,
+    piece: &Piece,
+    special_rights: &FxHashSet<Coordinate>,
+    game_rules: &GameRules,
+    indices: &SpatialIndices,
+    out: &mut MoveList,
+) {
+    if !special_rights.contains(from) {
+        return;
+    }
+
+    let bounds = &game_rules.world_bounds;
+    let opponent = piece.color().opponent();
+    let opponent_can_checkmate = match piece.color() {
+        PlayerColor::White => game_rules.black_win_condition.requires_check_evasion(),
+        PlayerColor::Black => game_rules.white_win_condition.requires_check_evasion(),
+        PlayerColor::Neutral => true,
+    };
+
+    for coord in special_rights.iter() {
+        if coord.y != from.y || coord.x == from.x {
+            continue;
+        }
+        if !board.get_piece(coord.x, coord.y).is_some_and(|p| {
+            p.color() == piece.color()
+                && p.piece_type() != PieceType::Pawn
+                && !p.piece_type().is_royal()
+        }) {
+            continue;
+        }
+
+        let (king_to_x, rook_to_x) = if coord.x > from.x {
+            (bounds.max_x - 1, bounds.max_x - 2)
+        } else {
+            (bounds.min_x + 2, bounds.min_x + 3)
+        };
+
+        let lo = from.x.min(coord.x).min(king_to_x).min(rook_to_x);
+        let hi = from.x.max(coord.x).max(king_to_x).max(rook_to_x);
+        let clear =
+            (lo..=hi).all(|x| x == from.x || x == coord.x || board.get_piece(x, from.y).is_none());
+        if !clear {
+            continue;
+        }
+
+        if opponent_can_checkmate {
+            let step = if king_to_x >= from.x { 1 } else { -1 };
+            let mut x = from.x;
+            let mut safe = true;
+            loop {
+                if is_square_attacked(board, &Coordinate::new(x, from.y), opponent, indices) {
+                    safe = false;
+                    break;
+                }
+                if x == king_to_x {
+                    break;
+                }
+                x += step;
+            }
+            if !safe {
+                continue;
+            }
+        }
+
+        let mut castling_move = Move::new(*from, Coordinate::new(king_to_x, from.y), *piece);
+        castling_move.rook_coord = Some(*coord);
+        out.push(castling_move);
+    }
+}
+
 /// Generate sliding moves directly into an output buffer
 #[inline]
 pub fn generate_sliding_moves_into(ctx: &SlidingMoveContext, out: &mut MoveList) {
@@ -3500,9 +4057,10 @@ pub fn generate_knightrider_moves_into(
     from: &Coordinate,
     piece: &Piece,
     gen_type: MoveGenType,
+    bounds: &WorldBounds,
     out: &mut MoveList,
 ) {
-    let moves = generate_knightrider_moves(board, from, piece);
+    let moves = generate_knightrider_moves(board, from, piece, bounds);
     for m in moves {
         let is_capture = board.is_occupied(m.to.x, m.to.y);
         // Filter based on gen_type
@@ -3520,93 +4078,125 @@ pub fn generate_knightrider_moves_into(
 mod tests {
     use super::*;
     use crate::game::GameState;
-    use std::sync::Mutex;
-    use std::sync::OnceLock;
 
-    static BOUNDS_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+    // ======================== Bounds Tests ========================
 
-    fn get_bounds_lock() -> &'static Mutex<()> {
-        BOUNDS_LOCK.get_or_init(|| Mutex::new(()))
+    #[test]
+    fn test_in_bounds_default() {
+        let bounds = &WorldBounds::default();
+        // Default bounds are very large (-1e15 to 1e15)
+        assert!(in_bounds(bounds, 0, 0));
+        assert!(in_bounds(bounds, 1000, 1000));
+        assert!(in_bounds(bounds, -1000, -1000));
+        assert!(in_bounds(bounds, 1_000_000_000, 1_000_000_000));
     }
 
-    // Helper function to reset world bounds to defaults
-    fn reset_world_bounds() {
-        set_world_bounds(
-            -1_000_000_000_000_000,
-            1_000_000_000_000_000,
-            -1_000_000_000_000_000,
-            1_000_000_000_000_000,
-        );
+    #[test]
+    fn test_world_bounds_new() {
+        let bounds = &WorldBounds::new(-100, 100, -50, 50);
+
+        assert!(in_bounds(bounds, 0, 0));
+        assert!(in_bounds(bounds, 100, 50));
+        assert!(in_bounds(bounds, -100, -50));
+        assert!(!in_bounds(bounds, 101, 0));
+        assert!(!in_bounds(bounds, 0, 51));
+
+        // Swapped edges are normalised
+        assert_eq!(WorldBounds::new(100, -100, 50, -50), *bounds);
     }
-    
-    // Helper to acquire bounds lock for tests that modify bounds
-    fn with_bounds_lock<F, R>(f: F) -> R
-    where
-        F: FnOnce() -> R,
-    {
-        let _guard = get_bounds_lock().lock().unwrap_or_else(|e| e.into_inner());
-        f()
+
+    #[test]
+    fn test_world_bounds_size() {
+        let bounds = WorldBounds::new(-100, 100, -50, 50);
+        assert_eq!(bounds.size(), 200, "Width is larger than height");
     }
-    
-    // ======================== Bounds Tests ========================
 
     #[test]
-    fn test_in_bounds_default() {
-        with_bounds_lock(|| {
-            reset_world_bounds();
-            // Default bounds are very large (-1e15 to 1e15)
-            assert!(in_bounds(0, 0));
-            assert!(in_bounds(1000, 1000));
-            assert!(in_bounds(-1000, -1000));
-            assert!(in_bounds(1_000_000_000, 1_000_000_000));
-        });
+    fn test_world_bounds_as_tuple() {
+        let bounds = WorldBounds::new(-10, 20, -30, 40);
+        assert_eq!(bounds.as_tuple(), (-10, 20, -30, 40));
     }
 
     #[test]
-    fn test_set_world_bounds() {
-        with_bounds_lock(|| {
-            reset_world_bounds();
-            // Set custom bounds
-            set_world_bounds(-100, 100, -50, 50);
-
-            assert!(in_bounds(0, 0));
-            assert!(in_bounds(100, 50));
-            assert!(in_bounds(-100, -50));
-            assert!(!in_bounds(101, 0));
-            assert!(!in_bounds(0, 51));
-
-            // Reset to large defaults
-            reset_world_bounds();
-        });
+    fn test_playable_region_contains_and_ray_limit() {
+        // Two 11x11 boxes joined on x = 10/11 with a 3x3 hole in the first.
+        let bounds = WorldBounds::from_region(
+            vec![Rect::new(0, 10, 0, 10), Rect::new(11, 21, 0, 10)],
+            vec![Rect::new(4, 6, 4, 6)],
+        );
+        assert_eq!(bounds.as_tuple(), (0, 21, 0, 10));
+        assert!(bounds.contains(0, 0));
+        assert!(bounds.contains(15, 5));
+        assert!(!bounds.contains(5, 5));
+        assert!(!bounds.contains(22, 5));
+
+        let region = bounds.region.as_deref().unwrap();
+        // East from (0,5): stops just before the hole.
+        assert_eq!(region.ray_limit(0, 5, 1, 0, 100), 3);
+        assert_eq!(region.first_gap(0, 5, 1, 0, 100), Some(4));
+        // East from (0,0): runs through both boxes to x = 21.
+        assert_eq!(region.ray_limit(0, 0, 1, 0, 100), 21);
+        assert_eq!(region.ray_limit(0, 0, 1, 0, 5), 5);
+        assert_eq!(region.first_gap(0, 0, 1, 0, 21), None);
+        // Diagonal from (0,0) hits the hole at (4,4).
+        assert_eq!(region.first_gap(0, 0, 1, 1, 100), Some(4));
+        // Knight direction from (0,0): (2,1), (4,2), (6,3), (8,4), (10,5), (12,6)...
+        assert_eq!(region.ray_limit(0, 0, 2, 1, 100), 10);
+
+        // A single box is a plain border.
+        assert!(
+            WorldBounds::from_region(vec![Rect::new(1, 8, 1, 8)], vec![])
+                .region
+                .is_none()
+        );
     }
 
     #[test]
-    fn test_get_world_size() {
-        with_bounds_lock(|| {
-            reset_world_bounds();
-            set_world_bounds(-100, 100, -50, 50);
-            let size = get_world_size();
-            assert_eq!(size, 200, "Width is larger than height");
-
-            // Reset
-            reset_world_bounds();
-        });
+    fn test_world_bounds_icn_round_trip() {
+        for token in [
+            "1,8,1,8",
+            "-8,18,-8,17~0,2,0,2",
+            "0,10,0,10+11,21,0,10~4,6,4,6~1,1,9,9",
+        ] {
+            assert!(WorldBounds::is_icn_token(token));
+            let bounds = WorldBounds::from_icn(token).unwrap();
+            assert_eq!(bounds.to_icn(), token);
+        }
+        assert!(!WorldBounds::is_icn_token("1,8,1"));
+        assert!(!WorldBounds::is_icn_token("1,8,1,8~"));
+        assert!(WorldBounds::from_icn("1,8,1,8+1,2").is_none());
     }
 
     #[test]
-    fn test_get_coord_bounds() {
-        with_bounds_lock(|| {
-            reset_world_bounds();
-            set_world_bounds(-10, 20, -30, 40);
-            let (min_x, max_x, min_y, max_y) = get_coord_bounds();
-            assert_eq!(min_x, -10);
-            assert_eq!(max_x, 20);
-            assert_eq!(min_y, -30);
-            assert_eq!(max_y, 40);
-
-            // Reset
-            reset_world_bounds();
-        });
+    fn test_region_hole_blocks_sliders_and_attacks() {
+        let mut game = GameState::new();
+        game.setup_position_from_icn("w 0,20,0,20~5,5,0,20 R0,2|K0,0|k10,2|q10,0");
+
+        let rook = Coordinate::new(0, 2);
+        let moves = game.get_legal_moves();
+        assert!(
+            moves
+                .iter()
+                .any(|m| m.from == rook && m.to == Coordinate::new(4, 2))
+        );
+        assert!(!moves.iter().any(|m| m.from == rook && m.to.x > 5));
+
+        let indices = &game.spatial_indices;
+        assert!(indices.find_first_blocker(0, 2, 1, 0).is_none());
+        assert!(!is_square_attacked(
+            &game.board,
+            &Coordinate::new(10, 2),
+            PlayerColor::White,
+            indices
+        ));
+        // The queen's rank is cut by the same wall.
+        assert!(!is_square_attacked(
+            &game.board,
+            &Coordinate::new(0, 0),
+            PlayerColor::Black,
+            indices
+        ));
+        assert!(!game.is_move_illegal());
     }
 
     // ======================== SpatialIndices Tests ========================
@@ -3756,133 +4346,157 @@ mod tests {
 
     #[test]
     fn test_knight_moves_generation() {
-        with_bounds_lock(|| {
-            reset_world_bounds();
-            let mut game = GameState::new();
-            game.setup_position_from_icn("w N4,4");
+        let mut game = GameState::new();
+        game.setup_position_from_icn("w N4,4");
 
-            let from = Coordinate::new(4, 4);
-            let piece = Piece::new(PieceType::Knight, PlayerColor::White);
+        let from = Coordinate::new(4, 4);
+        let piece = Piece::new(PieceType::Knight, PlayerColor::White);
 
-            let mut moves = MoveList::new();
-            generate_leaper_moves_into(&game.board, &from, &piece, 1, 2, MoveGenType::All, &mut moves);
-
-            // Knight has 8 possible moves from center
-            assert_eq!(moves.len(), 8, "Knight should have 8 moves from (4,4)");
-
-            // Check specific squares
-            let expected = [
-                (5, 6),
-                (6, 5),
-                (6, 3),
-                (5, 2),
-                (3, 2),
-                (2, 3),
-                (2, 5),
-                (3, 6),
-            ];
-            for (x, y) in expected {
-                assert!(
-                    moves.iter().any(|m| m.to.x == x && m.to.y == y),
-                    "Knight should be able to move to ({}, {})",
-                    x,
-                    y
-                );
-            }
-            reset_world_bounds();
-        });
+        let mut moves = MoveList::new();
+        generate_leaper_moves_into(
+            &game.board,
+            &from,
+            &piece,
+            1,
+            2,
+            MoveGenType::All,
+            &game.game_rules.world_bounds,
+            &mut moves,
+        );
+
+        // Knight has 8 possible moves from center
+        assert_eq!(moves.len(), 8, "Knight should have 8 moves from (4,4)");
+
+        // Check specific squares
+        let expected = [
+            (5, 6),
+            (6, 5),
+            (6, 3),
+            (5, 2),
+            (3, 2),
+            (2, 3),
+            (2, 5),
+            (3, 6),
+        ];
+        for (x, y) in expected {
+            assert!(
+                moves.iter().any(|m| m.to.x == x && m.to.y == y),
+                "Knight should be able to move to ({}, {})",
+                x,
+                y
+            );
+        }
     }
 
     #[test]
     fn test_king_moves_generation() {
-        with_bounds_lock(|| {
-            reset_world_bounds();
-            let mut game = GameState::new();
-            game.setup_position_from_icn("w K4,4");
-
-            let from = Coordinate::new(4, 4);
-            let piece = Piece::new(PieceType::King, PlayerColor::White);
-
-            let mut moves = MoveList::new();
-            generate_compass_moves_into(&game.board, &from, &piece, 1, MoveGenType::All, &mut moves);
+        let mut game = GameState::new();
+        game.setup_position_from_icn("w K4,4");
+
+        let from = Coordinate::new(4, 4);
+        let piece = Piece::new(PieceType::King, PlayerColor::White);
+
+        let mut moves = MoveList::new();
+        generate_compass_moves_into(
+            &game.board,
+            &from,
+            &piece,
+            1,
+            MoveGenType::All,
+            &game.game_rules.world_bounds,
+            &mut moves,
+        );
 
-            // King has 8 possible moves from center
-            assert_eq!(moves.len(), 8, "King should have 8 moves from (4,4)");
-            reset_world_bounds();
-        });
+        // King has 8 possible moves from center
+        assert_eq!(moves.len(), 8, "King should have 8 moves from (4,4)");
     }
 
     #[test]
     fn test_fairy_piece_camel() {
-        with_bounds_lock(|| {
-            reset_world_bounds();
-            let mut game = GameState::new();
-            game.setup_position_from_icn("w Ca4,4");
-
-            let from = Coordinate::new(4, 4);
-            let piece = Piece::new(PieceType::Camel, PlayerColor::White);
-
-            let mut moves = MoveList::new();
-            generate_leaper_moves_into(&game.board, &from, &piece, 1, 3, MoveGenType::All, &mut moves);
+        let mut game = GameState::new();
+        game.setup_position_from_icn("w Ca4,4");
+
+        let from = Coordinate::new(4, 4);
+        let piece = Piece::new(PieceType::Camel, PlayerColor::White);
+
+        let mut moves = MoveList::new();
+        generate_leaper_moves_into(
+            &game.board,
+            &from,
+            &piece,
+            1,
+            3,
+            MoveGenType::All,
+            &game.game_rules.world_bounds,
+            &mut moves,
+        );
 
-            // Camel leaps (1,3) - 8 squares
-            assert_eq!(moves.len(), 8, "Camel should have 8 moves from (4,4)");
+        // Camel leaps (1,3) - 8 squares
+        assert_eq!(moves.len(), 8, "Camel should have 8 moves from (4,4)");
 
-            // Check a specific camel square
-            assert!(
-                moves.iter().any(|m| m.to.x == 5 && m.to.y == 7),
-                "Camel should be able to move to (5, 7)"
-            );
-            reset_world_bounds();
-        });
+        // Check a specific camel square
+        assert!(
+            moves.iter().any(|m| m.to.x == 5 && m.to.y == 7),
+            "Camel should be able to move to (5, 7)"
+        );
     }
 
     #[test]
     fn test_fairy_piece_zebra() {
-        with_bounds_lock(|| {
-            reset_world_bounds();
-            let mut game = GameState::new();
-            game.setup_position_from_icn("w Z4,4");
-
-            let from = Coordinate::new(4, 4);
-            let piece = Piece::new(PieceType::Zebra, PlayerColor::White);
-
-            let mut moves = MoveList::new();
-            generate_leaper_moves_into(&game.board, &from, &piece, 2, 3, MoveGenType::All, &mut moves);
+        let mut game = GameState::new();
+        game.setup_position_from_icn("w Z4,4");
+
+        let from = Coordinate::new(4, 4);
+        let piece = Piece::new(PieceType::Zebra, PlayerColor::White);
+
+        let mut moves = MoveList::new();
+        generate_leaper_moves_into(
+            &game.board,
+            &from,
+            &piece,
+            2,
+            3,
+            MoveGenType::All,
+            &game.game_rules.world_bounds,
+            &mut moves,
+        );
 
-            // Zebra leaps (2,3) - 8 squares
-            assert_eq!(moves.len(), 8, "Zebra should have 8 moves from (4,4)");
-            reset_world_bounds();
-        });
+        // Zebra leaps (2,3) - 8 squares
+        assert_eq!(moves.len(), 8, "Zebra should have 8 moves from (4,4)");
     }
 
     #[test]
     fn test_negative_coordinates() {
-        with_bounds_lock(|| {
-            reset_world_bounds();
-            // Test that piece at negative coordinates generates moves correctly
-            let mut game = GameState::new();
-            game.setup_position_from_icn("w N-100,-100");
+        // Test that piece at negative coordinates generates moves correctly
+        let mut game = GameState::new();
+        game.setup_position_from_icn("w N-100,-100");
 
-            let from = Coordinate::new(-100, -100);
-            let piece = Piece::new(PieceType::Knight, PlayerColor::White);
+        let from = Coordinate::new(-100, -100);
+        let piece = Piece::new(PieceType::Knight, PlayerColor::White);
 
-            let mut moves = MoveList::new();
-            generate_leaper_moves_into(&game.board, &from, &piece, 1, 2, MoveGenType::All, &mut moves);
+        let mut moves = MoveList::new();
+        generate_leaper_moves_into(
+            &game.board,
+            &from,
+            &piece,
+            1,
+            2,
+            MoveGenType::All,
+            &game.game_rules.world_bounds,
+            &mut moves,
+        );
 
-            assert_eq!(
-                moves.len(),
-                8,
-                "Knight at negative coords should have 8 moves"
-            );
+        assert_eq!(
+            moves.len(),
+            8,
+            "Knight at negative coords should have 8 moves"
+        );
 
-            // Check one of the expected squares
-            assert!(
-                moves.iter().any(|m| m.to.x == -99 && m.to.y == -98),
-                "Knight should be able to move to (-99, -98)"
-            );
-            reset_world_bounds();
-        });
+        // Check one of the expected squares
+        assert!(
+            moves.iter().any(|m| m.to.x == -99 && m.to.y == -98),
+            "Knight should be able to move to (-99, -98)"
+        );
     }
 
     #[test]
@@ -3897,180 +4511,169 @@ mod tests {
 
     #[test]
     fn test_generate_pawn_moves() {
-        with_bounds_lock(|| {
-            reset_world_bounds();
-            let mut game = GameState::new();
-            game.setup_position_from_icn("w (8;q|1;q) P4,2|p5,3");
-
-            let from = Coordinate::new(4, 2);
-            let piece = Piece::new(PieceType::Pawn, PlayerColor::White);
-
-            let special = FxHashSet::default();
-            let mut moves = MoveList::new();
-            generate_pawn_moves_into(&game.board, &from, &piece, &special, &None, &game.game_rules, &mut moves);
+        let mut game = GameState::new();
+        game.setup_position_from_icn("w (8;q|1;q) P4,2|p5,3");
+
+        let from = Coordinate::new(4, 2);
+        let piece = Piece::new(PieceType::Pawn, PlayerColor::White);
+
+        let special = FxHashSet::default();
+        let mut moves = MoveList::new();
+        generate_pawn_moves_into(
+            &game.board,
+            &from,
+            &piece,
+            &special,
+            &None,
+            &game.game_rules,
+            &mut moves,
+        );
 
-            assert!(moves.len() >= 2, "Pawn should have at least 2 moves");
-            // Should include forward move and capture
-            assert!(
-                moves.iter().any(|m| m.to.y == 3 && m.to.x == 4),
-                "Forward move"
-            );
-            assert!(moves.iter().any(|m| m.to.y == 3 && m.to.x == 5), "Capture");
-            reset_world_bounds();
-        });
+        assert!(moves.len() >= 2, "Pawn should have at least 2 moves");
+        // Should include forward move and capture
+        assert!(
+            moves.iter().any(|m| m.to.y == 3 && m.to.x == 4),
+            "Forward move"
+        );
+        assert!(moves.iter().any(|m| m.to.y == 3 && m.to.x == 5), "Capture");
     }
 
     #[test]
     fn test_generate_sliding_moves_rook() {
-        with_bounds_lock(|| {
-            reset_world_bounds();
-            let mut game = GameState::new();
-            game.setup_position_from_icn("w R4,4");
-
-            let from = Coordinate::new(4, 4);
-            let piece = Piece::new(PieceType::Rook, PlayerColor::White);
-
-            let ortho = &[(1, 0), (-1, 0), (0, 1), (0, -1)];
-            let mut moves = MoveList::new();
-            generate_sliding_moves_into(
-                &SlidingMoveContext {
-                    board: &game.board,
-                    from: &from,
-                    piece: &piece,
-                    directions: ortho,
-                    indices: &game.spatial_indices,
-                    enemy_king_pos: None,
-                    visited_targets: None,
-                    pinned: &FxHashMap::default(),
-                },
-                &mut moves,
-            );
+        let mut game = GameState::new();
+        game.setup_position_from_icn("w R4,4");
+
+        let from = Coordinate::new(4, 4);
+        let piece = Piece::new(PieceType::Rook, PlayerColor::White);
+
+        let ortho = &[(1, 0), (-1, 0), (0, 1), (0, -1)];
+        let mut moves = MoveList::new();
+        generate_sliding_moves_into(
+            &SlidingMoveContext {
+                board: &game.board,
+                from: &from,
+                piece: &piece,
+                directions: ortho,
+                indices: &game.spatial_indices,
+                enemy_king_pos: None,
+                visited_targets: None,
+                pinned: &FxHashMap::default(),
+                bounds: &game.game_rules.world_bounds,
+            },
+            &mut moves,
+        );
 
-            // Rook on empty board should have many moves (limited by fallback)
-            assert!(!moves.is_empty(), "Rook should have some moves");
-            reset_world_bounds();
-        });
+        // Rook on empty board should have many moves (limited by fallback)
+        assert!(!moves.is_empty(), "Rook should have some moves");
     }
 
     #[test]
     fn test_generate_sliding_moves_bishop() {
-        with_bounds_lock(|| {
-            reset_world_bounds();
-            let mut game = GameState::new();
-            game.setup_position_from_icn("w B4,4");
-
-            let from = Coordinate::new(4, 4);
-            let piece = Piece::new(PieceType::Bishop, PlayerColor::White);
-
-            let diag = &[(1, 1), (1, -1), (-1, 1), (-1, -1)];
-            let mut moves = MoveList::new();
-            generate_sliding_moves_into(
-                &SlidingMoveContext {
-                    board: &game.board,
-                    from: &from,
-                    piece: &piece,
-                    directions: diag,
-                    indices: &game.spatial_indices,
-                    enemy_king_pos: None,
-                    visited_targets: None,
-                    pinned: &FxHashMap::default(),
-                },
-                &mut moves,
-            );
+        let mut game = GameState::new();
+        game.setup_position_from_icn("w B4,4");
+
+        let from = Coordinate::new(4, 4);
+        let piece = Piece::new(PieceType::Bishop, PlayerColor::White);
+
+        let diag = &[(1, 1), (1, -1), (-1, 1), (-1, -1)];
+        let mut moves = MoveList::new();
+        generate_sliding_moves_into(
+            &SlidingMoveContext {
+                board: &game.board,
+                from: &from,
+                piece: &piece,
+                directions: diag,
+                indices: &game.spatial_indices,
+                enemy_king_pos: None,
+                visited_targets: None,
+                pinned: &FxHashMap::default(),
+                bounds: &game.game_rules.world_bounds,
+            },
+            &mut moves,
+        );
 
-            assert!(!moves.is_empty(), "Bishop should have some moves");
-            reset_world_bounds();
-        });
+        assert!(!moves.is_empty(), "Bishop should have some moves");
     }
 
     #[test]
     fn test_is_square_attacked_by_knight() {
-        with_bounds_lock(|| {
-            reset_world_bounds();
-            let mut game = GameState::new();
-            game.setup_position_from_icn("w N4,4");
-
-            let target_attacked = Coordinate::new(5, 6); // Knight can attack this
-            let target_not_attacked = Coordinate::new(4, 5); // Knight cannot attack this
-
-            assert!(is_square_attacked(
-                &game.board,
-                &target_attacked,
-                PlayerColor::White,
-                &game.spatial_indices
-            ));
-            assert!(!is_square_attacked(
-                &game.board,
-                &target_not_attacked,
-                PlayerColor::White,
-                &game.spatial_indices
-            ));
-            reset_world_bounds();
-        });
+        let mut game = GameState::new();
+        game.setup_position_from_icn("w N4,4");
+
+        let target_attacked = Coordinate::new(5, 6); // Knight can attack this
+        let target_not_attacked = Coordinate::new(4, 5); // Knight cannot attack this
+
+        assert!(is_square_attacked(
+            &game.board,
+            &target_attacked,
+            PlayerColor::White,
+            &game.spatial_indices
+        ));
+        assert!(!is_square_attacked(
+            &game.board,
+            &target_not_attacked,
+            PlayerColor::White,
+            &game.spatial_indices
+        ));
     }
 
     #[test]
     fn test_is_square_attacked_by_rook() {
-        with_bounds_lock(|| {
-            reset_world_bounds();
-            let mut game = GameState::new();
-            game.setup_position_from_icn("w R4,4");
-
-            let target_file = Coordinate::new(4, 10); // Same file
-            let target_rank = Coordinate::new(10, 4); // Same rank
-
-            assert!(is_square_attacked(
-                &game.board,
-                &target_file,
-                PlayerColor::White,
-                &game.spatial_indices
-            ));
-            assert!(is_square_attacked(
-                &game.board,
-                &target_rank,
-                PlayerColor::White,
-                &game.spatial_indices
-            ));
-            reset_world_bounds();
-        });
+        let mut game = GameState::new();
+        game.setup_position_from_icn("w R4,4");
+
+        let target_file = Coordinate::new(4, 10); // Same file
+        let target_rank = Coordinate::new(10, 4); // Same rank
+
+        assert!(is_square_attacked(
+            &game.board,
+            &target_file,
+            PlayerColor::White,
+            &game.spatial_indices
+        ));
+        assert!(is_square_attacked(
+            &game.board,
+            &target_rank,
+            PlayerColor::White,
+            &game.spatial_indices
+        ));
     }
 
     #[test]
     fn test_is_square_attacked_blocked() {
-        with_bounds_lock(|| {
-            reset_world_bounds();
-            let mut game = GameState::new();
-            game.setup_position_from_icn("w R4,4|P4,6");
+        let mut game = GameState::new();
+        game.setup_position_from_icn("w R4,4|P4,6");
 
-            let target_blocked = Coordinate::new(4, 10); // Blocked by pawn at (4,6)
+        let target_blocked = Coordinate::new(4, 10); // Blocked by pawn at (4,6)
 
-            assert!(!is_square_attacked(
-                &game.board,
-                &target_blocked,
-                PlayerColor::White,
-                &game.spatial_indices
-            ));
-            reset_world_bounds();
-        });
+        assert!(!is_square_attacked(
+            &game.board,
+            &target_blocked,
+            PlayerColor::White,
+            &game.spatial_indices
+        ));
     }
 
     #[test]
     fn test_generate_castling_moves() {
-        with_bounds_lock(|| {
-            reset_world_bounds();
-            let mut game = GameState::new();
-            game.setup_position_from_icn("w K5,1+|R8,1+");
-
-            let from = Coordinate::new(5, 1);
-            let piece = Piece::new(PieceType::King, PlayerColor::White);
-
-            let moves = generate_castling_moves(&game.board, &from, &piece, &game.special_rights, &game.game_rules, &game.spatial_indices);
+        let mut game = GameState::new();
+        game.setup_position_from_icn("w K5,1+|R8,1+");
+
+        let from = Coordinate::new(5, 1);
+        let piece = Piece::new(PieceType::King, PlayerColor::White);
+
+        let moves = generate_castling_moves(
+            &game.board,
+            &from,
+            &piece,
+            &game.special_rights,
+            &game.game_rules,
+            &game.spatial_indices,
+        );
 
-            // Test that the function runs without panicking and returns a MoveList
-            // Castling availability depends on variant rules and board state
-            let _ = moves.len();
-            reset_world_bounds();
-        });
+        // Test that the function runs without panicking and returns a MoveList
+        // Castling availability depends on variant rules and board state
+        let _ = moves.len();
     }
 
     #[test]
@@ -4078,28 +4681,32 @@ mod tests {
         let from = Coordinate::new(0, 0);
 
         // Moving right (positive x)
-        let dist = ray_border_distance(&from, 1, 0);
+        let dist = ray_border_distance(&WorldBounds::default(), &from, 1, 0);
         assert!(dist.is_some());
         assert!(dist.unwrap() > 0);
     }
 
     #[test]
     fn test_generate_compass_moves() {
-        with_bounds_lock(|| {
-            reset_world_bounds();
-            let mut game = GameState::new();
-            game.setup_position_from_icn("w H4,4");
-
-            let from = Coordinate::new(4, 4);
-            let piece = Piece::new(PieceType::Hawk, PlayerColor::White);
-
-            let mut moves = MoveList::new();
-            generate_compass_moves_into(&game.board, &from, &piece, 2, MoveGenType::All, &mut moves);
+        let mut game = GameState::new();
+        game.setup_position_from_icn("w H4,4");
+
+        let from = Coordinate::new(4, 4);
+        let piece = Piece::new(PieceType::Hawk, PlayerColor::White);
+
+        let mut moves = MoveList::new();
+        generate_compass_moves_into(
+            &game.board,
+            &from,
+            &piece,
+            2,
+            MoveGenType::All,
+            &game.game_rules.world_bounds,
+            &mut moves,
+        );
 
-            // Distance 2 compass should have 8 moves (4 ortho + 4 diag)
-            assert_eq!(moves.len(), 8);
-            reset_world_bounds();
-        });
+        // Distance 2 compass should have 8 moves (4 ortho + 4 diag)
+        assert_eq!(moves.len(), 8);
     }
 
     #[test]
@@ -4113,154 +4720,155 @@ mod tests {
 
     #[test]
     fn test_find_blocker_via_indices() {
-        with_bounds_lock(|| {
-            reset_world_bounds();
-            let mut game = GameState::new();
-            game.setup_position_from_icn("w R4,4|P4,8");
-
-            let from = Coordinate::new(4, 4);
-
-            // Looking up (positive y)
-            let (dist, captures) =
-                find_blocker_via_indices(&game.board, &from, 0, 1, &game.spatial_indices, PlayerColor::White);
+        let mut game = GameState::new();
+        game.setup_position_from_icn("w R4,4|P4,8");
+
+        let from = Coordinate::new(4, 4);
+
+        // Looking up (positive y)
+        let (dist, captures) = find_blocker_via_indices(
+            &game.board,
+            &from,
+            0,
+            1,
+            &game.spatial_indices,
+            PlayerColor::White,
+        );
 
-            assert!(dist > 0, "Should find a blocker");
-            assert!(!captures, "Own piece should not be a capture");
-            reset_world_bounds();
-        });
+        assert!(dist > 0, "Should find a blocker");
+        assert!(!captures, "Own piece should not be a capture");
     }
 
     #[test]
     fn test_generate_knightrider_moves() {
-        with_bounds_lock(|| {
-            reset_world_bounds();
-            let mut game = GameState::new();
-            game.setup_position_from_icn("w Kr4,4");
+        let mut game = GameState::new();
+        game.setup_position_from_icn("w Kr4,4");
 
-            let from = Coordinate::new(4, 4);
-            let piece = Piece::new(PieceType::Knightrider, PlayerColor::White);
+        let from = Coordinate::new(4, 4);
+        let piece = Piece::new(PieceType::Knightrider, PlayerColor::White);
 
-            let moves = generate_knightrider_moves(&game.board, &from, &piece);
+        let moves =
+            generate_knightrider_moves(&game.board, &from, &piece, &game.game_rules.world_bounds);
 
-            // Knightrider should have at least 8 moves (the initial knight squares)
-            assert!(moves.len() >= 8, "Knightrider should have at least 8 moves");
-            reset_world_bounds();
-        });
+        // Knightrider should have at least 8 moves (the initial knight squares)
+        assert!(moves.len() >= 8, "Knightrider should have at least 8 moves");
     }
 
     #[test]
     fn test_generate_rose_moves() {
-        with_bounds_lock(|| {
-            reset_world_bounds();
-            let mut game = GameState::new();
-            game.setup_position_from_icn("w Ro4,4");
-
-            let from = Coordinate::new(4, 4);
-            let piece = Piece::new(PieceType::Rose, PlayerColor::White);
-
-            let mut moves = MoveList::new();
-            generate_rose_moves_into(&game.board, &from, &piece, MoveGenType::All, &mut moves);
+        let mut game = GameState::new();
+        game.setup_position_from_icn("w Ro4,4");
+
+        let from = Coordinate::new(4, 4);
+        let piece = Piece::new(PieceType::Rose, PlayerColor::White);
+
+        let mut moves = MoveList::new();
+        generate_rose_moves_into(
+            &game.board,
+            &from,
+            &piece,
+            MoveGenType::All,
+            &game.game_rules.world_bounds,
+            &mut moves,
+        );
 
-            assert!(!moves.is_empty(), "Rose should have some moves");
-            reset_world_bounds();
-        });
+        assert!(!moves.is_empty(), "Rose should have some moves");
     }
 
     #[test]
     fn test_get_legal_moves() {
-        with_bounds_lock(|| {
-            reset_world_bounds();
-            let mut game = GameState::new();
-            game.setup_position_from_icn("w K5,1|k5,8|P4,2");
-
-            let ctx = MoveGenContext {
-                special_rights: &game.special_rights,
-                en_passant: &game.en_passant,
-                game_rules: &game.game_rules,
-                indices: &game.spatial_indices,
-                enemy_king_pos: game.black_royals.first(),
-                pinned: &FxHashMap::default(),
-            };
+        let mut game = GameState::new();
+        game.setup_position_from_icn("w K5,1|k5,8|P4,2");
+
+        let ctx = MoveGenContext {
+            special_rights: &game.special_rights,
+            en_passant: &game.en_passant,
+            game_rules: &game.game_rules,
+            indices: &game.spatial_indices,
+            enemy_king_pos: game.black_royals.first(),
+            pinned: &FxHashMap::default(),
+        };
 
-            let moves = get_legal_moves(&game.board, PlayerColor::White, &ctx);
+        let moves = get_legal_moves(&game.board, PlayerColor::White, &ctx);
 
-            assert!(!moves.is_empty(), "White should have legal moves");
-            reset_world_bounds();
-        });
+        assert!(!moves.is_empty(), "White should have legal moves");
     }
 
     #[test]
     fn test_get_quiescence_captures() {
-        with_bounds_lock(|| {
-            reset_world_bounds();
-            let mut game = GameState::new();
-            game.setup_position_from_icn("w K5,1|k5,8|N4,4|p5,6");
-
-            let ctx = MoveGenContext {
-                special_rights: &game.special_rights,
-                en_passant: &game.en_passant,
-                game_rules: &game.game_rules,
-                indices: &game.spatial_indices,
-                enemy_king_pos: None,
-                pinned: &FxHashMap::default(),
-            };
+        let mut game = GameState::new();
+        game.setup_position_from_icn("w K5,1|k5,8|N4,4|p5,6");
+
+        let ctx = MoveGenContext {
+            special_rights: &game.special_rights,
+            en_passant: &game.en_passant,
+            game_rules: &game.game_rules,
+            indices: &game.spatial_indices,
+            enemy_king_pos: None,
+            pinned: &FxHashMap::default(),
+        };
 
-            let mut captures = MoveList::new();
-            get_quiescence_captures(&game.board, PlayerColor::White, &ctx, &mut captures);
+        let mut captures = MoveList::new();
+        get_quiescence_captures(&game.board, PlayerColor::White, &ctx, &mut captures);
 
-            // Should find the knight capture
-            assert!(!captures.is_empty(), "Should find capture moves");
-            reset_world_bounds();
-        });
+        // Should find the knight capture
+        assert!(!captures.is_empty(), "Should find capture moves");
     }
 
     #[test]
     fn test_generate_rose_moves_unblocked() {
-        with_bounds_lock(|| {
-            reset_world_bounds();
-            // Rose on empty board should have many moves
-            let mut game = GameState::new();
-            game.setup_position_from_icn("w Ro4,4");
-
-            let from = Coordinate::new(4, 4);
-            let piece = Piece::new(PieceType::Rose, PlayerColor::White);
-            let mut moves = MoveList::new();
-            generate_rose_moves_into(&game.board, &from, &piece, MoveGenType::All, &mut moves);
+        // Rose on empty board should have many moves
+        let mut game = GameState::new();
+        game.setup_position_from_icn("w Ro4,4");
+
+        let from = Coordinate::new(4, 4);
+        let piece = Piece::new(PieceType::Rose, PlayerColor::White);
+        let mut moves = MoveList::new();
+        generate_rose_moves_into(
+            &game.board,
+            &from,
+            &piece,
+            MoveGenType::All,
+            &game.game_rules.world_bounds,
+            &mut moves,
+        );
 
-            // Should have moves (each of 16 spirals can go up to 7 hops, though many overlap)
-            assert!(!moves.is_empty(), "Rose should have moves on empty board");
+        // Should have moves (each of 16 spirals can go up to 7 hops, though many overlap)
+        assert!(!moves.is_empty(), "Rose should have moves on empty board");
 
-            // First hop in any spiral should be a knight move
-            // Check that (-2, -1) from origin is in the moves
-            let has_knight_move = moves.iter().any(|m| m.to.x == 2 && m.to.y == 3);
-            assert!(
-                has_knight_move,
-                "Rose should be able to make knight-like first hops"
-            );
-            reset_world_bounds();
-        });
+        // First hop in any spiral should be a knight move
+        // Check that (-2, -1) from origin is in the moves
+        let has_knight_move = moves.iter().any(|m| m.to.x == 2 && m.to.y == 3);
+        assert!(
+            has_knight_move,
+            "Rose should be able to make knight-like first hops"
+        );
     }
 
     #[test]
     fn test_generate_rose_moves_blocked() {
-        with_bounds_lock(|| {
-            reset_world_bounds();
-            // Rose with a blocker that prevents some moves
-            let mut game = GameState::new();
-            game.setup_position_from_icn("w Ro4,4|P3,2");
-
-            let from = Coordinate::new(4, 4);
-            let piece = Piece::new(PieceType::Rose, PlayerColor::White);
-            let mut moves = MoveList::new();
-            generate_rose_moves_into(&game.board, &from, &piece, MoveGenType::All, &mut moves);
+        // Rose with a blocker that prevents some moves
+        let mut game = GameState::new();
+        game.setup_position_from_icn("w Ro4,4|P3,2");
+
+        let from = Coordinate::new(4, 4);
+        let piece = Piece::new(PieceType::Rose, PlayerColor::White);
+        let mut moves = MoveList::new();
+        generate_rose_moves_into(
+            &game.board,
+            &from,
+            &piece,
+            MoveGenType::All,
+            &game.game_rules.world_bounds,
+            &mut moves,
+        );
 
-            // Should NOT have the blocked square as a move (friendly piece)
-            let has_blocked_square = moves.iter().any(|m| m.to.x == 3 && m.to.y == 2);
-            assert!(
-                !has_blocked_square,
-                "Rose should not move to square occupied by friendly piece"
-            );
-        });
+        // Should NOT have the blocked square as a move (friendly piece)
+        let has_blocked_square = moves.iter().any(|m| m.to.x == 3 && m.to.y == 2);
+        assert!(
+            !has_blocked_square,
+            "Rose should not move to square occupied by friendly piece"
+        );
     }
 
     #[test]
@@ -4275,64 +4883,56 @@ mod tests {
     }
     #[test]
     fn test_long_distance_royal_targeting() {
-        with_bounds_lock(|| {
-            reset_world_bounds();
-            let mut game = GameState::new();
-            game.setup_position_from_icn("w Q10,-30|k77,-41");
-
-            let ctx = MoveGenContext {
-                special_rights: &game.special_rights,
-                en_passant: &game.en_passant,
-                game_rules: &game.game_rules,
-                indices: &game.spatial_indices,
-                enemy_king_pos: game.black_royals.first(),
-                pinned: &FxHashMap::default(),
-            };
+        let mut game = GameState::new();
+        game.setup_position_from_icn("w Q10,-30|k77,-41");
+
+        let ctx = MoveGenContext {
+            special_rights: &game.special_rights,
+            en_passant: &game.en_passant,
+            game_rules: &game.game_rules,
+            indices: &game.spatial_indices,
+            enemy_king_pos: game.black_royals.first(),
+            pinned: &FxHashMap::default(),
+        };
 
-            let moves = get_legal_moves(&game.board, PlayerColor::White, &ctx);
+        let moves = get_legal_moves(&game.board, PlayerColor::White, &ctx);
 
-            let target_from = Coordinate::new(10, -30);
-            let target_to = Coordinate::new(77, -30);
+        let target_from = Coordinate::new(10, -30);
+        let target_to = Coordinate::new(77, -30);
 
-            let found = moves
-                .iter()
-                .any(|m| m.from == target_from && m.to == target_to);
+        let found = moves
+            .iter()
+            .any(|m| m.from == target_from && m.to == target_to);
 
-            assert!(
-                found,
-                "Move (10,-30) -> (77,-30) should be generated to target King at (77,-41)"
-            );
-            reset_world_bounds();
-        });
+        assert!(
+            found,
+            "Move (10,-30) -> (77,-30) should be generated to target King at (77,-41)"
+        );
     }
 
     #[test]
     fn test_quiescence_generates_quiet_promotions() {
-        with_bounds_lock(|| {
-            reset_world_bounds();
-            let mut game = GameState::new();
-            game.setup_position_from_icn("w (8;q|1;q) P0,7");
-
-            let ctx = MoveGenContext {
-                special_rights: &game.special_rights,
-                en_passant: &game.en_passant,
-                game_rules: &game.game_rules,
-                indices: &game.spatial_indices,
-                enemy_king_pos: None,
-                pinned: &FxHashMap::default(),
-            };
-
-            let mut moves = MoveList::new();
-            get_quiescence_captures(&game.board, PlayerColor::White, &ctx, &mut moves);
+        let mut game = GameState::new();
+        game.setup_position_from_icn("w (8;q|1;q) P0,7");
+
+        let ctx = MoveGenContext {
+            special_rights: &game.special_rights,
+            en_passant: &game.en_passant,
+            game_rules: &game.game_rules,
+            indices: &game.spatial_indices,
+            enemy_king_pos: None,
+            pinned: &FxHashMap::default(),
+        };
 
-            // Should include quiet promotion to (0, 8)
-            let found_promo = moves.iter().any(|m| {
-                m.from.x == 0 && m.from.y == 7 && m.to.x == 0 && m.to.y == 8 && m.promotion.is_some()
-            });
+        let mut moves = MoveList::new();
+        get_quiescence_captures(&game.board, PlayerColor::White, &ctx, &mut moves);
 
-            assert!(found_promo, "QSearch should generate quiet pawn promotions");
-            reset_world_bounds();
+        // Should include quiet promotion to (0, 8)
+        let found_promo = moves.iter().any(|m| {
+            m.from.x == 0 && m.from.y == 7 && m.to.x == 0 && m.to.y == 8 && m.promotion.is_some()
         });
+
+        assert!(found_promo, "QSearch should generate quiet pawn promotions");
     }
 
     mod border_handling_tests {
@@ -4340,78 +4940,84 @@ mod tests {
 
         #[test]
         fn test_huygen_border_respect() {
-            super::with_bounds_lock(|| {
-                super::reset_world_bounds();
-                let mut game = GameState::new();
-                game.setup_position_from_icn("-5,5,-5,5 w Hy0,0");
-
-                let from = Coordinate::new(0, 0);
-                let piece = Piece::new(PieceType::Huygen, PlayerColor::White);
-
-                let mut moves = MoveList::new();
-                generate_huygen_moves_into(
-                    &game.board,
-                    &from,
-                    &piece,
-                    &game.spatial_indices,
-                    MoveGenType::All,
-                    &mut moves,
-                );
+            let mut game = GameState::new();
+            game.setup_position_from_icn("-5,5,-5,5 w Hy0,0");
 
-                for m in &moves {
-                    assert!(in_bounds(m.to.x, m.to.y), "Move {:?} is out of bounds", m);
-                }
+            let from = Coordinate::new(0, 0);
+            let piece = Piece::new(PieceType::Huygen, PlayerColor::White);
 
-                // Verify some moves were generated within bounds
-                assert!(!moves.is_empty());
+            let mut moves = MoveList::new();
+            generate_huygen_moves_into(
+                &game.board,
+                &from,
+                &piece,
+                &game.spatial_indices,
+                MoveGenType::All,
+                &game.game_rules.world_bounds,
+                &mut moves,
+            );
+
+            for m in &moves {
+                assert!(
+                    in_bounds(&game.game_rules.world_bounds, m.to.x, m.to.y),
+                    "Move {:?} is out of bounds",
+                    m
+                );
+            }
 
-                // Reset bounds
-                super::reset_world_bounds();
-            });
+            // Verify some moves were generated within bounds
+            assert!(!moves.is_empty());
         }
 
         #[test]
         fn test_rose_border_respect() {
-            super::with_bounds_lock(|| {
-                super::reset_world_bounds();
-                let mut game = GameState::new();
-                game.setup_position_from_icn("-2,2,-2,2 w Ro0,0");
-
-                let from = Coordinate::new(0, 0);
-                let piece = Piece::new(PieceType::Rose, PlayerColor::White);
+            let mut game = GameState::new();
+            game.setup_position_from_icn("-2,2,-2,2 w Ro0,0");
 
-                let mut moves = MoveList::new();
-                generate_rose_moves_into(&game.board, &from, &piece, MoveGenType::All, &mut moves);
+            let from = Coordinate::new(0, 0);
+            let piece = Piece::new(PieceType::Rose, PlayerColor::White);
 
-                for m in &moves {
-                    assert!(in_bounds(m.to.x, m.to.y), "Move {:?} is out of bounds", m);
-                }
+            let mut moves = MoveList::new();
+            generate_rose_moves_into(
+                &game.board,
+                &from,
+                &piece,
+                MoveGenType::All,
+                &game.game_rules.world_bounds,
+                &mut moves,
+            );
 
-                // Reset bounds
-                super::reset_world_bounds();
-            });
+            for m in &moves {
+                assert!(
+                    in_bounds(&game.game_rules.world_bounds, m.to.x, m.to.y),
+                    "Move {:?} is out of bounds",
+                    m
+                );
+            }
         }
 
         #[test]
         fn test_pawn_border_respect() {
-            super::with_bounds_lock(|| {
-                super::reset_world_bounds();
-                let mut game = GameState::new();
-                // Pawn at white terminal rank in a tiny world
-                game.setup_position_from_icn("-10,10,-10,5 w P0,5");
-
-                let from = Coordinate::new(0, 5);
-                let piece = Piece::new(PieceType::Pawn, PlayerColor::White);
+            let mut game = GameState::new();
+            // Pawn at white terminal rank in a tiny world
+            game.setup_position_from_icn("-10,10,-10,5 w P0,5");
 
-                let mut moves = MoveList::new();
-                generate_pawn_moves_into(&game.board, &from, &piece, &game.special_rights, &None, &game.game_rules, &mut moves);
+            let from = Coordinate::new(0, 5);
+            let piece = Piece::new(PieceType::Pawn, PlayerColor::White);
 
-                // Should have NO moves because they all go to y=6 which is out of bounds
-                assert!(moves.is_empty(), "Pawn should have no moves out of bounds");
+            let mut moves = MoveList::new();
+            generate_pawn_moves_into(
+                &game.board,
+                &from,
+                &piece,
+                &game.special_rights,
+                &None,
+                &game.game_rules,
+                &mut moves,
+            );
 
-                // Reset bounds
-                super::reset_world_bounds();
-            });
+            // Should have NO moves because they all go to y=6 which is out of bounds
+            assert!(moves.is_empty(), "Pawn should have no moves out of bounds");
         }
     }
 }
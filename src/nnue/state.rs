@@ -161,8 +161,9 @@ impl NnueState {
                     continue;
                 }
 
-                // If this is a piece being captured, skip it (it won't exist in new state)
-                if px == m.to.x && py == m.to.y {
+                // If this is a piece being captured, skip it (it won't exist in new state).
+                // A castling partner on the king's destination is moved, not captured.
+                if px == m.to.x && py == m.to.y && m.rook_coord.is_none() {
                     continue;
                 }
 
@@ -205,8 +206,7 @@ impl NnueState {
                 }
             }
 
-            if (m.to.x - m.from.x).abs() > 1
-                && let Some(rook_from) = m.rook_coord
+            if let Some(rook_from) = m.rook_coord
                 && let Some(rook) = gs.board.get_piece(rook_from.x, rook_from.y)
             {
                 let _is_friendly = true; // Rook is same color as King
@@ -237,7 +237,7 @@ impl NnueState {
                 }
 
                 // Add the rook feature at `rook_to`
-                let rook_to_x = m.from.x + if m.to.x > m.from.x { 1 } else { -1 };
+                let rook_to_x = m.to.x - if rook_from.x > m.from.x { 1 } else { -1 };
                 let rook_to_y = m.from.y;
 
                 let (dx_new, dy_new) = if us == crate::board::PlayerColor::White {
@@ -297,9 +297,9 @@ impl NnueState {
             // Note: If we captured something, we must also remove it from the enemy accumulator
             // because it's no longer on the board!
             //
-            // Case A: Standard Capture
-            // Case A: Standard Capture
-            if let Some(captured) = gs.board.get_piece(m.to.x, m.to.y)
+            // Case A: Standard Capture (castling never captures)
+            if m.rook_coord.is_none()
+                && let Some(captured) = gs.board.get_piece(m.to.x, m.to.y)
                 && let Some(idx) = super::features::relkp_feature_id(
                     them,
                     captured,
@@ -326,8 +326,7 @@ impl NnueState {
             // Case C: Castling Rook Move
             // From ENEMY perspective, the Rook also moved.
             // We must update the Rook's position in the enemy accumulator too.
-            if (m.to.x - m.from.x).abs() > 1
-                && let Some(rook_from) = m.rook_coord
+            if let Some(rook_from) = m.rook_coord
                 && let Some(rook) = gs.board.get_piece(rook_from.x, rook_from.y)
             {
                 // Remove Rook from old pos
@@ -346,7 +345,7 @@ impl NnueState {
                 }
 
                 // Add Rook to new pos
-                let rook_to_x = m.from.x + if m.to.x > m.from.x { 1 } else { -1 };
+                let rook_to_x = m.to.x - if rook_from.x > m.from.x { 1 } else { -1 };
                 let rook_to = crate::board::Coordinate::new(rook_to_x, m.from.y);
 
                 if let Some(idx) = super::features::relkp_feature_id(
@@ -13,6 +13,8 @@ use crate::search::params::{
     rfp_improving_mult, rfp_max_depth, rfp_mult_no_tt, rfp_mult_tt, rfp_worsening_mult,
     see_capture_hist_div, see_capture_linear, see_quiet_quad,
 };
+use crate::tablebase::TbValue;
+use crate::tablebase::syzygy::{self, Wdl};
 #[cfg(all(target_arch = "wasm32", not(target_os = "wasi")))]
 // For web WASM (browser), use js_sys::Date for timing
 #[cfg(all(target_arch = "wasm32", not(target_os = "wasi")))]
@@ -127,6 +129,9 @@ pub const MAX_QSEARCH_DEPTH: usize = 16;
 pub const INFINITY: i32 = 1_000_000;
 pub const MATE_VALUE: i32 = 900_000;
 pub const MATE_SCORE: i32 = 800_000;
+/// Score of a Syzygy win with no known mate distance. Below `MATE_SCORE`, and small
+/// enough to survive the TT's 16-bit score packing.
+pub const TB_WIN_SCORE: i32 = 30_000;
 pub const THINK_TIME_MS: u128 = 3000; // 3 seconds per move (default, may be overridden by caller)
 
 pub const MAX_SITE_SKILL: u32 = 8; // Current max skill level on the site
@@ -170,13 +175,8 @@ pub const fn is_loss(value: i32) -> bool {
     value < -MATE_SCORE
 }
 
-use std::sync::OnceLock;
 use std::sync::atomic::{AtomicBool, AtomicUsize};
-
-/// Global stop flag for all search threads.
-/// Also written externally (via [`crate::stop_flag_ptr`]) to abort an analysis mid-search
-/// when the wasm memory is shared, so `check_time` polls it every node batch.
-pub(crate) static GLOBAL_STOP: AtomicBool = AtomicBool::new(false);
+use std::sync::{Arc, LazyLock, OnceLock};
 
 /// Per-thread node counter for thread-aggregated NPS (Stockfish-style: total nodes across all
 /// search threads / wall-clock). Each slot is cache-line aligned so threads publishing their
@@ -186,50 +186,173 @@ pub(crate) static GLOBAL_STOP: AtomicBool = AtomicBool::new(false);
 pub(crate) struct NodeSlot(std::sync::atomic::AtomicU64);
 
 #[cfg(feature = "multithreading")]
-pub(crate) const SEARCH_NODE_SLOTS: usize = 16;
-#[cfg(feature = "multithreading")]
-pub(crate) static SEARCH_THREAD_NODES: [NodeSlot; SEARCH_NODE_SLOTS] =
-    [const { NodeSlot(std::sync::atomic::AtomicU64::new(0)) }; SEARCH_NODE_SLOTS];
-
-/// Publishes `nodes` to thread `id`'s slot (wraps if id exceeds the slot count).
-#[cfg(feature = "multithreading")]
-#[inline(always)]
-pub(crate) fn publish_thread_nodes(id: usize, nodes: u64) {
-    SEARCH_THREAD_NODES[id & (SEARCH_NODE_SLOTS - 1)]
-        .0
-        .store(nodes, std::sync::atomic::Ordering::Relaxed);
+pub(crate) const SEARCH_NODE_SLOTS: usize = MAX_SEARCH_THREADS;
+
+/// Most threads one search runs on (each gets its own node-count slot).
+pub const MAX_SEARCH_THREADS: usize = 16;
+
+/// Coordination state shared by every thread searching on behalf of one engine: the stop
+/// flag, the Lazy SMP shared TT and pawn history, the detached-helper lifecycle and the
+/// per-thread node counters. Each [`SearchInstance`] owns one, so independent engines in
+/// the same process never see each other's tables or stop requests.
+pub struct SearchContext {
+    /// Stop flag for all threads searching on this context. The global context's flag is
+    /// also written externally (via [`crate::stop_flag_ptr`]) to abort an analysis
+    /// mid-search when the wasm memory is shared, so `check_time` polls it every node batch.
+    pub(crate) stop: AtomicBool,
+    /// Transposition table size in MB used when (re)creating searchers and the shared TT.
+    tt_size_mb: AtomicUsize,
+    #[cfg(feature = "multithreading")]
+    shared_tt: OnceLock<SharedTranspositionTable>,
+    /// Set while a parallel search is active so probes and stores go to the shared TT.
+    #[cfg(feature = "multithreading")]
+    pub(crate) use_shared_tt: AtomicBool,
+    /// One pawn-history table shared by every search thread (Stockfish shares pawn history
+    /// across threads too). Concurrent i16 gravity updates race benignly, like the shared TT.
+    #[cfg(feature = "multithreading")]
+    pawn_hist: OnceLock<Box<shared_hist::Shared<PawnHistTable>>>,
+    /// Analysis-helper lifecycle epoch. Bumping it (new position / stop) makes every running
+    /// detached helper exit at its next slice boundary.
+    #[cfg(feature = "multithreading")]
+    pub(crate) helper_epoch: std::sync::atomic::AtomicU64,
+    /// Number of detached helpers currently searching (lets a resumed analysis — e.g. "go
+    /// deeper" after the helpers retired at 'done' — know it must spawn a fresh batch).
+    #[cfg(feature = "multithreading")]
+    pub(crate) helpers_live: AtomicUsize,
+    #[cfg(feature = "multithreading")]
+    thread_nodes: [NodeSlot; SEARCH_NODE_SLOTS],
 }
 
-/// Zeroes every thread's node slot; called at analysis start so retired helpers don't linger.
-#[cfg(feature = "multithreading")]
-pub(crate) fn reset_search_nodes() {
-    for slot in SEARCH_THREAD_NODES.iter() {
-        slot.0.store(0, std::sync::atomic::Ordering::Relaxed);
+impl SearchContext {
+    /// Creates an idle context whose tables will be sized at `tt_size_mb` (clamped to 1..=64).
+    pub fn new(tt_size_mb: usize) -> Self {
+        SearchContext {
+            stop: AtomicBool::new(false),
+            tt_size_mb: AtomicUsize::new(tt_size_mb.clamp(1, 64)),
+            #[cfg(feature = "multithreading")]
+            shared_tt: OnceLock::new(),
+            #[cfg(feature = "multithreading")]
+            use_shared_tt: AtomicBool::new(false),
+            #[cfg(feature = "multithreading")]
+            pawn_hist: OnceLock::new(),
+            #[cfg(feature = "multithreading")]
+            helper_epoch: std::sync::atomic::AtomicU64::new(0),
+            #[cfg(feature = "multithreading")]
+            helpers_live: AtomicUsize::new(0),
+            #[cfg(feature = "multithreading")]
+            thread_nodes: [const { NodeSlot(std::sync::atomic::AtomicU64::new(0)) };
+                SEARCH_NODE_SLOTS],
+        }
     }
-}
 
-/// Total nodes searched across all threads this search.
-#[cfg(feature = "multithreading")]
-pub(crate) fn aggregate_search_nodes() -> u64 {
-    SEARCH_THREAD_NODES
-        .iter()
-        .map(|s| s.0.load(std::sync::atomic::Ordering::Relaxed))
-        .sum()
+    /// Transposition table size in MB for searchers created on this context.
+    pub fn tt_size_mb(&self) -> usize {
+        self.tt_size_mb.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Asks every search running on this context to stop at its next node batch.
+    pub fn request_stop(&self) {
+        self.stop.store(true, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// Withdraws a stop request, ahead of a search that must not inherit it.
+    pub fn clear_stop(&self) {
+        self.stop.store(false, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// Address of the stop flag, for hosts that abort searches by writing to shared memory.
+    pub fn stop_flag_ptr(&self) -> *const AtomicBool {
+        &self.stop
+    }
+
+    /// Stops all detached analysis helpers (and any in-flight search) immediately.
+    #[cfg(feature = "multithreading")]
+    pub fn stop_analysis_helpers(&self) {
+        self.helper_epoch
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        self.request_stop();
+    }
+
+    /// The shared TT, if a multithreaded search has initialized it.
+    #[cfg(feature = "multithreading")]
+    #[inline(always)]
+    pub(crate) fn shared_tt(&self) -> Option<&SharedTranspositionTable> {
+        self.shared_tt.get()
+    }
+
+    /// Ensures the shared TT exists (sized by this context's TT size on first init).
+    /// Required before any multithreaded search that sets `use_shared_tt`.
+    #[cfg(feature = "multithreading")]
+    pub(crate) fn init_shared_tt(&self) {
+        self.shared_tt
+            .get_or_init(|| SharedTranspositionTable::new(self.tt_size_mb()));
+    }
+
+    #[cfg(feature = "multithreading")]
+    #[inline(always)]
+    fn pawn_table(&self) -> *mut PawnHistTable {
+        self.pawn_hist.get_or_init(zeroed_box).0.get()
+    }
+
+    /// Publishes `nodes` to thread `id`'s slot (wraps if id exceeds the slot count).
+    #[cfg(feature = "multithreading")]
+    #[inline(always)]
+    pub(crate) fn publish_thread_nodes(&self, id: usize, nodes: u64) {
+        self.thread_nodes[id & (SEARCH_NODE_SLOTS - 1)]
+            .0
+            .store(nodes, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// Zeroes every thread's node slot; called at analysis start so retired helpers don't linger.
+    #[cfg(feature = "multithreading")]
+    pub(crate) fn reset_search_nodes(&self) {
+        for slot in self.thread_nodes.iter() {
+            slot.0.store(0, std::sync::atomic::Ordering::Relaxed);
+        }
+    }
+
+    /// Total nodes searched across all threads this search.
+    #[cfg(feature = "multithreading")]
+    pub(crate) fn aggregate_search_nodes(&self) -> u64 {
+        self.thread_nodes
+            .iter()
+            .map(|s| s.0.load(std::sync::atomic::Ordering::Relaxed))
+            .sum()
+    }
+
+    /// Clears the tables shared between this context's threads (shared TT, pawn history).
+    fn clear_shared_tables(&self) {
+        #[cfg(feature = "multithreading")]
+        {
+            if let Some(tt) = self.shared_tt.get() {
+                tt.clear();
+            }
+            if let Some(table) = self.pawn_hist.get() {
+                // Racy-but-benign memset, like the per-search reset in `Searcher::clear`.
+                unsafe { std::ptr::write_bytes(table.0.get(), 0, 1) };
+            }
+        }
+    }
 }
 
-/// Transposition table size in MB used when (re)creating searchers and the shared TT.
-pub(crate) static TT_SIZE_MB: AtomicUsize = AtomicUsize::new(16);
+static GLOBAL_CONTEXT: LazyLock<Arc<SearchContext>> =
+    LazyLock::new(|| Arc::new(SearchContext::new(16)));
+
+/// The process-wide context behind the free search functions and [`GLOBAL_SEARCHER`].
+pub fn global_context() -> &'static Arc<SearchContext> {
+    &GLOBAL_CONTEXT
+}
 
-/// Sets the TT size for future searcher/shared-TT creations, and resizes the
-/// current thread's persistent searcher's local TT immediately if one exists.
+/// Sets the TT size for future searcher/shared-TT creations on the global context, and
+/// resizes the current thread's persistent searcher's local TT immediately if one exists.
 /// (An already-initialized shared TT can't be resized — respawn the worker for that.)
 pub fn set_tt_size_mb(mb: usize) {
-    TT_SIZE_MB.store(mb.clamp(1, 64), std::sync::atomic::Ordering::Relaxed);
-    GLOBAL_SEARCHER.with(|cell| {
-        if let Some(searcher) = cell.borrow_mut().as_mut() {
-            searcher.tt = LocalTranspositionTable::new(mb);
-        }
-    });
+    with_global_search(|search| search.set_tt_size_mb(mb));
+}
+
+/// [`SearchInstance::set_threads`] on this thread's global instance.
+pub fn set_threads(threads: usize) {
+    with_global_search(|search| search.set_threads(threads));
 }
 
 #[inline(always)]
@@ -281,19 +404,12 @@ fn zeroed_box<T>() -> Box<T> {
     }
 }
 
-/// One pawn-history table shared by every search thread (Stockfish shares pawn history
-/// across threads too). Concurrent i16 gravity updates race benignly, like the shared TT.
+/// Lock-free cell for history tables that every search thread of a context writes to
+/// (see [`SearchContext::pawn_hist`]).
 #[cfg(feature = "multithreading")]
 mod shared_hist {
     pub struct Shared<T>(pub std::cell::UnsafeCell<T>);
     unsafe impl<T> Sync for Shared<T> {}
-
-    static PAWN: std::sync::OnceLock<Box<Shared<super::PawnHistTable>>> =
-        std::sync::OnceLock::new();
-
-    pub fn pawn_table() -> *mut super::PawnHistTable {
-        PAWN.get_or_init(|| super::zeroed_box()).0.get()
-    }
 }
 
 /// Determines which correction history tables to use.
@@ -318,7 +434,17 @@ pub enum NodeType {
     All,
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+pub mod analysis;
+#[cfg(not(target_arch = "wasm32"))]
+pub use analysis::{Analysis, AnalysisOptions, DepthReport};
+pub mod book;
+#[cfg(not(target_arch = "wasm32"))]
+pub use book::{BookBuilder, GameRecord};
+pub use book::{Book, BookMove};
 pub mod params;
+pub mod time;
+pub use time::{TimeLimits, TimeManager};
 pub mod tt_defs;
 pub use tt_defs::{TTFlag, TTProbeParams, TTProbeResult, TTStoreParams};
 
@@ -350,18 +476,6 @@ pub use zobrist::{
 // TT Probe/Store (Dispatch wrapper)
 // ============================================================================
 
-#[cfg(feature = "multithreading")]
-static SHARED_TT: OnceLock<SharedTranspositionTable> = OnceLock::new();
-
-/// Ensures the shared TT exists (sized by [`TT_SIZE_MB`] on first init).
-/// Required before any multithreaded search that sets [`USE_SHARED_TT`].
-#[cfg(feature = "multithreading")]
-pub(crate) fn init_shared_tt() {
-    SHARED_TT.get_or_init(|| {
-        SharedTranspositionTable::new(TT_SIZE_MB.load(std::sync::atomic::Ordering::Relaxed))
-    });
-}
-
 /// Precomputed LMR table to avoid ln() calls at runtime.
 /// Indexed by [depth][moves_searched].
 static LMR_TABLE: OnceLock<[[i32; 256]; MAX_PLY]> = OnceLock::new();
@@ -398,11 +512,6 @@ fn get_lmr(depth: usize, moves: usize) -> i32 {
     table[depth][moves]
 }
 
-/// Flag to enable shared TT usage.
-/// Set to true when parallel search is active.
-#[cfg(feature = "multithreading")]
-pub(crate) static USE_SHARED_TT: AtomicBool = AtomicBool::new(false);
-
 /// Helper struct to satisfy closure syntax in get_or_init
 pub struct TranspositionTable;
 impl TranspositionTable {
@@ -517,8 +626,11 @@ impl<'a> TranspositionTableRef<'a> {
 #[inline(always)]
 pub fn probe_tt_with_shared(searcher: &Searcher, ctx: &ProbeContext) -> Option<TTProbeResult> {
     #[cfg(feature = "multithreading")]
-    if USE_SHARED_TT.load(std::sync::atomic::Ordering::Relaxed)
-        && let Some(tt) = SHARED_TT.get()
+    if searcher
+        .ctx
+        .use_shared_tt
+        .load(std::sync::atomic::Ordering::Relaxed)
+        && let Some(tt) = searcher.ctx.shared_tt()
     {
         return tt.probe(&crate::search::tt_defs::TTProbeParams {
             hash: ctx.hash,
@@ -545,8 +657,11 @@ pub fn probe_tt_with_shared(searcher: &Searcher, ctx: &ProbeContext) -> Option<T
 #[inline(always)]
 pub fn store_tt_with_shared(searcher: &mut Searcher, ctx: &StoreContext) {
     #[cfg(feature = "multithreading")]
-    if USE_SHARED_TT.load(std::sync::atomic::Ordering::Relaxed)
-        && let Some(tt) = SHARED_TT.get()
+    if searcher
+        .ctx
+        .use_shared_tt
+        .load(std::sync::atomic::Ordering::Relaxed)
+        && let Some(tt) = searcher.ctx.shared_tt()
     {
         tt.store(&crate::search::tt_defs::TTStoreParams {
             hash: ctx.hash,
@@ -593,35 +708,12 @@ pub struct SearcherHot {
     /// Tracks the minimum depth that must be completed before time stops are allowed.
     /// Set to 1 at search start, cleared to 0 after depth 1 completes.
     pub min_depth_required: usize,
-    /// Optimum time to use for this search (soft limit)
-    pub optimum_time_ms: u128,
-    /// Maximum time to use for this search (hard limit)
-    pub maximum_time_ms: u128,
-    /// Total best move changes (instability) persisted across iterations
-    pub tot_best_move_changes: f64,
-    /// Best move changes in the current iteration
-    pub best_move_changes: f64,
-    /// Nodes spent on the current best move (first root move) in the current iteration
-    pub best_move_nodes: u64,
-    /// Running average score smoothed across iterations
-    pub best_previous_average_score: i32,
-    /// Running scores for falling eval (circular buffer of last 4 iterations)
-    pub iter_values: [i32; 4],
-    /// Index into iter_values circular buffer
-    pub iter_idx: usize,
-    /// Previous time reduction factor (for smoothing across iterations)
-    pub prev_time_reduction: f64,
-    /// Depth at which best move was last changed
-    pub last_best_move_depth: usize,
-    /// Whether this is a "soft" time limit (suggested time, can exceed up to max)
-    /// vs a hard limit (must stop at maximum time). For untimed games with a
-    /// suggested per-move limit, this allows the engine to use more time when beneficial.
-    pub is_soft_limit: bool,
-    /// Calculated total time budget for this move, including dynamic factors.
-    /// Used by check_time for mid-depth stops.
-    pub total_time_ms: f64,
+    /// Optimum/maximum allocation and the dynamic factors that scale it between iterations.
+    pub time: TimeManager,
     /// Time (ms) when the current iterative deepening depth started.
     pub iter_start_ms: f64,
+    /// Hard node budget from [`SearchLimits::nodes`] (`u64::MAX` = none), checked every node.
+    pub node_limit: u64,
 }
 
 impl Default for Timer {
@@ -667,7 +759,7 @@ impl SearcherHot {
     ///
     /// Time management works differently based on `is_soft_limit`:
     /// - **Soft limit** (untimed game with suggested time): The engine can freely
-    ///   use up to `maximum_time_ms` if beneficial. Optimum is set higher
+    ///   use up to the maximum time if beneficial. Optimum is set higher
     ///   because there's no risk of flagging, and max is close to the full budget.
     /// - **Hard limit** (timed game): The engine must be conservative. Optimum is
     ///   set lower and max is capped to leave headroom for dynamic extensions
@@ -676,13 +768,58 @@ impl SearcherHot {
     /// The dynamic factors (fallingEval up to 1.7x, instability up to ~2.5x, etc.)
     /// multiply the optimum time, capped at maximum.
     pub fn set_time_limits(&mut self, opt_ms: u128, max_ms: u128, is_soft: bool) {
-        self.optimum_time_ms = opt_ms;
-        self.maximum_time_ms = max_ms;
-        self.is_soft_limit = is_soft;
+        self.time.set_limits(TimeLimits {
+            optimum_ms: opt_ms,
+            maximum_ms: max_ms,
+            is_soft,
+        });
         self.time_limit_ms = max_ms; // Used by check_time()
     }
 }
 
+/// Stop conditions and root restrictions for a search beyond its time limits
+/// (UCI `go nodes`, `go mate` and `go searchmoves`).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SearchLimits {
+    /// Hard node budget. Unlike the time limits it can end the search inside depth 1.
+    pub nodes: Option<u64>,
+    /// Stop once a mate in at most this many moves (not plies) is proven.
+    pub mate: Option<u32>,
+    /// When non-empty, only these root moves are searched.
+    pub search_moves: Vec<Move>,
+}
+
+impl SearchLimits {
+    /// True when these limits add nothing to a plain timed search.
+    pub fn is_unlimited(&self) -> bool {
+        self.nodes.is_none() && self.mate.is_none() && self.search_moves.is_empty()
+    }
+
+    /// Whether `score` (side to move's view) is a mate within the `mate` target.
+    pub fn mate_reached(&self, score: i32) -> bool {
+        self.mate
+            .is_some_and(|n| n > 0 && score >= mate_in(2 * n as usize - 1))
+    }
+
+    /// Keeps only the `search_moves` in `root_moves`. Returns whether the list changed; a
+    /// restriction that matches no legal move is ignored rather than leaving nothing to play.
+    fn restrict_root(&self, root_moves: &mut MoveList) -> bool {
+        if self.search_moves.is_empty() {
+            return false;
+        }
+        let allowed = |m: &Move| {
+            self.search_moves
+                .iter()
+                .any(|s| s.from == m.from && s.to == m.to && s.promotion == m.promotion)
+        };
+        if !root_moves.iter().any(allowed) || root_moves.iter().all(allowed) {
+            return false;
+        }
+        root_moves.retain(|m| allowed(m));
+        true
+    }
+}
+
 /// Lightweight statistics about the transposition table after a search.
 #[derive(Clone, Debug)]
 pub struct SearchStats {
@@ -812,12 +949,143 @@ fn select_best_thread(all_results: &[ThreadResult]) -> usize {
 }
 
 thread_local! {
-    pub(crate) static GLOBAL_SEARCHER: RefCell<Option<Searcher>> = const { RefCell::new(None) };
+    /// The persistent searcher behind the free search functions: one per thread, all on
+    /// the [`global_context`]. Engines with their own [`SearchInstance`] never touch it.
+    pub(crate) static GLOBAL_SEARCHER: RefCell<SearchInstance> =
+        RefCell::new(SearchInstance::on_context(Arc::clone(global_context())));
+
+    /// Persistent searcher of a Lazy SMP helper running on this (pool) thread. Rebuilt when
+    /// the thread starts helping a different context, so engines never share histories.
+    #[cfg(feature = "multithreading")]
+    static HELPER_SEARCHER: RefCell<Option<Searcher>> = const { RefCell::new(None) };
+}
+
+/// Runs `f` on this thread's instance on the global context, i.e. the state behind the
+/// free search functions ([`get_best_move`], [`analyse_position`], ...).
+pub fn with_global_search<R>(f: impl FnOnce(&mut SearchInstance) -> R) -> R {
+    GLOBAL_SEARCHER.with(|cell| f(&mut cell.borrow_mut()))
+}
+
+/// A persistent [`Searcher`] plus the [`SearchContext`] its threads coordinate through.
+/// Histories and the TT carry over between searches until [`SearchInstance::reset`], so an
+/// engine that owns one keeps its tables warm without sharing them with anyone else.
+pub struct SearchInstance {
+    ctx: Arc<SearchContext>,
+    searcher: Option<Searcher>,
+    /// Lazy SMP thread count for [`SearchInstance::search_streamed`] (1 = single-threaded).
+    threads: usize,
+    /// Opening book probed at the root by [`SearchInstance::get_best_move_parallel`].
+    book: Option<Arc<Book>>,
+}
+
+impl Default for SearchInstance {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SearchInstance {
+    /// Creates an instance with a context of its own, sized by the global TT size setting.
+    pub fn new() -> Self {
+        Self::on_context(Arc::new(SearchContext::new(global_context().tt_size_mb())))
+    }
+
+    /// Creates an instance that searches on `ctx`. The searcher itself is created lazily.
+    pub fn on_context(ctx: Arc<SearchContext>) -> Self {
+        SearchInstance {
+            ctx,
+            searcher: None,
+            threads: 1,
+            book: None,
+        }
+    }
+
+    pub fn context(&self) -> &Arc<SearchContext> {
+        &self.ctx
+    }
+
+    /// The persistent searcher, if one has been created since the last reset.
+    pub fn searcher(&self) -> Option<&Searcher> {
+        self.searcher.as_ref()
+    }
+
+    /// The persistent searcher, created with `time_limit_ms` if none exists yet.
+    pub fn searcher_mut(&mut self, time_limit_ms: u128) -> &mut Searcher {
+        let ctx = &self.ctx;
+        self.searcher
+            .get_or_insert_with(|| Searcher::with_context(time_limit_ms, Arc::clone(ctx)))
+    }
+
+    /// Sets the TT size for future tables on this instance's context, and resizes the
+    /// persistent searcher's local TT immediately if one exists.
+    pub fn set_tt_size_mb(&mut self, mb: usize) {
+        let mb = mb.clamp(1, 64);
+        self.ctx
+            .tt_size_mb
+            .store(mb, std::sync::atomic::Ordering::Relaxed);
+        if let Some(searcher) = self.searcher.as_mut() {
+            searcher.tt = LocalTranspositionTable::new(mb);
+        }
+    }
+
+    /// Sets how many threads [`SearchInstance::search_streamed`] searches with: the main
+    /// search plus `threads - 1` detached Lazy SMP helpers on the rayon pool, sharing a TT.
+    /// Builds without `multithreading` always search single-threaded.
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.clamp(1, MAX_SEARCH_THREADS);
+    }
+
+    /// Thread count set by [`SearchInstance::set_threads`].
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    /// Sets the opening book [`SearchInstance::get_best_move_parallel`] plays from while
+    /// the position is in it; `None` disables the book.
+    pub fn set_book(&mut self, book: Option<Arc<Book>>) {
+        self.book = book;
+    }
+
+    /// The book set by [`SearchInstance::set_book`].
+    pub fn book(&self) -> Option<&Arc<Book>> {
+        self.book.as_ref()
+    }
+
+    /// A weighted book move for the root, returned like a search result with score 0 and
+    /// no nodes searched. The dice roll comes from the searcher's seeded RNG.
+    fn probe_book(&mut self, game: &mut GameState) -> Option<(Move, i32, SearchStats)> {
+        let book = Arc::clone(self.book.as_ref()?);
+        let random = self.searcher_mut(4000).rng.next_u64();
+        let m = book.probe(game, random)?;
+        let stats = SearchStats {
+            nodes: 0,
+            ..self.tt_stats()
+        };
+        Some((m, 0, stats))
+    }
+
+    /// Current TT statistics. Creates the searcher if needed, so capacity is reported
+    /// even before the first search.
+    pub fn tt_stats(&mut self) -> SearchStats {
+        build_search_stats(self.searcher_mut(4000))
+    }
+
+    /// The completed depth from the last search, or 0 if no search has run yet.
+    pub fn completed_depth(&self) -> usize {
+        self.searcher.as_ref().map_or(0, |s| s.completed_depth)
+    }
+
+    /// Drops the searcher (histories, local TT) and clears the context's shared tables.
+    /// Call this when starting a brand new game so old entries don't carry over.
+    pub fn reset(&mut self) {
+        self.searcher = None;
+        self.ctx.clear_shared_tables();
+    }
 }
 
 fn build_search_stats(searcher: &Searcher) -> SearchStats {
     #[cfg(feature = "multithreading")]
-    let (cap, used, fill): (usize, usize, u32) = if let Some(tt) = SHARED_TT.get() {
+    let (cap, used, fill): (usize, usize, u32) = if let Some(tt) = searcher.ctx.shared_tt() {
         (tt.capacity(), tt.used_entries(), tt.fill_permille())
     } else {
         (
@@ -845,38 +1113,24 @@ fn build_search_stats(searcher: &Searcher) -> SearchStats {
 /// Return current TT statistics from the persistent global searcher, if any.
 /// When no global searcher exists yet, initializes one with default size to report capacity.
 pub fn get_current_tt_stats() -> SearchStats {
-    GLOBAL_SEARCHER.with(|cell| {
-        let mut opt = cell.borrow_mut();
-
-        // Ensure searcher exists so we can report its capacity/fill even before first search
-        let searcher = opt.get_or_insert_with(|| Searcher::new(4000));
-        build_search_stats(searcher)
-    })
+    with_global_search(|search| search.tt_stats())
 }
 
 /// Return the completed depth from the last search, or 0 if no search has run yet.
 pub fn get_completed_depth() -> usize {
-    GLOBAL_SEARCHER.with(|cell| cell.borrow().as_ref().map_or(0, |s| s.completed_depth))
+    with_global_search(|search| search.completed_depth())
 }
 
 /// Reset the global search state.
 /// Call this when starting a brand new game so old entries don't carry over.
 pub fn reset_search_state() {
-    GLOBAL_SEARCHER.with(|cell| {
-        *cell.borrow_mut() = None;
-    });
+    with_global_search(|search| search.reset());
 
     // Clear pawn structure cache for new game
     crate::evaluation::base::clear_pawn_cache();
 
     // Clear material cache for new game
     crate::evaluation::insufficient_material::clear_material_cache();
-
-    // Clear transposition table
-    #[cfg(feature = "multithreading")]
-    if let Some(tt) = SHARED_TT.get() {
-        tt.clear()
-    }
 }
 
 /// Search state that persists across the search
@@ -960,6 +1214,9 @@ pub struct Searcher {
     // Stored as (from_x, from_y, to_x, to_y) tuples for fast comparison without cloning
     pub excluded_moves: Vec<(i64, i64, i64, i64)>,
 
+    /// Node/mate/root-move limits of the current search; see [`Searcher::set_limits`].
+    pub limits: SearchLimits,
+
     // Correction History - variant-aware for optimal performance:
     // - PawnBased mode: pawn + material (for CoaIP/Classical/Chess variants)
     // - NonPawnBased mode: non-pawn + material + last-move (for other variants)
@@ -1010,6 +1267,15 @@ pub struct Searcher {
     pub plies_from_null: Box<[u8; MAX_PLY]>,
     pub tt: LocalTranspositionTable,
 
+    /// Coordination state (stop flag, shared TT, helper epoch) this searcher runs on.
+    pub ctx: Arc<SearchContext>,
+
+    /// Endgame tables loaded when the search started (see [`crate::tablebase::loaded`]).
+    pub tablebases: crate::tablebase::Tables,
+
+    /// Syzygy tables loaded when the search started (see [`syzygy::loaded`]).
+    pub syzygy: Option<Arc<syzygy::Syzygy>>,
+
     /// NNUE accumulator stack indexed by ply. `nnue_stack[ply]` holds the
     /// accumulator for the position at that ply (AFTER the parent's move).
     /// Sized `MAX_PLY + 2` to allow a child slot for the deepest ply.
@@ -1027,7 +1293,13 @@ pub struct Searcher {
 }
 
 impl Searcher {
+    /// Creates a searcher on the process-wide [`global_context`].
     pub fn new(time_limit_ms: u128) -> Self {
+        Self::with_context(time_limit_ms, Arc::clone(global_context()))
+    }
+
+    /// Creates a searcher whose stop flag and shared tables come from `ctx`.
+    pub fn with_context(time_limit_ms: u128, ctx: Arc<SearchContext>) -> Self {
         // Triangular PV table
         let pv_table = Box::new([None; MAX_PLY * MAX_PLY]);
 
@@ -1050,19 +1322,9 @@ impl Searcher {
                 stopped: false,
                 seldepth: 0,
                 min_depth_required: 1, // Must complete at least depth 1
-                optimum_time_ms: 0,
-                maximum_time_ms: 0,
-                tot_best_move_changes: 0.0,
-                best_move_changes: 0.0,
-                best_move_nodes: 0,
-                best_previous_average_score: 0,
-                iter_values: [0; 4],
-                iter_idx: 0,
-                prev_time_reduction: 1.0,
-                last_best_move_depth: 0,
-                is_soft_limit: false,
-                total_time_ms: 0.0,
+                time: TimeManager::new(),
                 iter_start_ms: 0.0,
+                node_limit: u64::MAX,
             },
             pv_table,
             pv_length: [0; MAX_PLY],
@@ -1114,6 +1376,7 @@ impl Searcher {
                 )
             },
             excluded_moves: Vec::new(),
+            limits: SearchLimits::default(),
             corrhist_mode: CorrHistMode::NonPawnBased, // Default, set based on variant at search start
             pawn_corrhist: unsafe {
                 Box::from_raw(
@@ -1162,7 +1425,10 @@ impl Searcher {
             },
             #[cfg(not(feature = "multithreading"))]
             pawn_history: zeroed_box(),
-            tt: LocalTranspositionTable::new(TT_SIZE_MB.load(std::sync::atomic::Ordering::Relaxed)),
+            tt: LocalTranspositionTable::new(ctx.tt_size_mb()),
+            ctx,
+            tablebases: crate::tablebase::loaded(),
+            syzygy: syzygy::loaded(),
 
             #[cfg(feature = "nnue")]
             nnue_stack: {
@@ -1331,11 +1597,13 @@ impl Searcher {
         // Only the coordinating thread (thread_id 0) bumps the shared generation.
         #[cfg(feature = "multithreading")]
         if self.thread_id == 0
-            && let Some(tt) = SHARED_TT.get()
+            && let Some(tt) = self.ctx.shared_tt()
         {
             tt.increment_age();
         }
         self.tt.increment_age();
+        self.tablebases = crate::tablebase::loaded();
+        self.syzygy = syzygy::loaded();
 
         // Reset cumulative counters
         self.hot.nodes = 0;
@@ -1347,17 +1615,13 @@ impl Searcher {
         self.hot.min_depth_required = 1;
 
         // Reset time management variables
-        self.hot.tot_best_move_changes = 0.0;
-        self.hot.best_move_changes = 0.0;
-        self.hot.best_move_nodes = 0;
-        self.hot.best_previous_average_score = 0;
-        self.hot.iter_values.fill(0);
-        self.hot.iter_idx = 0;
-        self.hot.prev_time_reduction = 1.0;
-        self.hot.last_best_move_depth = 0;
-        self.hot.total_time_ms = 0.0;
+        self.hot.time.reset();
         self.hot.iter_start_ms = 0.0;
 
+        // Limits only apply to the search they were set for
+        self.limits = SearchLimits::default();
+        self.hot.node_limit = u64::MAX;
+
         // Reset iterative deepening state
         self.prev_score = 0;
         self.completed_depth = 0;
@@ -1382,11 +1646,17 @@ impl Searcher {
         }
     }
 
+    /// Applies `limits` to the search just started with [`Searcher::new_search`].
+    pub fn set_limits(&mut self, limits: &SearchLimits) {
+        self.hot.node_limit = limits.nodes.unwrap_or(u64::MAX);
+        self.limits = limits.clone();
+    }
+
     /// Clears TT and resets all history tables to neutral values.
     pub fn clear(&mut self) {
         // Clear transposition table
         #[cfg(feature = "multithreading")]
-        if let Some(tt) = SHARED_TT.get() {
+        if let Some(tt) = self.ctx.shared_tt() {
             tt.clear();
         }
         self.tt.clear();
@@ -1452,7 +1722,7 @@ impl Searcher {
         // Reset pawn history (racy-but-benign memset of the shared table in MT builds)
         #[cfg(feature = "multithreading")]
         unsafe {
-            std::ptr::write_bytes(shared_hist::pawn_table(), 0, 1);
+            std::ptr::write_bytes(self.ctx.pawn_table(), 0, 1);
         }
         #[cfg(not(feature = "multithreading"))]
         for table in self.pawn_history.iter_mut() {
@@ -1563,7 +1833,7 @@ impl Searcher {
     pub fn pawn_hist(&self, ph_idx: usize, pt_idx: usize, to_idx: usize) -> i32 {
         #[cfg(feature = "multithreading")]
         unsafe {
-            (*shared_hist::pawn_table())[ph_idx][pt_idx][to_idx] as i32
+            (*self.ctx.pawn_table())[ph_idx][pt_idx][to_idx] as i32
         }
         #[cfg(not(feature = "multithreading"))]
         {
@@ -1575,7 +1845,7 @@ impl Searcher {
     #[inline(always)]
     pub fn pawn_hist_apply(&mut self, ph_idx: usize, pt_idx: usize, to_idx: usize, adj: i32) {
         #[cfg(feature = "multithreading")]
-        let entry = unsafe { &mut (*shared_hist::pawn_table())[ph_idx][pt_idx][to_idx] };
+        let entry = unsafe { &mut (*self.ctx.pawn_table())[ph_idx][pt_idx][to_idx] };
         #[cfg(not(feature = "multithreading"))]
         let entry = &mut self.pawn_history[ph_idx][pt_idx][to_idx];
         let cur = *entry as i32;
@@ -1612,6 +1882,12 @@ impl Searcher {
 
     #[inline]
     pub fn check_time(&mut self) -> bool {
+        // Node budget: a hard limit, so it doesn't wait for depth 1 to complete.
+        if self.hot.nodes >= self.hot.node_limit {
+            self.hot.stopped = true;
+            return true;
+        }
+
         // External/inter-thread stop request (helper threads, or an analysis abort
         // written directly into shared wasm memory by the main thread). Polled even
         // with no time limit — unlimited searches must still be stoppable. Detached
@@ -1619,15 +1895,20 @@ impl Searcher {
         if self.hot.nodes & 8191 == 0 {
             // Publish this thread's node count for thread-aggregated NPS.
             #[cfg(feature = "multithreading")]
-            publish_thread_nodes(self.thread_id, self.hot.nodes);
+            self.ctx
+                .publish_thread_nodes(self.thread_id, self.hot.nodes);
 
-            if GLOBAL_STOP.load(std::sync::atomic::Ordering::Relaxed) {
+            if self.ctx.stop.load(std::sync::atomic::Ordering::Relaxed) {
                 self.hot.stopped = true;
                 return true;
             }
             #[cfg(feature = "multithreading")]
             if self.helper_epoch != 0
-                && HELPER_EPOCH.load(std::sync::atomic::Ordering::Relaxed) != self.helper_epoch
+                && self
+                    .ctx
+                    .helper_epoch
+                    .load(std::sync::atomic::Ordering::Relaxed)
+                    != self.helper_epoch
             {
                 self.hot.stopped = true;
                 return true;
@@ -1646,8 +1927,8 @@ impl Searcher {
 
         if self.hot.nodes & 8191 == 0 {
             let elapsed = self.hot.timer.elapsed_ms() as f64;
-            let hard_limit = if self.hot.maximum_time_ms > 0 {
-                self.hot.maximum_time_ms as f64
+            let hard_limit = if self.hot.time.maximum_ms() > 0 {
+                self.hot.time.maximum_ms() as f64
             } else {
                 self.hot.time_limit_ms as f64
             };
@@ -1672,9 +1953,9 @@ impl Searcher {
 
             // If the current depth ALONE has consumed > 50% of the move budget, return.
             // ONLY for hard limits. For soft limits (fixed time), we want to use all time.
-            if !self.hot.is_soft_limit
-                && self.hot.total_time_ms > 0.0
-                && elapsed - self.hot.iter_start_ms > self.hot.total_time_ms * 0.50
+            if !self.hot.time.is_soft()
+                && self.hot.time.total_time_ms() > 0.0
+                && elapsed - self.hot.iter_start_ms > self.hot.time.total_time_ms() * 0.50
             {
                 self.hot.stopped = true;
                 return true;
@@ -1950,7 +2231,7 @@ impl Searcher {
             } else {
                 #[cfg(feature = "multithreading")]
                 {
-                    SHARED_TT.get().and_then(|tt| tt.probe_move(hash))
+                    self.ctx.shared_tt().and_then(|tt| tt.probe_move(hash))
                 }
                 #[cfg(not(feature = "multithreading"))]
                 None
@@ -2018,7 +2299,7 @@ impl Searcher {
                 } else {
                     #[cfg(feature = "multithreading")]
                     {
-                        SHARED_TT.get().and_then(|tt| tt.probe_move(hash))
+                        self.ctx.shared_tt().and_then(|tt| tt.probe_move(hash))
                     }
                     #[cfg(not(feature = "multithreading"))]
                     None
@@ -2080,7 +2361,7 @@ impl Searcher {
             } else {
                 #[cfg(feature = "multithreading")]
                 {
-                    SHARED_TT.get().and_then(|tt| tt.probe_move(hash))
+                    self.ctx.shared_tt().and_then(|tt| tt.probe_move(hash))
                 }
                 #[cfg(not(feature = "multithreading"))]
                 None
@@ -2110,6 +2391,45 @@ impl Searcher {
         self.print_info_multipv(game, depth, score, 1);
     }
 
+    /// The [`DepthInfo`] streamed after completing `depth` with `lines`.
+    fn depth_info<'a>(&self, depth: usize, lines: &'a [PVLine]) -> DepthInfo<'a> {
+        let time_ms = self.hot.timer.elapsed_ms();
+        #[cfg(feature = "multithreading")]
+        let hashfull = if let Some(tt) = self.ctx.shared_tt() {
+            tt.fill_permille()
+        } else {
+            self.tt.fill_permille()
+        };
+        #[cfg(not(feature = "multithreading"))]
+        let hashfull = self.tt.fill_permille();
+
+        // Thread-aggregated node count (Stockfish-style: total across all search
+        // threads). Publish this thread's latest count first, then sum every slot.
+        #[cfg(feature = "multithreading")]
+        let report_nodes = {
+            self.ctx
+                .publish_thread_nodes(self.thread_id, self.hot.nodes);
+            self.ctx.aggregate_search_nodes()
+        };
+        #[cfg(not(feature = "multithreading"))]
+        let report_nodes = self.hot.nodes;
+
+        DepthInfo {
+            depth,
+            seldepth: self.hot.seldepth,
+            nodes: report_nodes,
+            qnodes: self.hot.qnodes,
+            nps: if time_ms > 0 {
+                (report_nodes as u128 * 1000) / time_ms
+            } else {
+                0
+            },
+            time_ms,
+            hashfull,
+            lines,
+        }
+    }
+
     /// Print UCI-style info string with MultiPV index
     pub fn print_info_multipv(
         &self,
@@ -2125,7 +2445,7 @@ impl Searcher {
             0
         };
         #[cfg(feature = "multithreading")]
-        let tt_fill = if let Some(tt) = SHARED_TT.get() {
+        let tt_fill = if let Some(tt) = self.ctx.shared_tt() {
             tt.fill_permille()
         } else {
             self.tt.fill_permille()
@@ -2215,7 +2535,7 @@ impl Searcher {
             0
         };
         #[cfg(feature = "multithreading")]
-        let tt_fill = if let Some(tt) = SHARED_TT.get() {
+        let tt_fill = if let Some(tt) = self.ctx.shared_tt() {
             tt.fill_permille()
         } else {
             self.tt.fill_permille()
@@ -2256,11 +2576,35 @@ impl Searcher {
     }
 }
 
-/// Core timed search implementation using a provided searcher.
+/// [`search_with_searcher`] wrapped as a one-line [`MultiPVResult`].
+fn single_pv_result(
+    searcher: &mut Searcher,
+    game: &mut GameState,
+    max_depth: usize,
+    on_depth: Option<DepthCallback>,
+) -> MultiPVResult {
+    let mut lines: Vec<PVLine> = Vec::with_capacity(1);
+    if let Some((best_move, score)) = search_with_searcher(searcher, game, max_depth, on_depth) {
+        let pv = searcher.extract_pv_only(game, max_depth);
+        let depth = max_depth.min(searcher.hot.seldepth.max(1));
+        lines.push(PVLine {
+            mv: best_move,
+            score,
+            depth,
+            pv,
+        });
+    }
+    let stats = build_search_stats(searcher);
+    MultiPVResult { lines, stats }
+}
+
+/// Core timed search implementation using a provided searcher. `on_depth`, if given, gets
+/// a [`DepthInfo`] with the principal variation after every completed depth.
 fn search_with_searcher(
     searcher: &mut Searcher,
     game: &mut GameState,
     max_depth: usize,
+    mut on_depth: Option<DepthCallback>,
 ) -> Option<(Move, i32)> {
     let moves = game.get_legal_moves();
     if moves.is_empty() {
@@ -2286,6 +2630,17 @@ fn search_with_searcher(
         }
     }
 
+    // Root restriction (UCI `searchmoves`), then Syzygy: keep the moves that hold the
+    // best tablebase result.
+    let restricted = searcher.limits.restrict_root(&mut legal_moves);
+    let filtered = searcher
+        .syzygy
+        .as_deref()
+        .is_some_and(|tables| tables.filter_root_moves(game, &mut legal_moves));
+    if filtered || restricted {
+        fallback_move = legal_moves.first().copied();
+    }
+
     if legal_moves.is_empty() {
         return None;
     }
@@ -2294,8 +2649,9 @@ fn search_with_searcher(
     #[cfg(feature = "nnue")]
     searcher.nnue_init_root(game);
 
-    // If only one move, return immediately with a simple static eval as score.
-    if legal_moves.len() == 1 {
+    // If only one move, return immediately with a simple static eval as score. A streamed
+    // search still searches it, so the frontend gets its depth lines.
+    if legal_moves.len() == 1 && on_depth.is_none() {
         let single = legal_moves[0];
         #[cfg(feature = "nnue")]
         let score = searcher.adjusted_eval(game, evaluate(game, searcher.nnue_at(0)), 0, 0);
@@ -2338,14 +2694,14 @@ fn search_with_searcher(
 
         // Age out PV variability metric at START of each iteration
         // Note: Decay the PERSISTED tot, not the per-iteration changes.
-        searcher.hot.tot_best_move_changes /= 2.0;
+        searcher.hot.time.new_iteration();
 
         // Time check at start of each iteration - but always complete depth 1.
         if searcher.hot.min_depth_required == 0 && searcher.hot.time_limit_ms != u128::MAX {
             let elapsed = searcher.hot.timer.elapsed_ms() as f64;
 
             // 1. Hard stop if we've exceeded the maximum time.
-            if elapsed >= searcher.hot.maximum_time_ms as f64 {
+            if elapsed >= searcher.hot.time.maximum_ms() as f64 {
                 searcher.hot.stopped = true;
                 break;
             }
@@ -2353,13 +2709,13 @@ fn search_with_searcher(
             // Proactive stop: don't start next depth if most budget spent
             // For hard limits (timed games), we are more conservative (50%).
             // For soft limits (fixed time), we push much closer (90%) to use all time.
-            let proactive_threshold = if searcher.hot.is_soft_limit {
+            let proactive_threshold = if searcher.hot.time.is_soft() {
                 0.90
             } else {
                 0.50
             };
-            if searcher.hot.total_time_ms > 0.0
-                && elapsed > searcher.hot.total_time_ms * proactive_threshold
+            if searcher.hot.time.total_time_ms() > 0.0
+                && elapsed > searcher.hot.time.total_time_ms() * proactive_threshold
             {
                 break;
             }
@@ -2436,8 +2792,7 @@ fn search_with_searcher(
             if let Some(prev_coords) = prev_root_move_coords {
                 // Track best move changes for instability calculation
                 if prev_coords != coords {
-                    searcher.hot.best_move_changes += 1.0;
-                    searcher.hot.last_best_move_depth = depth;
+                    searcher.hot.time.best_move_changed(depth);
                 }
             }
             prev_root_move_coords = Some(coords);
@@ -2447,8 +2802,20 @@ fn search_with_searcher(
             searcher.print_info(game, depth, score);
         }
 
-        // Check global stop flag (for helper threads)
-        if GLOBAL_STOP.load(std::sync::atomic::Ordering::Relaxed) {
+        if !searcher.hot.stopped
+            && let (Some(cb), Some(mv)) = (on_depth.as_deref_mut(), best_move)
+        {
+            let line = PVLine {
+                mv,
+                score,
+                depth: depth.min(searcher.hot.seldepth.max(1)),
+                pv: searcher.extract_pv_only(game, depth),
+            };
+            cb(&searcher.depth_info(depth, std::slice::from_ref(&line)));
+        }
+
+        // Check the context's stop flag (for helper threads)
+        if searcher.ctx.stop.load(std::sync::atomic::Ordering::Relaxed) {
             searcher.hot.stopped = true;
         }
 
@@ -2458,83 +2825,20 @@ fn search_with_searcher(
         let mate_shortcut = searcher.thread_id == 0
             && searcher.hot.time_limit_ms != u128::MAX
             && (best_score >= mate_in(3) || best_score == mated_in(2));
-        if searcher.hot.stopped || mate_shortcut {
+        if searcher.hot.stopped || mate_shortcut || searcher.limits.mate_reached(best_score) {
             break;
         }
 
-        // Dynamic Time Management Check
+        // Dynamic Time Management Check: stop once the elapsed time passes the budget
+        // (the optimum scaled by stability, score trend and best-move effort).
         if searcher.hot.time_limit_ms != u128::MAX {
             let elapsed = searcher.hot.timer.elapsed_ms() as f64;
-
-            // Effort tracking: fraction of nodes spent on the best move
-            let nodes_effort = if searcher.hot.nodes > 0 {
-                (searcher.hot.best_move_nodes as f64 * 100000.0) / (searcher.hot.nodes as f64)
-            } else {
-                0.0
-            };
-            let high_best_move_effort = if nodes_effort >= 93340.0 { 0.76 } else { 1.0 };
-
-            // Accumulate instability changes from this iteration
-            searcher.hot.tot_best_move_changes += searcher.hot.best_move_changes;
-            searcher.hot.best_move_changes = 0.0;
-
-            // fallingEval: spend more time when score is dropping
-            let iter_val = searcher.hot.iter_values[searcher.hot.iter_idx];
-            let prev_avg = searcher.hot.best_previous_average_score;
-            let falling_eval = (11.85
-                + 2.24 * (prev_avg - best_score) as f64
-                + 0.93 * (iter_val - best_score) as f64)
-                / 100.0;
-            let falling_eval = falling_eval.clamp(0.57, 1.70);
-
-            // timeReduction: spend less time when best move is stable
-            let k = 0.51;
-            let center = (searcher.hot.last_best_move_depth as f64) + 12.15;
-            let time_reduction = 0.66 + 0.85 / (0.98 + (-k * (depth as f64 - center)).exp());
-
-            let reduction = (1.43 + searcher.hot.prev_time_reduction) / (2.28 * time_reduction);
-
-            // bestMoveInstability: spend more time when best move keeps changing
-            let instability = (1.02 + 2.14 * searcher.hot.tot_best_move_changes).min(2.5);
-
-            // Calculate totalTime with all factors
-            let mut total_factors =
-                (falling_eval * reduction * instability * high_best_move_effort).clamp(0.5, 2.5);
-
-            // If it's a soft limit (like fixed time per move), we want to use
-            // nearly all of the time, not stop early to save time.
-            if searcher.hot.is_soft_limit {
-                total_factors = total_factors.max(0.98);
-            }
-
-            let total_time = searcher.hot.optimum_time_ms as f64 * total_factors;
-
-            let hard_limit = searcher.hot.maximum_time_ms as f64;
-
-            // A search stop is triggered if the elapsed time exceeds the dynamic
-            // limit (calculated from optimum time and stability factors) or the
-            // hard maximum limit.
-            let effective_limit = total_time.min(hard_limit);
-            searcher.hot.total_time_ms = effective_limit; // Store for proactive checks
-
-            if elapsed > effective_limit {
+            let nodes = searcher.hot.nodes;
+            let budget = searcher.hot.time.iteration_done(depth, best_score, nodes);
+            if elapsed > budget {
                 searcher.hot.stopped = true;
                 break;
             }
-
-            // Update iteration tracking AFTER the time check
-            searcher.hot.iter_values[searcher.hot.iter_idx] = best_score;
-            searcher.hot.iter_idx = (searcher.hot.iter_idx + 1) & 3;
-
-            // Update running average score
-            if searcher.hot.best_previous_average_score == 0 {
-                searcher.hot.best_previous_average_score = best_score;
-            } else {
-                searcher.hot.best_previous_average_score =
-                    (best_score + searcher.hot.best_previous_average_score) / 2;
-            }
-
-            searcher.hot.prev_time_reduction = time_reduction;
         }
     }
 
@@ -2560,8 +2864,7 @@ pub fn get_best_move(
     )
 }
 
-
-#[cfg(feature = "multithreading")]
+/// [`SearchInstance::get_best_move_parallel`] on this thread's global instance.
 pub fn get_best_move_parallel(
     game: &mut GameState,
     max_depth: usize,
@@ -2570,191 +2873,41 @@ pub fn get_best_move_parallel(
     silent: bool,
     is_soft_limit: bool,
 ) -> Option<(Move, i32, SearchStats)> {
-    use std::sync::{Arc, Mutex};
-
-    // Clear global stop flag
-    GLOBAL_STOP.store(false, std::sync::atomic::Ordering::Relaxed);
-
-    // Lazy SMP runs only where a thread pool was explicitly provisioned
-    // (initThreadPool on wasm decides the count). Native builds always search
-    // single-threaded; parallelism there belongs to the caller (e.g. one
-    // engine per game), which must not share the global stop/TT coordination.
-    #[cfg(target_arch = "wasm32")]
-    let num_threads = rayon::current_num_threads().max(1);
-    #[cfg(not(target_arch = "wasm32"))]
-    let num_threads = 1;
-
-    USE_SHARED_TT.store(num_threads > 1, std::sync::atomic::Ordering::Relaxed);
-
-    if num_threads == 1 {
-        // Local TT is already initialized in Searcher::new (via get_best_move_threaded)
-        return get_best_move_threaded(
+    with_global_search(|search| {
+        search.get_best_move_parallel(
             game,
             max_depth,
             opt_time_ms,
             max_time_ms,
             silent,
-            0,
-            is_soft_limit,
-        );
-    }
-
-    // Initialize Shared TT for multithreaded search (sized by set_hash_size, like the local TTs).
-    init_shared_tt();
-
-    // Shared storage for thread results - all threads contribute to voting
-    let results: Arc<Mutex<Vec<ThreadResult>>> =
-        Arc::new(Mutex::new(Vec::with_capacity(num_threads)));
-
-    // in_place_scope, NOT scope: from outside the pool, `scope` migrates this closure onto a
-    // pool thread, so the "main" search would run on a random rayon worker (scattering the
-    // persistent thread-local searcher across threads move-to-move). in_place keeps it here.
-    rayon::in_place_scope(|s| {
-        // Spawn helper threads (1..num_threads)
-        for i in 1..num_threads {
-            let results_clone = Arc::clone(&results);
-            let mut game_clone = game.clone();
-
-            s.spawn(move |_| {
-                if let Some((best_move, score, stats)) = get_best_move_threaded(
-                    &mut game_clone,
-                    max_depth,
-                    opt_time_ms,
-                    max_time_ms,
-                    true, // Helpers are always silent
-                    i,
-                    is_soft_limit,
-                ) {
-                    // Get PV length from thread-local searcher
-                    let pv_len = GLOBAL_SEARCHER
-                        .with(|cell| cell.borrow().as_ref().map_or(1, |s| s.pv_length[0].max(1)));
-                    let completed_depth = GLOBAL_SEARCHER
-                        .with(|cell| cell.borrow().as_ref().map_or(1, |s| s.completed_depth));
-
-                    let result = ThreadResult {
-                        best_move,
-                        score,
-                        completed_depth: completed_depth.max(1),
-                        pv_length: pv_len,
-                        nodes: stats.nodes,
-                        thread_id: i,
-                    };
-                    if let Ok(mut results) = results_clone.lock() {
-                        results.push(result);
-                    }
-                }
-            });
-        }
-
-        // Run main search on thread 0 (this thread)
-        if let Some((best_move, score, stats)) = get_best_move_threaded(
-            game,
-            max_depth,
-            opt_time_ms,
-            max_time_ms,
-            silent, // Main thread respects silent flag
-            0,
             is_soft_limit,
-        ) {
-            let pv_len = GLOBAL_SEARCHER
-                .with(|cell| cell.borrow().as_ref().map_or(1, |s| s.pv_length[0].max(1)));
-            let completed_depth = GLOBAL_SEARCHER
-                .with(|cell| cell.borrow().as_ref().map_or(1, |s| s.completed_depth));
-
-            let result = ThreadResult {
-                best_move,
-                score,
-                completed_depth: completed_depth.max(1),
-                pv_length: pv_len,
-                nodes: stats.nodes,
-                thread_id: 0,
-            };
-            if let Ok(mut results) = results.lock() {
-                results.push(result);
-            }
-        }
-
-        // Signal all helper threads to stop
-        GLOBAL_STOP.store(true, std::sync::atomic::Ordering::Relaxed);
-    });
-
-    // All threads have finished - now apply thread voting to select best move
-    let all_results = Arc::try_unwrap(results)
-        .ok()
-        .and_then(|m| m.into_inner().ok())
-        .unwrap_or_default();
-
-    if all_results.is_empty() {
-        return None;
-    }
-
-    // Select the winning thread by Stockfish-style weighted voting.
-    let best_idx = select_best_thread(&all_results);
-    let best_result = &all_results[best_idx];
-
-    // Aggregate total nodes from all threads for accurate NPS reporting
-    let total_nodes: u64 = all_results.iter().map(|r| r.nodes).sum();
-
-    // Build stats from aggregated data
-    let (cap, used, fill) = if let Some(tt) = SHARED_TT.get() {
-        (tt.capacity(), tt.used_entries(), tt.fill_permille())
-    } else {
-        GLOBAL_SEARCHER.with(|cell| {
-            cell.borrow().as_ref().map_or((0, 0, 0), |s| {
-                (s.tt.capacity(), s.tt.used_entries(), s.tt.fill_permille())
-            })
-        })
-    };
-    let stats = SearchStats {
-        nodes: total_nodes,
-        tt_capacity: cap,
-        tt_used: used,
-        tt_fill_permille: fill,
-    };
-
-    Some((best_result.best_move, best_result.score, stats))
-}
-
-#[cfg(not(feature = "multithreading"))]
-pub fn get_best_move_parallel(
-    game: &mut GameState,
-    max_depth: usize,
-    opt_time_ms: u128,
-    max_time_ms: u128,
-    silent: bool,
-    is_soft_limit: bool,
-) -> Option<(Move, i32, SearchStats)> {
-    // Local TT is already initialized in Searcher::new (via get_best_move_threaded)
-    get_best_move_threaded(
-        game,
-        max_depth,
-        opt_time_ms,
-        max_time_ms,
-        silent,
-        0,
-        is_soft_limit,
-    )
+        )
+    })
 }
 
-/// Time-limited search with thread_id for Lazy SMP.
-/// Helper threads (thread_id > 0) skip the first move to distribute work.
-/// Uses persistent GLOBAL_SEARCHER - TT and histories persist across searches.
-/// Call reset_search_state() to clear for a new game.
-/// Analysis-helper lifecycle epoch. Bumping it (new position / stop) makes every running
-/// detached helper exit at its next slice boundary.
+/// Stops all detached analysis helpers (and any in-flight search) on the global context.
 #[cfg(feature = "multithreading")]
-pub(crate) static HELPER_EPOCH: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
-/// Number of detached helpers currently searching (lets a resumed analysis — e.g. "go
-/// deeper" after the helpers retired at 'done' — know it must spawn a fresh batch).
-#[cfg(feature = "multithreading")]
-#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
-pub(crate) static HELPERS_LIVE: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+pub fn stop_analysis_helpers() {
+    global_context().stop_analysis_helpers();
+}
 
-/// Stops all detached analysis helpers (and any in-flight search) immediately.
+/// Runs `f` on this thread's persistent helper searcher, rebuilding it first if it last
+/// helped a different context (its histories belong to another engine).
 #[cfg(feature = "multithreading")]
-pub fn stop_analysis_helpers() {
-    HELPER_EPOCH.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-    GLOBAL_STOP.store(true, std::sync::atomic::Ordering::Relaxed);
+fn with_helper_searcher<R>(
+    ctx: &Arc<SearchContext>,
+    time_limit_ms: u128,
+    f: impl FnOnce(&mut Searcher) -> R,
+) -> R {
+    HELPER_SEARCHER.with(|cell| {
+        let mut opt = cell.borrow_mut();
+        if opt.as_ref().is_some_and(|s| !Arc::ptr_eq(&s.ctx, ctx)) {
+            *opt = None;
+        }
+        let searcher =
+            opt.get_or_insert_with(|| Searcher::with_context(time_limit_ms, Arc::clone(ctx)));
+        f(searcher)
+    })
 }
 
 /// Detached Lazy SMP analysis helper: ONE continuous unbounded iterative-deepening search
@@ -2762,27 +2915,24 @@ pub fn stop_analysis_helpers() {
 /// It searches straight through the worker's JS yields and retires within a node batch once
 /// its epoch is superseded (position change / stop) via check_time.
 #[cfg(feature = "multithreading")]
-#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
-pub(crate) fn helper_run(mut game: GameState, epoch: u64, thread_id: usize) {
-    if HELPER_EPOCH.load(std::sync::atomic::Ordering::Relaxed) != epoch {
+fn helper_run(ctx: Arc<SearchContext>, mut game: GameState, epoch: u64, thread_id: usize) {
+    if ctx.helper_epoch.load(std::sync::atomic::Ordering::Relaxed) != epoch {
         return; // Superseded while queued behind the previous batch.
     }
-    HELPERS_LIVE.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    ctx.helpers_live
+        .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
     game.recompute_piece_counts();
     game.recompute_correction_hashes();
 
-    GLOBAL_SEARCHER.with(|cell| {
-        let mut opt = cell.borrow_mut();
-        let searcher = opt.get_or_insert_with(|| Searcher::new(u128::MAX));
-
-        // Unique RNG per helper for search diversity (mirrors get_best_move_threaded).
+    with_helper_searcher(&ctx, u128::MAX, |searcher| {
+        // Unique RNG per helper for search diversity (mirrors run_timed_search).
         let base_seed = searcher.seed;
         searcher.rng = Prng::new(base_seed.wrapping_add(thread_id as u64));
         searcher.new_search();
 
         searcher.thread_id = thread_id;
         searcher.helper_epoch = epoch;
-        // No time limit: only GLOBAL_STOP or an epoch bump ends this search.
+        // No time limit: only the stop flag or an epoch bump ends this search.
         searcher.hot.set_time_limits(u128::MAX, u128::MAX, true);
         searcher.silent = true;
         searcher.hot.timer.reset();
@@ -2792,13 +2942,53 @@ pub(crate) fn helper_run(mut game: GameState, epoch: u64, thread_id: usize) {
             .move_rule_limit
             .map_or(i32::MAX, |v| v as i32);
 
-        let _ = search_with_searcher(searcher, &mut game, MAX_PLY);
+        let _ = search_with_searcher(searcher, &mut game, MAX_PLY, None);
         searcher.helper_epoch = 0;
     });
-    HELPERS_LIVE.fetch_sub(1, std::sync::atomic::Ordering::Relaxed);
+    ctx.helpers_live
+        .fetch_sub(1, std::sync::atomic::Ordering::Relaxed);
 }
 
+/// Lazy SMP helper search (always silent) on this thread's helper searcher for `ctx`.
+/// Returns the thread's result for voting.
+#[cfg(feature = "multithreading")]
 pub(crate) fn get_best_move_threaded(
+    ctx: &Arc<SearchContext>,
+    game: &mut GameState,
+    max_depth: usize,
+    opt_time_ms: u128,
+    max_time_ms: u128,
+    thread_id: usize,
+    is_soft_limit: bool,
+) -> Option<ThreadResult> {
+    with_helper_searcher(ctx, max_time_ms, |searcher| {
+        let (best_move, score, stats) = run_timed_search(
+            searcher,
+            game,
+            max_depth,
+            opt_time_ms,
+            max_time_ms,
+            true,
+            thread_id,
+            is_soft_limit,
+        )?;
+        Some(ThreadResult {
+            best_move,
+            score,
+            completed_depth: searcher.completed_depth.max(1),
+            pv_length: searcher.pv_length[0].max(1),
+            nodes: stats.nodes,
+            thread_id,
+        })
+    })
+}
+
+/// Time-limited search with thread_id for Lazy SMP.
+/// Helper threads (thread_id > 0) skip the first move to distribute work.
+/// `searcher` is persistent - TT and histories carry over between searches.
+#[allow(clippy::too_many_arguments)]
+fn run_timed_search(
+    searcher: &mut Searcher,
     game: &mut GameState,
     max_depth: usize,
     opt_time_ms: u128,
@@ -2812,75 +3002,483 @@ pub(crate) fn get_best_move_threaded(
     // Initialize correction history hashes
     game.recompute_correction_hashes();
 
-    // Use persistent global searcher
-    GLOBAL_SEARCHER.with(|cell| {
-        let mut opt = cell.borrow_mut();
+    // If this is a helper thread, ensure it has a unique RNG state based on global seed
+    if thread_id > 0 {
+        // Helpers don't mutate global seed, but use it to seed their local RNG
+        // We use wrapping_add to ensure deterministic variation per thread
+        let base_seed = searcher.seed;
+        searcher.rng = Prng::new(base_seed.wrapping_add(thread_id as u64));
+    }
 
-        // Get or create the persistent searcher
-        let searcher = opt.get_or_insert_with(|| Searcher::new(max_time_ms));
+    searcher.thread_id = thread_id;
+
+    // Initialize searcher for this search
+    searcher.new_search();
+
+    // Update search parameters for this search
+    searcher
+        .hot
+        .set_time_limits(opt_time_ms, max_time_ms, is_soft_limit);
+    searcher.silent = silent;
+    searcher.hot.timer.reset();
+
+    // Set correction mode based on variant (zero overhead during search)
+    searcher.set_corrhist_mode(game);
+    searcher.move_rule_limit = game
+        .game_rules
+        .move_rule_limit
+        .map_or(i32::MAX, |v| v as i32);
+
+    let result = search_with_searcher(searcher, game, max_depth, None);
+    let stats = build_search_stats(searcher);
+    result.map(|(m, eval)| (m, eval, stats))
+}
 
-        // If this is a helper thread, ensure it has a unique RNG state based on global seed
-        if thread_id > 0 {
-            // Helpers don't mutate global seed, but use it to seed their local RNG
-            // We use wrapping_add to ensure deterministic variation per thread
-            let base_seed = searcher.seed;
-            searcher.rng = Prng::new(base_seed.wrapping_add(thread_id as u64));
+/// MultiPV-enabled search that returns up to `multi_pv` best moves with their evaluations.
+///
+/// When `multi_pv` is 1, this has zero overhead - it's equivalent to `get_best_move`.
+/// For `multi_pv` > 1, at each depth all root moves are searched and the top N are returned.
+pub fn get_best_moves_multipv(
+    game: &mut GameState,
+    max_depth: usize,
+    opt_time_ms: u128,
+    max_time_ms: u128,
+    multi_pv: usize,
+    silent: bool,
+    is_soft_limit: bool,
+) -> MultiPVResult {
+    with_global_search(|search| {
+        search.get_best_moves_multipv(
+            game,
+            max_depth,
+            opt_time_ms,
+            max_time_ms,
+            multi_pv,
+            silent,
+            is_soft_limit,
+        )
+    })
+}
+
+/// [`SearchInstance::get_best_move_with_limits`] on this thread's global instance.
+pub fn get_best_move_with_limits(
+    game: &mut GameState,
+    max_depth: usize,
+    opt_time_ms: u128,
+    max_time_ms: u128,
+    limits: &SearchLimits,
+    silent: bool,
+    is_soft_limit: bool,
+) -> Option<(Move, i32, SearchStats)> {
+    with_global_search(|search| {
+        search.get_best_move_with_limits(
+            game,
+            max_depth,
+            opt_time_ms,
+            max_time_ms,
+            limits,
+            silent,
+            is_soft_limit,
+        )
+    })
+}
+
+/// [`SearchInstance::search_streamed`] on this thread's global instance.
+#[allow(clippy::too_many_arguments)]
+pub fn search_streamed(
+    game: &mut GameState,
+    max_depth: usize,
+    opt_time_ms: u128,
+    max_time_ms: u128,
+    multi_pv: usize,
+    is_soft_limit: bool,
+    limits: &SearchLimits,
+    on_depth: DepthCallback,
+) -> MultiPVResult {
+    with_global_search(|search| {
+        search.search_streamed(
+            game,
+            max_depth,
+            opt_time_ms,
+            max_time_ms,
+            multi_pv,
+            is_soft_limit,
+            limits,
+            on_depth,
+        )
+    })
+}
+
+/// [`SearchInstance::analyse_position`] on this thread's global instance.
+pub fn analyse_position(
+    game: &mut GameState,
+    max_depth: usize,
+    start_depth: usize,
+    slice_ms: u128,
+    multi_pv: usize,
+    on_depth: DepthCallback,
+) -> MultiPVResult {
+    with_global_search(|search| {
+        search.analyse_position(game, max_depth, start_depth, slice_ms, multi_pv, on_depth)
+    })
+}
+
+/// Sets the global seed and re-initializes the PRNG.
+/// This affects subsequent calls to functions that use GLOBAL_SEARCHER.
+pub fn set_global_params(seed: u64, noise_amp: Option<i32>) {
+    with_global_search(|search| search.set_params(seed, noise_amp));
+}
+
+/// Selects a move from MultiPV results using strength-limiting logic.
+fn pick_best(result: &MultiPVResult, skill_level: u32, rng: &mut Prng) -> Option<(Move, i32)> {
+    if result.lines.is_empty() {
+        return None;
+    }
+    if result.lines.len() == 1 || skill_level >= 20 {
+        let best = &result.lines[0];
+        return Some((best.mv, best.score));
+    }
+
+    let top_score = result.lines[0].score;
+    let last_score = result.lines.last().unwrap().score;
+    let delta = (top_score - last_score).min(100); // 100 cp = PawnValue
+    let weakness = 120 - 2 * skill_level as i32;
+
+    let mut max_score = -INFINITY;
+    let mut chosen_idx = 0;
+
+    for (idx, line) in result.lines.iter().enumerate() {
+        let rng_val = (rng.next_f64() * (weakness as f64)) as i32;
+        let push = (weakness * (top_score - line.score) + delta * rng_val) / 128;
+
+        if line.score + push >= max_score {
+            max_score = line.score + push;
+            chosen_idx = idx;
         }
+    }
 
-        searcher.thread_id = thread_id;
+    let best = &result.lines[chosen_idx];
+    Some((best.mv, best.score))
+}
 
-        // Initialize searcher for this search
-        searcher.new_search();
+impl SearchInstance {
+    /// Timed search on this instance, running Lazy SMP helpers where a thread pool was
+    /// provisioned. Returns the best move, its evaluation and TT statistics.
+    #[cfg(feature = "multithreading")]
+    pub fn get_best_move_parallel(
+        &mut self,
+        game: &mut GameState,
+        max_depth: usize,
+        opt_time_ms: u128,
+        max_time_ms: u128,
+        silent: bool,
+        is_soft_limit: bool,
+    ) -> Option<(Move, i32, SearchStats)> {
+        use std::sync::Mutex;
+
+        if let Some(book_move) = self.probe_book(game) {
+            return Some(book_move);
+        }
+
+        // Clear any stale stop request
+        self.ctx
+            .stop
+            .store(false, std::sync::atomic::Ordering::Relaxed);
+
+        // Lazy SMP runs only where a thread pool was explicitly provisioned
+        // (initThreadPool on wasm decides the count). Native builds always search
+        // single-threaded; parallelism there belongs to the caller (e.g. one
+        // engine per game), which must not share the global stop/TT coordination.
+        #[cfg(target_arch = "wasm32")]
+        let num_threads = rayon::current_num_threads().max(1);
+        #[cfg(not(target_arch = "wasm32"))]
+        let num_threads = 1;
+
+        self.ctx
+            .use_shared_tt
+            .store(num_threads > 1, std::sync::atomic::Ordering::Relaxed);
+
+        if num_threads == 1 {
+            // Local TT is already initialized in Searcher::new
+            return run_timed_search(
+                self.searcher_mut(max_time_ms),
+                game,
+                max_depth,
+                opt_time_ms,
+                max_time_ms,
+                silent,
+                0,
+                is_soft_limit,
+            );
+        }
 
-        // Update search parameters for this search
-        searcher
-            .hot
-            .set_time_limits(opt_time_ms, max_time_ms, is_soft_limit);
-        searcher.silent = silent;
-        searcher.hot.timer.reset();
+        // Initialize Shared TT for multithreaded search (sized by set_hash_size, like the local TTs).
+        self.ctx.init_shared_tt();
+
+        // Shared storage for thread results - all threads contribute to voting
+        let results: Arc<Mutex<Vec<ThreadResult>>> =
+            Arc::new(Mutex::new(Vec::with_capacity(num_threads)));
+        let ctx = Arc::clone(&self.ctx);
+
+        // in_place_scope, NOT scope: from outside the pool, `scope` migrates this closure onto a
+        // pool thread, so the "main" search would run on a random rayon worker (scattering the
+        // persistent searcher's work across threads move-to-move). in_place keeps it here.
+        rayon::in_place_scope(|s| {
+            // Spawn helper threads (1..num_threads)
+            for i in 1..num_threads {
+                let results_clone = Arc::clone(&results);
+                let mut game_clone = game.clone();
+                let ctx = Arc::clone(&ctx);
+
+                s.spawn(move |_| {
+                    if let Some(result) = get_best_move_threaded(
+                        &ctx,
+                        &mut game_clone,
+                        max_depth,
+                        opt_time_ms,
+                        max_time_ms,
+                        i,
+                        is_soft_limit,
+                    ) && let Ok(mut results) = results_clone.lock()
+                    {
+                        results.push(result);
+                    }
+                });
+            }
 
-        // Set correction mode based on variant (zero overhead during search)
-        searcher.set_corrhist_mode(game);
-        searcher.move_rule_limit = game
-            .game_rules
-            .move_rule_limit
-            .map_or(i32::MAX, |v| v as i32);
+            // Run main search on thread 0 (this thread)
+            let searcher = self.searcher_mut(max_time_ms);
+            if let Some((best_move, score, stats)) = run_timed_search(
+                searcher,
+                game,
+                max_depth,
+                opt_time_ms,
+                max_time_ms,
+                silent, // Main thread respects silent flag
+                0,
+                is_soft_limit,
+            ) {
+                let result = ThreadResult {
+                    best_move,
+                    score,
+                    completed_depth: searcher.completed_depth.max(1),
+                    pv_length: searcher.pv_length[0].max(1),
+                    nodes: stats.nodes,
+                    thread_id: 0,
+                };
+                if let Ok(mut results) = results.lock() {
+                    results.push(result);
+                }
+            }
+
+            // Signal all helper threads to stop
+            ctx.request_stop();
+        });
+
+        // All threads have finished - now apply thread voting to select best move
+        let all_results = Arc::try_unwrap(results)
+            .ok()
+            .and_then(|m| m.into_inner().ok())
+            .unwrap_or_default();
+
+        if all_results.is_empty() {
+            return None;
+        }
+
+        // Select the winning thread by Stockfish-style weighted voting.
+        let best_idx = select_best_thread(&all_results);
+        let best_result = &all_results[best_idx];
+
+        // Aggregate total nodes from all threads for accurate NPS reporting
+        let total_nodes: u64 = all_results.iter().map(|r| r.nodes).sum();
+
+        // Build stats from aggregated data
+        let (cap, used, fill) = if let Some(tt) = self.ctx.shared_tt() {
+            (tt.capacity(), tt.used_entries(), tt.fill_permille())
+        } else {
+            self.searcher.as_ref().map_or((0, 0, 0), |s| {
+                (s.tt.capacity(), s.tt.used_entries(), s.tt.fill_permille())
+            })
+        };
+        let stats = SearchStats {
+            nodes: total_nodes,
+            tt_capacity: cap,
+            tt_used: used,
+            tt_fill_permille: fill,
+        };
+
+        Some((best_result.best_move, best_result.score, stats))
+    }
+
+    /// Timed search on this instance. Returns the best move, its evaluation and TT statistics.
+    #[cfg(not(feature = "multithreading"))]
+    pub fn get_best_move_parallel(
+        &mut self,
+        game: &mut GameState,
+        max_depth: usize,
+        opt_time_ms: u128,
+        max_time_ms: u128,
+        silent: bool,
+        is_soft_limit: bool,
+    ) -> Option<(Move, i32, SearchStats)> {
+        if let Some(book_move) = self.probe_book(game) {
+            return Some(book_move);
+        }
+
+        // Clear any stale stop request
+        self.ctx
+            .stop
+            .store(false, std::sync::atomic::Ordering::Relaxed);
+
+        // Local TT is already initialized in Searcher::new
+        run_timed_search(
+            self.searcher_mut(max_time_ms),
+            game,
+            max_depth,
+            opt_time_ms,
+            max_time_ms,
+            silent,
+            0,
+            is_soft_limit,
+        )
+    }
+
+    /// MultiPV search on this instance; see [`get_best_moves_multipv`].
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_best_moves_multipv(
+        &mut self,
+        game: &mut GameState,
+        max_depth: usize,
+        opt_time_ms: u128,
+        max_time_ms: u128,
+        multi_pv: usize,
+        silent: bool,
+        is_soft_limit: bool,
+    ) -> MultiPVResult {
+        let multi_pv = multi_pv.max(1);
+        let searcher =
+            self.prepare_timed_search(game, opt_time_ms, max_time_ms, silent, is_soft_limit);
+
+        // MultiPV = 1: Zero overhead path - just do normal search
+        if multi_pv == 1 {
+            return single_pv_result(searcher, game, max_depth, None);
+        }
+
+        // MultiPV > 1: Search with special root handling to collect multiple best moves
+        get_best_moves_multipv_impl(
+            searcher, game, max_depth, multi_pv, silent, None, None, None,
+        )
+    }
 
-        let result = search_with_searcher(searcher, game, max_depth);
+    /// Single-threaded timed search that also honours the node budget, mate target and
+    /// root restriction in `limits`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_best_move_with_limits(
+        &mut self,
+        game: &mut GameState,
+        max_depth: usize,
+        opt_time_ms: u128,
+        max_time_ms: u128,
+        limits: &SearchLimits,
+        silent: bool,
+        is_soft_limit: bool,
+    ) -> Option<(Move, i32, SearchStats)> {
+        let searcher =
+            self.prepare_timed_search(game, opt_time_ms, max_time_ms, silent, is_soft_limit);
+        searcher.set_limits(limits);
+        let result = search_with_searcher(searcher, game, max_depth, None);
         let stats = build_search_stats(searcher);
         result.map(|(m, eval)| (m, eval, stats))
-    })
-}
+    }
 
-/// MultiPV-enabled search that returns up to `multi_pv` best moves with their evaluations.
-///
-/// When `multi_pv` is 1, this has zero overhead - it's equivalent to `get_best_move`.
-/// For `multi_pv` > 1, at each depth all root moves are searched and the top N are returned.
-pub fn get_best_moves_multipv(
-    game: &mut GameState,
-    max_depth: usize,
-    opt_time_ms: u128,
-    max_time_ms: u128,
-    multi_pv: usize,
-    silent: bool,
-    is_soft_limit: bool,
-) -> MultiPVResult {
-    // Clear any stale stop request (check_time polls GLOBAL_STOP).
-    GLOBAL_STOP.store(false, std::sync::atomic::Ordering::Relaxed);
+    /// Timed MultiPV search that streams a [`DepthInfo`] to `on_depth` after every completed
+    /// iterative-deepening depth, for protocol frontends that print per-depth `info` lines.
+    /// `multi_pv == 1` runs the normal single-PV search; a stop request ends either with
+    /// the last completed depth's lines.
+    ///
+    /// Unlike the other searches this keeps a pending stop request, so a stop sent right
+    /// after the search was handed to another thread is not lost. Callers clear the flag
+    /// with [`SearchContext::clear_stop`] before starting it.
+    #[allow(clippy::too_many_arguments)]
+    pub fn search_streamed(
+        &mut self,
+        game: &mut GameState,
+        max_depth: usize,
+        opt_time_ms: u128,
+        max_time_ms: u128,
+        multi_pv: usize,
+        is_soft_limit: bool,
+        limits: &SearchLimits,
+        on_depth: DepthCallback,
+    ) -> MultiPVResult {
+        #[cfg(feature = "multithreading")]
+        let parallel = self.threads > 1;
+        #[cfg(feature = "multithreading")]
+        {
+            self.ctx
+                .use_shared_tt
+                .store(parallel, std::sync::atomic::Ordering::Relaxed);
+            if parallel {
+                self.ensure_analysis_helpers(game, 1, self.threads);
+            }
+        }
 
-    // Ensure fast per-color piece counts are in sync with the board
-    game.recompute_piece_counts();
-    // Initialize correction history hashes
-    game.recompute_correction_hashes();
+        let searcher = self.ready_timed_search(game, opt_time_ms, max_time_ms, true, is_soft_limit);
+        searcher.set_limits(limits);
+        let result = if multi_pv <= 1 {
+            single_pv_result(searcher, game, max_depth, Some(on_depth))
+        } else {
+            get_best_moves_multipv_impl(
+                searcher,
+                game,
+                max_depth,
+                multi_pv,
+                true,
+                None,
+                None,
+                Some(on_depth),
+            )
+        };
 
-    let multi_pv = multi_pv.max(1);
+        #[cfg(feature = "multithreading")]
+        if parallel {
+            self.ctx.stop_analysis_helpers();
+        }
+        result
+    }
 
-    // Use persistent global searcher pattern:
-    GLOBAL_SEARCHER.with(|cell| {
-        let mut opt = cell.borrow_mut();
+    /// Common setup for the timed searches on this instance: clears any stale stop request,
+    /// then [`ready_timed_search`](Self::ready_timed_search).
+    fn prepare_timed_search(
+        &mut self,
+        game: &mut GameState,
+        opt_time_ms: u128,
+        max_time_ms: u128,
+        silent: bool,
+        is_soft_limit: bool,
+    ) -> &mut Searcher {
+        // Clear any stale stop request (check_time polls the context's stop flag).
+        self.ctx.clear_stop();
+        self.ready_timed_search(game, opt_time_ms, max_time_ms, silent, is_soft_limit)
+    }
+
+    /// Syncs the game's incremental counters and readies the persistent searcher, leaving
+    /// the stop flag alone.
+    fn ready_timed_search(
+        &mut self,
+        game: &mut GameState,
+        opt_time_ms: u128,
+        max_time_ms: u128,
+        silent: bool,
+        is_soft_limit: bool,
+    ) -> &mut Searcher {
+        // Ensure fast per-color piece counts are in sync with the board
+        game.recompute_piece_counts();
+        // Initialize correction history hashes
+        game.recompute_correction_hashes();
 
         // Get or create the persistent searcher
-        let searcher = opt.get_or_insert_with(|| Searcher::new(max_time_ms));
+        let searcher = self.searcher_mut(max_time_ms);
 
         // Initialize searcher for this search
         searcher.new_search();
@@ -2897,68 +3495,47 @@ pub fn get_best_moves_multipv(
             .game_rules
             .move_rule_limit
             .map_or(i32::MAX, |v| v as i32);
+        searcher
+    }
 
-        // MultiPV = 1: Zero overhead path - just do normal search
-        if multi_pv == 1 {
-            let mut lines: Vec<PVLine> = Vec::with_capacity(1);
-            if let Some((best_move, score)) = search_with_searcher(searcher, game, max_depth) {
-                let pv = searcher.extract_pv_only(game, max_depth);
-                let depth = max_depth.min(searcher.hot.seldepth.max(1));
-                lines.push(PVLine {
-                    mv: best_move,
-                    score,
-                    depth,
-                    pv,
-                });
-            }
-            let stats = build_search_stats(searcher);
-            return MultiPVResult { lines, stats };
-        }
-
-        // MultiPV > 1: Search with special root handling to collect multiple best moves
-        get_best_moves_multipv_impl(
-            searcher, game, max_depth, multi_pv, silent, None, None, None,
-        )
-    })
-}
-
-/// Analysis driver: a time-sliced MultiPV search that streams a [`DepthInfo`] to
-/// `on_depth` after every completed iterative-deepening depth.
-///
-/// Designed to be called repeatedly with short `slice_ms` budgets from a worker that
-/// yields to its message queue between slices. Pass `start_depth = last_reached + 1`
-/// so each slice *resumes* the iterative deepening rather than re-walking from depth 1
-/// (the persistent TT makes the resumed depth cheap) — this is what lets "go deeper"
-/// actually progress instead of oscillating at the target depth. The first iteration
-/// always completes regardless of the slice budget (`min_depth_required`), so a slice
-/// always advances by at least one depth. Unlike the gameplay wrappers this always
-/// takes the MultiPV root path (even for `multi_pv == 1`) so every depth reports full
-/// PV lines.
-pub fn analyse_position(
-    game: &mut GameState,
-    max_depth: usize,
-    start_depth: usize,
-    slice_ms: u128,
-    multi_pv: usize,
-    on_depth: DepthCallback,
-) -> MultiPVResult {
-    // Clear any stale stop request (a stop may have been written externally).
-    GLOBAL_STOP.store(false, std::sync::atomic::Ordering::Relaxed);
-
-    game.recompute_piece_counts();
-    game.recompute_correction_hashes();
-
-    let multi_pv = multi_pv.max(1);
-
-    // start_depth == 1 marks the first slice of a fresh position; anything higher is a
-    // resume of the same position's search, where we KEEP the accumulated heuristics
-    // (history, killers, PV, TT) so the resumed depth benefits from warm move ordering
-    // — a cold new_search() at a high depth would explode the node count.
-    let fresh = start_depth <= 1;
-
-    GLOBAL_SEARCHER.with(|cell| {
-        let mut opt = cell.borrow_mut();
-        let searcher = opt.get_or_insert_with(|| Searcher::new(slice_ms));
+    /// Analysis driver: a time-sliced MultiPV search that streams a [`DepthInfo`] to
+    /// `on_depth` after every completed iterative-deepening depth.
+    ///
+    /// Designed to be called repeatedly with short `slice_ms` budgets from a worker that
+    /// yields to its message queue between slices. Pass `start_depth = last_reached + 1`
+    /// so each slice *resumes* the iterative deepening rather than re-walking from depth 1
+    /// (the persistent TT makes the resumed depth cheap) — this is what lets "go deeper"
+    /// actually progress instead of oscillating at the target depth. The first iteration
+    /// always completes regardless of the slice budget (`min_depth_required`), so a slice
+    /// always advances by at least one depth. Unlike the gameplay wrappers this always
+    /// takes the MultiPV root path (even for `multi_pv == 1`) so every depth reports full
+    /// PV lines.
+    pub fn analyse_position(
+        &mut self,
+        game: &mut GameState,
+        max_depth: usize,
+        start_depth: usize,
+        slice_ms: u128,
+        multi_pv: usize,
+        on_depth: DepthCallback,
+    ) -> MultiPVResult {
+        // Clear any stale stop request (a stop may have been written externally).
+        self.ctx
+            .stop
+            .store(false, std::sync::atomic::Ordering::Relaxed);
+
+        game.recompute_piece_counts();
+        game.recompute_correction_hashes();
+
+        let multi_pv = multi_pv.max(1);
+
+        // start_depth == 1 marks the first slice of a fresh position; anything higher is a
+        // resume of the same position's search, where we KEEP the accumulated heuristics
+        // (history, killers, PV, TT) so the resumed depth benefits from warm move ordering
+        // — a cold new_search() at a high depth would explode the node count.
+        let fresh = start_depth <= 1;
+
+        let searcher = self.searcher_mut(slice_ms);
 
         if fresh {
             searcher.new_search();
@@ -2971,7 +3548,8 @@ pub fn analyse_position(
             searcher.hot.stopped = false;
             searcher.hot.min_depth_required = 1;
             searcher.hot.iter_start_ms = 0.0;
-            searcher.hot.total_time_ms = 0.0;
+            searcher.hot.time.set_total_time_ms(0.0);
+            searcher.set_limits(&SearchLimits::default());
         }
         // No per-node time limit: `check_time` must never abort mid-depth, so every depth
         // completes fully and its result is deterministic. Responsiveness instead comes
@@ -3002,90 +3580,118 @@ pub fn analyse_position(
             deadline,
             Some(on_depth),
         )
-    })
-}
+    }
 
-/// Sets the global seed and re-initializes the PRNG.
-/// This affects subsequent calls to functions that use GLOBAL_SEARCHER.
-pub fn set_global_params(seed: u64, noise_amp: Option<i32>) {
-    GLOBAL_SEARCHER.with(|cell| {
-        let mut opt = cell.borrow_mut();
-        // Get or create the persistent searcher.
+    /// Multithreaded analysis: makes sure `num_threads - 1` detached Lazy SMP helpers are
+    /// searching `game` and filling this instance's shared TT. A new position
+    /// (`start_depth <= 1`), or a resume with no batch alive (e.g. "go deeper" after the
+    /// helpers retired at done), retires any previous batch and launches a fresh one.
+    /// Callers clear the stop flag first; a pending stop also ends the new helpers.
+    #[cfg(feature = "multithreading")]
+    pub(crate) fn ensure_analysis_helpers(
+        &self,
+        game: &GameState,
+        start_depth: usize,
+        num_threads: usize,
+    ) {
+        self.ctx.init_shared_tt();
+        self.ctx
+            .use_shared_tt
+            .store(true, std::sync::atomic::Ordering::Relaxed);
+
+        if start_depth <= 1
+            || self
+                .ctx
+                .helpers_live
+                .load(std::sync::atomic::Ordering::Relaxed)
+                == 0
+        {
+            let epoch = self
+                .ctx
+                .helper_epoch
+                .fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+                + 1;
+            for i in 1..num_threads {
+                let game_clone = game.clone();
+                let ctx = Arc::clone(&self.ctx);
+                rayon::spawn(move || helper_run(ctx, game_clone, epoch, i));
+            }
+        }
+    }
+
+    /// Sets the seed and noise amplitude for this instance's searches and
+    /// re-initializes the PRNG.
+    pub fn set_params(&mut self, seed: u64, noise_amp: Option<i32>) {
         // If not initialized, we use a default max_time (e.g. 1000) which will be updated later.
-        let searcher = opt.get_or_insert_with(|| Searcher::new(1000));
+        let searcher = self.searcher_mut(1000);
 
         searcher.seed = seed;
         searcher.rng = Prng::new(seed);
         searcher.noise_amp = noise_amp.unwrap_or(0);
-    });
-}
-
-/// Selects a move from MultiPV results using strength-limiting logic.
-fn pick_best(result: &MultiPVResult, skill_level: u32, rng: &mut Prng) -> Option<(Move, i32)> {
-    if result.lines.is_empty() {
-        return None;
-    }
-    if result.lines.len() == 1 || skill_level >= 20 {
-        let best = &result.lines[0];
-        return Some((best.mv, best.score));
     }
 
-    let top_score = result.lines[0].score;
-    let last_score = result.lines.last().unwrap().score;
-    let delta = (top_score - last_score).min(100); // 100 cp = PawnValue
-    let weakness = 120 - 2 * skill_level as i32;
-
-    let mut max_score = -INFINITY;
-    let mut chosen_idx = 0;
-
-    for (idx, line) in result.lines.iter().enumerate() {
-        let rng_val = (rng.next_f64() * (weakness as f64)) as i32;
-        let push = (weakness * (top_score - line.score) + delta * rng_val) / 128;
-
-        if line.score + push >= max_score {
-            max_score = line.score + push;
-            chosen_idx = idx;
+    /// Entry point for searches with strength limiting.
+    /// Consolidated to use standard search path with root-level move selection.
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_best_move_limited(
+        &mut self,
+        game: &mut GameState,
+        max_depth: usize,
+        opt_time_ms: u128,
+        max_time_ms: u128,
+        strength_level: Option<u32>,
+        silent: bool,
+        is_soft_limit: bool,
+    ) -> Option<(Move, i32, SearchStats)> {
+        // Clear any stale stop request (check_time polls the context's stop flag).
+        self.ctx
+            .stop
+            .store(false, std::sync::atomic::Ordering::Relaxed);
+
+        let input_skill = strength_level.unwrap_or(MAX_SITE_SKILL);
+        if input_skill >= MAX_SITE_SKILL {
+            game.recompute_piece_counts();
+            game.recompute_correction_hashes();
+            return self.get_best_move_parallel(
+                game,
+                max_depth,
+                opt_time_ms,
+                max_time_ms,
+                silent,
+                is_soft_limit,
+            );
         }
-    }
-
-    let best = &result.lines[chosen_idx];
-    Some((best.mv, best.score))
-}
-
-/// Entry point for searches with strength limiting.
-/// Consolidated to use standard search path with root-level move selection.
-pub(crate) fn get_best_move_limited(
-    game: &mut GameState,
-    max_depth: usize,
-    opt_time_ms: u128,
-    max_time_ms: u128,
-    strength_level: Option<u32>,
-    silent: bool,
-    is_soft_limit: bool,
-) -> Option<(Move, i32, SearchStats)> {
-    // Clear any stale stop request (check_time polls GLOBAL_STOP).
-    GLOBAL_STOP.store(false, std::sync::atomic::Ordering::Relaxed);
-
-    game.recompute_piece_counts();
-    game.recompute_correction_hashes();
-
-    let input_skill = strength_level
-        .unwrap_or(MAX_SITE_SKILL)
-        .clamp(1, MAX_SITE_SKILL);
-    if input_skill >= MAX_SITE_SKILL {
-        return get_best_move_parallel(
+        self.get_best_move_weakened(
             game,
             max_depth,
             opt_time_ms,
             max_time_ms,
+            input_skill,
             silent,
             is_soft_limit,
-        );
+        )
     }
 
-    GLOBAL_SEARCHER.with(|cell| {
-        let mut opt = cell.borrow_mut();
-        let searcher = opt.get_or_insert_with(|| Searcher::new(max_time_ms));
+    /// The search behind [`get_best_move_limited`](Self::get_best_move_limited) for a site
+    /// skill below [`MAX_SITE_SKILL`]. Like [`search_streamed`](Self::search_streamed) it
+    /// keeps a pending stop request, so callers clear the flag with
+    /// [`SearchContext::clear_stop`] before starting it.
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_best_move_weakened(
+        &mut self,
+        game: &mut GameState,
+        max_depth: usize,
+        opt_time_ms: u128,
+        max_time_ms: u128,
+        skill: u32,
+        silent: bool,
+        is_soft_limit: bool,
+    ) -> Option<(Move, i32, SearchStats)> {
+        game.recompute_piece_counts();
+        game.recompute_correction_hashes();
+
+        let input_skill = skill.clamp(1, MAX_SITE_SKILL);
+        let searcher = self.searcher_mut(max_time_ms);
 
         searcher.new_search();
         searcher.silent = silent;
@@ -3123,7 +3729,7 @@ pub(crate) fn get_best_move_limited(
 
         if multi_pv > 1 {
             // For MultiPV: cap dynamic time at optimum to prevent runaway extensions
-            searcher.hot.total_time_ms = opt_time_ms as f64;
+            searcher.hot.time.set_total_time_ms(opt_time_ms as f64);
         }
 
         if multi_pv > 1 {
@@ -3140,11 +3746,11 @@ pub(crate) fn get_best_move_limited(
             let stats = result.stats.clone();
             pick_best(&result, skill_level, &mut searcher.rng).map(|(m, eval)| (m, eval, stats))
         } else {
-            let res = search_with_searcher(searcher, game, max_depth);
+            let res = search_with_searcher(searcher, game, max_depth, None);
             let stats = build_search_stats(searcher);
             res.map(|(m, eval)| (m, eval, stats))
         }
-    })
+    }
 }
 
 pub(crate) fn get_best_moves_multipv_impl(
@@ -3165,7 +3771,7 @@ pub(crate) fn get_best_moves_multipv_impl(
     // aggregated NPS counts only this position's search, not retired helpers from the last one.
     #[cfg(feature = "multithreading")]
     if on_depth.is_some() {
-        reset_search_nodes();
+        searcher.ctx.reset_search_nodes();
     }
 
     // Initialize NNUE accumulator stack (stored on searcher).
@@ -3197,6 +3803,12 @@ pub(crate) fn get_best_moves_multipv_impl(
         }
     }
 
+    // Root restriction (UCI `searchmoves`), then Syzygy.
+    searcher.limits.restrict_root(&mut legal_root_moves);
+    if let Some(tables) = searcher.syzygy.as_deref() {
+        tables.filter_root_moves(game, &mut legal_root_moves);
+    }
+
     if legal_root_moves.is_empty() {
         let stats = build_search_stats(searcher);
         return MultiPVResult {
@@ -3254,28 +3866,28 @@ pub(crate) fn get_best_moves_multipv_impl(
 
         searcher.reset_for_iteration();
         searcher.hot.iter_start_ms = searcher.hot.timer.elapsed_ms() as f64;
-        searcher.hot.tot_best_move_changes /= 2.0;
+        searcher.hot.time.new_iteration();
 
         // Time check at start of each iteration - but always complete depth 1
         if searcher.hot.min_depth_required == 0 && searcher.hot.time_limit_ms != u128::MAX {
             let elapsed = searcher.hot.timer.elapsed_ms() as f64;
 
             // Hard stop at maximum time
-            if elapsed >= searcher.hot.maximum_time_ms as f64 {
+            if elapsed >= searcher.hot.time.maximum_ms() as f64 {
                 searcher.hot.stopped = true;
                 break;
             }
 
             // Proactive stop: don't start a new iteration if we've used most of our time.
             // Use a threshold based on soft/hard limit (more conservative for hard limits).
-            let proactive_threshold = if searcher.hot.is_soft_limit {
+            let proactive_threshold = if searcher.hot.time.is_soft() {
                 0.76 // Stop at 76% of total_time for soft limits
             } else {
                 0.60 // Stop at 60% for hard limits (leave some buffer)
             };
 
-            if searcher.hot.total_time_ms > 0.0
-                && elapsed > searcher.hot.total_time_ms * proactive_threshold
+            if searcher.hot.time.total_time_ms() > 0.0
+                && elapsed > searcher.hot.time.total_time_ms() * proactive_threshold
             {
                 break;
             }
@@ -3308,13 +3920,15 @@ pub(crate) fn get_best_moves_multipv_impl(
             let elapsed = searcher.hot.timer.elapsed_ms() as f64;
 
             // Hard stop at maximum time
-            if searcher.hot.maximum_time_ms > 0 && elapsed > searcher.hot.maximum_time_ms as f64 {
+            let max_ms = searcher.hot.time.maximum_ms();
+            if max_ms > 0 && elapsed > max_ms as f64 {
                 searcher.hot.stopped = true;
                 break;
             }
 
             // Proactive stop at total_time - don't start new moves if time budget is exhausted
-            if searcher.hot.total_time_ms > 0.0 && elapsed > searcher.hot.total_time_ms {
+            let total_time_ms = searcher.hot.time.total_time_ms();
+            if total_time_ms > 0.0 && elapsed > total_time_ms {
                 searcher.hot.stopped = true;
                 break;
             }
@@ -3484,40 +4098,7 @@ pub(crate) fn get_best_moves_multipv_impl(
             // Only stream a completed depth to the analysis UI (a partial first depth
             // is committed above for correctness but not emitted, to avoid a flicker).
             if depth_completed && let Some(cb) = on_depth.as_deref_mut() {
-                let time_ms = searcher.hot.timer.elapsed_ms();
-                #[cfg(feature = "multithreading")]
-                let hashfull = if let Some(tt) = SHARED_TT.get() {
-                    tt.fill_permille()
-                } else {
-                    searcher.tt.fill_permille()
-                };
-                #[cfg(not(feature = "multithreading"))]
-                let hashfull = searcher.tt.fill_permille();
-
-                // Thread-aggregated node count (Stockfish-style: total across all search
-                // threads). Publish this thread's latest count first, then sum every slot.
-                #[cfg(feature = "multithreading")]
-                let report_nodes = {
-                    publish_thread_nodes(searcher.thread_id, searcher.hot.nodes);
-                    aggregate_search_nodes()
-                };
-                #[cfg(not(feature = "multithreading"))]
-                let report_nodes = searcher.hot.nodes;
-
-                cb(&DepthInfo {
-                    depth,
-                    seldepth: searcher.hot.seldepth,
-                    nodes: report_nodes,
-                    qnodes: searcher.hot.qnodes,
-                    nps: if time_ms > 0 {
-                        (report_nodes as u128 * 1000) / time_ms
-                    } else {
-                        0
-                    },
-                    time_ms,
-                    hashfull,
-                    lines: &best_lines,
-                });
+                cb(&searcher.depth_info(depth, &best_lines));
             }
 
             searcher.prev_score = if !root_scores.is_empty() {
@@ -3539,7 +4120,10 @@ pub(crate) fn get_best_moves_multipv_impl(
                 let best = best_lines[0].score;
                 worst >= mate_in(3) || best == mated_in(2)
             };
-        if mate_resolved {
+        let mate_target = best_lines
+            .first()
+            .is_some_and(|line| searcher.limits.mate_reached(line.score));
+        if mate_resolved || mate_target {
             break;
         }
 
@@ -3766,7 +4350,8 @@ fn negamax_root(
         // divided by (a single-iteration value can never reach the effort
         // threshold). Recorded before the cutoff so a move-0 fail-high still counts.
         if move_idx == 0 {
-            searcher.hot.best_move_nodes += searcher.hot.nodes - nodes_before_move;
+            let spent = searcher.hot.nodes - nodes_before_move;
+            searcher.hot.time.add_best_move_nodes(spent);
         }
 
         if alpha >= beta {
@@ -3911,12 +4496,50 @@ fn negamax(ctx: &mut NegamaxContext) -> i32 {
             return -MATE_VALUE + ply as i32;
         }
 
+        // King of the Hill loss: the opponent's king just reached the hill
+        if game.has_lost_by_koth() {
+            return -MATE_VALUE + ply as i32;
+        }
+
         // Mate distance pruning: if we already found a faster mate, prune
         alpha = alpha.max(mated_in(ply));
         beta = beta.min(mate_in(ply + 1));
         if alpha >= beta {
             return alpha;
         }
+
+        // Tablebase hit: the exact distance to mate in a solved ending
+        if let Some(value) = searcher.tablebases.probe(game) {
+            return match value {
+                TbValue::Win(plies) => mate_in(ply + plies as usize),
+                TbValue::Loss(plies) => mated_in(ply + plies as usize),
+                TbValue::Draw => value_draw(searcher.hot.nodes),
+            };
+        }
+
+        // Syzygy hit: only right after a zeroing move, when the 50-move count is known
+        if game.halfmove_clock == 0
+            && let Some(wdl) = searcher
+                .syzygy
+                .as_deref()
+                .and_then(|tables| tables.probe_wdl(game))
+        {
+            let value = match wdl {
+                Wdl::Win => TB_WIN_SCORE - ply as i32,
+                Wdl::Loss => -TB_WIN_SCORE + ply as i32,
+                _ => value_draw(searcher.hot.nodes),
+            };
+            // A known win or loss only bounds the score: keep searching for the mate
+            // unless the bound already cuts.
+            let cutoff = match wdl {
+                Wdl::Win => value >= beta,
+                Wdl::Loss => value <= alpha,
+                _ => true,
+            };
+            if cutoff {
+                return value;
+            }
+        }
     }
 
     // Save original bounds for TT flag determination
@@ -4525,7 +5148,7 @@ fn negamax(ctx: &mut NegamaxContext) -> i32 {
                 ^ piece_key(p_type, p_color, m.from.x, m.from.y)
                 ^ piece_key(p_type, p_color, m.to.x, m.to.y);
             #[cfg(feature = "multithreading")]
-            if let Some(tt) = SHARED_TT.get() {
+            if let Some(tt) = searcher.ctx.shared_tt() {
                 tt.prefetch_entry(child_hash);
             }
             searcher.tt.prefetch_entry(child_hash);
@@ -5327,8 +5950,8 @@ fn quiescence(
         return VALUE_DRAW;
     }
 
-    // Royal capture loss must be resolved before TT probes.
-    if game.has_lost_by_royal_capture() {
+    // Royal capture and hill losses must be resolved before TT probes.
+    if game.has_lost_by_royal_capture() || game.has_lost_by_koth() {
         return -MATE_VALUE + ply as i32;
     }
 
@@ -5747,17 +6370,7 @@ mod tests {
     use super::*;
     use crate::board::{Coordinate, Piece, PieceType, PlayerColor};
     use crate::game::GameState;
-    use crate::moves::{Move, set_world_bounds};
-
-    // Helper function to reset world bounds to defaults
-    fn reset_world_bounds() {
-        set_world_bounds(
-            -1_000_000_000_000_000,
-            1_000_000_000_000_000,
-            -1_000_000_000_000_000,
-            1_000_000_000_000_000,
-        );
-    }
+    use crate::moves::Move;
 
     #[test]
     fn test_corrhist_constants() {
@@ -5772,35 +6385,73 @@ mod tests {
     #[test]
     #[cfg(feature = "multithreading")]
     fn test_mt_analyse_with_helpers() {
-        reset_world_bounds();
         let mut game = GameState::new();
-        game.setup_position_from_icn(crate::Variant::Chess.starting_icn());
+        game.setup_position_from_icn(&crate::Variant::Chess.starting_icn());
 
-        GLOBAL_STOP.store(false, std::sync::atomic::Ordering::Relaxed);
-        init_shared_tt();
-        USE_SHARED_TT.store(true, std::sync::atomic::Ordering::Relaxed);
+        let mut search = SearchInstance::new();
+        let ctx = Arc::clone(search.context());
+        ctx.init_shared_tt();
+        ctx.use_shared_tt
+            .store(true, std::sync::atomic::Ordering::Relaxed);
 
         let helper_game = game.clone();
         std::thread::scope(|s| {
             let mut handles = Vec::new();
             for i in 1..4usize {
                 let mut game_clone = helper_game.clone();
+                let ctx = Arc::clone(&ctx);
                 handles.push(s.spawn(move || {
-                    let _ = get_best_move_threaded(&mut game_clone, 12, 2_000, 2_000, true, i, true);
+                    let _ =
+                        get_best_move_threaded(&ctx, &mut game_clone, 12, 2_000, 2_000, i, true);
                 }));
             }
 
             let mut cb = |_: &DepthInfo| {};
-            let result = analyse_position(&mut game, 10, 1, 2_000, 2, &mut cb);
-            GLOBAL_STOP.store(true, std::sync::atomic::Ordering::Relaxed);
+            let result = search.analyse_position(&mut game, 10, 1, 2_000, 2, &mut cb);
+            ctx.request_stop();
             assert!(!result.lines.is_empty(), "MT analyse should produce PV lines");
 
             for handle in handles {
                 handle.join().expect("helper thread panicked");
             }
         });
+    }
+
+    #[test]
+    fn test_search_instances_do_not_share_state() {
+        let mut game = GameState::new();
+        game.setup_position_from_icn(&crate::Variant::Chess.starting_icn());
+
+        let mut first = SearchInstance::new();
+        let mut second = SearchInstance::new();
+        first.set_params(1, None);
+        let (_, _, stats) = first
+            .get_best_move_parallel(&mut game, 4, u128::MAX, u128::MAX, true, true)
+            .expect("start position has legal moves");
+        assert!(stats.tt_used > 0);
+        assert_eq!(first.completed_depth(), 4);
+
+        // The second instance has neither a searcher nor any TT entries of its own.
+        assert!(second.searcher().is_none());
+        assert_eq!(second.tt_stats().tt_used, 0);
+
+        // A stop request on one context is invisible to the other, which still searches.
+        first.context().request_stop();
+        assert!(
+            !second
+                .context()
+                .stop
+                .load(std::sync::atomic::Ordering::Relaxed)
+        );
+        assert!(
+            second
+                .get_best_move_parallel(&mut game, 2, u128::MAX, u128::MAX, true, true)
+                .is_some()
+        );
 
-        USE_SHARED_TT.store(false, std::sync::atomic::Ordering::Relaxed);
+        first.reset();
+        assert!(first.searcher().is_none());
+        assert_eq!(first.tt_stats().tt_used, 0);
     }
 
     /// Time-to-depth benchmark mirroring the wasm analysis worker's 180ms slice cadence.
@@ -5818,8 +6469,9 @@ mod tests {
         #[allow(non_snake_case)]
         let SLICE_MS: u128 = std::env::var("BENCH_SLICE").ok().and_then(|v| v.parse().ok()).unwrap_or(180);
 
-        reset_world_bounds();
-        set_tt_size_mb(tt_mb);
+        let mut search = SearchInstance::new();
+        search.set_tt_size_mb(tt_mb);
+        let ctx = Arc::clone(search.context());
         let _ = rayon::ThreadPoolBuilder::new().num_threads(threads).build_global();
 
         // Startpos + a normal opening, like a real analysis position (BENCH_POS picks one).
@@ -5836,8 +6488,9 @@ mod tests {
 
         let use_mt = mode.starts_with("mt");
         if use_mt {
-            init_shared_tt();
-            USE_SHARED_TT.store(true, std::sync::atomic::Ordering::Relaxed);
+            ctx.init_shared_tt();
+            ctx.use_shared_tt
+                .store(true, std::sync::atomic::Ordering::Relaxed);
         }
 
         let t0 = std::time::Instant::now();
@@ -5853,8 +6506,8 @@ mod tests {
             "st" => {
                 while reached < target {
                     let start = (reached + 1).min(target);
-                    GLOBAL_STOP.store(false, std::sync::atomic::Ordering::Relaxed);
-                    let r = analyse_position(&mut game, target, start, SLICE_MS, 1, &mut cb);
+                    ctx.stop.store(false, std::sync::atomic::Ordering::Relaxed);
+                    let r = search.analyse_position(&mut game, target, start, SLICE_MS, 1, &mut cb);
                     let d = r.lines.first().map_or(0, |l| l.depth);
                     if d <= reached { break; }
                     reached = d;
@@ -5862,39 +6515,43 @@ mod tests {
             }
             // The shipped wasm design: detached helpers spawned once, main sliced like the worker.
             "mt_detached" => {
-                let epoch = HELPER_EPOCH.fetch_add(1, std::sync::atomic::Ordering::Relaxed) + 1;
-                GLOBAL_STOP.store(false, std::sync::atomic::Ordering::Relaxed);
-                for i in 1..threads {
-                    let gc = helper_game.clone();
-                    rayon::spawn(move || helper_run(gc, epoch, i));
-                }
+                ctx.clear_stop();
+                search.ensure_analysis_helpers(&helper_game, 1, threads);
                 while reached < target {
                     let start = (reached + 1).min(target);
-                    let r = analyse_position(&mut game, target, start, SLICE_MS, 1, &mut cb);
+                    let r = search.analyse_position(&mut game, target, start, SLICE_MS, 1, &mut cb);
                     let d = r.lines.first().map_or(0, |l| l.depth);
                     if d <= reached { break; }
                     reached = d;
                 }
-                stop_analysis_helpers();
+                ctx.stop_analysis_helpers();
             }
             // Helpers persist for the WHOLE search; main runs unsliced to target.
             "mt_full" => {
-                GLOBAL_STOP.store(false, std::sync::atomic::Ordering::Relaxed);
+                ctx.stop.store(false, std::sync::atomic::Ordering::Relaxed);
                 rayon::in_place_scope(|s| {
                     for i in 1..threads {
                         let mut gc = helper_game.clone();
+                        let ctx = Arc::clone(&ctx);
                         s.spawn(move |_| {
-                            let _ = get_best_move_threaded(&mut gc, target, 600_000, 600_000, true, i, true);
+                            let _ = get_best_move_threaded(
+                                &ctx, &mut gc, target, 600_000, 600_000, i, true,
+                            );
                         });
                     }
-                    let _ = analyse_position(&mut game, target, 1, 0, 1, &mut cb);
-                    GLOBAL_STOP.store(true, std::sync::atomic::Ordering::Relaxed);
+                    let _ = search.analyse_position(&mut game, target, 1, 0, 1, &mut cb);
+                    ctx.request_stop();
                 });
             }
             other => panic!("unknown BENCH_MODE {other}"),
         }
-        println!("TOTAL {}ms mode={} tt={}MB threads={}", t0.elapsed().as_millis(), mode, tt_mb, threads);
-        USE_SHARED_TT.store(false, std::sync::atomic::Ordering::Relaxed);
+        println!(
+            "TOTAL {}ms mode={} tt={}MB threads={}",
+            t0.elapsed().as_millis(),
+            mode,
+            tt_mb,
+            threads
+        );
     }
 
     // ======================== Timer Tests ========================
@@ -6159,6 +6816,27 @@ mod tests {
         assert!(best_move.piece.piece_type() != PieceType::Void);
     }
 
+    #[test]
+    fn test_search_takes_the_hill() {
+        let mut game = GameState::new();
+        // Black is up a rook, but White's king is one step from the hill.
+        game.setup_position_from_icn("w koth K3,3|k20,20|r30,25|r31,26");
+
+        let (best_move, eval, _stats) = get_best_move(&mut game, 4, 1000, true, true).unwrap();
+        assert!(game.game_rules.koth_squares.contains(&best_move.to));
+        assert_eq!(eval, mate_in(1));
+    }
+
+    #[test]
+    fn test_search_guards_the_hill() {
+        let mut game = GameState::new();
+        // Black to move must stop the king from stepping onto d4 next move.
+        game.setup_position_from_icn("b koth K3,3|k20,20|r30,25|r31,26");
+
+        let (_best_move, eval, _stats) = get_best_move(&mut game, 4, 1000, true, true).unwrap();
+        assert!(!is_loss(eval), "a rook can cover the hill: {eval}");
+    }
+
     #[test]
     fn test_get_best_move_returns_result() {
         let mut game = GameState::new();
@@ -6330,6 +7008,45 @@ mod tests {
         assert!(result.is_some());
     }
 
+    #[test]
+    fn test_concurrent_bounded_and_unbounded_search() {
+        // World bounds live on each game, so a bounded Chess search must not
+        // pick up the unbounded border of a Classical search on another thread.
+        std::thread::scope(|s| {
+            let chess = s.spawn(|| {
+                let mut game = GameState::new();
+                game.setup_position_from_icn(&crate::Variant::Chess.starting_icn());
+                for m in game.get_legal_moves() {
+                    assert!(
+                        (1..=8).contains(&m.to.x) && (1..=8).contains(&m.to.y),
+                        "Chess move left the board: {:?}",
+                        m
+                    );
+                }
+                get_best_move(&mut game, 3, 2000, true, true)
+            });
+            let classical = s.spawn(|| {
+                let mut game = GameState::new();
+                game.setup_position_from_icn(&crate::Variant::Classical.starting_icn());
+                game.make_move(&Move::new(
+                    Coordinate::new(5, 2),
+                    Coordinate::new(5, 4),
+                    Piece::new(PieceType::Pawn, PlayerColor::White),
+                ));
+                let moves = game.get_legal_moves();
+                assert!(
+                    moves.iter().any(|m| m.to.x == 0),
+                    "Classical knights should reach the 0 file"
+                );
+                get_best_move(&mut game, 3, 2000, true, true)
+            });
+
+            let (chess_move, _, _) = chess.join().unwrap().expect("Chess search found no move");
+            assert!((1..=8).contains(&chess_move.to.x) && (1..=8).contains(&chess_move.to.y));
+            assert!(classical.join().unwrap().is_some());
+        });
+    }
+
     // ======================== Format PV Tests ========================
 
     #[test]
@@ -6443,6 +7160,80 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_search_streamed_reports_every_depth() {
+        let mut game = GameState::new();
+        game.setup_position_from_icn("w K0,0|Q4,4|k7,7|r5,5");
+
+        let mut depths = Vec::new();
+        let result = search_streamed(
+            &mut game,
+            3,
+            u128::MAX,
+            u128::MAX,
+            2,
+            true,
+            &SearchLimits::default(),
+            &mut |info: &DepthInfo| {
+                assert_eq!(info.lines.len(), 2);
+                assert!(info.lines.iter().all(|line| !line.pv.is_empty()));
+                depths.push(info.depth);
+            },
+        );
+
+        assert_eq!(depths, vec![1, 2, 3]);
+        assert_eq!(result.lines.len(), 2);
+
+        // A single PV streams from the normal search.
+        let mut depths = Vec::new();
+        let result = search_streamed(
+            &mut game,
+            3,
+            u128::MAX,
+            u128::MAX,
+            1,
+            true,
+            &SearchLimits::default(),
+            &mut |info: &DepthInfo| {
+                assert_eq!(info.lines.len(), 1);
+                assert!(!info.lines[0].pv.is_empty());
+                depths.push(info.depth);
+            },
+        );
+
+        assert_eq!(depths, vec![1, 2, 3]);
+        assert_eq!(result.lines.len(), 1);
+    }
+
+    #[test]
+    fn test_search_limits_cap_nodes_and_restrict_root() {
+        let mut game = GameState::new();
+        game.setup_position_from_icn("w K0,0|Q4,4|k7,7|r5,5");
+        let mut search = SearchInstance::new();
+
+        let budget = SearchLimits {
+            nodes: Some(2_000),
+            ..SearchLimits::default()
+        };
+        let (_, _, stats) = search
+            .get_best_move_with_limits(&mut game, 50, u128::MAX, u128::MAX, &budget, true, true)
+            .expect("node-limited search still returns a move");
+        assert!(stats.nodes < 2_100, "searched {} nodes", stats.nodes);
+
+        let king_moves: Vec<Move> = ["0,0>0,1", "0,0>1,0"]
+            .iter()
+            .map(|token| game.find_site_move(token).expect("legal king move"))
+            .collect();
+        let restricted = SearchLimits {
+            search_moves: king_moves.clone(),
+            ..SearchLimits::default()
+        };
+        let (best, _, _) = search
+            .get_best_move_with_limits(&mut game, 3, u128::MAX, u128::MAX, &restricted, true, true)
+            .expect("restricted search returns a move");
+        assert!(king_moves.contains(&best));
+    }
+
     #[test]
     fn test_tt_integration_via_local() {
         let mut tt = LocalTranspositionTable::new(16);
@@ -6486,7 +7277,6 @@ mod tests {
 
     #[test]
     fn test_search_mate_in_one() {
-        reset_world_bounds();
         let mut game = GameState::new();
         game.setup_position_from_icn("w K-5,-5|R5,5|k0,0|p-1,-1|p0,-1|p1,-1|p-1,0|p1,0|p-1,1|p1,1");
 
@@ -0,0 +1,250 @@
+//! Background analysis for native hosts.
+//!
+//! [`Analysis::start`] runs an untimed streamed search on its own thread (plus Lazy SMP
+//! helpers on the rayon pool when built with `multithreading`) and keeps the newest
+//! completed depth for the host to [`poll`](Analysis::poll). It searches on a context of
+//! its own, so several analyses and the global searcher never share a TT or stop flag.
+
+use super::{
+    DepthInfo, MAX_PLY, MultiPVResult, PVLine, SearchContext, SearchInstance, SearchLimits,
+    global_context,
+};
+use crate::game::GameState;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// How an [`Analysis`] searches.
+#[derive(Clone, Debug)]
+pub struct AnalysisOptions {
+    /// Main search plus `threads - 1` Lazy SMP helpers (clamped to 1..=MAX_SEARCH_THREADS).
+    pub threads: usize,
+    /// PV lines reported per depth.
+    pub multi_pv: usize,
+    /// Depth at which the analysis finishes on its own.
+    pub max_depth: usize,
+    /// Node budget, mate target and root move restriction.
+    pub limits: SearchLimits,
+    /// Transposition table size in MB (clamped to 1..=64).
+    pub hash_mb: usize,
+}
+
+impl Default for AnalysisOptions {
+    fn default() -> Self {
+        AnalysisOptions {
+            threads: 1,
+            multi_pv: 1,
+            max_depth: MAX_PLY,
+            limits: SearchLimits::default(),
+            hash_mb: global_context().tt_size_mb(),
+        }
+    }
+}
+
+/// Owned copy of a [`DepthInfo`], as kept by an [`Analysis`].
+#[derive(Clone, Debug)]
+pub struct DepthReport {
+    pub depth: usize,
+    pub seldepth: usize,
+    pub nodes: u64,
+    pub qnodes: u64,
+    pub nps: u128,
+    pub time_ms: u128,
+    pub hashfull: u32,
+    pub lines: Vec<PVLine>,
+}
+
+impl From<&DepthInfo<'_>> for DepthReport {
+    fn from(info: &DepthInfo<'_>) -> Self {
+        DepthReport {
+            depth: info.depth,
+            seldepth: info.seldepth,
+            nodes: info.nodes,
+            qnodes: info.qnodes,
+            nps: info.nps,
+            time_ms: info.time_ms,
+            hashfull: info.hashfull,
+            lines: info.lines.to_vec(),
+        }
+    }
+}
+
+/// Newest completed depth, numbered so `poll` can tell whether it has been seen.
+#[derive(Default)]
+struct Latest {
+    seq: u64,
+    report: Option<DepthReport>,
+}
+
+/// Handle to a running background analysis. Dropping it stops the search.
+pub struct Analysis {
+    ctx: Arc<SearchContext>,
+    latest: Arc<Mutex<Latest>>,
+    seen: u64,
+    main: Option<JoinHandle<MultiPVResult>>,
+}
+
+impl Analysis {
+    /// Starts analysing `game` in the background. Returns immediately; the search runs
+    /// until [`Analysis::stop`], `max_depth` or a limit in `options` ends it.
+    pub fn start(game: &GameState, options: AnalysisOptions) -> Analysis {
+        let ctx = Arc::new(SearchContext::new(options.hash_mb));
+        let latest = Arc::new(Mutex::new(Latest::default()));
+        // The streamed search keeps a pending stop, so one sent before it gets going
+        // still ends it; clear the flag here, before the thread exists, instead.
+        ctx.clear_stop();
+
+        let main = {
+            let ctx = Arc::clone(&ctx);
+            let latest = Arc::clone(&latest);
+            let mut game = game.clone();
+            thread::Builder::new()
+                .name("analysis".to_string())
+                .spawn(move || {
+                    let mut search = SearchInstance::on_context(ctx);
+                    search.set_threads(options.threads);
+                    search.search_streamed(
+                        &mut game,
+                        options.max_depth.max(1),
+                        u128::MAX,
+                        u128::MAX,
+                        options.multi_pv,
+                        true,
+                        &options.limits,
+                        &mut |info: &DepthInfo| {
+                            let mut latest = latest.lock().unwrap_or_else(PoisonError::into_inner);
+                            latest.seq += 1;
+                            latest.report = Some(DepthReport::from(info));
+                        },
+                    )
+                })
+                .expect("failed to spawn the analysis thread")
+        };
+
+        Analysis {
+            ctx,
+            latest,
+            seen: 0,
+            main: Some(main),
+        }
+    }
+
+    /// The newest completed depth if it arrived since the last `poll`.
+    pub fn poll(&mut self) -> Option<DepthReport> {
+        let latest = self.latest.lock().unwrap_or_else(PoisonError::into_inner);
+        if latest.seq == self.seen {
+            return None;
+        }
+        self.seen = latest.seq;
+        latest.report.clone()
+    }
+
+    /// The newest completed depth, whether or not it has been polled.
+    pub fn latest(&self) -> Option<DepthReport> {
+        self.latest
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .report
+            .clone()
+    }
+
+    /// False once the search has ended, by [`Analysis::stop`] or on its own.
+    pub fn is_running(&self) -> bool {
+        self.main.as_ref().is_some_and(|main| !main.is_finished())
+    }
+
+    /// Stops the search and joins its threads. Returns the lines of the last completed
+    /// depth, or `None` if the analysis was already stopped.
+    pub fn stop(&mut self) -> Option<MultiPVResult> {
+        let main = self.main.take()?;
+        self.request_stop();
+        let result = main.join().ok();
+
+        #[cfg(feature = "multithreading")]
+        while self
+            .ctx
+            .helpers_live
+            .load(std::sync::atomic::Ordering::Relaxed)
+            > 0
+        {
+            thread::sleep(Duration::from_millis(1));
+        }
+        result
+    }
+
+    fn request_stop(&self) {
+        #[cfg(feature = "multithreading")]
+        self.ctx.stop_analysis_helpers();
+        #[cfg(not(feature = "multithreading"))]
+        self.ctx.request_stop();
+    }
+}
+
+impl Drop for Analysis {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[test]
+    fn test_analysis_streams_depths_until_stopped() {
+        let mut game = GameState::new();
+        game.setup_standard_chess();
+
+        let mut analysis = Analysis::start(
+            &game,
+            AnalysisOptions {
+                threads: 2,
+                multi_pv: 2,
+                ..AnalysisOptions::default()
+            },
+        );
+        assert!(analysis.is_running());
+
+        let deadline = Instant::now() + Duration::from_secs(30);
+        let mut deepest = 0;
+        while deepest < 4 && Instant::now() < deadline {
+            if let Some(report) = analysis.poll() {
+                assert!(report.depth > deepest, "polled depths only grow");
+                deepest = report.depth;
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+        assert!(deepest >= 4, "analysis reached depth {deepest}");
+
+        let result = analysis.stop().expect("first stop returns the final lines");
+        assert!(!analysis.is_running());
+        assert_eq!(result.lines.len(), 2);
+        assert_eq!(analysis.latest().map(|r| r.lines.len()), Some(2));
+        assert!(analysis.stop().is_none());
+    }
+
+    #[test]
+    fn test_analysis_finishes_at_max_depth() {
+        let mut game = GameState::new();
+        game.setup_standard_chess();
+
+        let mut analysis = Analysis::start(
+            &game,
+            AnalysisOptions {
+                max_depth: 3,
+                ..AnalysisOptions::default()
+            },
+        );
+        let deadline = Instant::now() + Duration::from_secs(30);
+        while analysis.is_running() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(5));
+        }
+
+        assert_eq!(analysis.latest().map(|r| r.depth), Some(3));
+        let result = analysis
+            .stop()
+            .expect("a finished analysis keeps its lines");
+        assert_eq!(result.lines.len(), 1);
+    }
+}
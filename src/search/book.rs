@@ -0,0 +1,493 @@
+//! Opening book.
+//!
+//! A [`Book`] maps positions to the moves played from them in a set of games, weighted by
+//! how those games went for the side that played the move. Positions are keyed by
+//! [`GameState::hash`] mixed with the world bounds and win conditions, since several
+//! variants (Classical, Chess, All Pieces Classical, ...) start from the same array but
+//! are different games. Books are built natively by [`BookBuilder`] and stored in a
+//! compact little-endian binary file that both native hosts and wasm can load.
+
+use crate::board::{Coordinate, PieceType};
+use crate::game::GameState;
+use crate::moves::Move;
+use rustc_hash::FxHashMap;
+use std::io::{Cursor, Read};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::Variant;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::board::PlayerColor;
+
+/// Magic bytes for file identification
+const MAGIC: &[u8; 8] = b"APBOOK1\0";
+
+/// One book move and its weight (2 per game the mover won, 1 per draw).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BookMove {
+    pub from: Coordinate,
+    pub to: Coordinate,
+    pub promotion: Option<PieceType>,
+    pub weight: u32,
+}
+
+impl BookMove {
+    fn matches(&self, m: &Move) -> bool {
+        m.from == self.from && m.to == self.to && m.promotion == self.promotion
+    }
+}
+
+/// Weighted book moves per position.
+#[derive(Clone, Debug, Default)]
+pub struct Book {
+    entries: FxHashMap<u64, Vec<BookMove>>,
+}
+
+/// The key `game` is stored under: its Zobrist hash, salted with the rules the hash
+/// does not cover.
+pub fn book_key(game: &GameState) -> u64 {
+    let rules = &game.game_rules;
+    let bounds = &rules.world_bounds;
+    [
+        bounds.min_x as u64,
+        bounds.max_x as u64,
+        bounds.min_y as u64,
+        bounds.max_y as u64,
+        rules.white_win_condition as u64,
+        rules.black_win_condition as u64,
+    ]
+    .into_iter()
+    .fold(game.hash, |key, value| mix(key ^ value))
+}
+
+/// splitmix64 finalizer.
+fn mix(mut z: u64) -> u64 {
+    z = z.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+impl Book {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of positions in the book.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Book moves stored for `game`, heaviest first. Not checked for legality.
+    pub fn moves(&self, game: &GameState) -> &[BookMove] {
+        self.entries
+            .get(&book_key(game))
+            .map_or(&[], |moves| moves.as_slice())
+    }
+
+    /// Picks a legal book move for `game` with probability proportional to its weight,
+    /// using `random` as the dice roll. `None` when the position is out of book.
+    pub fn probe(&self, game: &mut GameState, random: u64) -> Option<Move> {
+        let entries = self.entries.get(&book_key(game))?;
+        let candidates: Vec<(Move, u32)> = entries
+            .iter()
+            .filter_map(|entry| Some((game.find_legal_move(|m| entry.matches(m))?, entry.weight)))
+            .collect();
+
+        let total: u64 = candidates.iter().map(|&(_, w)| w as u64).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut roll = random % total;
+        for (m, weight) in candidates {
+            if roll < weight as u64 {
+                return Some(m);
+            }
+            roll -= weight as u64;
+        }
+        None
+    }
+
+    /// Serializes the book. Positions are written in key order, so equal books produce
+    /// equal files.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut keys: Vec<u64> = self.entries.keys().copied().collect();
+        keys.sort_unstable();
+
+        let mut out = Vec::with_capacity(12 + keys.len() * 48);
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&(keys.len() as u32).to_le_bytes());
+        for key in keys {
+            let moves = &self.entries[&key];
+            out.extend_from_slice(&key.to_le_bytes());
+            out.extend_from_slice(&(moves.len() as u16).to_le_bytes());
+            for m in moves {
+                for v in [m.from.x, m.from.y, m.to.x, m.to.y] {
+                    out.extend_from_slice(&v.to_le_bytes());
+                }
+                out.push(m.promotion.map_or(0, |p| p as u8));
+                out.extend_from_slice(&m.weight.to_le_bytes());
+            }
+        }
+        out
+    }
+
+    /// Loads a book written by [`Book::to_bytes`].
+    pub fn from_bytes(data: &[u8]) -> Result<Self, &'static str> {
+        let mut cursor = Cursor::new(data);
+
+        let mut magic = [0u8; 8];
+        cursor
+            .read_exact(&mut magic)
+            .map_err(|_| "Failed to read magic")?;
+        if &magic != MAGIC {
+            return Err("Invalid magic bytes");
+        }
+
+        let positions = read_u32(&mut cursor)?;
+        let mut entries = FxHashMap::default();
+        for _ in 0..positions {
+            let key = u64::from_le_bytes(read_array(&mut cursor)?);
+            let count = u16::from_le_bytes(read_array(&mut cursor)?);
+            let mut moves = Vec::with_capacity(count as usize);
+            for _ in 0..count {
+                let from = Coordinate::new(read_i64(&mut cursor)?, read_i64(&mut cursor)?);
+                let to = Coordinate::new(read_i64(&mut cursor)?, read_i64(&mut cursor)?);
+                let [promo] = read_array(&mut cursor)?;
+                let promotion = match promo {
+                    0 => None,
+                    p if p <= PieceType::Pawn as u8 => Some(PieceType::from_u8(p)),
+                    _ => return Err("Invalid promotion piece"),
+                };
+                let weight = read_u32(&mut cursor)?;
+                moves.push(BookMove {
+                    from,
+                    to,
+                    promotion,
+                    weight,
+                });
+            }
+            entries.insert(key, moves);
+        }
+        Ok(Book { entries })
+    }
+}
+
+fn read_array<const N: usize>(cursor: &mut Cursor<&[u8]>) -> Result<[u8; N], &'static str> {
+    let mut buf = [0u8; N];
+    cursor
+        .read_exact(&mut buf)
+        .map_err(|_| "Unexpected end of book")?;
+    Ok(buf)
+}
+
+fn read_u32(cursor: &mut Cursor<&[u8]>) -> Result<u32, &'static str> {
+    Ok(u32::from_le_bytes(read_array(cursor)?))
+}
+
+fn read_i64(cursor: &mut Cursor<&[u8]>) -> Result<i64, &'static str> {
+    Ok(i64::from_le_bytes(read_array(cursor)?))
+}
+
+/// Replays finished games into a [`Book`], up to `max_ply` plies into each game.
+#[cfg(not(target_arch = "wasm32"))]
+pub struct BookBuilder {
+    max_ply: usize,
+    games: usize,
+    entries: FxHashMap<u64, Vec<BookMove>>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl BookBuilder {
+    pub fn new(max_ply: usize) -> Self {
+        BookBuilder {
+            max_ply,
+            games: 0,
+            entries: FxHashMap::default(),
+        }
+    }
+
+    /// Games added so far.
+    pub fn games(&self) -> usize {
+        self.games
+    }
+
+    /// Adds the first `max_ply` moves of a game played from `game`. `moves` are site
+    /// tokens (`x,y>x,y[=P]`, optionally prefixed with the piece code); `winner` is
+    /// `None` for a draw.
+    pub fn add_game<'a>(
+        &mut self,
+        mut game: GameState,
+        moves: impl IntoIterator<Item = &'a str>,
+        winner: Option<PlayerColor>,
+    ) -> Result<(), String> {
+        // Replay the whole game first so a bad move leaves the book untouched.
+        let mut played = Vec::new();
+        for token in moves.into_iter().take(self.max_ply) {
+            let m = game
+                .find_site_move(token)
+                .ok_or_else(|| format!("illegal move: {token}"))?;
+            let weight = match winner {
+                Some(color) if color == game.turn => 2,
+                Some(_) => 0,
+                None => 1,
+            };
+            played.push((
+                book_key(&game),
+                BookMove {
+                    from: m.from,
+                    to: m.to,
+                    promotion: m.promotion,
+                    weight,
+                },
+            ));
+            game.make_move(&m);
+        }
+
+        for (key, book_move) in played {
+            let known = self.entries.entry(key).or_default();
+            let same = |entry: &&mut BookMove| {
+                (entry.from, entry.to, entry.promotion)
+                    == (book_move.from, book_move.to, book_move.promotion)
+            };
+            match known.iter_mut().find(same) {
+                Some(entry) => entry.weight += book_move.weight,
+                None => known.push(book_move),
+            }
+        }
+        self.games += 1;
+        Ok(())
+    }
+
+    /// Adds a game in ICN with its move segment, as saved by `sprt --games`.
+    pub fn add_icn_game(&mut self, icn: &str) -> Result<(), String> {
+        self.add_record(GameRecord::from_icn(icn)?)
+    }
+
+    /// Adds a `generated_games.txt` line from `game_gen`.
+    pub fn add_generated_game(&mut self, line: &str) -> Result<(), String> {
+        self.add_record(GameRecord::from_generated(line)?)
+    }
+
+    fn add_record(&mut self, record: GameRecord) -> Result<(), String> {
+        self.add_game(
+            record.start,
+            record.moves.iter().map(String::as_str),
+            record.winner,
+        )
+    }
+
+    /// The finished book. Moves that never scored are dropped, and so are positions
+    /// left without moves.
+    pub fn build(self) -> Book {
+        let mut entries = self.entries;
+        entries.retain(|_, moves| {
+            moves.retain(|m| m.weight > 0);
+            moves.sort_by_key(|m| std::cmp::Reverse(m.weight));
+            !moves.is_empty()
+        });
+        Book { entries }
+    }
+}
+
+/// A finished game read from one of the game files our tools write: where it started,
+/// its moves as site tokens and who won (`None` for a draw).
+#[cfg(not(target_arch = "wasm32"))]
+pub struct GameRecord {
+    pub start: GameState,
+    pub moves: Vec<String>,
+    pub winner: Option<PlayerColor>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl GameRecord {
+    /// A game in ICN with its move segment, as saved by `sprt --games`. The result
+    /// comes from the `[Result "..."]` tag; unfinished games are rejected.
+    pub fn from_icn(icn: &str) -> Result<Self, String> {
+        let winner = match icn_tag(icn, "Result") {
+            Some("1-0") => Some(PlayerColor::White),
+            Some("0-1") => Some(PlayerColor::Black),
+            Some("1/2-1/2") => None,
+            Some(other) => return Err(format!("unfinished game: {other}")),
+            None => return Err("missing Result tag".to_string()),
+        };
+
+        // Tags stay with the setup so the Variant tag still applies.
+        let icn = icn.trim();
+        let mut content = icn;
+        while content.starts_with('[')
+            && let Some(end) = content.find(']')
+        {
+            content = content[end + 1..].trim_start();
+        }
+        let tags = &icn[..icn.len() - content.len()];
+
+        let mut setup = vec![tags];
+        let mut moves = Vec::new();
+        for token in content.split_whitespace() {
+            if token.contains('>') {
+                moves.extend(token.split('|').filter(|m| !m.is_empty()).map(String::from));
+            } else {
+                setup.push(token);
+            }
+        }
+
+        let start = GameState::try_from_icn(&setup.join(" ")).map_err(|e| e.to_string())?;
+        Ok(GameRecord {
+            start,
+            moves,
+            winner,
+        })
+    }
+
+    /// A `generated_games.txt` line (`Variant|result|moves...` from `game_gen`, where
+    /// the result is 1, 0 or -1 from White's side and promotions are appended without `=`).
+    pub fn from_generated(line: &str) -> Result<Self, String> {
+        let mut parts = line.trim().split('|');
+        let name = parts.next().unwrap_or_default();
+        let variant = Variant::ALL
+            .into_iter()
+            .find(|v| format!("{v:?}") == name)
+            .or_else(|| Variant::try_parse(name))
+            .ok_or_else(|| format!("unknown variant: {name}"))?;
+        let winner = match parts.next() {
+            Some("1") => Some(PlayerColor::White),
+            Some("-1") => Some(PlayerColor::Black),
+            Some("0") => None,
+            other => return Err(format!("bad result: {}", other.unwrap_or_default())),
+        };
+
+        let mut start = GameState::new();
+        start.setup_position_from_icn(&format!(
+            "[Variant \"{}\"] {}",
+            variant.to_str(),
+            variant.starting_icn()
+        ));
+        Ok(GameRecord {
+            start,
+            moves: parts.map(compact_to_site_token).collect(),
+            winner,
+        })
+    }
+}
+
+/// Value of an ICN `[Name "value"]` tag.
+#[cfg(not(target_arch = "wasm32"))]
+fn icn_tag<'a>(icn: &'a str, name: &str) -> Option<&'a str> {
+    let prefix = format!("[{name} \"");
+    let start = icn.find(&prefix)? + prefix.len();
+    let end = start + icn[start..].find('"')?;
+    Some(&icn[start..end])
+}
+
+/// `1,7>1,8Q` -> `1,7>1,8=Q`.
+#[cfg(not(target_arch = "wasm32"))]
+fn compact_to_site_token(token: &str) -> String {
+    let coords = token.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    if coords.len() == token.len() {
+        token.to_string()
+    } else {
+        format!("{}={}", coords, &token[coords.len()..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLASSICAL_GAME: &str = "[Event \"SPRT Test Game 0\"] [Variant \"Classical\"] [Result \"1-0\"] w 0/100 1 (8|1) P1,2+|P2,2+|P3,2+|P4,2+|P5,2+|P6,2+|P7,2+|P8,2+|p1,7+|p2,7+|p3,7+|p4,7+|p5,7+|p6,7+|p7,7+|p8,7+|R1,1+|R8,1+|r1,8+|r8,8+|N2,1|N7,1|n2,8|n7,8|B3,1|B6,1|b3,8|b6,8|Q4,1|q4,8|K5,1+|k5,8+ P5,2>5,4|p5,7>5,5|N7,1>6,3";
+
+    #[test]
+    fn test_book_round_trips_and_weights_by_result() {
+        let mut builder = BookBuilder::new(2);
+        builder.add_icn_game(CLASSICAL_GAME).unwrap();
+        builder
+            .add_generated_game("Classical|0|5,2>5,4|5,7>5,5|7,1>6,3")
+            .unwrap();
+        builder
+            .add_generated_game("Classical|-1|4,2>4,4|4,7>4,5")
+            .unwrap();
+        assert_eq!(builder.games(), 3);
+
+        let book = Book::from_bytes(&builder.build().to_bytes()).unwrap();
+        // The start position and the positions after 5,2>5,4 and 4,2>4,4; the ply limit
+        // leaves out 7,1>6,3.
+        assert_eq!(book.len(), 3);
+
+        let mut game = GameState::new();
+        game.setup_position_from_icn(&Variant::Classical.starting_icn());
+        let first = book.moves(&game);
+        assert_eq!(first.len(), 1, "the lost 4,2>4,4 scores nothing");
+        assert_eq!(first[0].to, Coordinate::new(5, 4));
+        assert_eq!(first[0].weight, 3);
+
+        let m = book
+            .probe(&mut game, 12345)
+            .expect("start position is in book");
+        assert_eq!(
+            (m.from, m.to),
+            (Coordinate::new(5, 2), Coordinate::new(5, 4))
+        );
+        game.make_move(&m);
+        // Black lost the first game and drew the second.
+        assert_eq!(book.moves(&game)[0].weight, 1);
+    }
+
+    #[test]
+    fn test_rejected_game_adds_nothing() {
+        let mut builder = BookBuilder::new(4);
+        assert!(
+            builder
+                .add_generated_game("Classical|1|5,2>5,4|5,7>5,3")
+                .is_err()
+        );
+        assert_eq!(builder.games(), 0);
+        assert!(builder.build().is_empty());
+    }
+
+    #[test]
+    fn test_book_keeps_variants_with_the_same_array_apart() {
+        let mut builder = BookBuilder::new(1);
+        builder.add_generated_game("Chess|1|4,2>4,4").unwrap();
+        let book = builder.build();
+
+        let mut chess = GameState::new();
+        chess.setup_position_from_icn(&Variant::Chess.starting_icn());
+        let mut classical = GameState::new();
+        classical.setup_position_from_icn(&Variant::Classical.starting_icn());
+
+        assert!(book.probe(&mut chess, 0).is_some());
+        assert!(book.probe(&mut classical, 0).is_none());
+    }
+
+    #[test]
+    fn test_search_instance_plays_from_book_at_the_root() {
+        let mut builder = BookBuilder::new(1);
+        builder.add_generated_game("Palace|1|6,2>6,3").unwrap();
+        let mut search = super::super::SearchInstance::new();
+        search.set_book(Some(std::sync::Arc::new(builder.build())));
+
+        let mut game = GameState::new();
+        game.setup_position_from_icn(&Variant::Palace.starting_icn());
+        let (m, score, stats) = search
+            .get_best_move_parallel(&mut game, 1, u128::MAX, u128::MAX, true, true)
+            .unwrap();
+        assert_eq!((m.to, score, stats.nodes), (Coordinate::new(6, 3), 0, 0));
+
+        game.make_move(&m);
+        let (_, _, stats) = search
+            .get_best_move_parallel(&mut game, 1, u128::MAX, u128::MAX, true, true)
+            .unwrap();
+        assert!(stats.nodes > 0, "out of book, the position is searched");
+    }
+
+    #[test]
+    fn test_compact_promotion_tokens() {
+        assert_eq!(compact_to_site_token("1,7>1,8Q"), "1,7>1,8=Q");
+        assert_eq!(compact_to_site_token("1,7>1,8"), "1,7>1,8");
+        assert!(Book::from_bytes(b"APBOOK1\0\x01\0\0\0").is_err());
+    }
+}
@@ -283,6 +283,17 @@ impl StagedMoveGen {
             return m;
         }
         let dx = m.to.x - m.from.x;
+        if game.game_rules.chess960 {
+            // A one-square step is read as an ordinary king move; anything else
+            // on the rank is matched against the Fischer-random castles.
+            if m.to.y == m.from.y && dx.abs() != 1 {
+                m.rook_coord = Self::chess960_castles(game, &m)
+                    .iter()
+                    .find(|c| c.to == m.to)
+                    .and_then(|c| c.rook_coord);
+            }
+            return m;
+        }
         if dx.abs() != 2 {
             return m;
         }
@@ -303,6 +314,21 @@ impl StagedMoveGen {
         m
     }
 
+    /// Castling moves the king on `m.from` has under Fischer-random rules.
+    fn chess960_castles(game: &GameState, m: &Move) -> MoveList {
+        let mut castles = MoveList::new();
+        crate::moves::generate_castling_moves_into(
+            &game.board,
+            &m.from,
+            &m.piece,
+            &game.special_rights,
+            &game.game_rules,
+            &game.spatial_indices,
+            &mut castles,
+        );
+        castles
+    }
+
     #[inline]
     fn is_tt_move(&self, m: &Move) -> bool {
         Self::moves_match(m, &self.tt_move)
@@ -339,6 +365,14 @@ impl StagedMoveGen {
             return false;
         }
 
+        // A Fischer-random castle may leave the king in place or land it on its
+        // partner, so it is checked against the generator, not the geometry below.
+        if game.game_rules.chess960 && m.rook_coord.is_some() && piece.piece_type().is_royal() {
+            return Self::chess960_castles(game, m)
+                .iter()
+                .any(|c| c.to == m.to && c.rook_coord == m.rook_coord);
+        }
+
         // 3. Target Occupancy Check (Generic). Fetch the target tile once and
         // read both the packed piece (for identity) and the occupancy bit. A
         // neutral Void packs to 0 yet occupies, so pawn pushes below must test
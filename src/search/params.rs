@@ -7,7 +7,6 @@
 
 #[cfg(any(feature = "param_tuning", feature = "search_tuning"))]
 use once_cell::sync::Lazy;
-#[cfg(any(feature = "param_tuning", feature = "search_tuning"))]
 use serde::{Deserialize, Serialize};
 #[cfg(any(feature = "param_tuning", feature = "search_tuning"))]
 use std::sync::RwLock;
@@ -191,8 +190,9 @@ pub const TUNABLE_EVAL_PARAM_SPECS: &[EvalParamSpec] = &[
     EvalParamSpec::new("eg_outpost_bonus", crate::evaluation::base::DEFAULT_EVAL_EG_OUTPOST_BONUS as i64, 0, 250, 2.0, 0.002, "Endgame outpost bonus"),
 ];
 
-#[cfg(any(feature = "param_tuning", feature = "eval_tuning"))]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// One full set of evaluation parameters. Missing JSON fields take the compile-time
+/// `DEFAULT_EVAL_*` values, so tuning output and per-variant profiles may be partial.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(default)]
 pub struct EvalParams {
     pub pawn: i32,
@@ -223,37 +223,41 @@ pub struct EvalParams {
     pub eg_outpost_bonus: i32,
 }
 
-#[cfg(any(feature = "param_tuning", feature = "eval_tuning"))]
+impl EvalParams {
+    /// The compile-time `DEFAULT_EVAL_*` values.
+    pub const DEFAULT: Self = Self {
+        pawn: crate::evaluation::base::DEFAULT_EVAL_PAWN,
+        knight: crate::evaluation::base::DEFAULT_EVAL_KNIGHT,
+        bishop: crate::evaluation::base::DEFAULT_EVAL_BISHOP,
+        rook: crate::evaluation::base::DEFAULT_EVAL_ROOK,
+        guard: crate::evaluation::base::DEFAULT_EVAL_GUARD,
+        centaur: crate::evaluation::base::DEFAULT_EVAL_CENTAUR,
+        compound_bonus: crate::evaluation::base::DEFAULT_EVAL_COMPOUND_BONUS,
+        camel: crate::evaluation::base::DEFAULT_EVAL_CAMEL,
+        giraffe: crate::evaluation::base::DEFAULT_EVAL_GIRAFFE,
+        zebra: crate::evaluation::base::DEFAULT_EVAL_ZEBRA,
+        knightrider: crate::evaluation::base::DEFAULT_EVAL_KNIGHTRIDER,
+        hawk: crate::evaluation::base::DEFAULT_EVAL_HAWK,
+        archbishop: crate::evaluation::base::DEFAULT_EVAL_ARCHBISHOP,
+        rose: crate::evaluation::base::DEFAULT_EVAL_ROSE,
+        huygen: crate::evaluation::base::DEFAULT_EVAL_HUYGEN,
+        chancellor_bonus: crate::evaluation::base::DEFAULT_EVAL_CHANCELLOR_BONUS,
+        mg_doubled_pawn_penalty: crate::evaluation::base::DEFAULT_EVAL_MG_DOUBLED_PAWN_PENALTY,
+        eg_doubled_pawn_penalty: crate::evaluation::base::DEFAULT_EVAL_EG_DOUBLED_PAWN_PENALTY,
+        mg_bishop_pair_bonus: crate::evaluation::base::DEFAULT_EVAL_MG_BISHOP_PAIR_BONUS,
+        eg_bishop_pair_bonus: crate::evaluation::base::DEFAULT_EVAL_EG_BISHOP_PAIR_BONUS,
+        rook_open_file_bonus: crate::evaluation::base::DEFAULT_EVAL_ROOK_OPEN_FILE_BONUS,
+        rook_semi_open_file_bonus: crate::evaluation::base::DEFAULT_EVAL_ROOK_SEMI_OPEN_FILE_BONUS,
+        queen_open_file_bonus: crate::evaluation::base::DEFAULT_EVAL_QUEEN_OPEN_FILE_BONUS,
+        queen_semi_open_file_bonus: crate::evaluation::base::DEFAULT_EVAL_QUEEN_SEMI_OPEN_FILE_BONUS,
+        mg_outpost_bonus: crate::evaluation::base::DEFAULT_EVAL_MG_OUTPOST_BONUS,
+        eg_outpost_bonus: crate::evaluation::base::DEFAULT_EVAL_EG_OUTPOST_BONUS,
+    };
+}
+
 impl Default for EvalParams {
     fn default() -> Self {
-        Self {
-            pawn: crate::evaluation::base::DEFAULT_EVAL_PAWN,
-            knight: crate::evaluation::base::DEFAULT_EVAL_KNIGHT,
-            bishop: crate::evaluation::base::DEFAULT_EVAL_BISHOP,
-            rook: crate::evaluation::base::DEFAULT_EVAL_ROOK,
-            guard: crate::evaluation::base::DEFAULT_EVAL_GUARD,
-            centaur: crate::evaluation::base::DEFAULT_EVAL_CENTAUR,
-            compound_bonus: crate::evaluation::base::DEFAULT_EVAL_COMPOUND_BONUS,
-            camel: crate::evaluation::base::DEFAULT_EVAL_CAMEL,
-            giraffe: crate::evaluation::base::DEFAULT_EVAL_GIRAFFE,
-            zebra: crate::evaluation::base::DEFAULT_EVAL_ZEBRA,
-            knightrider: crate::evaluation::base::DEFAULT_EVAL_KNIGHTRIDER,
-            hawk: crate::evaluation::base::DEFAULT_EVAL_HAWK,
-            archbishop: crate::evaluation::base::DEFAULT_EVAL_ARCHBISHOP,
-            rose: crate::evaluation::base::DEFAULT_EVAL_ROSE,
-            huygen: crate::evaluation::base::DEFAULT_EVAL_HUYGEN,
-            chancellor_bonus: crate::evaluation::base::DEFAULT_EVAL_CHANCELLOR_BONUS,
-            mg_doubled_pawn_penalty: crate::evaluation::base::DEFAULT_EVAL_MG_DOUBLED_PAWN_PENALTY,
-            eg_doubled_pawn_penalty: crate::evaluation::base::DEFAULT_EVAL_EG_DOUBLED_PAWN_PENALTY,
-            mg_bishop_pair_bonus: crate::evaluation::base::DEFAULT_EVAL_MG_BISHOP_PAIR_BONUS,
-            eg_bishop_pair_bonus: crate::evaluation::base::DEFAULT_EVAL_EG_BISHOP_PAIR_BONUS,
-            rook_open_file_bonus: crate::evaluation::base::DEFAULT_EVAL_ROOK_OPEN_FILE_BONUS,
-            rook_semi_open_file_bonus: crate::evaluation::base::DEFAULT_EVAL_ROOK_SEMI_OPEN_FILE_BONUS,
-            queen_open_file_bonus: crate::evaluation::base::DEFAULT_EVAL_QUEEN_OPEN_FILE_BONUS,
-            queen_semi_open_file_bonus: crate::evaluation::base::DEFAULT_EVAL_QUEEN_SEMI_OPEN_FILE_BONUS,
-            mg_outpost_bonus: crate::evaluation::base::DEFAULT_EVAL_MG_OUTPOST_BONUS,
-            eg_outpost_bonus: crate::evaluation::base::DEFAULT_EVAL_EG_OUTPOST_BONUS,
-        }
+        Self::DEFAULT
     }
 }
 
@@ -282,6 +286,21 @@ pub fn get_eval_params_as_json() -> String {
     }
 }
 
+/// The live evaluation parameters: the compile-time defaults, or under eval tuning the
+/// values last set with `set_eval_params_from_json`. Games with an eval profile use
+/// theirs instead (see `GameState::eval_params`).
+#[inline]
+pub fn live_eval_params() -> EvalParams {
+    #[cfg(any(feature = "param_tuning", feature = "eval_tuning"))]
+    {
+        *EVAL_PARAMS.read().unwrap()
+    }
+    #[cfg(not(any(feature = "param_tuning", feature = "eval_tuning")))]
+    {
+        EvalParams::DEFAULT
+    }
+}
+
 #[cfg(any(feature = "param_tuning", feature = "eval_tuning"))]
 macro_rules! eval_param {
     ($field:ident) => {{ EVAL_PARAMS.read().unwrap().$field }};
@@ -517,6 +536,29 @@ pub fn set_search_params_from_json(json: &str) -> bool {
     }
 }
 
+/// Overrides one search parameter by its [`TUNABLE_PARAM_SPECS`] name, clamped to the
+/// spec's range. Returns false for a name without a spec.
+#[cfg(any(feature = "param_tuning", feature = "search_tuning"))]
+pub fn set_search_param(name: &str, value: i64) -> bool {
+    let Some(spec) = TUNABLE_PARAM_SPECS.iter().find(|spec| spec.name == name) else {
+        return false;
+    };
+    let Ok(mut guard) = SEARCH_PARAMS.write() else {
+        return false;
+    };
+    let Ok(mut json) = serde_json::to_value(&*guard) else {
+        return false;
+    };
+    json[name] = spec.clamp_value(value).into();
+    match serde_json::from_value::<SearchParams>(json) {
+        Ok(params) => {
+            *guard = params;
+            true
+        }
+        Err(_) => false,
+    }
+}
+
 #[cfg(any(feature = "param_tuning", feature = "search_tuning"))]
 pub fn get_search_params_as_json() -> String {
     match SEARCH_PARAMS.read() {
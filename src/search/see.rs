@@ -520,6 +520,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_see_uses_game_piece_values() {
+        let game = create_test_game_from_icn("[PieceValues \"Q=50\"] w (8;q|1;q) Q4,4|p5,5|p6,6");
+
+        let m = Move::new(
+            Coordinate::new(4, 4),
+            Coordinate::new(5, 5),
+            Piece::new(PieceType::Queen, PlayerColor::White),
+        );
+
+        // A queen worth less than the pawn it takes wins the exchange.
+        assert_eq!(static_exchange_eval_impl(&game, &m), 50);
+    }
+
     #[test]
     fn test_see_ge_threshold_pass() {
         let mut game = create_test_game_from_icn("w (8;q|1;q) P4,4|q5,5");
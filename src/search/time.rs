@@ -0,0 +1,441 @@
+//! Time management: how long to think about a move.
+//!
+//! [`TimeManager::allocate`] turns the side to move's clock into an optimum and a
+//! maximum thinking time. During iterative deepening the searcher's [`TimeManager`]
+//! is told about best-move changes, the score trend and the node share of the best
+//! move, and stretches or shrinks the optimum accordingly (never past the maximum).
+
+use crate::board::PlayerColor;
+use crate::game::GameState;
+
+/// Default allowance for GUI/network latency per move.
+pub const DEFAULT_MOVE_OVERHEAD_MS: u64 = 50;
+
+/// Minimum thinking time handed out for a clocked move.
+const MIN_THINK_MS: u64 = 10;
+
+/// Move horizon assumed when the clock has no `movestogo` (about 50 more moves).
+const DEFAULT_CENTI_MTG: i64 = 5051;
+
+/// The side to move's clock.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TimeControl {
+    pub remaining_ms: u64,
+    pub increment_ms: u64,
+    /// Moves until the clock is topped up again (`movestogo`); `None` for sudden death
+    /// and increment controls.
+    pub moves_to_go: Option<u32>,
+}
+
+impl TimeControl {
+    /// The clock of `turn`'s side, or `None` for a neutral side to move.
+    pub fn for_side(
+        turn: PlayerColor,
+        wtime: u64,
+        btime: u64,
+        winc: u64,
+        binc: u64,
+        moves_to_go: Option<u32>,
+    ) -> Option<TimeControl> {
+        let (remaining_ms, increment_ms) = match turn {
+            PlayerColor::White => (wtime, winc),
+            PlayerColor::Black => (btime, binc),
+            PlayerColor::Neutral => return None,
+        };
+        Some(TimeControl {
+            remaining_ms,
+            increment_ms,
+            moves_to_go: moves_to_go.filter(|&n| n > 0),
+        })
+    }
+}
+
+/// Thinking time for one move.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimeLimits {
+    /// Target time; the dynamic factors scale it during the search.
+    pub optimum_ms: u128,
+    /// Hard cap, never exceeded.
+    pub maximum_ms: u128,
+    /// A soft limit (a suggested per-move time, no clock to lose on) lets the search
+    /// use nearly all of it; a hard limit (a real clock) is spent conservatively.
+    pub is_soft: bool,
+}
+
+impl TimeLimits {
+    /// No time limit: only depth or a stop request ends the search.
+    pub const INFINITE: TimeLimits = TimeLimits {
+        optimum_ms: u128::MAX,
+        maximum_ms: u128::MAX,
+        is_soft: true,
+    };
+
+    /// A suggested per-move time with no clock behind it.
+    pub fn fixed(ms: u128) -> TimeLimits {
+        TimeLimits {
+            optimum_ms: ms,
+            maximum_ms: ms,
+            is_soft: true,
+        }
+    }
+
+    /// An exact per-move time (`movetime`) that must not be exceeded.
+    pub fn movetime(ms: u128) -> TimeLimits {
+        TimeLimits {
+            optimum_ms: ms,
+            maximum_ms: ms,
+            is_soft: false,
+        }
+    }
+
+    /// `(optimum, maximum, is_soft)`, the argument order of the search entry points.
+    pub fn as_tuple(self) -> (u128, u128, bool) {
+        (self.optimum_ms, self.maximum_ms, self.is_soft)
+    }
+}
+
+/// Plies played so far, estimated from the fullmove number and side to move.
+pub fn game_ply(game: &GameState) -> u32 {
+    game.fullmove_number.saturating_sub(1).saturating_mul(2)
+        + u32::from(game.turn == PlayerColor::Black)
+}
+
+/// Time allocation for one search, plus the iteration-to-iteration state that decides
+/// whether the next depth is worth starting.
+#[derive(Clone, Debug)]
+pub struct TimeManager {
+    limits: TimeLimits,
+    /// Best move changes (instability), halved at the start of every iteration.
+    tot_best_move_changes: f64,
+    /// Best move changes in the current iteration.
+    best_move_changes: f64,
+    /// Nodes spent on the first root move, accumulated across iterations so the ratio
+    /// stays comparable to the cumulative node count it is divided by.
+    best_move_nodes: u64,
+    /// Running average score smoothed across iterations.
+    best_previous_average_score: i32,
+    /// Scores of the last 4 iterations (circular buffer), for falling-eval detection.
+    iter_values: [i32; 4],
+    iter_idx: usize,
+    /// Previous time reduction factor (for smoothing across iterations).
+    prev_time_reduction: f64,
+    /// Depth at which the best move last changed.
+    last_best_move_depth: usize,
+    /// Current budget for this move including the dynamic factors (0 until the first
+    /// iteration has been scored). Used for proactive and mid-depth stops.
+    total_time_ms: f64,
+}
+
+impl Default for TimeManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TimeManager {
+    pub fn new() -> Self {
+        TimeManager {
+            limits: TimeLimits {
+                optimum_ms: 0,
+                maximum_ms: 0,
+                is_soft: false,
+            },
+            tot_best_move_changes: 0.0,
+            best_move_changes: 0.0,
+            best_move_nodes: 0,
+            best_previous_average_score: 0,
+            iter_values: [0; 4],
+            iter_idx: 0,
+            prev_time_reduction: 1.0,
+            last_best_move_depth: 0,
+            total_time_ms: 0.0,
+        }
+    }
+
+    /// Optimum and maximum thinking time for a move played on `tc`, `ply` plies into the
+    /// game. Returns `None` when the clock holds no time at all (no remaining time and no
+    /// increment), leaving the fallback to the caller.
+    ///
+    /// Sudden death and increment controls assume a ~50-move horizon (shortened when under
+    /// a second is left) over which every future increment is counted; with `movestogo`
+    /// the horizon is the moves left until the clock is topped up, and the time is spread
+    /// evenly over them instead.
+    pub fn allocate(tc: &TimeControl, ply: u32, move_overhead_ms: u64) -> Option<TimeLimits> {
+        if tc.remaining_ms == 0 && tc.increment_ms == 0 {
+            return None;
+        }
+
+        // Increment only: at least give ourselves a small buffer based on increment.
+        let remaining_ms = if tc.remaining_ms > 0 {
+            tc.remaining_ms
+        } else {
+            tc.increment_ms.max(500)
+        };
+        let inc_ms = tc.increment_ms;
+        let ply = ply as f64;
+
+        // Remaining time minus one move overhead
+        let scaled_time = remaining_ms.saturating_sub(move_overhead_ms);
+
+        // Move horizon in centimoves (moves to go * 100)
+        let centi_mtg: i64 = match tc.moves_to_go {
+            Some(mtg) => (mtg as i64 * 100).min(5000),
+            None if scaled_time >= 1000 => DEFAULT_CENTI_MTG,
+            // Under a second left: gradually shorten the horizon
+            None => ((scaled_time as f64) * 5.051) as i64,
+        }
+        .max(100); // At least 1 move expected
+
+        // Time usable over the horizon: remaining + inc * (MTG - 1) - overhead * (2 + MTG)
+        let time_left = (remaining_ms as i64
+            + (inc_ms as i64 * (centi_mtg - 100) - move_overhead_ms as i64 * (200 + centi_mtg))
+                / 100)
+            .max(1) as f64;
+
+        let (opt_scale, max_scale) = match tc.moves_to_go {
+            None => {
+                // Logarithmic adjustment that prevents overspending at long time controls.
+                // 10s games: log10(10000) = 4 -> 0.816; 40m games: log10(2400000) -> 1.56
+                let original_time_adjust = (0.3128 * time_left.max(1.0).log10() - 0.4354).max(0.1);
+                let log_time_sec = (scaled_time as f64 / 1000.0).max(0.001).log10();
+                let opt_constant = (0.0032116 + 0.000321123 * log_time_sec).min(0.00508017);
+                let max_constant = (3.3977 + 3.0395 * log_time_sec).max(2.94761);
+
+                // Share of time_left to use for this move
+                let opt_scale = ((0.0121431 + (ply + 2.94693_f64).powf(0.461073) * opt_constant)
+                    .min(0.213035 * remaining_ms as f64 / time_left))
+                    * original_time_adjust;
+                // Multiplier from optimum to maximum
+                let max_scale = max_constant.min(6.67704) + ply / 11.9847;
+                (opt_scale, max_scale)
+            }
+            Some(_) => {
+                let mtg = centi_mtg as f64 / 100.0;
+                let opt_scale =
+                    ((0.88 + ply / 116.4) / mtg).min(0.88 * remaining_ms as f64 / time_left);
+                let max_scale = 1.3 + 0.11 * mtg;
+                (opt_scale, max_scale)
+            }
+        };
+
+        let optimum = (opt_scale * time_left) as u64;
+        let maximum = ((max_scale * optimum as f64)
+            .min(0.825179 * remaining_ms as f64 - move_overhead_ms as f64)
+            - 10.0)
+            .max(0.0) as u64;
+
+        let optimum = optimum.max(MIN_THINK_MS);
+        let maximum = maximum.max(optimum);
+
+        // Final safety cap: never exceed 82.5% of remaining time
+        let absolute_cap =
+            (((remaining_ms as f64) * 0.825 - move_overhead_ms as f64) as u64).max(MIN_THINK_MS);
+
+        // Timed games are hard limits (risk of flagging).
+        Some(TimeLimits {
+            optimum_ms: optimum.min(absolute_cap) as u128,
+            maximum_ms: maximum.min(absolute_cap) as u128,
+            is_soft: false,
+        })
+    }
+
+    /// Starts managing a new search with `limits`.
+    pub fn start(&mut self, limits: TimeLimits) {
+        self.reset();
+        self.limits = limits;
+    }
+
+    /// Clears the per-search state, keeping the limits.
+    pub fn reset(&mut self) {
+        let limits = self.limits;
+        *self = Self::new();
+        self.limits = limits;
+    }
+
+    pub fn limits(&self) -> TimeLimits {
+        self.limits
+    }
+
+    pub fn set_limits(&mut self, limits: TimeLimits) {
+        self.limits = limits;
+    }
+
+    pub fn optimum_ms(&self) -> u128 {
+        self.limits.optimum_ms
+    }
+
+    pub fn maximum_ms(&self) -> u128 {
+        self.limits.maximum_ms
+    }
+
+    pub fn is_soft(&self) -> bool {
+        self.limits.is_soft
+    }
+
+    /// Budget for this move including the dynamic factors (0 before the first iteration).
+    pub fn total_time_ms(&self) -> f64 {
+        self.total_time_ms
+    }
+
+    /// Overrides the dynamic budget (MultiPV pins it at the optimum, analysis clears it).
+    pub fn set_total_time_ms(&mut self, ms: f64) {
+        self.total_time_ms = ms;
+    }
+
+    /// Called at the start of every iteration: older instability counts for less.
+    pub fn new_iteration(&mut self) {
+        self.tot_best_move_changes /= 2.0;
+    }
+
+    /// The best root move changed at `depth`.
+    pub fn best_move_changed(&mut self, depth: usize) {
+        self.best_move_changes += 1.0;
+        self.last_best_move_depth = depth;
+    }
+
+    /// `nodes` were spent searching the current best (first) root move.
+    pub fn add_best_move_nodes(&mut self, nodes: u64) {
+        self.best_move_nodes += nodes;
+    }
+
+    /// Scores a finished iteration and returns the time budget for this move: the
+    /// optimum stretched when the score is falling or the best move keeps changing,
+    /// shrunk when the best move is stable or takes nearly all the nodes, and capped
+    /// at the maximum. The search should stop once the elapsed time passes it.
+    pub fn iteration_done(&mut self, depth: usize, best_score: i32, total_nodes: u64) -> f64 {
+        // Effort tracking: fraction of nodes spent on the best move
+        let nodes_effort = if total_nodes > 0 {
+            (self.best_move_nodes as f64 * 100000.0) / (total_nodes as f64)
+        } else {
+            0.0
+        };
+        let high_best_move_effort = if nodes_effort >= 93340.0 { 0.76 } else { 1.0 };
+
+        // Accumulate instability changes from this iteration
+        self.tot_best_move_changes += self.best_move_changes;
+        self.best_move_changes = 0.0;
+
+        // fallingEval: spend more time when score is dropping
+        let iter_val = self.iter_values[self.iter_idx];
+        let prev_avg = self.best_previous_average_score;
+        let falling_eval =
+            (11.85 + 2.24 * (prev_avg - best_score) as f64 + 0.93 * (iter_val - best_score) as f64)
+                / 100.0;
+        let falling_eval = falling_eval.clamp(0.57, 1.70);
+
+        // timeReduction: spend less time when best move is stable
+        let k = 0.51;
+        let center = (self.last_best_move_depth as f64) + 12.15;
+        let time_reduction = 0.66 + 0.85 / (0.98 + (-k * (depth as f64 - center)).exp());
+
+        let reduction = (1.43 + self.prev_time_reduction) / (2.28 * time_reduction);
+
+        // bestMoveInstability: spend more time when best move keeps changing
+        let instability = (1.02 + 2.14 * self.tot_best_move_changes).min(2.5);
+
+        let mut total_factors =
+            (falling_eval * reduction * instability * high_best_move_effort).clamp(0.5, 2.5);
+
+        // If it's a soft limit (like fixed time per move), we want to use
+        // nearly all of the time, not stop early to save time.
+        if self.limits.is_soft {
+            total_factors = total_factors.max(0.98);
+        }
+
+        let total_time = self.limits.optimum_ms as f64 * total_factors;
+        self.total_time_ms = total_time.min(self.limits.maximum_ms as f64);
+
+        // Update iteration tracking
+        self.iter_values[self.iter_idx] = best_score;
+        self.iter_idx = (self.iter_idx + 1) & 3;
+
+        if self.best_previous_average_score == 0 {
+            self.best_previous_average_score = best_score;
+        } else {
+            self.best_previous_average_score = (best_score + self.best_previous_average_score) / 2;
+        }
+
+        self.prev_time_reduction = time_reduction;
+
+        self.total_time_ms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clock(remaining_ms: u64, increment_ms: u64, moves_to_go: Option<u32>) -> TimeControl {
+        TimeControl {
+            remaining_ms,
+            increment_ms,
+            moves_to_go,
+        }
+    }
+
+    #[test]
+    fn empty_clock_has_no_allocation() {
+        assert_eq!(TimeManager::allocate(&clock(0, 0, None), 0, 50), None);
+    }
+
+    #[test]
+    fn allocation_stays_within_the_clock() {
+        for (remaining, inc, mtg) in [
+            (60_000, 0, None),
+            (60_000, 1_000, None),
+            (300, 0, None),
+            (0, 1_500, None),
+            (60_000, 0, Some(1)),
+            (60_000, 0, Some(40)),
+        ] {
+            let limits = TimeManager::allocate(&clock(remaining, inc, mtg), 20, 50).unwrap();
+            assert!(!limits.is_soft);
+            assert!(limits.optimum_ms >= MIN_THINK_MS as u128);
+            assert!(limits.maximum_ms >= limits.optimum_ms);
+            assert!(limits.maximum_ms <= remaining.max(inc.max(500)) as u128);
+        }
+    }
+
+    #[test]
+    fn moves_to_go_spreads_the_clock_over_the_remaining_moves() {
+        let last_move = TimeManager::allocate(&clock(60_000, 0, Some(1)), 40, 50).unwrap();
+        let many_moves = TimeManager::allocate(&clock(60_000, 0, Some(40)), 40, 50).unwrap();
+        assert!(last_move.optimum_ms > 10 * many_moves.optimum_ms);
+    }
+
+    #[test]
+    fn increment_and_overhead_shift_the_allocation() {
+        let sudden_death = TimeManager::allocate(&clock(60_000, 0, None), 20, 50).unwrap();
+        let increment = TimeManager::allocate(&clock(60_000, 1_000, None), 20, 50).unwrap();
+        let laggy = TimeManager::allocate(&clock(60_000, 0, None), 20, 1_000).unwrap();
+        assert!(increment.optimum_ms > sudden_death.optimum_ms);
+        assert!(laggy.optimum_ms < sudden_death.optimum_ms);
+    }
+
+    #[test]
+    fn unstable_best_move_earns_more_time_than_a_stable_one() {
+        let limits = TimeLimits {
+            optimum_ms: 1_000,
+            maximum_ms: 5_000,
+            is_soft: false,
+        };
+
+        let mut stable = TimeManager::new();
+        stable.start(limits);
+        let mut unstable = TimeManager::new();
+        unstable.start(limits);
+
+        let (mut stable_budget, mut unstable_budget) = (0.0, 0.0);
+        for depth in 1..=10 {
+            stable.new_iteration();
+            unstable.new_iteration();
+            unstable.best_move_changed(depth);
+            stable_budget = stable.iteration_done(depth, 30, 1_000);
+            unstable_budget = unstable.iteration_done(depth, 30, 1_000);
+        }
+
+        assert!(unstable_budget > stable_budget);
+        assert!(unstable_budget <= limits.maximum_ms as f64);
+        assert_eq!(unstable.total_time_ms(), unstable_budget);
+    }
+}
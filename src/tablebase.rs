@@ -0,0 +1,1022 @@
+//! Endgame tablebases for small pawnless endings.
+//!
+//! [`generate`] solves every position of a 3–4 piece ending (both kings plus at most two
+//! other pieces, no pawns) by retrograde analysis and stores the distance to mate in
+//! plies. Positions are normalised in one of two ways:
+//!
+//! - [`Geometry::Bounded`] tables cover a whole `width` x `height` board such as `Chess`,
+//!   counted from the corner of the world bounds. Positions that are neither won nor
+//!   lost are draws.
+//! - [`Geometry::Window`] tables cover the infinite plane, translated so the defending
+//!   king sits on the origin and every other piece is within `radius` squares of it. A
+//!   move that takes a piece out of the window, or leaves the defending king too far from
+//!   the others, is treated as a way out for whoever plays it. A win is therefore only
+//!   recorded when the winner never needs to leave the window and the loser can never
+//!   escape it; that containment is what makes a window DTM a real mate on the plane (an
+//!   upper bound on the unrestricted distance). Everything else is unknown, not drawn.
+//!
+//! Tables ignore the 50-move rule, castling, obstacles, playable regions and every win
+//! condition but checkmate; [`Tables::probe`] only answers for positions where none of
+//! those apply. That leaves `Obstocean` out: its 22 x 16 board would fit a bounded table,
+//! but its obstacles are still on the board in practically every ending, and a position
+//! with obstacles is never probed.
+
+mod generate;
+pub mod syzygy;
+
+pub use generate::generate;
+
+use crate::attacks::{
+    CAMEL_OFFSETS, DIAG_DIRS, GIRAFFE_OFFSETS, HAWK_OFFSETS, KING_OFFSETS, KNIGHT_OFFSETS,
+    KNIGHTRIDER_DIRS, ORTHO_DIRS, ZEBRA_OFFSETS,
+};
+use crate::board::{PieceType, PlayerColor};
+use crate::game::{GameState, WinCondition};
+use arrayvec::ArrayVec;
+use std::io::{Cursor, Read};
+use std::sync::{Arc, PoisonError, RwLock};
+
+/// Magic bytes for file identification
+const MAGIC: &[u8; 8] = b"APTBDTM1";
+
+/// Most pieces a table can hold, kings included.
+pub const MAX_PIECES: usize = 4;
+
+// Stored values: a distance in plies tagged with the result for the side to move.
+const WIN: u16 = 0x4000;
+const LOSS: u16 = 0x8000;
+const INVALID: u16 = 0xFFFF;
+const DIST_MASK: u16 = 0x3FFF;
+
+type Square = (i64, i64);
+
+/// How a table places pieces.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Geometry {
+    /// The whole board, with (0, 0) at the world bounds' minimum corner.
+    Bounded { width: u8, height: u8 },
+    /// The infinite plane around the defending king, out to `radius` squares.
+    Window { radius: u8 },
+}
+
+impl Geometry {
+    /// Squares a single piece can stand on.
+    pub fn squares(&self) -> usize {
+        match *self {
+            Geometry::Bounded { width, height } => width as usize * height as usize,
+            Geometry::Window { radius } => (2 * radius as usize + 1).pow(2),
+        }
+    }
+
+    fn contains(&self, (x, y): Square) -> bool {
+        match *self {
+            Geometry::Bounded { width, height } => {
+                (0..width as i64).contains(&x) && (0..height as i64).contains(&y)
+            }
+            Geometry::Window { radius } => x.abs() <= radius as i64 && y.abs() <= radius as i64,
+        }
+    }
+
+    fn square_index(&self, (x, y): Square) -> usize {
+        match *self {
+            Geometry::Bounded { width, .. } => (y * width as i64 + x) as usize,
+            Geometry::Window { radius } => {
+                let r = radius as i64;
+                ((y + r) * (2 * r + 1) + x + r) as usize
+            }
+        }
+    }
+
+    fn square_at(&self, index: usize) -> Square {
+        match *self {
+            Geometry::Bounded { width, .. } => {
+                let width = width as usize;
+                ((index % width) as i64, (index / width) as i64)
+            }
+            Geometry::Window { radius } => {
+                let side = 2 * radius as usize + 1;
+                let r = radius as i64;
+                ((index % side) as i64 - r, (index / side) as i64 - r)
+            }
+        }
+    }
+
+    fn is_window(&self) -> bool {
+        matches!(self, Geometry::Window { .. })
+    }
+}
+
+/// The pieces besides the two kings. The attacker is the side the material is named
+/// from; the defender's king is the one window tables are centred on.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Material {
+    pub attacker: Vec<PieceType>,
+    pub defender: Vec<PieceType>,
+}
+
+impl Material {
+    pub fn new(mut attacker: Vec<PieceType>, mut defender: Vec<PieceType>) -> Self {
+        attacker.sort_by_key(|&pt| pt as u8);
+        defender.sort_by_key(|&pt| pt as u8);
+        Material { attacker, defender }
+    }
+
+    /// Parses names like `KQvK`, `KRRvK` or `KQvKR`, using the ICN piece letters.
+    pub fn parse(name: &str) -> Option<Self> {
+        let (attacker, defender) = name.split_once(['v', 'V'])?;
+        let side = |s: &str| -> Option<Vec<PieceType>> {
+            let rest = s.strip_prefix(['K', 'k'])?;
+            rest.chars()
+                .map(|c| c.to_string().parse::<PieceType>().ok())
+                .collect()
+        };
+        Some(Material::new(side(attacker)?, side(defender)?))
+    }
+
+    /// The name `parse` reads back.
+    pub fn name(&self) -> String {
+        let side = |pieces: &[PieceType]| -> String {
+            std::iter::once('K')
+                .chain(pieces.iter().map(|pt| pt.get_char().to_ascii_uppercase()))
+                .collect()
+        };
+        format!("{}v{}", side(&self.attacker), side(&self.defender))
+    }
+
+    /// Pieces on the board, kings included.
+    pub fn piece_count(&self) -> usize {
+        2 + self.attacker.len() + self.defender.len()
+    }
+
+    fn check(&self) -> Result<(), String> {
+        if self.piece_count() > MAX_PIECES {
+            return Err(format!(
+                "{} has more than {} pieces",
+                self.name(),
+                MAX_PIECES
+            ));
+        }
+        match self
+            .attacker
+            .iter()
+            .chain(&self.defender)
+            .find(|&&pt| pt.is_royal() || Movement::of(pt).is_none())
+        {
+            Some(pt) => Err(format!("{:?} is not supported in tablebases", pt)),
+            None => Ok(()),
+        }
+    }
+
+    /// Every material one capture away.
+    fn captures(&self) -> Vec<Material> {
+        let mut out: Vec<Material> = Vec::new();
+        for i in 0..self.attacker.len() {
+            let mut attacker = self.attacker.clone();
+            attacker.remove(i);
+            out.push(Material::new(attacker, self.defender.clone()));
+        }
+        for i in 0..self.defender.len() {
+            let mut defender = self.defender.clone();
+            defender.remove(i);
+            out.push(Material::new(self.attacker.clone(), defender));
+        }
+        out.dedup();
+        out
+    }
+}
+
+/// A probed result for the side to move, with the distance to mate in plies.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TbValue {
+    Win(u16),
+    Loss(u16),
+    Draw,
+}
+
+type Offsets = &'static [(i64, i64)];
+
+/// How a piece type moves: fixed leaps plus unlimited slides.
+struct Movement {
+    leaps: ArrayVec<Offsets, 2>,
+    slides: Offsets,
+}
+
+impl Movement {
+    fn of(pt: PieceType) -> Option<Movement> {
+        let (leaps, slides): (&[Offsets], Offsets) = match pt {
+            PieceType::King | PieceType::Guard => (&[&KING_OFFSETS], &[]),
+            PieceType::Knight => (&[&KNIGHT_OFFSETS], &[]),
+            PieceType::Camel => (&[&CAMEL_OFFSETS], &[]),
+            PieceType::Giraffe => (&[&GIRAFFE_OFFSETS], &[]),
+            PieceType::Zebra => (&[&ZEBRA_OFFSETS], &[]),
+            PieceType::Hawk => (&[&HAWK_OFFSETS], &[]),
+            PieceType::Centaur => (&[&KING_OFFSETS, &KNIGHT_OFFSETS], &[]),
+            PieceType::Rook => (&[], &ORTHO_DIRS),
+            PieceType::Bishop => (&[], &DIAG_DIRS),
+            PieceType::Queen => (&[], &KING_OFFSETS),
+            PieceType::Knightrider => (&[], &KNIGHTRIDER_DIRS),
+            PieceType::Chancellor => (&[&KNIGHT_OFFSETS], &ORTHO_DIRS),
+            PieceType::Archbishop => (&[&KNIGHT_OFFSETS], &DIAG_DIRS),
+            PieceType::Amazon => (&[&KNIGHT_OFFSETS], &KING_OFFSETS),
+            _ => return None,
+        };
+        Some(Movement {
+            leaps: leaps.iter().copied().collect(),
+            slides,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Side {
+    Attacker = 0,
+    Defender = 1,
+}
+
+impl Side {
+    fn opponent(self) -> Side {
+        match self {
+            Side::Attacker => Side::Defender,
+            Side::Defender => Side::Attacker,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Unit {
+    side: Side,
+    piece: PieceType,
+    sq: Square,
+}
+
+// Units are always ordered defending king, attacking king, attacker's pieces, defender's
+// pieces, each side's pieces sorted by type like `Material`.
+const DEFENDING_KING: usize = 0;
+const ATTACKING_KING: usize = 1;
+
+/// A table position in table coordinates.
+#[derive(Clone, Debug)]
+struct Position {
+    stm: Side,
+    units: ArrayVec<Unit, MAX_PIECES>,
+}
+
+/// Where a legal move leads.
+enum Successor {
+    Quiet,
+    Capture(Position),
+    /// Off the board of a window table.
+    Outside,
+}
+
+impl Position {
+    fn occupant(&self, sq: Square) -> Option<usize> {
+        self.units.iter().position(|u| u.sq == sq)
+    }
+
+    fn attacks(&self, attacker: usize, target: Square) -> bool {
+        let unit = self.units[attacker];
+        let Some(movement) = Movement::of(unit.piece) else {
+            return false;
+        };
+        let (dx, dy) = (target.0 - unit.sq.0, target.1 - unit.sq.1);
+        if movement.leaps.iter().any(|set| set.contains(&(dx, dy))) {
+            return true;
+        }
+        movement.slides.iter().any(|&(ux, uy)| {
+            let k = if ux != 0 { dx / ux } else { dy / uy };
+            k > 0
+                && dx == k * ux
+                && dy == k * uy
+                && (1..k).all(|j| {
+                    self.occupant((unit.sq.0 + j * ux, unit.sq.1 + j * uy))
+                        .is_none()
+                })
+        })
+    }
+
+    fn in_check(&self, side: Side) -> bool {
+        let king = match side {
+            Side::Defender => DEFENDING_KING,
+            Side::Attacker => ATTACKING_KING,
+        };
+        let target = self.units[king].sq;
+        (0..self.units.len()).any(|i| self.units[i].side != side && self.attacks(i, target))
+    }
+
+    /// The side that just moved can't be left in check.
+    fn is_legal(&self) -> bool {
+        !self.in_check(self.stm.opponent())
+    }
+
+    /// Moves the frame of a window table back onto the defending king.
+    fn recentre(&mut self) {
+        let (kx, ky) = self.units[DEFENDING_KING].sq;
+        for unit in &mut self.units {
+            unit.sq = (unit.sq.0 - kx, unit.sq.1 - ky);
+        }
+    }
+
+    fn fits(&self, geometry: Geometry) -> bool {
+        self.units.iter().all(|u| geometry.contains(u.sq))
+    }
+
+    /// Calls `f` for every legal move of the side to move.
+    fn successors(&self, geometry: Geometry, mut f: impl FnMut(Successor)) {
+        for i in 0..self.units.len() {
+            if self.units[i].side != self.stm {
+                continue;
+            }
+            let Some(movement) = Movement::of(self.units[i].piece) else {
+                continue;
+            };
+            let from = self.units[i].sq;
+            for set in &movement.leaps {
+                for &(dx, dy) in set.iter() {
+                    self.try_move(geometry, i, (from.0 + dx, from.1 + dy), &mut f);
+                }
+            }
+            for &(dx, dy) in movement.slides {
+                let mut k = 1;
+                while self.try_move(geometry, i, (from.0 + k * dx, from.1 + k * dy), &mut f) {
+                    k += 1;
+                }
+            }
+        }
+    }
+
+    /// Plays unit `i` to `to` if that's legal. Returns whether a slide can go further.
+    fn try_move(
+        &self,
+        geometry: Geometry,
+        i: usize,
+        to: Square,
+        f: &mut impl FnMut(Successor),
+    ) -> bool {
+        let window = geometry.is_window();
+        if !window && !geometry.contains(to) {
+            return false;
+        }
+
+        let mut next = self.clone();
+        next.stm = self.stm.opponent();
+        next.units[i].sq = to;
+        let captured = self.occupant(to);
+        if let Some(j) = captured {
+            let target = self.units[j];
+            if target.side == self.stm || target.piece.is_royal() {
+                return false;
+            }
+            next.units.remove(j);
+        }
+        if next.in_check(self.stm) {
+            return captured.is_none() && geometry.contains(to);
+        }
+
+        if window && i == DEFENDING_KING {
+            next.recentre();
+        }
+        if !next.fits(geometry) {
+            f(Successor::Outside);
+            // Every further square along the slide is outside as well.
+            return false;
+        }
+        match captured {
+            Some(_) => f(Successor::Capture(next)),
+            None => f(Successor::Quiet),
+        }
+        captured.is_none()
+    }
+
+    /// Calls `f` for every legal position where the side that just moved played a quiet
+    /// move leading here.
+    fn predecessors(&self, geometry: Geometry, mut f: impl FnMut(Position)) {
+        let mover = self.stm.opponent();
+        for i in 0..self.units.len() {
+            if self.units[i].side != mover {
+                continue;
+            }
+            let Some(movement) = Movement::of(self.units[i].piece) else {
+                continue;
+            };
+
+            if geometry.is_window() && i == DEFENDING_KING {
+                // The king came from -d, so the rest of the board shifts by +d.
+                for &(dx, dy) in KING_OFFSETS.iter() {
+                    if self.occupant((-dx, -dy)).is_some() {
+                        continue;
+                    }
+                    let mut prev = self.clone();
+                    prev.stm = mover;
+                    for unit in prev.units.iter_mut().skip(1) {
+                        unit.sq = (unit.sq.0 + dx, unit.sq.1 + dy);
+                    }
+                    if prev.fits(geometry) && prev.is_legal() {
+                        f(prev);
+                    }
+                }
+                continue;
+            }
+
+            let from = self.units[i].sq;
+            let mut unmove = |to: Square| -> bool {
+                if !geometry.contains(to) || self.occupant(to).is_some() {
+                    return false;
+                }
+                let mut prev = self.clone();
+                prev.stm = mover;
+                prev.units[i].sq = to;
+                if prev.is_legal() {
+                    f(prev);
+                }
+                true
+            };
+            for set in &movement.leaps {
+                for &(dx, dy) in set.iter() {
+                    unmove((from.0 + dx, from.1 + dy));
+                }
+            }
+            for &(dx, dy) in movement.slides {
+                let mut k = 1;
+                while unmove((from.0 + k * dx, from.1 + k * dy)) {
+                    k += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Maps positions of one material to table indices: the side to move, then one square
+/// per unit (the defending king's is implicit in window tables).
+#[derive(Clone, Debug)]
+struct Layout {
+    geometry: Geometry,
+    kinds: ArrayVec<(Side, PieceType), MAX_PIECES>,
+}
+
+impl Layout {
+    fn new(geometry: Geometry, material: &Material) -> Self {
+        let mut kinds = ArrayVec::new();
+        kinds.push((Side::Defender, PieceType::King));
+        kinds.push((Side::Attacker, PieceType::King));
+        kinds.extend(material.attacker.iter().map(|&pt| (Side::Attacker, pt)));
+        kinds.extend(material.defender.iter().map(|&pt| (Side::Defender, pt)));
+        Layout { geometry, kinds }
+    }
+
+    fn first_slot(&self) -> usize {
+        if self.geometry.is_window() { 1 } else { 0 }
+    }
+
+    fn len(&self) -> usize {
+        2 * self
+            .geometry
+            .squares()
+            .pow((self.kinds.len() - self.first_slot()) as u32)
+    }
+
+    fn matches(&self, pos: &Position) -> bool {
+        pos.units
+            .iter()
+            .map(|u| (u.side, u.piece))
+            .eq(self.kinds.iter().copied())
+    }
+
+    fn index(&self, pos: &Position) -> usize {
+        let squares = self.geometry.squares();
+        pos.units[self.first_slot()..]
+            .iter()
+            .fold(pos.stm as usize, |index, unit| {
+                index * squares + self.geometry.square_index(unit.sq)
+            })
+    }
+
+    /// The position at `index`, or `None` if two pieces share a square.
+    fn decode(&self, mut index: usize) -> Option<Position> {
+        let squares = self.geometry.squares();
+        let mut units: ArrayVec<Unit, MAX_PIECES> = self
+            .kinds
+            .iter()
+            .map(|&(side, piece)| Unit {
+                side,
+                piece,
+                sq: (0, 0),
+            })
+            .collect();
+        for unit in units[self.first_slot()..].iter_mut().rev() {
+            unit.sq = self.geometry.square_at(index % squares);
+            index /= squares;
+        }
+        let stm = if index == 0 {
+            Side::Attacker
+        } else {
+            Side::Defender
+        };
+        for (i, unit) in units.iter().enumerate() {
+            if units[..i].iter().any(|u| u.sq == unit.sq) {
+                return None;
+            }
+        }
+        Some(Position { stm, units })
+    }
+}
+
+fn decode_value(raw: u16) -> Option<TbValue> {
+    match raw {
+        INVALID => None,
+        _ if raw & WIN != 0 => Some(TbValue::Win(raw & DIST_MASK)),
+        _ if raw & LOSS != 0 => Some(TbValue::Loss(raw & DIST_MASK)),
+        _ => Some(TbValue::Draw),
+    }
+}
+
+/// A solved ending: one DTM value per position.
+pub struct Tablebase {
+    layout: Layout,
+    material: Material,
+    values: Vec<u16>,
+}
+
+impl Tablebase {
+    pub fn geometry(&self) -> Geometry {
+        self.layout.geometry
+    }
+
+    pub fn material(&self) -> &Material {
+        &self.material
+    }
+
+    /// Number of positions, legal or not.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// The longest forced mate in plies, from the winner's side.
+    pub fn longest_win(&self) -> u16 {
+        self.values
+            .iter()
+            .filter_map(|&raw| match decode_value(raw) {
+                Some(TbValue::Win(plies)) => Some(plies),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn value(&self, pos: &Position) -> Option<TbValue> {
+        match decode_value(self.values[self.layout.index(pos)])? {
+            TbValue::Draw if self.layout.geometry.is_window() => None,
+            value => Some(value),
+        }
+    }
+
+    /// Looks up `game` if it has exactly this table's material and fits its geometry.
+    pub fn probe(&self, game: &GameState) -> Option<TbValue> {
+        self.probe_snapshot(&Snapshot::of(game)?)
+    }
+
+    fn probe_snapshot(&self, snapshot: &Snapshot) -> Option<TbValue> {
+        [PlayerColor::White, PlayerColor::Black]
+            .into_iter()
+            .find_map(|attacker| self.position_of(snapshot, attacker))
+            .and_then(|pos| self.value(&pos))
+    }
+
+    /// `snapshot` in table coordinates, with `attacker` as the attacking side.
+    fn position_of(&self, snapshot: &Snapshot, attacker: PlayerColor) -> Option<Position> {
+        let (att, def) = if attacker == PlayerColor::White {
+            (&snapshot.white, &snapshot.black)
+        } else {
+            (&snapshot.black, &snapshot.white)
+        };
+        let king = |pieces: &Pieces| -> Option<Square> {
+            let mut kings = pieces.iter().filter(|(pt, _)| *pt == PieceType::King);
+            let king = kings.next()?;
+            kings.next().is_none().then_some(king.1)
+        };
+        let others = |pieces: &Pieces| -> Pieces {
+            let mut others: Pieces = pieces
+                .iter()
+                .filter(|(pt, _)| *pt != PieceType::King)
+                .copied()
+                .collect();
+            others.sort_by_key(|(pt, _)| *pt as u8);
+            others
+        };
+        let (att_others, def_others) = (others(att), others(def));
+        if !att_others
+            .iter()
+            .map(|(pt, _)| pt)
+            .eq(&self.material.attacker)
+            || !def_others
+                .iter()
+                .map(|(pt, _)| pt)
+                .eq(&self.material.defender)
+        {
+            return None;
+        }
+
+        let def_king = king(def)?;
+        let bounds = snapshot.bounds;
+        let origin = match self.layout.geometry {
+            Geometry::Bounded { width, height } => {
+                if bounds.max_x - bounds.min_x + 1 != width as i64
+                    || bounds.max_y - bounds.min_y + 1 != height as i64
+                {
+                    return None;
+                }
+                (bounds.min_x, bounds.min_y)
+            }
+            Geometry::Window { radius } => {
+                let r = radius as i64;
+                // The window's edge has to be the board's or nothing: a wall inside it
+                // would take away squares the table assumed.
+                if def_king.0 - r < bounds.min_x
+                    || def_king.0 + r > bounds.max_x
+                    || def_king.1 - r < bounds.min_y
+                    || def_king.1 + r > bounds.max_y
+                {
+                    return None;
+                }
+                def_king
+            }
+        };
+
+        let stm = if snapshot.turn == attacker {
+            Side::Attacker
+        } else {
+            Side::Defender
+        };
+        let units = [
+            (Side::Defender, PieceType::King, def_king),
+            (Side::Attacker, PieceType::King, king(att)?),
+        ]
+        .into_iter()
+        .chain(att_others.iter().map(|&(pt, sq)| (Side::Attacker, pt, sq)))
+        .chain(def_others.iter().map(|&(pt, sq)| (Side::Defender, pt, sq)))
+        .map(|(side, piece, sq)| Unit {
+            side,
+            piece,
+            sq: (sq.0 - origin.0, sq.1 - origin.1),
+        })
+        .collect();
+        let pos = Position { stm, units };
+        pos.fits(self.layout.geometry).then_some(pos)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(32 + 2 * self.values.len());
+        out.extend_from_slice(MAGIC);
+        match self.layout.geometry {
+            Geometry::Bounded { width, height } => out.extend_from_slice(&[0, width, height]),
+            Geometry::Window { radius } => out.extend_from_slice(&[1, radius, 0]),
+        }
+        for pieces in [&self.material.attacker, &self.material.defender] {
+            out.push(pieces.len() as u8);
+            out.extend(pieces.iter().map(|&pt| pt as u8));
+        }
+        out.extend_from_slice(&(self.values.len() as u32).to_le_bytes());
+        for value in &self.values {
+            out.extend_from_slice(&value.to_le_bytes());
+        }
+        out
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Self, &'static str> {
+        let mut cursor = Cursor::new(data);
+        if &read_array::<8>(&mut cursor)? != MAGIC {
+            return Err("Invalid tablebase magic bytes");
+        }
+
+        let [tag, a, b] = read_array::<3>(&mut cursor)?;
+        let geometry = match tag {
+            0 if a > 0 && b > 0 => Geometry::Bounded {
+                width: a,
+                height: b,
+            },
+            1 if a > 0 => Geometry::Window { radius: a },
+            _ => return Err("Invalid tablebase geometry"),
+        };
+
+        let mut sides = [Vec::new(), Vec::new()];
+        for pieces in &mut sides {
+            let [count] = read_array::<1>(&mut cursor)?;
+            for _ in 0..count {
+                let [v] = read_array::<1>(&mut cursor)?;
+                if v > PieceType::Pawn as u8 {
+                    return Err("Invalid tablebase piece");
+                }
+                pieces.push(PieceType::from_u8(v));
+            }
+        }
+        let [attacker, defender] = sides;
+        let material = Material::new(attacker, defender);
+        material
+            .check()
+            .map_err(|_| "Unsupported tablebase material")?;
+
+        let layout = Layout::new(geometry, &material);
+        let len = u32::from_le_bytes(read_array(&mut cursor)?) as usize;
+        if len != layout.len() {
+            return Err("Tablebase size doesn't match its material");
+        }
+        let mut values = Vec::with_capacity(len);
+        for _ in 0..len {
+            values.push(u16::from_le_bytes(read_array(&mut cursor)?));
+        }
+        Ok(Tablebase {
+            layout,
+            material,
+            values,
+        })
+    }
+}
+
+fn read_array<const N: usize>(cursor: &mut Cursor<&[u8]>) -> Result<[u8; N], &'static str> {
+    let mut buf = [0u8; N];
+    cursor
+        .read_exact(&mut buf)
+        .map_err(|_| "Unexpected end of tablebase")?;
+    Ok(buf)
+}
+
+type Pieces = ArrayVec<(PieceType, Square), MAX_PIECES>;
+
+/// The parts of a game a probe needs, gathered once for all loaded tables.
+struct Snapshot {
+    turn: PlayerColor,
+    white: Pieces,
+    black: Pieces,
+    bounds: Bounds,
+}
+
+#[derive(Clone, Copy)]
+struct Bounds {
+    min_x: i64,
+    max_x: i64,
+    min_y: i64,
+    max_y: i64,
+}
+
+impl Snapshot {
+    fn of(game: &GameState) -> Option<Snapshot> {
+        let pieces = game.white_piece_count as usize + game.black_piece_count as usize;
+        let rules = &game.game_rules;
+        let bounds = &rules.world_bounds;
+        if pieces > MAX_PIECES
+            // Neutral pieces are on the board but in neither count.
+            || game.board.len() != pieces
+            || bounds.region.is_some()
+            || rules.white_win_condition != WinCondition::Checkmate
+            || rules.black_win_condition != WinCondition::Checkmate
+            || game
+                .special_rights
+                .iter()
+                .any(|c| game.board.get_piece(c.x, c.y).is_some())
+        {
+            return None;
+        }
+
+        let collect = |is_white: bool| -> Pieces {
+            game.board
+                .iter_pieces_by_color(is_white)
+                .map(|(x, y, piece)| (piece.piece_type(), (x, y)))
+                .collect()
+        };
+        Some(Snapshot {
+            turn: game.turn,
+            white: collect(true),
+            black: collect(false),
+            bounds: Bounds {
+                min_x: bounds.min_x,
+                max_x: bounds.max_x,
+                min_y: bounds.min_y,
+                max_y: bounds.max_y,
+            },
+        })
+    }
+}
+
+/// A snapshot of the loaded tables, taken with [`loaded`] when a search starts so the
+/// search probes them without touching the registry lock.
+#[derive(Clone, Default)]
+pub struct Tables(Option<Arc<[Arc<Tablebase>]>>);
+
+impl Tables {
+    /// The first table's result for `game`, if any table covers it.
+    #[inline]
+    pub fn probe(&self, game: &GameState) -> Option<TbValue> {
+        let tables = self.0.as_deref()?;
+        let snapshot = Snapshot::of(game)?;
+        tables
+            .iter()
+            .find_map(|table| table.probe_snapshot(&snapshot))
+    }
+}
+
+static TABLES: RwLock<Tables> = RwLock::new(Tables(None));
+
+/// Makes `table` available to searches started from now on, replacing any loaded table
+/// of the same material and geometry.
+pub fn register(table: Tablebase) {
+    let mut tables = TABLES.write().unwrap_or_else(PoisonError::into_inner);
+    let mut kept: Vec<Arc<Tablebase>> = tables
+        .0
+        .iter()
+        .flat_map(|loaded| loaded.iter())
+        .filter(|t| !(t.geometry() == table.geometry() && t.material == table.material))
+        .cloned()
+        .collect();
+    kept.push(Arc::new(table));
+    *tables = Tables(Some(kept.into()));
+}
+
+/// Unloads every table.
+pub fn clear() {
+    *TABLES.write().unwrap_or_else(PoisonError::into_inner) = Tables(None);
+}
+
+/// The currently loaded tables.
+pub fn loaded() -> Tables {
+    TABLES
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solve(geometry: Geometry, name: &str) -> Vec<Tablebase> {
+        generate(geometry, &Material::parse(name).unwrap()).unwrap()
+    }
+
+    fn probe_any(tables: &[Tablebase], game: &GameState) -> Option<TbValue> {
+        tables.iter().find_map(|t| t.probe(game))
+    }
+
+    /// The ICN of table position `index`, with the attacker as white.
+    fn icn_at(table: &Tablebase, index: usize, prefix: &str) -> Option<String> {
+        let pos = table.layout.decode(index).filter(Position::is_legal)?;
+        let pieces: Vec<String> = pos
+            .units
+            .iter()
+            .map(|u| {
+                let code = u.piece.to_site_code();
+                let code = match u.side {
+                    Side::Attacker => code.to_string(),
+                    Side::Defender => code.to_lowercase(),
+                };
+                format!("{}{},{}", code, u.sq.0 + 1, u.sq.1 + 1)
+            })
+            .collect();
+        let turn = if pos.stm == Side::Attacker { "w" } else { "b" };
+        Some(format!("{} {}{}", turn, prefix, pieces.join("|")))
+    }
+
+    /// Checks a table value against the engine's own move generation.
+    fn assert_consistent(tables: &[Tablebase], icn: &str) {
+        let mut game = GameState::new();
+        game.setup_position_from_icn(icn);
+        let value = probe_any(tables, &game);
+
+        let mut children = Vec::new();
+        for m in game.get_legal_moves().iter() {
+            let undo = game.make_move(m);
+            if !game.is_move_illegal() {
+                children.push(probe_any(tables, &game));
+            }
+            game.undo_move(m, undo);
+        }
+        let fastest_loss = children
+            .iter()
+            .filter_map(|v| match v {
+                Some(TbValue::Loss(n)) => Some(*n),
+                _ => None,
+            })
+            .min();
+
+        match value {
+            Some(TbValue::Win(n)) => assert_eq!(fastest_loss, Some(n - 1), "{icn}"),
+            Some(TbValue::Loss(0)) => {
+                assert!(children.is_empty() && game.is_in_check(), "{icn}")
+            }
+            Some(TbValue::Loss(n)) => {
+                let slowest = children.iter().map(|v| match v {
+                    Some(TbValue::Win(m)) => Some(*m),
+                    _ => None,
+                });
+                assert_eq!(slowest.max(), Some(Some(n - 1)), "{icn}");
+                assert!(
+                    children.iter().all(|v| matches!(v, Some(TbValue::Win(_)))),
+                    "{icn}"
+                );
+            }
+            Some(TbValue::Draw) | None => assert_eq!(fastest_loss, None, "{icn}"),
+        }
+    }
+
+    #[test]
+    fn test_material_names_round_trip() {
+        let material = Material::parse("KRQvK").unwrap();
+        assert_eq!(material.attacker, vec![PieceType::Queen, PieceType::Rook]);
+        assert_eq!(material.name(), "KQRvK");
+        assert_eq!(Material::parse("KMvKN").unwrap().name(), "KMvKN");
+        assert!(Material::parse("KQK").is_none());
+        assert!(
+            generate(
+                Geometry::Window { radius: 3 },
+                &Material::parse("KPvK").unwrap()
+            )
+            .is_err()
+        );
+        assert!(
+            generate(
+                Geometry::Window { radius: 3 },
+                &Material::parse("KQRRvK").unwrap()
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_classic_chess_longest_mates() {
+        let chess = Geometry::Bounded {
+            width: 8,
+            height: 8,
+        };
+        // Mate in 10 with the queen and in 16 with the rook.
+        assert_eq!(solve(chess, "KQvK").last().unwrap().longest_win(), 19);
+        assert_eq!(solve(chess, "KRvK").last().unwrap().longest_win(), 31);
+    }
+
+    #[test]
+    fn test_bounded_table_agrees_with_engine_moves() {
+        let chess = Geometry::Bounded {
+            width: 8,
+            height: 8,
+        };
+        let tables = solve(chess, "KRvK");
+        let krk = tables.last().unwrap();
+
+        let mut game = GameState::new();
+        game.setup_position_from_icn("w 1,8,1,8 K2,6|R8,1|k1,8");
+        assert_eq!(krk.probe(&game), Some(TbValue::Win(1)));
+        game.setup_position_from_icn("b 1,8,1,8 K2,6|R8,8|k1,8");
+        assert_eq!(krk.probe(&game), Some(TbValue::Loss(0)));
+        // Mirrored colours probe the same table.
+        game.setup_position_from_icn("b 1,8,1,8 k2,6|r8,1|K1,8");
+        assert_eq!(krk.probe(&game), Some(TbValue::Win(1)));
+        // Not an 8x8 board.
+        game.setup_position_from_icn("w K2,6|R8,1|k1,8");
+        assert_eq!(krk.probe(&game), None);
+
+        for index in (0..krk.len()).step_by(997) {
+            if let Some(icn) = icn_at(krk, index, "1,8,1,8 ") {
+                assert_consistent(&tables, &icn);
+            }
+        }
+    }
+
+    #[test]
+    fn test_window_table_mates_on_the_open_board() {
+        let tables = solve(Geometry::Window { radius: 3 }, "KMvK");
+        let table = tables.last().unwrap();
+
+        let mut game = GameState::new();
+        game.setup_position_from_icn("b K0,-2|AM0,-1|k0,0");
+        assert_eq!(table.probe(&game), Some(TbValue::Loss(0)));
+        game.setup_position_from_icn("w K10,8|AM13,9|k10,10");
+        assert_eq!(table.probe(&game), Some(TbValue::Win(1)));
+        // The amazon is outside the window.
+        game.setup_position_from_icn("w K0,-2|AM9,-1|k0,0");
+        assert_eq!(table.probe(&game), None);
+
+        for index in (0..table.len()).step_by(7) {
+            if let Some(icn) = icn_at(table, index, "") {
+                assert_consistent(&tables, &icn);
+            }
+        }
+    }
+
+    #[test]
+    fn test_tables_round_trip_through_bytes() {
+        let tables = solve(Geometry::Window { radius: 2 }, "KQvKN");
+        let table = tables.last().unwrap();
+        let bytes = table.to_bytes();
+        let loaded = Tablebase::from_bytes(&bytes).unwrap();
+        assert_eq!(loaded.geometry(), table.geometry());
+        assert_eq!(loaded.material(), table.material());
+        assert_eq!(loaded.values, table.values);
+
+        assert!(Tablebase::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        let mut corrupt = bytes.clone();
+        corrupt[0] = b'X';
+        assert!(Tablebase::from_bytes(&corrupt).is_err());
+    }
+}
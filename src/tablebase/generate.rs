@@ -0,0 +1,204 @@
+//! Retrograde analysis.
+//!
+//! Every position is first scored from its own moves: captures are looked up in the
+//! already solved smaller tables, moves out of a window count as an escape, and the rest
+//! are counted. Mates seed the search, which then walks backwards one ply at a time: a
+//! lost position makes every quiet predecessor a win, and a position whose moves all
+//! lead to wins for the opponent (with no escape left) is lost.
+
+use super::{
+    DIST_MASK, Geometry, INVALID, LOSS, Layout, Material, Position, Successor, Tablebase, TbValue,
+    WIN, decode_value,
+};
+
+// Per-position state while solving, next to the still-unresolved value.
+/// The side to move has a move that doesn't lose: stalemate, an escape or a drawing
+/// capture.
+const CAN_DRAW: u16 = 0x8000;
+/// A winning move is known; the distance is queued in the low bits.
+const WIN_QUEUED: u16 = 0x4000;
+
+/// Largest table `generate` will allocate.
+const MAX_POSITIONS: usize = 1 << 28;
+
+/// Solves `material` on `geometry`, together with every ending its captures lead into.
+/// The requested table comes last, after the smaller ones it was built on.
+pub fn generate(geometry: Geometry, material: &Material) -> Result<Vec<Tablebase>, String> {
+    material.check()?;
+    match geometry {
+        Geometry::Bounded { width, height } if width == 0 || height == 0 => {
+            return Err("Bounded tables need a non-empty board".to_string());
+        }
+        Geometry::Window { radius: 0 } => {
+            return Err("Window tables need a radius of at least 1".to_string());
+        }
+        _ => {}
+    }
+    let mut tables = Vec::new();
+    build(geometry, material, &mut tables)?;
+    Ok(tables)
+}
+
+fn build(
+    geometry: Geometry,
+    material: &Material,
+    tables: &mut Vec<Tablebase>,
+) -> Result<(), String> {
+    if tables.iter().any(|t| &t.material == material) {
+        return Ok(());
+    }
+    for sub in material.captures() {
+        build(geometry, &sub, tables)?;
+    }
+    let layout = Layout::new(geometry, material);
+    let positions = geometry
+        .squares()
+        .checked_pow((layout.kinds.len() - layout.first_slot()) as u32)
+        .and_then(|n| n.checked_mul(2))
+        .filter(|&n| n <= MAX_POSITIONS)
+        .ok_or_else(|| format!("{} is too large to generate", material.name()))?;
+    debug_assert_eq!(positions, layout.len());
+
+    let table = Solver::new(layout, tables).solve(material.clone());
+    tables.push(table);
+    Ok(())
+}
+
+struct Solver<'a> {
+    layout: Layout,
+    subtables: &'a [Tablebase],
+    values: Vec<u16>,
+    state: Vec<u16>,
+    remaining: Vec<u8>,
+    /// Positions to resolve, by distance in plies. Odd distances are wins for the side
+    /// to move, even ones losses.
+    queue: Vec<Vec<u32>>,
+}
+
+impl<'a> Solver<'a> {
+    fn new(layout: Layout, subtables: &'a [Tablebase]) -> Self {
+        let len = layout.len();
+        Solver {
+            layout,
+            subtables,
+            values: vec![0; len],
+            state: vec![0; len],
+            remaining: vec![0; len],
+            queue: Vec::new(),
+        }
+    }
+
+    fn solve(mut self, material: Material) -> Tablebase {
+        for index in 0..self.values.len() {
+            self.init(index);
+        }
+        let mut plies = 0;
+        while plies < self.queue.len() {
+            for index in std::mem::take(&mut self.queue[plies]) {
+                self.resolve(index as usize, plies as u16);
+            }
+            plies += 1;
+        }
+        Tablebase {
+            layout: self.layout,
+            material,
+            values: self.values,
+        }
+    }
+
+    fn capture_value(&self, pos: &Position) -> Option<TbValue> {
+        let table = self.subtables.iter().find(|t| t.layout.matches(pos))?;
+        decode_value(table.values[table.layout.index(pos)])
+    }
+
+    fn init(&mut self, index: usize) {
+        let Some(pos) = self.layout.decode(index).filter(Position::is_legal) else {
+            self.values[index] = INVALID;
+            return;
+        };
+
+        let mut any_move = false;
+        let mut can_draw = false;
+        let mut quiet = 0u32;
+        let mut best_win: Option<u16> = None;
+        let mut longest_loss = 0u16;
+        pos.successors(self.layout.geometry, |succ| {
+            any_move = true;
+            match succ {
+                Successor::Quiet => quiet += 1,
+                Successor::Outside => can_draw = true,
+                Successor::Capture(next) => match self.capture_value(&next) {
+                    Some(TbValue::Loss(plies)) => {
+                        best_win = Some(best_win.map_or(plies + 1, |w| w.min(plies + 1)));
+                    }
+                    Some(TbValue::Win(plies)) => longest_loss = longest_loss.max(plies + 1),
+                    // Unresolved in a window table: nothing is known past the capture.
+                    _ => can_draw = true,
+                },
+            }
+        });
+
+        if !any_move {
+            if pos.in_check(pos.stm) {
+                push(&mut self.queue, 0, index);
+            } else {
+                self.state[index] = CAN_DRAW;
+            }
+            return;
+        }
+
+        self.remaining[index] = u8::try_from(quiet).expect("at most 255 quiet moves");
+        if let Some(plies) = best_win {
+            self.state[index] = WIN_QUEUED | plies;
+            push(&mut self.queue, plies, index);
+        } else {
+            self.state[index] = if can_draw { CAN_DRAW } else { 0 } | longest_loss;
+            if quiet == 0 && !can_draw {
+                push(&mut self.queue, longest_loss, index);
+            }
+        }
+    }
+
+    fn resolve(&mut self, index: usize, plies: u16) {
+        if self.values[index] != 0 {
+            return;
+        }
+        let won = plies % 2 == 1;
+        self.values[index] = if won { WIN } else { LOSS } | plies;
+
+        let pos = self
+            .layout
+            .decode(index)
+            .expect("queued positions are valid");
+        pos.predecessors(self.layout.geometry, |prev| {
+            let q = self.layout.index(&prev);
+            if self.values[q] != 0 {
+                return;
+            }
+            let state = self.state[q];
+            if won {
+                // One more of q's moves is known to lose.
+                if state & WIN_QUEUED != 0 {
+                    return;
+                }
+                self.remaining[q] -= 1;
+                if self.remaining[q] == 0 && state & CAN_DRAW == 0 {
+                    let plies = (plies + 1).max(state & DIST_MASK);
+                    push(&mut self.queue, plies, q);
+                }
+            } else if state & WIN_QUEUED == 0 || state & DIST_MASK > plies + 1 {
+                self.state[q] = WIN_QUEUED | (plies + 1);
+                push(&mut self.queue, plies + 1, q);
+            }
+        });
+    }
+}
+
+fn push(queue: &mut Vec<Vec<u32>>, plies: u16, index: usize) {
+    debug_assert!(plies <= DIST_MASK);
+    let plies = plies as usize;
+    if queue.len() <= plies {
+        queue.resize_with(plies + 1, Vec::new);
+    }
+    queue[plies].push(index as u32);
+}
@@ -0,0 +1,1450 @@
+//! Syzygy tablebases for the `Chess` variant.
+//!
+//! Positions that [`EvalKind::Chess`] recognises are ordinary 8x8 chess, so the standard
+//! `.rtbw` (win/draw/loss) and `.rtbz` (distance to zeroing) files apply to them once
+//! castling is gone and both sides play for checkmate. The decoder follows the format's
+//! reference prober (as found in Stockfish's `tbprobe.cpp`): pieces are mapped to an index
+//! through the same symmetry reductions, and values are read back from the Huffman-coded,
+//! pair-compressed blocks. Files are read into memory the first time a position needs
+//! them.
+//!
+//! Tables leave out positions with en passant rights and store "don't care" values where
+//! a capture decides the game, so every probe first plays out the captures, as the
+//! reference does.
+
+use crate::board::{PieceType, PlayerColor};
+use crate::evaluation::eval_kind::EvalKind;
+use crate::game::{GameState, WinCondition};
+use crate::moves::{Move, MoveList};
+use arrayvec::ArrayVec;
+use rustc_hash::FxHashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, OnceLock, PoisonError, RwLock};
+
+/// Most pieces a Syzygy table can hold, kings included.
+pub const MAX_SYZYGY_PIECES: usize = 7;
+
+const WDL_MAGIC: [u8; 4] = [0x71, 0xE8, 0x23, 0x5D];
+const DTZ_MAGIC: [u8; 4] = [0xD7, 0x66, 0x0C, 0xA5];
+
+// Table header flags.
+const SPLIT: u8 = 1;
+const HAS_PAWNS: u8 = 2;
+
+// Per-table flags.
+const STM: u8 = 1;
+const MAPPED: u8 = 2;
+const WIN_PLIES: u8 = 4;
+const LOSS_PLIES: u8 = 8;
+const WIDE: u8 = 16;
+const SINGLE_VALUE: u8 = 128;
+
+/// Rank spread used to order root moves by distance to zeroing.
+const MAX_DTZ: i32 = 1 << 18;
+
+/// Result for the side to move. The cursed and blessed results are wins and losses that
+/// the 50-move rule turns into draws.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Wdl {
+    Loss = -2,
+    BlessedLoss = -1,
+    Draw = 0,
+    CursedWin = 1,
+    Win = 2,
+}
+
+impl Wdl {
+    fn from_score(score: i32) -> Wdl {
+        match score {
+            ..=-2 => Wdl::Loss,
+            -1 => Wdl::BlessedLoss,
+            0 => Wdl::Draw,
+            1 => Wdl::CursedWin,
+            _ => Wdl::Win,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Kind {
+    Wdl,
+    Dtz,
+}
+
+impl Kind {
+    fn extension(self) -> &'static str {
+        match self {
+            Kind::Wdl => "rtbw",
+            Kind::Dtz => "rtbz",
+        }
+    }
+
+    fn magic(self) -> [u8; 4] {
+        match self {
+            Kind::Wdl => WDL_MAGIC,
+            Kind::Dtz => DTZ_MAGIC,
+        }
+    }
+}
+
+/// Lookup tables shared by every index computation.
+struct Maps {
+    /// Squares a2-h7 to 0..47, counting down from the edge files and the lower ranks.
+    pawns: [u64; 64],
+    /// Squares below the a1-h8 diagonal to 0..27.
+    b1h1h7: [u64; 64],
+    /// The a1-d1-d4 triangle to 0..9, diagonal squares last.
+    a1d1d4: [u64; 64],
+    /// Both kings, the first inside the triangle: 462 placements.
+    kk: [[u64; 64]; 10],
+    binomial: [[u64; 64]; MAX_SYZYGY_PIECES],
+    lead_pawn_idx: [[u64; 64]; 6],
+    lead_pawns_size: [[u64; 4]; 6],
+}
+
+static MAPS: LazyLock<Box<Maps>> = LazyLock::new(Maps::new);
+
+fn file_of(sq: u8) -> u8 {
+    sq & 7
+}
+
+fn rank_of(sq: u8) -> u8 {
+    sq >> 3
+}
+
+/// Signed distance from the a1-h8 diagonal; negative below it.
+fn off_diagonal(sq: u8) -> i32 {
+    rank_of(sq) as i32 - file_of(sq) as i32
+}
+
+impl Maps {
+    fn new() -> Box<Maps> {
+        let mut maps = Box::new(Maps {
+            pawns: [0; 64],
+            b1h1h7: [0; 64],
+            a1d1d4: [0; 64],
+            kk: [[0; 64]; 10],
+            binomial: [[0; 64]; MAX_SYZYGY_PIECES],
+            lead_pawn_idx: [[0; 64]; 6],
+            lead_pawns_size: [[0; 4]; 6],
+        });
+
+        let mut code = 0;
+        for sq in 0..64u8 {
+            if off_diagonal(sq) < 0 {
+                maps.b1h1h7[sq as usize] = code;
+                code += 1;
+            }
+        }
+
+        let mut diagonal = Vec::new();
+        code = 0;
+        for sq in 0..=27u8 {
+            if off_diagonal(sq) < 0 && file_of(sq) <= 3 {
+                maps.a1d1d4[sq as usize] = code;
+                code += 1;
+            } else if off_diagonal(sq) == 0 && file_of(sq) <= 3 {
+                diagonal.push(sq);
+            }
+        }
+        for sq in diagonal {
+            maps.a1d1d4[sq as usize] = code;
+            code += 1;
+        }
+
+        // A king on the diagonal keeps the other on or below it; placements with both
+        // on the diagonal come last.
+        let mut both_on_diagonal = Vec::new();
+        code = 0;
+        for idx in 0..10 {
+            for s1 in 0..=27u8 {
+                if maps.a1d1d4[s1 as usize] != idx || (idx == 0 && s1 != 1) {
+                    continue;
+                }
+                for s2 in 0..64u8 {
+                    let touching = (file_of(s1) as i32 - file_of(s2) as i32).abs() <= 1
+                        && (rank_of(s1) as i32 - rank_of(s2) as i32).abs() <= 1;
+                    if touching || (off_diagonal(s1) == 0 && off_diagonal(s2) > 0) {
+                        continue;
+                    }
+                    if off_diagonal(s1) == 0 && off_diagonal(s2) == 0 {
+                        both_on_diagonal.push((idx, s2));
+                    } else {
+                        maps.kk[idx as usize][s2 as usize] = code;
+                        code += 1;
+                    }
+                }
+            }
+        }
+        for (idx, s2) in both_on_diagonal {
+            maps.kk[idx as usize][s2 as usize] = code;
+            code += 1;
+        }
+
+        maps.binomial[0][0] = 1;
+        for n in 1..64 {
+            for k in 0..MAX_SYZYGY_PIECES.min(n + 1) {
+                maps.binomial[k][n] = if k > 0 {
+                    maps.binomial[k - 1][n - 1]
+                } else {
+                    0
+                } + if k < n { maps.binomial[k][n - 1] } else { 0 };
+            }
+        }
+
+        let mut available = 47;
+        for lead in 1..=5 {
+            for file in 0..4u8 {
+                let mut idx = 0;
+                for rank in 1..=6u8 {
+                    let sq = rank * 8 + file;
+                    if lead == 1 {
+                        maps.pawns[sq as usize] = available;
+                        maps.pawns[(sq ^ 7) as usize] = available - 1;
+                        available -= 2;
+                    }
+                    maps.lead_pawn_idx[lead][sq as usize] = idx;
+                    idx += maps.binomial[lead - 1][maps.pawns[sq as usize] as usize];
+                }
+                maps.lead_pawns_size[lead][file as usize] = idx;
+            }
+        }
+        maps
+    }
+}
+
+fn read<const N: usize>(data: &[u8], at: usize) -> Option<[u8; N]> {
+    data.get(at..at.checked_add(N)?)?.try_into().ok()
+}
+
+fn le16(data: &[u8], at: usize) -> Option<u16> {
+    read(data, at).map(u16::from_le_bytes)
+}
+
+fn le32(data: &[u8], at: usize) -> Option<u32> {
+    read(data, at).map(u32::from_le_bytes)
+}
+
+/// Big-endian word of a compressed block. The decoder may refill past the last block,
+/// so missing bytes read as zero.
+fn block_word(data: &[u8], at: usize) -> u32 {
+    let mut word = [0; 4];
+    for (i, b) in word.iter_mut().enumerate() {
+        *b = data.get(at + i).copied().unwrap_or(0);
+    }
+    u32::from_be_bytes(word)
+}
+
+/// Material of a table, derived from its name.
+#[derive(Clone, Debug)]
+struct Info {
+    piece_count: usize,
+    has_pawns: bool,
+    has_unique: bool,
+    /// Pawns of the leading colour, then of the other.
+    pawn_count: [u8; 2],
+    symmetric: bool,
+}
+
+impl Info {
+    fn of(name: &str) -> Option<Info> {
+        let (white, black) = name.split_once('v')?;
+        let count = |side: &str, c: char| side.chars().filter(|&p| p == c).count();
+        let valid =
+            |side: &str| side.starts_with('K') && side.chars().all(|c| "KQRBNP".contains(c));
+        if !valid(white) || !valid(black) {
+            return None;
+        }
+        let unique = |side: &str| "QRBNP".chars().any(|c| count(side, c) == 1);
+        let (wp, bp) = (count(white, 'P') as u8, count(black, 'P') as u8);
+        let white_leads = bp == 0 || (wp > 0 && bp >= wp);
+        Some(Info {
+            piece_count: white.len() + black.len(),
+            has_pawns: wp + bp > 0,
+            has_unique: unique(white) || unique(black),
+            pawn_count: if white_leads { [wp, bp] } else { [bp, wp] },
+            symmetric: white == black,
+        })
+    }
+}
+
+/// Decoding state for one side and leading file of a table.
+#[derive(Clone, Debug, Default)]
+struct Pairs {
+    flags: u8,
+    pieces: [u8; MAX_SYZYGY_PIECES],
+    group_len: [usize; MAX_SYZYGY_PIECES + 1],
+    group_idx: [u64; MAX_SYZYGY_PIECES + 1],
+    block_size: u64,
+    span: u64,
+    sparse_index_size: u64,
+    blocks_num: u64,
+    block_length_size: u64,
+    min_sym_len: u8,
+    lowest_sym: usize,
+    base64: Vec<u64>,
+    symlen: Vec<u8>,
+    btree: usize,
+    sparse_index: usize,
+    block_length: usize,
+    data: usize,
+    map_idx: [u16; 4],
+}
+
+impl Pairs {
+    /// Splits `pieces` into groups and sizes each one. `order` says where the leading
+    /// group and, with pawns on both sides, the other side's pawns come in the index.
+    fn set_groups(&mut self, info: &Info, order: [u8; 2], file: usize) {
+        let maps = &*MAPS;
+        let mut first_len: i32 = if info.has_pawns {
+            0
+        } else if info.has_unique {
+            3
+        } else {
+            2
+        };
+        let mut n = 0;
+        self.group_len[0] = 1;
+        for i in 1..info.piece_count {
+            first_len -= 1;
+            if first_len > 0 || self.pieces[i] == self.pieces[i - 1] {
+                self.group_len[n] += 1;
+            } else {
+                n += 1;
+                self.group_len[n] = 1;
+            }
+        }
+        n += 1;
+        self.group_len[n] = 0;
+
+        let both_pawns = info.has_pawns && info.pawn_count[1] > 0;
+        let mut next = if both_pawns { 2 } else { 1 };
+        let mut free = 64 - self.group_len[0] - if both_pawns { self.group_len[1] } else { 0 };
+        let mut idx: u64 = 1;
+        let mut k = 0u8;
+        while next < n || k == order[0] || k == order[1] {
+            if k == order[0] {
+                self.group_idx[0] = idx;
+                idx *= if info.has_pawns {
+                    maps.lead_pawns_size[self.group_len[0]][file]
+                } else if info.has_unique {
+                    31332
+                } else {
+                    462
+                };
+            } else if k == order[1] {
+                self.group_idx[1] = idx;
+                idx *= maps.binomial[self.group_len[1]][48 - self.group_len[0]];
+            } else {
+                self.group_idx[next] = idx;
+                idx *= maps.binomial[self.group_len[next]][free];
+                free -= self.group_len[next];
+                next += 1;
+            }
+            k += 1;
+        }
+        self.group_idx[n] = idx;
+    }
+
+    fn table_size(&self) -> u64 {
+        let n = self.group_len.iter().position(|&len| len == 0).unwrap_or(0);
+        self.group_idx[n]
+    }
+
+    /// Reads the compression header at `at`, returning where the next one starts.
+    fn set_sizes(&mut self, data: &[u8], mut at: usize) -> Option<usize> {
+        self.flags = *data.get(at)?;
+        at += 1;
+        if self.flags & SINGLE_VALUE != 0 {
+            // The single value is kept in min_sym_len.
+            self.min_sym_len = *data.get(at)?;
+            return Some(at + 1);
+        }
+
+        let [block, span, padding] = read::<3>(data, at)?;
+        if block >= 32 || span >= 32 {
+            return None;
+        }
+        self.block_size = 1 << block;
+        self.span = 1 << span;
+        self.sparse_index_size = self.table_size().div_ceil(self.span);
+        self.blocks_num = le32(data, at + 3)? as u64;
+        self.block_length_size = self.blocks_num + padding as u64;
+        let [max_sym_len, min_sym_len] = read::<2>(data, at + 7)?;
+        if min_sym_len == 0 || max_sym_len < min_sym_len || max_sym_len > 32 {
+            return None;
+        }
+        self.min_sym_len = min_sym_len;
+        at += 9;
+        self.lowest_sym = at;
+
+        // Canonical Huffman: longer codes have lower values, so base64[i] is the lowest
+        // 64-bit left-aligned code of length min_sym_len + i.
+        let lengths = (max_sym_len - min_sym_len) as usize + 1;
+        self.base64 = vec![0; lengths];
+        for i in (0..lengths - 1).rev() {
+            let lowest = le16(data, self.lowest_sym + 2 * i)? as u64;
+            let next = le16(data, self.lowest_sym + 2 * (i + 1))? as u64;
+            self.base64[i] = (self.base64[i + 1] + lowest).checked_sub(next)? / 2;
+        }
+        for (i, base) in self.base64.iter_mut().enumerate() {
+            *base <<= 64 - i - min_sym_len as usize;
+        }
+        at += 2 * lengths;
+
+        let symbols = le16(data, at)? as usize;
+        at += 2;
+        self.btree = at;
+        data.get(at..at + 3 * symbols)?;
+        self.symlen = vec![0; symbols];
+        let mut visited = vec![false; symbols];
+        for sym in 0..symbols {
+            if !visited[sym] {
+                self.symlen[sym] = self.set_symlen(data, sym, &mut visited)?;
+            }
+        }
+        Some(at + 3 * symbols + (symbols & 1))
+    }
+
+    /// Number of values symbol `sym` expands to, minus one.
+    fn set_symlen(&mut self, data: &[u8], sym: usize, visited: &mut [bool]) -> Option<u8> {
+        visited[sym] = true;
+        let right = self.right(data, sym) as usize;
+        if right == 0xFFF {
+            return Some(0);
+        }
+        let left = self.left(data, sym) as usize;
+        for child in [left, right] {
+            if !*visited.get(child)? {
+                self.symlen[child] = self.set_symlen(data, child, visited)?;
+            }
+        }
+        self.symlen[left]
+            .checked_add(self.symlen[right])?
+            .checked_add(1)
+    }
+
+    fn left(&self, data: &[u8], sym: usize) -> u16 {
+        let at = self.btree + 3 * sym;
+        ((data[at + 1] as u16 & 0xF) << 8) | data[at] as u16
+    }
+
+    fn right(&self, data: &[u8], sym: usize) -> u16 {
+        let at = self.btree + 3 * sym;
+        ((data[at + 2] as u16) << 4) | (data[at + 1] as u16 >> 4)
+    }
+
+    /// The value stored at `idx`.
+    fn decompress(&self, data: &[u8], idx: u64) -> Option<u16> {
+        if self.flags & SINGLE_VALUE != 0 {
+            return Some(self.min_sym_len as u16);
+        }
+        if idx >= self.table_size() {
+            return None;
+        }
+
+        // Sparse entry k points at the value k * span + span / 2.
+        let k = (idx / self.span) as usize;
+        let entry = self.sparse_index + 6 * k;
+        let mut block = le32(data, entry)? as usize;
+        let mut offset = le16(data, entry + 4)? as i64;
+        offset += (idx % self.span) as i64 - (self.span / 2) as i64;
+
+        let block_length = |b: usize| -> Option<i64> {
+            (b < self.block_length_size as usize)
+                .then(|| le16(data, self.block_length + 2 * b))?
+                .map(i64::from)
+        };
+        while offset < 0 {
+            block = block.checked_sub(1)?;
+            offset += block_length(block)? + 1;
+        }
+        while offset > block_length(block)? {
+            offset -= block_length(block)? + 1;
+            block += 1;
+        }
+
+        let mut at = self.data + block * self.block_size as usize;
+        let mut buf = ((block_word(data, at) as u64) << 32) | block_word(data, at + 4) as u64;
+        at += 8;
+        let mut buf_size = 64;
+        let min_len = self.min_sym_len as usize;
+        let mut sym;
+        loop {
+            let mut len = 0;
+            while buf < self.base64[len] {
+                len += 1;
+                if len == self.base64.len() {
+                    return None;
+                }
+            }
+            sym = ((buf - self.base64[len]) >> (64 - len - min_len)) as usize;
+            sym += le16(data, self.lowest_sym + 2 * len)? as usize;
+            let expands = *self.symlen.get(sym)? as i64 + 1;
+            if offset < expands {
+                break;
+            }
+            offset -= expands;
+            len += min_len;
+            buf <<= len;
+            buf_size -= len;
+            if buf_size <= 32 {
+                buf_size += 32;
+                buf |= (block_word(data, at) as u64) << (64 - buf_size);
+                at += 4;
+            }
+        }
+
+        // Pairs expand into adjacent values: walk down to the leaf holding ours.
+        while self.symlen[sym] != 0 {
+            let left = self.left(data, sym) as usize;
+            let left_len = *self.symlen.get(left)? as i64 + 1;
+            if offset < left_len {
+                sym = left;
+            } else {
+                offset -= left_len;
+                sym = self.right(data, sym) as usize;
+            }
+            self.symlen.get(sym)?;
+        }
+        Some(self.left(data, sym))
+    }
+}
+
+/// Where a position lives in a table.
+enum Located {
+    Index {
+        pairs: usize,
+        file: usize,
+        idx: u64,
+    },
+    /// DTZ tables only store one side to move.
+    ChangeStm,
+}
+
+/// One loaded `.rtbw` or `.rtbz` file.
+struct Table {
+    data: Vec<u8>,
+    kind: Kind,
+    info: Info,
+    sides: usize,
+    items: Vec<Pairs>,
+    /// Start of the DTZ value maps.
+    map: usize,
+}
+
+impl Table {
+    fn parse(data: Vec<u8>, kind: Kind, info: Info) -> Result<Table, &'static str> {
+        const CORRUPT: &str = "Corrupt Syzygy table";
+        if data.get(..4) != Some(&kind.magic()[..]) {
+            return Err("Not a Syzygy table");
+        }
+        if info.piece_count > MAX_SYZYGY_PIECES {
+            return Err("Too many pieces for a Syzygy table");
+        }
+        let flags = *data.get(4).ok_or(CORRUPT)?;
+        if (flags & HAS_PAWNS != 0) != info.has_pawns || (flags & SPLIT != 0) == info.symmetric {
+            return Err(CORRUPT);
+        }
+        let sides = if kind == Kind::Wdl && !info.symmetric {
+            2
+        } else {
+            1
+        };
+        let files = if info.has_pawns { 4 } else { 1 };
+        let mut table = Table {
+            data: Vec::new(),
+            kind,
+            info,
+            sides,
+            items: vec![Pairs::default(); sides * files],
+            map: 0,
+        };
+        table.read_layout(&data, files).ok_or(CORRUPT)?;
+        table.data = data;
+        Ok(table)
+    }
+
+    fn read_layout(&mut self, data: &[u8], files: usize) -> Option<()> {
+        let both_pawns = self.info.has_pawns && self.info.pawn_count[1] > 0;
+        let sides = self.sides;
+        let mut at = 5;
+        for file in 0..files {
+            let first = *data.get(at)?;
+            let second = if both_pawns { *data.get(at + 1)? } else { 0xFF };
+            let order = [[first & 0xF, second & 0xF], [first >> 4, second >> 4]];
+            at += 1 + both_pawns as usize;
+            for k in 0..self.info.piece_count {
+                let byte = *data.get(at)?;
+                for side in 0..sides {
+                    let piece = if side == 1 { byte >> 4 } else { byte & 0xF };
+                    self.items[file * sides + side].pieces[k] = piece;
+                }
+                at += 1;
+            }
+            let info = self.info.clone();
+            for (side, order) in order.into_iter().take(sides).enumerate() {
+                self.items[file * sides + side].set_groups(&info, order, file);
+            }
+        }
+        at += at & 1;
+
+        for item in &mut self.items {
+            at = item.set_sizes(data, at)?;
+        }
+        if self.kind == Kind::Dtz {
+            at = self.set_dtz_map(data, at)?;
+        }
+        for item in &mut self.items {
+            item.sparse_index = at;
+            at += 6 * item.sparse_index_size as usize;
+        }
+        for item in &mut self.items {
+            item.block_length = at;
+            at += 2 * item.block_length_size as usize;
+        }
+        for item in &mut self.items {
+            at = (at + 0x3F) & !0x3F;
+            item.data = at;
+            at += (item.blocks_num * item.block_size) as usize;
+        }
+        (at <= data.len()).then_some(())
+    }
+
+    fn set_dtz_map(&mut self, data: &[u8], mut at: usize) -> Option<usize> {
+        self.map = at;
+        for item in &mut self.items {
+            if item.flags & MAPPED == 0 {
+                continue;
+            }
+            if item.flags & WIDE != 0 {
+                at += at & 1;
+                for i in 0..4 {
+                    item.map_idx[i] = u16::try_from((at - self.map) / 2 + 1).ok()?;
+                    at += 2 * le16(data, at)? as usize + 2;
+                }
+            } else {
+                for i in 0..4 {
+                    item.map_idx[i] = u16::try_from(at - self.map + 1).ok()?;
+                    at += *data.get(at)? as usize + 1;
+                }
+            }
+        }
+        Some(at + (at & 1))
+    }
+
+    /// The index of `board`, with colours swapped when the table is named from black's
+    /// side.
+    fn locate(&self, board: &Board, black_stronger: bool) -> Option<Located> {
+        let maps = &*MAPS;
+        let flip = (self.info.symmetric && board.stm == 1) || black_stronger;
+        let flip_color = if flip { 8 } else { 0 };
+        let flip_squares = if flip { 56 } else { 0 };
+        let stm = flip as u8 ^ board.stm;
+
+        let mut squares = [0u8; MAX_SYZYGY_PIECES];
+        let mut pieces = [0u8; MAX_SYZYGY_PIECES];
+        let mut size = 0;
+        let mut lead_pawns = 0;
+        let mut lead_pawn = None;
+        let mut tb_file = 0;
+
+        if self.info.has_pawns {
+            let pawn = self.items[0].pieces[0] ^ flip_color;
+            lead_pawn = Some(pawn);
+            for &(sq, piece) in &board.pieces {
+                if piece == pawn {
+                    squares[size] = sq ^ flip_squares;
+                    size += 1;
+                }
+            }
+            lead_pawns = size;
+            let lead = (0..lead_pawns).max_by_key(|&i| maps.pawns[squares[i] as usize])?;
+            squares.swap(0, lead);
+            tb_file = file_of(squares[0]).min(7 - file_of(squares[0])) as usize;
+        }
+
+        if self.kind == Kind::Dtz {
+            let flags = self.items[tb_file * self.sides].flags;
+            let either = self.info.symmetric && !self.info.has_pawns;
+            if flags & STM != stm && !either {
+                return Some(Located::ChangeStm);
+            }
+        }
+
+        for &(sq, piece) in &board.pieces {
+            if Some(piece) != lead_pawn {
+                squares[size] = sq ^ flip_squares;
+                pieces[size] = piece ^ flip_color;
+                size += 1;
+            }
+        }
+        if size != self.info.piece_count {
+            return None;
+        }
+
+        let pairs = tb_file * self.sides + stm as usize % self.sides;
+        let d = &self.items[pairs];
+        for i in lead_pawns..size.saturating_sub(1) {
+            if let Some(j) = (i + 1..size).find(|&j| d.pieces[i] == pieces[j]) {
+                pieces.swap(i, j);
+                squares.swap(i, j);
+            }
+        }
+
+        if file_of(squares[0]) > 3 {
+            for sq in &mut squares[..size] {
+                *sq ^= 7;
+            }
+        }
+
+        let mut idx;
+        if self.info.has_pawns {
+            idx = maps.lead_pawn_idx[lead_pawns][squares[0] as usize];
+            squares[1..lead_pawns].sort_by_key(|&sq| maps.pawns[sq as usize]);
+            for (i, &sq) in squares[..lead_pawns].iter().enumerate().skip(1) {
+                idx += maps.binomial[i][maps.pawns[sq as usize] as usize];
+            }
+        } else {
+            if rank_of(squares[0]) > 3 {
+                for sq in &mut squares[..size] {
+                    *sq ^= 56;
+                }
+            }
+            for i in 0..d.group_len[0] {
+                let off = off_diagonal(squares[i]);
+                if off == 0 {
+                    continue;
+                }
+                if off > 0 {
+                    for sq in &mut squares[i..size] {
+                        *sq = ((*sq >> 3) | (*sq << 3)) & 63;
+                    }
+                }
+                break;
+            }
+
+            if self.info.has_unique {
+                let [s0, s1, s2] = [squares[0], squares[1], squares[2]].map(u64::from);
+                let adjust1 = (s1 > s0) as u64;
+                let adjust2 = (s2 > s0) as u64 + (s2 > s1) as u64;
+                let rank = |sq: u64| sq >> 3;
+                idx = if off_diagonal(squares[0]) != 0 {
+                    (maps.a1d1d4[s0 as usize] * 63 + (s1 - adjust1)) * 62 + s2 - adjust2
+                } else if off_diagonal(squares[1]) != 0 {
+                    (6 * 63 + rank(s0) * 28 + maps.b1h1h7[s1 as usize]) * 62 + s2 - adjust2
+                } else if off_diagonal(squares[2]) != 0 {
+                    6 * 63 * 62
+                        + 4 * 28 * 62
+                        + rank(s0) * 7 * 28
+                        + (rank(s1) - adjust1) * 28
+                        + maps.b1h1h7[s2 as usize]
+                } else {
+                    6 * 63 * 62
+                        + 4 * 28 * 62
+                        + 4 * 7 * 28
+                        + rank(s0) * 7 * 6
+                        + (rank(s1) - adjust1) * 6
+                        + (rank(s2) - adjust2)
+                };
+            } else {
+                idx = maps.kk[maps.a1d1d4[squares[0] as usize] as usize][squares[1] as usize];
+            }
+        }
+
+        idx *= d.group_idx[0];
+        let mut start = d.group_len[0];
+        let mut remaining_pawns = self.info.has_pawns && self.info.pawn_count[1] > 0;
+        let mut next = 1;
+        while d.group_len[next] != 0 {
+            let len = d.group_len[next];
+            squares[start..start + len].sort_unstable();
+            let mut n = 0;
+            for i in 0..len {
+                let sq = squares[start + i];
+                let adjust = squares[..start].iter().filter(|&&s| sq > s).count();
+                let free = (sq as usize).checked_sub(adjust + 8 * remaining_pawns as usize)?;
+                n += maps.binomial[i + 1][free];
+            }
+            remaining_pawns = false;
+            idx += n * d.group_idx[next];
+            start += len;
+            next += 1;
+        }
+        Some(Located::Index {
+            pairs,
+            file: tb_file,
+            idx,
+        })
+    }
+
+    /// The WDL score of `board`, or its DTZ in plies given the WDL score `wdl`. `None`
+    /// means a DTZ table stored the other side to move.
+    fn probe(&self, board: &Board, black_stronger: bool, wdl: i32) -> Option<Option<i32>> {
+        let Located::Index { pairs, file, idx } = self.locate(board, black_stronger)? else {
+            return Some(None);
+        };
+        let value = self.items[pairs].decompress(&self.data, idx)? as i32;
+        Some(Some(match self.kind {
+            Kind::Wdl => value - 2,
+            Kind::Dtz => self.dtz_plies(file, value, wdl)?,
+        }))
+    }
+
+    fn dtz_plies(&self, file: usize, value: i32, wdl: i32) -> Option<i32> {
+        const WDL_MAP: [usize; 5] = [1, 3, 0, 2, 0];
+        let item = &self.items[file * self.sides];
+        let flags = item.flags;
+        let mut value = value;
+        if flags & MAPPED != 0 {
+            let idx = item.map_idx[WDL_MAP[(wdl + 2) as usize]] as usize + value as usize;
+            value = if flags & WIDE != 0 {
+                le16(&self.data, self.map + 2 * idx)? as i32
+            } else {
+                *self.data.get(self.map + idx)? as i32
+            };
+        }
+        let in_moves = match wdl {
+            2 => flags & WIN_PLIES == 0,
+            -2 => flags & LOSS_PLIES == 0,
+            _ => true,
+        };
+        if in_moves {
+            value *= 2;
+        }
+        Some(value + 1)
+    }
+}
+
+/// A position as the tables see it: squares a1 = 0 to h8 = 63, pieces 1 (pawn) to 6
+/// (king) plus 8 for black, in square order.
+struct Board {
+    stm: u8,
+    pieces: ArrayVec<(u8, u8), MAX_SYZYGY_PIECES>,
+}
+
+impl Board {
+    /// `None` unless `game` is plain chess the tables cover.
+    fn of(game: &GameState) -> Option<Board> {
+        let rules = &game.game_rules;
+        if game.eval_kind != EvalKind::Chess
+            || rules.white_win_condition != WinCondition::Checkmate
+            || rules.black_win_condition != WinCondition::Checkmate
+            || game.board.len() > MAX_SYZYGY_PIECES
+        {
+            return None;
+        }
+        let mut pieces = ArrayVec::new();
+        for (x, y, piece) in game.board.iter() {
+            if !(1..=8).contains(&x) || !(1..=8).contains(&y) {
+                return None;
+            }
+            let code = match piece.piece_type() {
+                PieceType::Pawn => 1,
+                PieceType::Knight => 2,
+                PieceType::Bishop => 3,
+                PieceType::Rook => 4,
+                PieceType::Queen => 5,
+                PieceType::King => 6,
+                _ => return None,
+            };
+            let color = match piece.color() {
+                PlayerColor::White => 0,
+                PlayerColor::Black => 8,
+                PlayerColor::Neutral => return None,
+            };
+            // Castling rights; a pawn's double step is already part of the tables.
+            if matches!(piece.piece_type(), PieceType::King | PieceType::Rook)
+                && game.special_rights.iter().any(|c| c.x == x && c.y == y)
+            {
+                return None;
+            }
+            pieces.push(((y as u8 - 1) * 8 + x as u8 - 1, code | color));
+        }
+        pieces.sort_unstable();
+        Some(Board {
+            stm: (game.turn == PlayerColor::Black) as u8,
+            pieces,
+        })
+    }
+
+    /// One side's half of a table name, such as `KRP`.
+    fn side_name(&self, color: u8) -> String {
+        "KQRBNP"
+            .chars()
+            .zip([6, 5, 4, 3, 2, 1])
+            .flat_map(|(c, code)| {
+                let n = self.pieces.iter().filter(|p| p.1 == code | color).count();
+                std::iter::repeat_n(c, n)
+            })
+            .collect()
+    }
+}
+
+fn dtz_before_zeroing(wdl: i32) -> i32 {
+    match wdl {
+        2 => 1,
+        1 => 101,
+        -1 => -101,
+        -2 => -1,
+        _ => 0,
+    }
+}
+
+/// Makes `m`, runs `f` with whether it captured if it was legal, and takes it back.
+fn with_move<T>(
+    game: &mut GameState,
+    m: &Move,
+    f: impl FnOnce(&mut GameState, bool) -> T,
+) -> Option<T> {
+    let undo = game.make_move(m);
+    let result = if game.is_move_illegal() {
+        None
+    } else {
+        let capture = undo.captured_piece.is_some() || undo.ep_captured_piece.is_some();
+        Some(f(game, capture))
+    };
+    game.undo_move(m, undo);
+    result
+}
+
+fn is_pawn_move(m: &Move) -> bool {
+    m.piece.piece_type() == PieceType::Pawn
+}
+
+/// The Syzygy files in a set of directories. Each file is read the first time a probe
+/// needs it; after that, probes look it up without taking a lock.
+pub struct Syzygy {
+    files: FxHashMap<String, (PathBuf, OnceLock<Option<Table>>)>,
+    max_pieces: usize,
+}
+
+impl Syzygy {
+    /// Indexes the `.rtbw` and `.rtbz` files in `paths`, a list separated like `PATH`.
+    pub fn open(paths: &str) -> Syzygy {
+        let mut files = FxHashMap::default();
+        let mut max_pieces = 0;
+        for dir in std::env::split_paths(paths) {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                    continue;
+                };
+                let Some((stem, ext)) = name.split_once('.') else {
+                    continue;
+                };
+                let Some(info) = Info::of(stem) else {
+                    continue;
+                };
+                if ext == "rtbw" || ext == "rtbz" {
+                    if ext == "rtbw" {
+                        max_pieces = max_pieces.max(info.piece_count);
+                    }
+                    files
+                        .entry(name.to_string())
+                        .or_insert_with(|| (path, OnceLock::new()));
+                }
+            }
+        }
+        Syzygy {
+            files,
+            max_pieces: max_pieces.min(MAX_SYZYGY_PIECES),
+        }
+    }
+
+    /// Number of table files found.
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Most pieces of any WDL table found, kings included.
+    pub fn max_pieces(&self) -> usize {
+        self.max_pieces
+    }
+
+    fn load(&self, stem: &str, kind: Kind) -> Option<&Table> {
+        let (path, table) = self.files.get(&format!("{}.{}", stem, kind.extension()))?;
+        table.get_or_init(|| Self::read(path, stem, kind)).as_ref()
+    }
+
+    fn read(path: &Path, stem: &str, kind: Kind) -> Option<Table> {
+        let data = std::fs::read(path).ok()?;
+        Table::parse(data, kind, Info::of(stem)?).ok()
+    }
+
+    /// The table for `board`'s material, and whether it is named from black's side.
+    fn table(&self, board: &Board, kind: Kind) -> Option<(&Table, bool)> {
+        let white = board.side_name(0);
+        let black = board.side_name(8);
+        let stem = format!("{}v{}", white, black);
+        if self
+            .files
+            .contains_key(&format!("{}.{}", stem, kind.extension()))
+        {
+            return Some((self.load(&stem, kind)?, false));
+        }
+        Some((self.load(&format!("{}v{}", black, white), kind)?, true))
+    }
+
+    fn probe_table(&self, game: &GameState, kind: Kind, wdl: i32) -> Option<Option<i32>> {
+        let board = Board::of(game)?;
+        if board.pieces.len() == 2 {
+            return Some(Some(0));
+        }
+        let (table, black_stronger) = self.table(&board, kind)?;
+        table.probe(&board, black_stronger, wdl)
+    }
+
+    /// Resolves the captures (and pawn moves, for `zeroing`) before trusting the table.
+    /// Returns the score and whether the best move is a zeroing one.
+    fn search(&self, game: &mut GameState, zeroing: bool) -> Option<(i32, bool)> {
+        let mut best = -2;
+        let mut total = 0;
+        let mut searched = 0;
+        for m in game.get_legal_moves().iter() {
+            let Some(value) = with_move(game, m, |game, capture| {
+                if !(capture || zeroing && is_pawn_move(m)) {
+                    return Some(None);
+                }
+                self.search(game, false).map(|(v, _)| Some(-v))
+            }) else {
+                continue;
+            };
+            total += 1;
+            let Some(value) = value? else {
+                continue;
+            };
+            searched += 1;
+            if value > best {
+                best = value;
+                if value >= 2 {
+                    return Some((value, true));
+                }
+            }
+        }
+
+        let no_more_moves = searched > 0 && searched == total;
+        let value = if no_more_moves {
+            best
+        } else {
+            self.probe_table(game, Kind::Wdl, 0)??
+        };
+        if best >= value {
+            return Some((best, best > 0 || no_more_moves));
+        }
+        Some((value, false))
+    }
+
+    /// The result for the side to move in `game`, if the tables cover it.
+    pub fn probe_wdl(&self, game: &mut GameState) -> Option<Wdl> {
+        self.wdl(game).map(Wdl::from_score)
+    }
+
+    fn wdl(&self, game: &mut GameState) -> Option<i32> {
+        if game.board.len() > self.max_pieces {
+            return None;
+        }
+        Board::of(game)?;
+        self.search(game, false).map(|(wdl, _)| wdl)
+    }
+
+    /// Plies to the next capture, pawn move or mate: positive when the side to move
+    /// wins, negative when it loses, zero for a draw. Cursed wins and blessed losses are
+    /// offset by 100.
+    pub fn probe_dtz(&self, game: &mut GameState) -> Option<i32> {
+        if game.board.len() > self.max_pieces {
+            return None;
+        }
+        Board::of(game)?;
+        self.dtz(game)
+    }
+
+    fn dtz(&self, game: &mut GameState) -> Option<i32> {
+        let (wdl, zeroing_best) = self.search(game, true)?;
+        if wdl == 0 {
+            return Some(0);
+        }
+        if zeroing_best {
+            return Some(dtz_before_zeroing(wdl));
+        }
+        if let Some(dtz) = self.probe_table(game, Kind::Dtz, wdl)? {
+            let cursed = if wdl.abs() == 1 { 100 } else { 0 };
+            return Some((dtz + cursed) * wdl.signum());
+        }
+
+        // Only the other side to move is stored: look one ply ahead.
+        let mut min_dtz = 0xFFFF;
+        for m in game.get_legal_moves().iter() {
+            let Some(result) = with_move(game, m, |game, capture| {
+                let zeroing = capture || is_pawn_move(m);
+                let mut dtz = if zeroing {
+                    -dtz_before_zeroing(self.search(game, false)?.0)
+                } else {
+                    -self.dtz(game)?
+                };
+                let mates = dtz == 1 && game.is_in_check() && !game.has_legal_move();
+                if !zeroing {
+                    dtz += dtz.signum();
+                }
+                Some((dtz, mates))
+            }) else {
+                continue;
+            };
+            let (dtz, mates) = result?;
+            if mates {
+                min_dtz = 1;
+            }
+            if dtz < min_dtz && dtz.signum() == wdl.signum() {
+                min_dtz = dtz;
+            }
+        }
+        Some(if min_dtz == 0xFFFF { -1 } else { min_dtz })
+    }
+
+    /// Keeps only the root moves that preserve the best result, ranked by distance to
+    /// zeroing under the 50-move rule. Falls back to WDL ranks when a DTZ table is
+    /// missing. Returns whether any move was dropped.
+    pub fn filter_root_moves(&self, game: &mut GameState, moves: &mut MoveList) -> bool {
+        if game.board.len() > self.max_pieces || Board::of(game).is_none() || moves.is_empty() {
+            return false;
+        }
+        let Some(ranks) = self
+            .dtz_ranks(game, moves)
+            .or_else(|| self.wdl_ranks(game, moves))
+        else {
+            return false;
+        };
+        let best = ranks.iter().copied().max().unwrap_or(0);
+        if ranks.iter().all(|&r| r == best) {
+            return false;
+        }
+        let mut ranks = ranks.into_iter();
+        moves.retain(|_| ranks.next() == Some(best));
+        true
+    }
+
+    fn dtz_ranks(&self, game: &mut GameState, moves: &MoveList) -> Option<Vec<i32>> {
+        let cnt50 = game.halfmove_clock as i32;
+        moves
+            .iter()
+            .map(|m| {
+                let dtz = with_move(game, m, |game, capture| {
+                    let mut dtz = if capture || is_pawn_move(m) {
+                        dtz_before_zeroing(-self.search(game, false)?.0)
+                    } else {
+                        let dtz = -self.dtz(game)?;
+                        dtz + dtz.signum()
+                    };
+                    if dtz == 2 && game.is_in_check() && !game.has_legal_move() {
+                        dtz = 1;
+                    }
+                    Some(dtz)
+                })??;
+                Some(if dtz > 0 {
+                    if dtz + cnt50 <= 99 {
+                        MAX_DTZ - dtz
+                    } else {
+                        MAX_DTZ / 2 - (dtz + cnt50)
+                    }
+                } else if dtz < 0 {
+                    if -dtz * 2 + cnt50 < 100 {
+                        -MAX_DTZ - dtz
+                    } else {
+                        -MAX_DTZ / 2 + (-dtz + cnt50)
+                    }
+                } else {
+                    0
+                })
+            })
+            .collect()
+    }
+
+    fn wdl_ranks(&self, game: &mut GameState, moves: &MoveList) -> Option<Vec<i32>> {
+        moves
+            .iter()
+            .map(|m| with_move(game, m, |game, _| self.search(game, false).map(|(v, _)| -v))?)
+            .collect()
+    }
+}
+
+static TABLES: RwLock<Option<Arc<Syzygy>>> = RwLock::new(None);
+
+/// Loads the tables in `paths` for searches started from now on, replacing any earlier
+/// set. An empty path or `<empty>` unloads them. Returns the number of files found.
+pub fn set_path(paths: &str) -> usize {
+    let paths = paths.trim();
+    let tables = (!paths.is_empty() && paths != "<empty>")
+        .then(|| Syzygy::open(paths))
+        .filter(|t| !t.is_empty());
+    let count = tables.as_ref().map_or(0, Syzygy::len);
+    *TABLES.write().unwrap_or_else(PoisonError::into_inner) = tables.map(Arc::new);
+    count
+}
+
+/// The currently loaded tables, taken once when a search starts so the search probes
+/// them without touching the registry lock.
+pub fn loaded() -> Option<Arc<Syzygy>> {
+    TABLES
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tablebase::{Geometry, Material, Position, Side, TbValue, decode_value, generate};
+
+    /// A solved table position as a board, with the attacker as white.
+    fn board_of(pos: &Position) -> Board {
+        let mut pieces: ArrayVec<(u8, u8), MAX_SYZYGY_PIECES> = pos
+            .units
+            .iter()
+            .map(|u| {
+                let code = match u.piece {
+                    PieceType::Knight => 2,
+                    PieceType::Bishop => 3,
+                    PieceType::Rook => 4,
+                    PieceType::Queen => 5,
+                    _ => 6,
+                };
+                let color = if u.side == Side::Attacker { 0 } else { 8 };
+                ((u.sq.1 * 8 + u.sq.0) as u8, code | color)
+            })
+            .collect();
+        pieces.sort_unstable();
+        Board {
+            stm: (pos.stm == Side::Defender) as u8,
+            pieces,
+        }
+    }
+
+    /// The board as an ICN, colours swapped when `swap` is set.
+    fn icn(board: &Board, swap: bool) -> String {
+        let pieces: Vec<String> = board
+            .pieces
+            .iter()
+            .map(|&(sq, piece)| {
+                let letter = ['P', 'N', 'B', 'R', 'Q', 'K'][(piece & 7) as usize - 1];
+                let white = (piece & 8 == 0) != swap;
+                let letter = if white {
+                    letter
+                } else {
+                    letter.to_ascii_lowercase()
+                };
+                format!("{}{},{}", letter, file_of(sq) + 1, rank_of(sq) + 1)
+            })
+            .collect();
+        let turn = if (board.stm == 0) != swap { "w" } else { "b" };
+        format!("{} 1,8,1,8 {}", turn, pieces.join("|"))
+    }
+
+    /// Every legal position of a three-man `material` with its retrograde solution.
+    fn solve(material: &str) -> Vec<(Board, TbValue)> {
+        let chess = Geometry::Bounded {
+            width: 8,
+            height: 8,
+        };
+        let tables = generate(chess, &Material::parse(material).unwrap()).unwrap();
+        let table = tables.last().unwrap();
+        (0..table.len())
+            .filter_map(|index| {
+                let pos = table.layout.decode(index).filter(Position::is_legal)?;
+                Some((board_of(&pos), decode_value(table.values[index])?))
+            })
+            .collect()
+    }
+
+    /// The bundled KRvK and KQvK tables. They are written by
+    /// `scripts/syzygy_fixtures.py`, which shares no code with this module.
+    fn bundled() -> Syzygy {
+        Syzygy::open(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/syzygy"
+        ))
+    }
+
+    #[test]
+    fn test_index_maps() {
+        let maps = &*MAPS;
+        let kk = maps.kk.iter().flatten().max().unwrap();
+        assert_eq!(kk + 1, 462);
+        assert_eq!(maps.a1d1d4[1], 0);
+        assert_eq!(maps.a1d1d4[27], 9);
+        assert_eq!(maps.b1h1h7.iter().max(), Some(&27));
+        let mut pawns: Vec<u64> = (8..56).map(|sq| maps.pawns[sq]).collect();
+        pawns.sort_unstable();
+        assert_eq!(pawns, (0..48).collect::<Vec<_>>());
+        assert_eq!(maps.binomial[2][5], 10);
+        assert_eq!(maps.lead_pawns_size[1][0], 6);
+    }
+
+    #[test]
+    fn test_pawn_indices_are_canonical() {
+        let mut data = WDL_MAGIC.to_vec();
+        data.push(SPLIT | HAS_PAWNS);
+        for _ in 0..4 {
+            data.extend([0x00, 0x11, 0x66, 0xEE]);
+        }
+        data.push(0);
+        for _ in 0..8 {
+            data.extend([SINGLE_VALUE, 2]);
+        }
+        data.resize(data.len() + 64, 0);
+        let table = Table::parse(data, Kind::Wdl, Info::of("KPvK").unwrap()).unwrap();
+
+        let mut seen = FxHashMap::default();
+        for stm in 0..2 {
+            for pawn in 8..56u8 {
+                for wk in 0..64u8 {
+                    for bk in 0..64u8 {
+                        if wk == bk || pawn == wk || pawn == bk {
+                            continue;
+                        }
+                        let mut pieces = ArrayVec::new();
+                        pieces.extend([(pawn, 1), (wk, 6), (bk, 14)]);
+                        pieces.sort_unstable();
+                        let board = Board { stm, pieces };
+                        let Some(Located::Index { pairs, idx, .. }) = table.locate(&board, false)
+                        else {
+                            panic!("unindexed position");
+                        };
+                        assert!(idx < table.items[pairs].table_size());
+                        let mirror = (stm, pawn ^ 7, wk ^ 7, bk ^ 7);
+                        let first = *seen.entry((pairs, idx)).or_insert((stm, pawn, wk, bk));
+                        assert!(first == (stm, pawn, wk, bk) || first == mirror);
+                    }
+                }
+            }
+        }
+        assert_eq!(
+            table.probe(
+                &Board::of(&chess("w 1,8,1,8 K1,1|P2,2|k8,8")).unwrap(),
+                false,
+                0
+            ),
+            Some(Some(0))
+        );
+    }
+
+    /// Promotions and bounds that make a position plain chess.
+    const CHESS: &str = "(8;Q,R,B,N|1;q,r,b,n) 1,8,1,8 ";
+
+    fn chess(icn: &str) -> GameState {
+        let mut game = GameState::new();
+        game.setup_position_from_icn(&icn.replacen("1,8,1,8 ", CHESS, 1));
+        game
+    }
+
+    /// Probes every `step`th solved position, with both colourings, against `tables`.
+    fn assert_probes_match(tables: &Syzygy, solved: &[(Board, TbValue)], step: usize) {
+        for (board, value) in solved.iter().step_by(step) {
+            let (wdl, dtz) = match *value {
+                TbValue::Win(n) => (Wdl::Win, n as i32),
+                TbValue::Loss(0) => (Wdl::Loss, -1),
+                TbValue::Loss(n) => (Wdl::Loss, -(n as i32)),
+                TbValue::Draw => (Wdl::Draw, 0),
+            };
+            for swap in [false, true] {
+                let icn = icn(board, swap);
+                let mut game = chess(&icn);
+                assert_eq!(game.eval_kind, EvalKind::Chess, "{icn}");
+                assert_eq!(tables.probe_wdl(&mut game), Some(wdl), "{icn}");
+                assert_eq!(tables.probe_dtz(&mut game), Some(dtz), "{icn}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_probes_match_retrograde_solution() {
+        let tables = bundled();
+        assert_eq!(tables.len(), 4);
+        assert_eq!(tables.max_pieces(), 3);
+
+        // Mate in one, and a king walking into one; a rook left hanging; stalemate.
+        let known = [
+            ("w 1,8,1,8 K2,6|R8,1|k1,8", Wdl::Win, 1),
+            ("b 1,8,1,8 K2,6|R8,2|k1,8", Wdl::Loss, -2),
+            ("w 1,8,1,8 K2,6|Q8,7|k1,8", Wdl::Win, 1),
+            ("b 1,8,1,8 K1,1|R2,7|k1,8", Wdl::Draw, 0),
+            ("b 1,8,1,8 K2,6|Q3,7|k1,8", Wdl::Draw, 0),
+        ];
+        for (icn, wdl, dtz) in known {
+            let mut game = chess(icn);
+            assert_eq!(tables.probe_wdl(&mut game), Some(wdl), "{icn}");
+            assert_eq!(tables.probe_dtz(&mut game), Some(dtz), "{icn}");
+        }
+
+        for material in ["KRvK", "KQvK"] {
+            assert_probes_match(&tables, &solve(material), 41);
+        }
+    }
+
+    #[test]
+    fn test_root_filter_keeps_fastest_wins() {
+        let tables = bundled();
+
+        let mut game = chess("w 1,8,1,8 K3,3|R5,5|k7,7");
+        let dtz = tables.probe_dtz(&mut game).unwrap();
+        assert!(dtz > 1);
+        let mut moves: MoveList = game
+            .get_legal_moves()
+            .into_iter()
+            .filter(|m| with_move(&mut game, m, |_, _| ()).is_some())
+            .collect();
+        let before = moves.len();
+        assert!(tables.filter_root_moves(&mut game, &mut moves));
+        assert!(!moves.is_empty() && moves.len() < before);
+        for m in &moves {
+            let child = with_move(&mut game, m, |game, _| tables.probe_dtz(game)).unwrap();
+            assert_eq!(child, Some(-(dtz - 1)));
+        }
+
+        // A lost position keeps its slowest defences.
+        let mut game = chess("b 1,8,1,8 K4,4|R8,2|k5,6");
+        let mut moves: MoveList = game
+            .get_legal_moves()
+            .into_iter()
+            .filter(|m| with_move(&mut game, m, |_, _| ()).is_some())
+            .collect();
+        tables.filter_root_moves(&mut game, &mut moves);
+        let dtz = tables.probe_dtz(&mut game).unwrap();
+        for m in &moves {
+            let child = with_move(&mut game, m, |game, _| tables.probe_dtz(game)).unwrap();
+            assert_eq!(child, Some(-dtz - 1));
+        }
+    }
+
+    #[test]
+    fn test_only_plain_chess_is_probed() {
+        let tables = bundled();
+
+        assert_eq!(
+            tables.probe_wdl(&mut chess("w 1,8,1,8 K2,6|R8,1|k1,8")),
+            Some(Wdl::Win)
+        );
+        // Castling rights are not in the tables.
+        assert_eq!(
+            tables.probe_wdl(&mut chess("w 1,8,1,8 K5,1+|R8,1+|k5,8")),
+            None
+        );
+        // Neither are other boards or missing material.
+        assert_eq!(tables.probe_wdl(&mut chess("w K2,6|R8,1|k1,8")), None);
+        assert_eq!(
+            tables.probe_wdl(&mut chess("w 1,8,1,8 K2,6|B8,1|k1,8")),
+            None
+        );
+        // Bare kings need no table.
+        assert_eq!(
+            tables.probe_wdl(&mut chess("w 1,8,1,8 K2,6|k1,8")),
+            Some(Wdl::Draw)
+        );
+    }
+}
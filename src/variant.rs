@@ -0,0 +1,545 @@
+//! Variant definitions.
+//!
+//! Every variant the engine knows is a [`VariantDef`] in one process-wide registry: its
+//! names, starting position, bounds, win conditions, promotion rule and tags. The named
+//! [`Variant`] values are the built-in set, read from `variant/builtin.json`. More can be
+//! added at runtime with [`register`] or [`load_json`]; they get [`Variant::Custom`] ids
+//! and then work wherever a built-in does (the `[Variant "..."]` tag, eval profiles,
+//! `UCI_Variant`, the tools' variant lists). Native builds also load the file named by
+//! the `APEIRON_VARIANTS` environment variable on first use, so every binary, and every
+//! engine process `sprt` starts, sees the same set. A file that fails to load is reported
+//! on stderr and the built-ins are used alone.
+//!
+//! Definition files use the format of `builtin.json`:
+//!
+//! ```json
+//! { "variants": [ {
+//!     "name": "Mini_Knightline",
+//!     "aliases": ["Mini Knightline"],
+//!     "pieces": "K5,1|N4,1|N6,1|P3,2+|P4,2+|P5,2+|P6,2+|P7,2+|k5,8|n4,8|n6,8|p3,7+|p4,7+|p5,7+|p6,7+|p7,7+",
+//!     "promotion": "(8;n,q|1;n,q)",
+//!     "bounds": [1, 9, 1, 8],
+//!     "win_conditions": "checkmate",
+//!     "move_rule": 100,
+//!     "tags": ["base-eval"]
+//! } ] }
+//! ```
+//!
+//! Only `name` and `pieces` are required; the promotion rule defaults to `(8|1)`, the move
+//! rule to 100 halfmoves and the board to unbounded. Tags are free-form. The tools select
+//! by these: `site` (live on infinitechess.org), `selfplay` (`game_gen`), `base-eval`
+//! (played by the base evaluation), `exotic` (base-eval variants with several kings or an
+//! unusual win condition), `multi-king`, `coaip`, `nnue-eligible` (`gen_nnue_data`) and
+//! `tuning` (the `spsa` default).
+
+use std::sync::{Arc, LazyLock, PoisonError, RwLock};
+
+use serde::Deserialize;
+
+use crate::game::GameState;
+
+/// Environment variable naming a definition file that native builds load on first use.
+pub const VARIANTS_ENV: &str = "APEIRON_VARIANTS";
+
+const BUILTIN_JSON: &str = include_str!("variant/builtin.json");
+
+const UNBOUNDED: (i64, i64, i64, i64) = (
+    -1_000_000_000_000_000,
+    1_000_000_000_000_000,
+    -1_000_000_000_000_000,
+    1_000_000_000_000_000,
+);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Variant {
+    Classical,
+    ConfinedClassical,
+    ClassicalPlus,
+    CoaIP,
+    CoaIPHO,
+    CoaIPRO,
+    CoaIPNO,
+    Palace,
+    Pawndard,
+    Core,
+    Standarch,
+    SpaceClassic,
+    Space,
+    Abundance,
+    PawnHorde,
+    Knightline,
+    Obstocean,
+    Chess,
+    // Custom variant that uses all fairy leapers
+    ScatteredLeapers,
+    // Custom variants that uses more than 1 king
+    DoubleKingClassical,
+    DoubleKingChess,
+    TripleKingMaze,     // variant created by Nikita
+    AllPiecesClassical, // Classical setup, allpiecescaptured win condition (tests base eval)
+    /// A variant added to the registry at runtime, by its registry index. Only ids the
+    /// registry hands out are valid.
+    Custom(u16),
+}
+
+impl Variant {
+    /// Every built-in variant, in declaration (and `builtin.json`) order.
+    pub const ALL: [Variant; 23] = [
+        Variant::Classical,
+        Variant::ConfinedClassical,
+        Variant::ClassicalPlus,
+        Variant::CoaIP,
+        Variant::CoaIPHO,
+        Variant::CoaIPRO,
+        Variant::CoaIPNO,
+        Variant::Palace,
+        Variant::Pawndard,
+        Variant::Core,
+        Variant::Standarch,
+        Variant::SpaceClassic,
+        Variant::Space,
+        Variant::Abundance,
+        Variant::PawnHorde,
+        Variant::Knightline,
+        Variant::Obstocean,
+        Variant::Chess,
+        Variant::ScatteredLeapers,
+        Variant::DoubleKingClassical,
+        Variant::DoubleKingChess,
+        Variant::TripleKingMaze,
+        Variant::AllPiecesClassical,
+    ];
+
+    /// Every registered variant: the built-ins, then those added at runtime.
+    pub fn all() -> Vec<Variant> {
+        (0..registry().len()).map(Variant::from_index).collect()
+    }
+
+    /// Every registered variant carrying `tag`, in registry order.
+    pub fn tagged(tag: &str) -> Vec<Variant> {
+        registry()
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.def.has_tag(tag))
+            .map(|(index, _)| Variant::from_index(index))
+            .collect()
+    }
+
+    fn from_index(index: usize) -> Variant {
+        Variant::ALL
+            .get(index)
+            .copied()
+            .unwrap_or(Variant::Custom(index as u16))
+    }
+
+    fn index(self) -> usize {
+        match self {
+            Variant::Custom(index) => index as usize,
+            builtin => Variant::ALL.iter().position(|&v| v == builtin).unwrap(),
+        }
+    }
+
+    fn entry(self) -> Arc<Entry> {
+        Arc::clone(&registry()[self.index()])
+    }
+
+    /// A copy of this variant's definition.
+    pub fn def(&self) -> VariantDef {
+        self.entry().def.clone()
+    }
+
+    pub fn starting_icn(&self) -> String {
+        self.entry().icn.clone()
+    }
+
+    pub fn to_str(&self) -> String {
+        self.entry().def.name.clone()
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.entry().def.has_tag(tag)
+    }
+
+    pub fn parse(s: &str) -> Self {
+        Self::try_parse(s).unwrap_or(Variant::Classical) // Default fallback
+    }
+
+    /// Like [`Variant::parse`], but returns `None` for unrecognised names. Matches the
+    /// canonical name or an alias, ignoring case and treating spaces as underscores.
+    pub fn try_parse(s: &str) -> Option<Self> {
+        let key = normalize(s);
+        registry()
+            .iter()
+            .position(|entry| entry.keys.contains(&key))
+            .map(Variant::from_index)
+    }
+
+    pub fn get_default_bounds(&self) -> (i64, i64, i64, i64) {
+        self.entry()
+            .def
+            .bounds
+            .map(|[left, right, bottom, top]| (left, right, bottom, top))
+            .unwrap_or(UNBOUNDED)
+    }
+}
+
+impl std::str::FromStr for Variant {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Variant::parse(s))
+    }
+}
+
+/// One variant's definition. See the module docs for the file format.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VariantDef {
+    /// Canonical name, as written in the `[Variant "..."]` tag.
+    pub name: String,
+    /// Other names [`Variant::try_parse`] accepts.
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// ICN piece list of the starting position.
+    pub pieces: String,
+    /// ICN promotion rule, `(white_rank;pieces|black_rank;pieces)`.
+    #[serde(default = "default_promotion")]
+    pub promotion: String,
+    /// World bounds as `[left, right, bottom, top]`; unbounded when absent.
+    #[serde(default)]
+    pub bounds: Option<[i64; 4]>,
+    /// ICN win conditions, e.g. `checkmate,allpiecescaptured`; checkmate when absent.
+    #[serde(default)]
+    pub win_conditions: Option<String>,
+    /// Halfmove limit of the move rule.
+    #[serde(default = "default_move_rule")]
+    pub move_rule: u32,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+fn default_promotion() -> String {
+    "(8|1)".to_string()
+}
+
+fn default_move_rule() -> u32 {
+    100
+}
+
+impl VariantDef {
+    /// The starting position as ICN.
+    pub fn starting_icn(&self) -> String {
+        let mut icn = format!("w 0/{} 1 {}", self.move_rule, self.promotion);
+        if let Some([left, right, bottom, top]) = self.bounds {
+            icn.push_str(&format!(" {},{},{},{}", left, right, bottom, top));
+        }
+        if let Some(win_conditions) = &self.win_conditions {
+            icn.push(' ');
+            icn.push_str(win_conditions);
+        }
+        icn.push(' ');
+        icn.push_str(&self.pieces);
+        icn
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DefFile {
+    variants: Vec<VariantDef>,
+}
+
+/// A registered definition with its starting ICN and lookup keys worked out once.
+struct Entry {
+    def: VariantDef,
+    icn: String,
+    keys: Vec<String>,
+}
+
+impl Entry {
+    fn new(def: VariantDef) -> Self {
+        let keys = std::iter::once(&def.name)
+            .chain(&def.aliases)
+            .map(|name| normalize(name))
+            .collect();
+        Entry {
+            icn: def.starting_icn(),
+            def,
+            keys,
+        }
+    }
+}
+
+fn normalize(name: &str) -> String {
+    name.trim().to_lowercase().replace(' ', "_")
+}
+
+static REGISTRY: LazyLock<RwLock<Vec<Arc<Entry>>>> =
+    LazyLock::new(|| RwLock::new(initial_entries()));
+
+fn registry() -> std::sync::RwLockReadGuard<'static, Vec<Arc<Entry>>> {
+    REGISTRY.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// The built-ins, plus the `APEIRON_VARIANTS` file when it loads cleanly. A bad file is
+/// reported and skipped rather than taking the process down on first registry access.
+fn initial_entries() -> Vec<Arc<Entry>> {
+    let builtin: DefFile = serde_json::from_str(BUILTIN_JSON).expect("valid builtin.json");
+    #[cfg_attr(target_arch = "wasm32", allow(unused_mut))]
+    let mut entries: Vec<Arc<Entry>> = builtin
+        .variants
+        .into_iter()
+        .map(|def| Arc::new(Entry::new(def)))
+        .collect();
+    assert_eq!(
+        entries.len(),
+        Variant::ALL.len(),
+        "builtin.json out of sync"
+    );
+
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(path) = std::env::var_os(VARIANTS_ENV)
+        && let Err(e) = extend_from_file(&mut entries, std::path::Path::new(&path))
+    {
+        eprintln!(
+            "{}={}: {}; using the built-in variants only",
+            VARIANTS_ENV,
+            path.to_string_lossy(),
+            e
+        );
+    }
+    entries
+}
+
+/// Adds a definition file's variants to `entries`, all or none.
+#[cfg(not(target_arch = "wasm32"))]
+fn extend_from_file(entries: &mut Vec<Arc<Entry>>, path: &std::path::Path) -> Result<(), String> {
+    let mut updated = entries.clone();
+    insert(&mut updated, validate(read_defs(path)?)?)?;
+    *entries = updated;
+    Ok(())
+}
+
+/// Checks that each definition sets up a valid position before it goes anywhere near
+/// the registry.
+fn validate(defs: Vec<VariantDef>) -> Result<Vec<Entry>, String> {
+    defs.into_iter()
+        .map(|def| {
+            if def.name.trim().is_empty() {
+                return Err("variant without a name".to_string());
+            }
+            let entry = Entry::new(def);
+            GameState::try_from_icn(&entry.icn)
+                .map_err(|e| format!("{}: {}", entry.def.name, e))?;
+            Ok(entry)
+        })
+        .collect()
+}
+
+/// Adds `new` to `entries`. A definition with the name of an earlier runtime one replaces
+/// it under the same id; built-ins cannot be redefined and names must stay unambiguous.
+fn insert(entries: &mut Vec<Arc<Entry>>, new: Vec<Entry>) -> Result<Vec<Variant>, String> {
+    let mut added = Vec::with_capacity(new.len());
+    for entry in new {
+        let same_name = entries.iter().position(|e| e.keys[0] == entry.keys[0]);
+        if same_name.is_some_and(|index| index < Variant::ALL.len()) {
+            return Err(format!("'{}' is a built-in variant", entry.def.name));
+        }
+        let clash = entries.iter().enumerate().find(|&(index, e)| {
+            Some(index) != same_name && e.keys.iter().any(|key| entry.keys.contains(key))
+        });
+        if let Some((_, other)) = clash {
+            return Err(format!(
+                "'{}' shares a name with '{}'",
+                entry.def.name, other.def.name
+            ));
+        }
+        let entry = Arc::new(entry);
+        let index = match same_name {
+            Some(index) => {
+                entries[index] = entry;
+                index
+            }
+            None => {
+                if entries.len() > u16::MAX as usize {
+                    return Err("too many variants".to_string());
+                }
+                entries.push(entry);
+                entries.len() - 1
+            }
+        };
+        added.push(Variant::from_index(index));
+    }
+    Ok(added)
+}
+
+/// Registers one definition, returning its id.
+pub fn register(def: VariantDef) -> Result<Variant, String> {
+    load_defs(vec![def]).map(|added| added[0])
+}
+
+/// Registers every definition in a JSON definition file's contents, all or none.
+pub fn load_json(json: &str) -> Result<Vec<Variant>, String> {
+    let file: DefFile = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    load_defs(file.variants)
+}
+
+/// Reads and registers a JSON definition file, all or none.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load_file(path: impl AsRef<std::path::Path>) -> Result<Vec<Variant>, String> {
+    load_defs(read_defs(path.as_ref())?)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read_defs(path: &std::path::Path) -> Result<Vec<VariantDef>, String> {
+    let json = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let file: DefFile = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+    Ok(file.variants)
+}
+
+fn load_defs(defs: Vec<VariantDef>) -> Result<Vec<Variant>, String> {
+    let new = validate(defs)?;
+    let mut entries = REGISTRY.write().unwrap_or_else(PoisonError::into_inner);
+    let mut updated = entries.clone();
+    let added = insert(&mut updated, new)?;
+    *entries = updated;
+    Ok(added)
+}
+
+/// Resolves a tool's variant list: `all`, a tag, or comma-separated variant names.
+pub fn select(spec: &str) -> Result<Vec<Variant>, String> {
+    let spec = spec.trim();
+    if spec.eq_ignore_ascii_case("all") {
+        return Ok(Variant::all());
+    }
+    let tagged = Variant::tagged(spec);
+    if !tagged.is_empty() {
+        return Ok(tagged);
+    }
+    spec.split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| Variant::try_parse(name).ok_or_else(|| format!("unknown variant '{}'", name)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_definitions_compose_starting_icn() {
+        assert_eq!(Variant::Obstocean.def().bounds, Some([-6, 15, -3, 12]));
+        assert!(
+            Variant::TripleKingMaze
+                .starting_icn()
+                .starts_with("w 0/200 1 (8|1) -8,18,-8,17 checkmate,allroyalscaptured vo0,0|")
+        );
+        assert!(Variant::CoaIPHO.has_tag("coaip"));
+        assert_eq!(
+            Variant::tagged("multi-king"),
+            [
+                Variant::DoubleKingClassical,
+                Variant::DoubleKingChess,
+                Variant::TripleKingMaze
+            ]
+        );
+        for variant in Variant::ALL {
+            assert!(
+                GameState::try_from_icn(&variant.starting_icn()).is_ok(),
+                "{:?}",
+                variant
+            );
+        }
+    }
+
+    #[test]
+    fn test_registered_variant_is_parsed_and_selected() {
+        let json = r#"{ "variants": [ {
+            "name": "Test_Knight_Duel",
+            "aliases": ["Knight Duel Test"],
+            "pieces": "K5,1|N4,1|P5,2+|k5,8|n4,8|p5,7+",
+            "bounds": [1, 8, 1, 8],
+            "tags": ["test-registry"]
+        } ] }"#;
+        let added = load_json(json).unwrap();
+        let variant = added[0];
+        assert!(matches!(variant, Variant::Custom(_)));
+        assert_eq!(Variant::try_parse("knight duel test"), Some(variant));
+        assert_eq!(variant.to_str(), "Test_Knight_Duel");
+        assert_eq!(
+            variant.starting_icn(),
+            "w 0/100 1 (8|1) 1,8,1,8 K5,1|N4,1|P5,2+|k5,8|n4,8|p5,7+"
+        );
+        assert_eq!(select("test-registry").unwrap(), [variant]);
+        assert!(Variant::all().contains(&variant));
+
+        // Reloading the same name keeps the id; the tag round-trips through ICN.
+        let old = variant.entry();
+        assert_eq!(load_json(json).unwrap(), [variant]);
+        assert_eq!(
+            Arc::strong_count(&old),
+            1,
+            "replaced entry is freed with its last user"
+        );
+        let mut game = GameState::new();
+        game.setup_position_from_icn(&format!(
+            "[Variant \"Test_Knight_Duel\"] {}",
+            variant.starting_icn()
+        ));
+        assert_eq!(game.variant, Some(variant));
+        assert!(game.to_icn().starts_with("[Variant \"Test_Knight_Duel\"]"));
+    }
+
+    #[test]
+    fn test_bad_definitions_are_rejected() {
+        let def = |name: &str, pieces: &str| VariantDef {
+            name: name.to_string(),
+            aliases: Vec::new(),
+            pieces: pieces.to_string(),
+            promotion: default_promotion(),
+            bounds: None,
+            win_conditions: None,
+            move_rule: default_move_rule(),
+            tags: Vec::new(),
+        };
+        assert!(
+            register(def("Chess", "K5,1|k5,8"))
+                .unwrap_err()
+                .contains("built-in")
+        );
+        assert!(register(def("Test_Bad_Piece", "K5,1|zz5,8")).is_err());
+        let mut alias_clash = def("Test_Alias_Clash", "K5,1|k5,8");
+        alias_clash.aliases.push("Classical+".to_string());
+        assert!(
+            register(alias_clash)
+                .unwrap_err()
+                .contains("Classical_Plus")
+        );
+        assert_eq!(Variant::try_parse("Test_Bad_Piece"), None);
+        assert!(load_json(r#"{ "variants": [ { "name": "Test_No_Pieces" } ] }"#).is_err());
+        assert_eq!(
+            select("Chess, knightline").unwrap(),
+            [Variant::Chess, Variant::Knightline]
+        );
+        assert!(select("Chess,Atlantis").is_err());
+    }
+
+    #[test]
+    fn test_bad_definition_file_leaves_entries_alone() {
+        let path =
+            std::env::temp_dir().join(format!("apeiron-variants-{}.json", std::process::id()));
+        std::fs::write(&path, r#"{ "variants": [ { "name": "Test_Truncated""#).unwrap();
+        let mut entries = registry().clone();
+        let before = entries.len();
+        assert!(extend_from_file(&mut entries, &path).is_err());
+        assert_eq!(entries.len(), before);
+        assert!(extend_from_file(&mut entries, &path.with_extension("missing")).is_err());
+        assert_eq!(entries.len(), before);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
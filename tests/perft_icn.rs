@@ -1,5 +1,5 @@
 #![cfg(not(coverage))]
-use apeiron::board::{Coordinate, PieceType};
+use apeiron::board::Coordinate;
 use apeiron::game::GameState;
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
@@ -61,11 +61,11 @@ fn fen_to_icn(fen: &str) -> String {
 
                 let mut is_special = false;
 
-                // King castling rights
-                if c == 'K' && (castling.contains('K') || castling.contains('Q')) {
+                // King castling rights (KQkq or Shredder-FEN file letters)
+                if c == 'K' && castling.chars().any(|f| f.is_ascii_uppercase()) {
                     is_special = true;
                 }
-                if c == 'k' && (castling.contains('k') || castling.contains('q')) {
+                if c == 'k' && castling.chars().any(|f| f.is_ascii_lowercase()) {
                     is_special = true;
                 }
 
@@ -83,6 +83,15 @@ fn fen_to_icn(fen: &str) -> String {
                     is_special = true;
                 }
 
+                // Rooks named by file (Shredder-FEN, used by Chess960 positions)
+                let file = (b'a' + (x - 1) as u8) as char;
+                if c == 'R' && y == 1 && castling.contains(file.to_ascii_uppercase()) {
+                    is_special = true;
+                }
+                if c == 'r' && y == 8 && castling.contains(file) {
+                    is_special = true;
+                }
+
                 // Pawns: Standard chess pawns on 2nd and 7th rank have double move rights
                 if c == 'P' && y == 2 {
                     is_special = true;
@@ -128,7 +137,7 @@ pub fn perft(game: &mut GameState, depth: usize) -> PerftStats {
 
     for m in &legal_moves {
         // Strict Castling Check
-        if m.piece.piece_type() == PieceType::King && (m.to.x - m.from.x).abs() > 1 {
+        if m.rook_coord.is_some() {
             // 1. Cannot castle out of check
             if game.is_in_check() {
                 continue;
@@ -166,7 +175,7 @@ pub fn perft(game: &mut GameState, depth: usize) -> PerftStats {
                 stats.nodes += 1;
 
                 // Captures
-                if undo.captured_piece.is_some() {
+                if undo.captured_piece.is_some() || undo.ep_captured_piece.is_some() {
                     stats.captures += 1;
                 }
 
@@ -175,8 +184,8 @@ pub fn perft(game: &mut GameState, depth: usize) -> PerftStats {
                     stats.en_passant += 1;
                 }
 
-                // Castles
-                if m.piece.piece_type() == PieceType::King && (m.to.x - m.from.x).abs() > 1 {
+                // Castles (a Chess960 king may move one square or none)
+                if m.rook_coord.is_some() {
                     stats.castles += 1;
                 }
 
@@ -193,9 +202,7 @@ pub fn perft(game: &mut GameState, depth: usize) -> PerftStats {
                     let mut has_legal = false;
 
                     for op_m in &opponent_moves {
-                        if op_m.piece.piece_type() == PieceType::King
-                            && (op_m.to.x - op_m.from.x).abs() > 1
-                        {
+                        if op_m.rook_coord.is_some() {
                             if game.is_in_check() {
                                 continue;
                             }
@@ -246,11 +253,28 @@ fn run_fen_perft(
     depth: usize,
     expected_nodes: u64,
     expected_stats: Option<PerftStats>,
+) {
+    run_perft(name, fen, false, depth, expected_nodes, expected_stats);
+}
+
+/// Perft under Fischer-random castling rules; `fen` uses Shredder-FEN castling files.
+fn run_chess960_perft(name: &str, fen: &str, depth: usize, expected_nodes: u64) {
+    run_perft(name, fen, true, depth, expected_nodes, None);
+}
+
+fn run_perft(
+    name: &str,
+    fen: &str,
+    chess960: bool,
+    depth: usize,
+    expected_nodes: u64,
+    expected_stats: Option<PerftStats>,
 ) {
     let icn = fen_to_icn(fen);
     let mut game = GameState::new();
 
     game.setup_position_from_icn(&icn);
+    game.game_rules.chess960 = chess960;
     let start = std::time::Instant::now();
     let stats = perft(&mut game, depth);
     let duration = start.elapsed();
@@ -282,6 +306,19 @@ fn run_fen_perft(
     }
 }
 
+/// Checks the castles among the legal moves of a Chess960 position; `fen` uses
+/// Shredder-FEN castling files.
+fn assert_chess960_castles(name: &str, fen: &str, expected_castles: u64) {
+    let mut game = GameState::new();
+    game.setup_position_from_icn(&fen_to_icn(fen));
+    game.game_rules.chess960 = true;
+    let stats = perft(&mut game, 1);
+    assert_eq!(
+        stats.castles, expected_castles,
+        "Castles mismatch for {name}"
+    );
+}
+
 #[test]
 fn perft_initial_position() {
     let expected = PerftStats {
@@ -379,4 +416,63 @@ fn perft_position_5() {
         62_379,
         None,
     );
-}
\ No newline at end of file
+}
+
+#[test]
+fn perft_chess960_standard_start() {
+    run_chess960_perft(
+        "960 Start Pos",
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w HAha - 0 1",
+        3,
+        8_902,
+    );
+}
+
+#[test]
+fn perft_chess960_position_1() {
+    run_chess960_perft(
+        "960 Pos 1",
+        "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+        3,
+        12_189,
+    );
+}
+
+#[test]
+fn perft_chess960_position_2() {
+    run_chess960_perft(
+        "960 Pos 2",
+        "2nnrbkr/p1qppppp/8/1ppb4/6PP/3PP3/PPP2P2/BQNNRBKR w HEhe - 1 9",
+        3,
+        18_002,
+    );
+}
+
+// Castles among the legal moves of hand-built Chess960 positions.
+
+#[test]
+fn perft_chess960_castles_both_sides() {
+    // King b1 between its rooks: both castles are open.
+    assert_chess960_castles("960 Both Sides", "rk5r/8/8/8/8/8/8/RK5R w HAha - 0 1", 2);
+}
+
+#[test]
+fn perft_chess960_castling_through_an_attacked_square() {
+    // The e8 rook covers e1, which the king crosses on its way to g1.
+    assert_chess960_castles("960 Attacked Path", "rk2r3/8/8/8/8/8/8/RK5R w HA - 0 1", 1);
+}
+
+#[test]
+fn perft_chess960_king_lands_on_its_rook() {
+    // King f1 and rook g1 swap squares.
+    assert_chess960_castles("960 Swap", "1k6/8/8/8/8/8/8/5KR1 w G - 0 1", 1);
+}
+
+#[test]
+fn perft_chess960_standard_start_has_no_castles() {
+    assert_chess960_castles(
+        "960 Start Pos",
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w HAha - 0 1",
+        0,
+    );
+}
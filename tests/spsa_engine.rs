@@ -0,0 +1,89 @@
+#![cfg(feature = "param_tuning")]
+//! Round trip through one `spsa engine` process, as the tuner drives it between moves.
+
+use apeiron::Variant;
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_engine_protocol_round_trip() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_spsa"))
+        .env("RAYON_NUM_THREADS", "1")
+        .arg("engine")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("spsa binary starts");
+    let mut stdin = child.stdin.take().unwrap();
+    let stdout = BufReader::new(child.stdout.take().unwrap());
+
+    // Lines arrive on a channel so a hung engine fails the test instead of blocking it.
+    let (tx, lines) = mpsc::channel();
+    thread::spawn(move || {
+        for line in stdout.lines() {
+            let Ok(line) = line else { break };
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    let mut send = |line: &str| {
+        writeln!(stdin, "{}", line)
+            .and_then(|_| stdin.flush())
+            .unwrap()
+    };
+    let read_until = |prefix: &str, timeout: Duration| -> Vec<String> {
+        let deadline = Instant::now() + timeout;
+        let mut read = Vec::new();
+        loop {
+            let left = deadline.saturating_duration_since(Instant::now());
+            let line = lines
+                .recv_timeout(left)
+                .unwrap_or_else(|_| panic!("no {} line in time, got {:?}", prefix, read));
+            let done = line.starts_with(prefix);
+            read.push(line);
+            if done {
+                return read;
+            }
+        }
+    };
+
+    send("isready");
+    read_until("readyok", Duration::from_secs(10));
+    send("newgame Classical");
+    send(&format!(
+        "position icn {}",
+        Variant::Classical.starting_icn()
+    ));
+    send("go wtime 10000 btime 10000 winc 0 binc 0 depth 3");
+    let replies = read_until("bestmove", Duration::from_secs(30));
+    assert!(replies.iter().any(|line| line.starts_with("info score ")));
+    let bestmove = replies.last().unwrap();
+    assert_ne!(bestmove, "bestmove none");
+    assert_eq!(bestmove.split_whitespace().count(), 3, "{}", bestmove);
+
+    // An hour on the clock: only `stop` ends this search in time.
+    send("go wtime 3600000 btime 3600000 winc 0 binc 0");
+    thread::sleep(Duration::from_millis(300));
+    send("stop");
+    let replies = read_until("bestmove", Duration::from_secs(10));
+    assert_ne!(replies.last().unwrap(), "bestmove none");
+
+    // The same process still answers, then exits on quit.
+    send("isready");
+    read_until("readyok", Duration::from_secs(10));
+    send("quit");
+    let deadline = Instant::now() + Duration::from_secs(10);
+    let status = loop {
+        if let Some(status) = child.try_wait().unwrap() {
+            break status;
+        }
+        assert!(Instant::now() < deadline, "engine did not quit");
+        thread::sleep(Duration::from_millis(10));
+    };
+    assert!(status.success());
+}